@@ -0,0 +1,610 @@
+use crate::{node::Node, object::Object, state::State};
+use crate::error::{check_argc, dartthrow, evalerror};
+use crate::evalhelp::{call_function_with, create_list, create_map, overridden, safe_to_string, MaybeRef};
+use crate::eventloop;
+use crate::objsys::RefKey;
+
+
+/// JsonEncoder.withIndent, written as a top level function of the convert library.
+pub fn call_static(
+    fnode: &Node,
+    name: &str,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if !state.looktables.contains_key("dart:convert") {
+        // As Dart, without an import of dart:convert.
+        evalerror("Undefined name 'JsonEncoder'.", state, fnode);
+    }
+    let fname = match name {
+        "withIndent" => "_jsonEncoderWithIndent",
+        _ => evalerror(
+            format!("Method not found: 'JsonEncoder.{}'.", name),
+            state,
+            fnode
+        )
+    };
+    let func = eventloop::library_function("dart:convert", fname, state);
+    call_function_with(MaybeRef::None, &func, args, fnode, state)
+}
+
+
+/// Encode an object as JSON, as JsonEncoder.convert does. Args are the
+/// object, the indent or null, and the toEncodable function or null.
+pub fn encode(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__JSON_ENCODE", 3, args.len(), fnode, state);
+    let indent = match &args[1] {
+        Object::String(s) => Some(s.clone()),
+        Object::Null => None,
+        x => evalerror(format!("Expected String for __JSON_ENCODE(). Got: {}", x), state, fnode)
+    };
+    let to_encodable = match &args[2] {
+        Object::Null => None,
+        f => Some(f.clone())
+    };
+
+    let mut encoder = Encoder {
+        fnode,
+        indent,
+        to_encodable,
+        seen: Vec::new(),
+        out: String::new()
+    };
+    encoder.write_object(&args[0], 0, state);
+    Object::String(encoder.out)
+}
+
+
+struct Encoder<'a> {
+    fnode: &'a Node,
+    indent: Option<String>,
+    to_encodable: Option<Object>,
+    // The lists, maps and objects being written, to find cycles.
+    seen: Vec<RefKey>,
+    out: String
+}
+
+
+impl Encoder<'_> {
+
+    /// Write an object, which is made encodable by toEncodable,
+    /// or by its toJson method, if it is not already.
+    fn write_object(&mut self, obj: &Object, depth: usize, state: &mut State) {
+
+        if self.write_value(obj, depth, state) {
+            return;
+        }
+        self.check_cycle(obj, state);
+
+        let custom = match &self.to_encodable {
+            Some(f) => {
+                let f = f.clone();
+                call_function_with(MaybeRef::None, &f, vec![obj.clone()], self.fnode, state)
+            }
+            None => match self.to_json(obj, state) {
+                Some(json) => json,
+                // As dart.
+                None => dartthrow(
+                    format!("Converting object to an encodable object failed: {}", safe_to_string(obj, state)),
+                    state,
+                    self.fnode
+                )
+            }
+        };
+
+        // The custom value may be held nowhere else while it is written.
+        let kept = custom.heap_ref().cloned();
+        if let Some(rk) = &kept {
+            state.constructing.push(rk.clone());
+        }
+        if !self.write_value(&custom, depth, state) {
+            // As dart.
+            dartthrow(
+                format!("Converting object did not return an encodable object: {}", safe_to_string(obj, state)),
+                state,
+                self.fnode
+            );
+        }
+        if kept.is_some() {
+            state.constructing.pop();
+        }
+        self.seen.pop();
+    }
+
+
+    fn to_json(&self, obj: &Object, state: &mut State) -> Option<Object> {
+        let rk = obj.heap_ref().filter(|rk| state.objsys.has_instance(rk))?.clone();
+        let cname = state.objsys.get_instance(&rk).classname.clone();
+        let meth = overridden(&cname, "toJson", state)?;
+        Some(call_function_with(MaybeRef::Ref(rk), &meth, vec![], self.fnode, state))
+    }
+
+
+    /// Write null, a bool, a finite number, a string, or a list or map of
+    /// encodable objects. Gives false for other objects, and for maps with
+    /// keys that are not strings.
+    fn write_value(&mut self, obj: &Object, depth: usize, state: &mut State) -> bool {
+
+        match obj {
+            Object::Null => self.out.push_str("null"),
+            Object::Bool(b) => self.out.push_str(&b.to_string()),
+            Object::Int(n) => self.out.push_str(&n.to_string()),
+            Object::Double(x) if x.is_finite() => self.out.push_str(&obj.to_string()),
+            Object::String(s) => write_string(s, &mut self.out),
+            Object::Reference(rk) if state.objsys.has_instance(rk) => {
+                match state.objsys.get_instance(rk).classname.as_str() {
+                    "List" => {
+                        let els = elements(obj, "__list", state);
+                        self.check_cycle(obj, state);
+                        self.write_list(&els, depth, state);
+                        self.seen.pop();
+                    }
+                    "Map" => {
+                        let keys = map_elements(obj, "__keys", state);
+                        if !keys.iter().all(|k| matches!(k, Object::String(_))) {
+                            return false;
+                        }
+                        let vals = map_elements(obj, "__values", state);
+                        self.check_cycle(obj, state);
+                        self.write_map(&keys, &vals, depth, state);
+                        self.seen.pop();
+                    }
+                    _ => return false
+                }
+            }
+            _ => return false
+        }
+        true
+    }
+
+
+    fn write_list(&mut self, els: &[Object], depth: usize, state: &mut State) {
+
+        if els.is_empty() {
+            self.out.push_str("[]");
+            return;
+        }
+        self.out.push('[');
+        for (i, el) in els.iter().enumerate() {
+            if i > 0 {
+                self.out.push(',');
+            }
+            self.newline(depth + 1);
+            self.write_object(el, depth + 1, state);
+        }
+        self.newline(depth);
+        self.out.push(']');
+    }
+
+
+    fn write_map(&mut self, keys: &[Object], vals: &[Object], depth: usize, state: &mut State) {
+
+        if keys.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+        self.out.push('{');
+        for (i, (key, val)) in keys.iter().zip(vals).enumerate() {
+            if i > 0 {
+                self.out.push(',');
+            }
+            self.newline(depth + 1);
+            if let Object::String(s) = key {
+                write_string(s, &mut self.out);
+            }
+            self.out.push(':');
+            if self.indent.is_some() {
+                self.out.push(' ');
+            }
+            self.write_object(val, depth + 1, state);
+        }
+        self.newline(depth);
+        self.out.push('}');
+    }
+
+
+    /// A line break and indentation, when indenting.
+    fn newline(&mut self, depth: usize) {
+        if let Some(indent) = &self.indent {
+            self.out.push('\n');
+            self.out.push_str(&indent.repeat(depth));
+        }
+    }
+
+
+    fn check_cycle(&mut self, obj: &Object, state: &State) {
+        if let Some(rk) = obj.heap_ref() {
+            if self.seen.contains(rk) {
+                // As dart.
+                dartthrow("Cyclic error in JSON stringify", state, self.fnode);
+            }
+            self.seen.push(rk.clone());
+        }
+        else {
+            // Kept even, as the caller pops.
+            self.seen.push(RefKey(String::new()));
+        }
+    }
+}
+
+
+/// The elements of a List instance.
+fn elements(list: &Object, field: &str, state: &State) -> Vec<Object> {
+    let rk = list.heap_ref().unwrap();
+    match state.objsys.get_instance(rk).get_field(field) {
+        Object::Reference(ilist_rk) => state.objsys.get_list(&ilist_rk).els.clone(),
+        x => panic!("Internal {} field was not a reference: {}", field, x)
+    }
+}
+
+
+/// The keys or values of a Map instance.
+fn map_elements(map: &Object, field: &str, state: &State) -> Vec<Object> {
+    let rk = map.heap_ref().unwrap();
+    let list = state.objsys.get_instance(rk).get_field(field);
+    elements(&list, "__list", state)
+}
+
+
+/// A string in double quotes, with quotes, backslashes
+/// and control characters escaped, as Dart does.
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c)
+        }
+    }
+    out.push('"');
+}
+
+
+/// Decode a JSON string into lists, maps, strings, numbers, bools and null.
+/// Throws a FormatException, with the offset of the error, if it is not JSON.
+pub fn decode(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__JSON_DECODE", 1, args.len(), fnode, state);
+    let source = match &args[0] {
+        Object::String(s) => s.clone(),
+        x => evalerror(format!("Expected String for __JSON_DECODE(). Got: {}", x), state, fnode)
+    };
+
+    let mut parser = Parser { chars: source.chars().collect(), pos: 0 };
+    let result = parser.value(state).and_then(|val| {
+        parser.skip_whitespace();
+        match parser.chars.get(parser.pos) {
+            None => Ok(val),
+            Some(_) => Err(parser.fail())
+        }
+    });
+    match result {
+        Ok(val) => val,
+        Err((offset, message)) => dartthrow(format_exception(message, &parser.chars, offset), state, fnode)
+    }
+}
+
+
+// An error, at an offset in the source.
+type Failure = (usize, &'static str);
+
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize
+}
+
+
+impl Parser {
+
+    fn value(&mut self, state: &mut State) -> Result<Object, Failure> {
+
+        self.skip_whitespace();
+        match self.chars.get(self.pos) {
+            Some('{') => self.map(state),
+            Some('[') => self.list(state),
+            Some('"') => Ok(Object::String(self.string()?)),
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.word("true", Object::Bool(true)),
+            Some('f') => self.word("false", Object::Bool(false)),
+            Some('n') => self.word("null", Object::Null),
+            _ => Err(self.fail())
+        }
+    }
+
+
+    fn list(&mut self, state: &mut State) -> Result<Object, Failure> {
+
+        self.pos += 1;
+        let mut els = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.pos) == Some(&']') {
+            self.pos += 1;
+        }
+        else {
+            loop {
+                els.push(self.value(state)?);
+                self.skip_whitespace();
+                match self.chars.get(self.pos) {
+                    Some(',') => self.pos += 1,
+                    Some(']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.fail())
+                }
+            }
+        }
+        let list = create_list(els, state);
+        if let Object::Reference(rk) = &list {
+            state.objsys.get_instance_mut(rk).typeargs = vec![String::from("dynamic")];
+        }
+        Ok(list)
+    }
+
+
+    fn map(&mut self, state: &mut State) -> Result<Object, Failure> {
+
+        self.pos += 1;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.pos) == Some(&'}') {
+            self.pos += 1;
+        }
+        else {
+            loop {
+                self.skip_whitespace();
+                if self.chars.get(self.pos) != Some(&'"') {
+                    return Err(self.fail());
+                }
+                let key = Object::String(self.string()?);
+                self.skip_whitespace();
+                if self.chars.get(self.pos) != Some(&':') {
+                    return Err(self.fail());
+                }
+                self.pos += 1;
+                entries.push((key, self.value(state)?));
+                self.skip_whitespace();
+                match self.chars.get(self.pos) {
+                    Some(',') => self.pos += 1,
+                    Some('}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.fail())
+                }
+            }
+        }
+        Ok(create_map(entries, "String", "dynamic", state))
+    }
+
+
+    fn string(&mut self) -> Result<String, Failure> {
+
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            let c = match self.chars.get(self.pos) {
+                Some(c) => *c,
+                None => return Err(self.fail())
+            };
+            self.pos += 1;
+            match c {
+                '"' => return Ok(s),
+                '\\' => {
+                    let e = match self.chars.get(self.pos) {
+                        Some(e) => *e,
+                        None => return Err(self.fail())
+                    };
+                    self.pos += 1;
+                    match e {
+                        '"' | '\\' | '/' => s.push(e),
+                        'b' => s.push('\u{8}'),
+                        'f' => s.push('\u{c}'),
+                        'n' => s.push('\n'),
+                        'r' => s.push('\r'),
+                        't' => s.push('\t'),
+                        'u' => s.push(self.unicode_escape()?),
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.fail());
+                        }
+                    }
+                }
+                c if (c as u32) < 0x20 => {
+                    self.pos -= 1;
+                    return Err((self.pos, "Control character in string"));
+                }
+                c => s.push(c)
+            }
+        }
+    }
+
+
+    /// The character of a \uXXXX escape, after the u. A surrogate
+    /// pair is two escapes. Lone surrogates become U+FFFD.
+    fn unicode_escape(&mut self) -> Result<char, Failure> {
+
+        let high = self.hex4()?;
+        if (0xd800..0xdc00).contains(&high)
+            && self.chars.get(self.pos) == Some(&'\\')
+            && self.chars.get(self.pos + 1) == Some(&'u') {
+            let start = self.pos;
+            self.pos += 2;
+            let low = self.hex4()?;
+            if (0xdc00..0xe000).contains(&low) {
+                let c = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                return Ok(char::from_u32(c).unwrap());
+            }
+            self.pos = start;
+        }
+        Ok(char::from_u32(high).unwrap_or('\u{fffd}'))
+    }
+
+
+    fn hex4(&mut self) -> Result<u32, Failure> {
+        let mut n = 0;
+        for _ in 0..4 {
+            match self.chars.get(self.pos).and_then(|c| c.to_digit(16)) {
+                Some(d) => n = n * 16 + d,
+                None => return Err(self.fail())
+            }
+            self.pos += 1;
+        }
+        Ok(n)
+    }
+
+
+    /// A number, as -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?
+    /// It is an int without a fraction or exponent, if it fits.
+    fn number(&mut self) -> Result<Object, Failure> {
+
+        let start = self.pos;
+        let mut is_int = true;
+
+        if self.chars.get(self.pos) == Some(&'-') {
+            self.pos += 1;
+        }
+        match self.chars.get(self.pos) {
+            Some('0') => self.pos += 1,
+            Some('1'..='9') => self.digits(),
+            _ => return Err(self.fail())
+        }
+        if self.chars.get(self.pos) == Some(&'.') {
+            is_int = false;
+            self.pos += 1;
+            if !matches!(self.chars.get(self.pos), Some('0'..='9')) {
+                return Err(self.fail());
+            }
+            self.digits();
+        }
+        if matches!(self.chars.get(self.pos), Some('e' | 'E')) {
+            is_int = false;
+            self.pos += 1;
+            if matches!(self.chars.get(self.pos), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            if !matches!(self.chars.get(self.pos), Some('0'..='9')) {
+                return Err(self.fail());
+            }
+            self.digits();
+        }
+
+        let text: String = self.chars[start..self.pos].iter().collect();
+        if is_int {
+            if let Ok(n) = text.parse::<i64>() {
+                return Ok(Object::Int(n));
+            }
+        }
+        Ok(Object::Double(text.parse::<f64>().unwrap()))
+    }
+
+
+    fn digits(&mut self) {
+        while matches!(self.chars.get(self.pos), Some('0'..='9')) {
+            self.pos += 1;
+        }
+    }
+
+
+    fn word(&mut self, word: &str, val: Object) -> Result<Object, Failure> {
+        for c in word.chars() {
+            if self.chars.get(self.pos) != Some(&c) {
+                return Err(self.fail());
+            }
+            self.pos += 1;
+        }
+        Ok(val)
+    }
+
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.get(self.pos), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+
+    /// A failure at the current position.
+    fn fail(&self) -> Failure {
+        if self.pos >= self.chars.len() {
+            (self.chars.len(), "Unexpected end of input")
+        }
+        else {
+            (self.pos, "Unexpected character")
+        }
+    }
+}
+
+
+/// A FormatException as Dart writes it, with the line of the source
+/// where the error is, and a caret under the offending character.
+fn format_exception(message: &str, source: &[char], offset: usize) -> String {
+
+    // The line and column of the offset.
+    let mut linenum = 1;
+    let mut line_start = 0;
+    let mut previous_cr = false;
+    for (i, c) in source[..offset].iter().enumerate() {
+        if *c == '\n' {
+            if line_start != i || !previous_cr {
+                linenum += 1;
+            }
+            line_start = i + 1;
+            previous_cr = false;
+        }
+        else if *c == '\r' {
+            linenum += 1;
+            line_start = i + 1;
+            previous_cr = true;
+        }
+    }
+    let mut report = if linenum > 1 {
+        format!("FormatException: {} (at line {}, character {})\n", message, linenum, offset - line_start + 1)
+    }
+    else {
+        format!("FormatException: {} (at character {})\n", message, offset + 1)
+    };
+
+    let line_end = source[offset..].iter()
+        .position(|c| *c == '\n' || *c == '\r')
+        .map_or(source.len(), |i| offset + i);
+
+    // Long lines are cut to show the part around the offset.
+    let (mut start, mut end) = (line_start, line_end);
+    let (mut prefix, mut postfix) = ("", "");
+    if line_end - line_start > 78 {
+        if offset - line_start < 75 {
+            end = start + 75;
+            postfix = "...";
+        }
+        else if line_end - offset < 75 {
+            start = end - 75;
+            prefix = "...";
+        }
+        else {
+            start = offset - 36;
+            end = offset + 36;
+            prefix = "...";
+            postfix = "...";
+        }
+    }
+    let slice: String = source[start..end].iter().collect();
+    report.push_str(&format!("{}{}{}\n", prefix, slice, postfix));
+    report.push_str(&format!("{}^\n", " ".repeat(offset - start + prefix.len())));
+    report
+}
@@ -0,0 +1,411 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{node::Node, object::Object, state::State};
+use crate::error::{check_argc, evalerror};
+use crate::evalhelp::{call_function, call_function_with, create_list, MaybeRef};
+use crate::eventloop;
+use crate::timezone::{self, civil_from_days, days_from_civil};
+
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+// As Dart, 100,000,000 days to either side of the epoch.
+const MAX_MICROS: i128 = 8_640_000_000_000_000_000;
+
+
+/// DateTime.now, DateTime.utc, DateTime.parse and the others. They are
+/// written as top level functions of the datetime library, and take
+/// their arguments as given, since some are named.
+pub fn call_static(
+    fnode: &Node,
+    name: &str,
+    state: &mut State) -> Object {
+
+    let fname = match name {
+        "now" => "_dateTimeNow",
+        "timestamp" => "_dateTimeTimestamp",
+        "utc" => "_dateTimeUtc",
+        "parse" => "_dateTimeParse",
+        "tryParse" => "_dateTimeTryParse",
+        "fromMillisecondsSinceEpoch" => "_dateTimeFromMilliseconds",
+        "fromMicrosecondsSinceEpoch" => "_dateTimeFromMicroseconds",
+        _ => evalerror(
+            format!("Method not found: 'DateTime.{}'.", name),
+            state,
+            fnode
+        )
+    };
+    let func = eventloop::library_function("auto:datetime.dart", fname, state);
+    call_function(MaybeRef::None, &func, &fnode.children[0], state)
+}
+
+
+/// The static getters of DateTime and Duration, like DateTime.monday
+/// and Duration.zero.
+pub fn constant(node: &Node, typename: &str, name: &str, state: &mut State) -> Option<Object> {
+
+    let n = match (typename, name) {
+        ("DateTime", "monday") => 1,
+        ("DateTime", "tuesday") => 2,
+        ("DateTime", "wednesday") => 3,
+        ("DateTime", "thursday") => 4,
+        ("DateTime", "friday") => 5,
+        ("DateTime", "saturday") => 6,
+        ("DateTime", "sunday") => 7,
+        ("DateTime", "daysPerWeek") => 7,
+        ("DateTime", "january") => 1,
+        ("DateTime", "february") => 2,
+        ("DateTime", "march") => 3,
+        ("DateTime", "april") => 4,
+        ("DateTime", "may") => 5,
+        ("DateTime", "june") => 6,
+        ("DateTime", "july") => 7,
+        ("DateTime", "august") => 8,
+        ("DateTime", "september") => 9,
+        ("DateTime", "october") => 10,
+        ("DateTime", "november") => 11,
+        ("DateTime", "december") => 12,
+        ("DateTime", "monthsPerYear") => 12,
+        ("Duration", "microsecondsPerMillisecond") => 1000,
+        ("Duration", "microsecondsPerSecond") => MICROS_PER_SECOND,
+        ("Duration", "microsecondsPerMinute") => 60 * MICROS_PER_SECOND,
+        ("Duration", "microsecondsPerHour") => 3600 * MICROS_PER_SECOND,
+        ("Duration", "microsecondsPerDay") => MICROS_PER_DAY,
+        ("Duration", "millisecondsPerSecond") => 1000,
+        ("Duration", "millisecondsPerMinute") => 60_000,
+        ("Duration", "millisecondsPerHour") => 3_600_000,
+        ("Duration", "millisecondsPerDay") => 86_400_000,
+        ("Duration", "secondsPerMinute") => 60,
+        ("Duration", "secondsPerHour") => 3600,
+        ("Duration", "secondsPerDay") => 86400,
+        ("Duration", "minutesPerHour") => 60,
+        ("Duration", "minutesPerDay") => 1440,
+        ("Duration", "hoursPerDay") => 24,
+        ("Duration", "zero") => {
+            let func = eventloop::library_function("auto:duration.dart", "_durationZero", state);
+            return Some(call_function_with(MaybeRef::None, &func, Vec::new(), node, state));
+        }
+        _ => return None
+    };
+    Some(Object::Int(n))
+}
+
+
+fn int_args(fname: &str, args: &[Object], fnode: &Node, state: &State) -> Vec<i64> {
+    args.iter().map(|a| match a {
+        Object::Int(n) => *n,
+        x => evalerror(format!("Expected int for {}(). Got: {}", fname, x), state, fnode)
+    }).collect()
+}
+
+
+fn micros_arg(fname: &str, arg: &Object, fnode: &Node, state: &State) -> i64 {
+    int_args(fname, std::slice::from_ref(arg), fnode, state)[0]
+}
+
+
+fn bool_arg(fname: &str, arg: &Object, fnode: &Node, state: &State) -> bool {
+    match arg {
+        Object::Bool(b) => *b,
+        x => evalerror(format!("Expected bool for {}(). Got: {}", fname, x), state, fnode)
+    }
+}
+
+
+/// The offset from UTC, in seconds, and the zone name at a time.
+fn local_time(micros: i64, is_utc: bool) -> timezone::LocalTime {
+    if is_utc {
+        return timezone::LocalTime { offset: 0, name: String::from("UTC") };
+    }
+    timezone::local().at(micros.div_euclid(MICROS_PER_SECOND))
+}
+
+
+/// Microseconds since the epoch for a date and time, which is local
+/// unless in UTC. Parts outside their range carry over, as a month
+/// of 13 into the next year. None when outside the range of DateTime.
+fn from_fields(is_utc: bool, parts: &[i64]) -> Option<i64> {
+
+    let (year, month) = (parts[0] as i128, parts[1] as i128 - 1);
+    let year = year + month.div_euclid(12);
+    if year.abs() > 1_000_000 {
+        return None;
+    }
+    let days = days_from_civil(year as i64, month.rem_euclid(12) as i64 + 1, 1) as i128 + parts[2] as i128 - 1;
+    let micros = days * MICROS_PER_DAY as i128
+        + parts[3] as i128 * 3_600_000_000
+        + parts[4] as i128 * 60_000_000
+        + parts[5] as i128 * 1_000_000
+        + parts[6] as i128 * 1000
+        + parts[7] as i128;
+    // Leaves room for the offset of local time.
+    if micros.abs() > MAX_MICROS + MICROS_PER_DAY as i128 {
+        return None;
+    }
+    let mut micros = micros as i64;
+    if !is_utc {
+        let local = micros.div_euclid(MICROS_PER_SECOND);
+        micros -= (local - timezone::local().to_utc(local)) * MICROS_PER_SECOND;
+    }
+    if (micros as i128).abs() > MAX_MICROS {
+        return None;
+    }
+    Some(micros)
+}
+
+
+/// Microseconds since the epoch, now. With a virtual clock,
+/// this moves with the clock from the start of the program.
+pub fn now(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__DATETIME_NOW", 0, args.len(), fnode, state);
+    let wall = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros() as i64;
+    let start = wall - state.start_time.elapsed().as_micros() as i64;
+    Object::Int(start + eventloop::now(state) as i64)
+}
+
+
+/// Microseconds since the epoch for __DATETIME_FROM_FIELDS(isUtc, year,
+/// month, day, hour, minute, second, millisecond, microsecond), or null
+/// if outside the range of DateTime.
+pub fn from_parts(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__DATETIME_FROM_FIELDS", 9, args.len(), fnode, state);
+    let is_utc = bool_arg("__DATETIME_FROM_FIELDS", &args[0], fnode, state);
+    let parts = int_args("__DATETIME_FROM_FIELDS", &args[1..], fnode, state);
+    match from_fields(is_utc, &parts) {
+        Some(micros) => Object::Int(micros),
+        None => Object::Null
+    }
+}
+
+
+/// The parts of a time as a list: year, month, day, hour, minute, second,
+/// millisecond, microsecond, weekday, offset from UTC in seconds, and the
+/// name of the time zone.
+pub fn fields(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__DATETIME_FIELDS", 2, args.len(), fnode, state);
+    let micros = micros_arg("__DATETIME_FIELDS", &args[0], fnode, state);
+    let is_utc = bool_arg("__DATETIME_FIELDS", &args[1], fnode, state);
+
+    let zone = local_time(micros, is_utc);
+    let local = micros + zone.offset * MICROS_PER_SECOND;
+    let days = local.div_euclid(MICROS_PER_DAY);
+    let time = local.rem_euclid(MICROS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+
+    let vals = vec![
+        year,
+        month,
+        day,
+        time / 3_600_000_000,
+        time / 60_000_000 % 60,
+        time / 1_000_000 % 60,
+        time / 1000 % 1000,
+        time % 1000,
+        timezone::weekday(days),
+        zone.offset
+    ];
+    let mut vals: Vec<Object> = vals.into_iter().map(Object::Int).collect();
+    vals.push(Object::String(zone.name));
+    create_list(vals, state)
+}
+
+
+/// The year as Dart writes it, with at least four digits, or
+/// as six digits with a sign for ISO 8601 outside 0 to 9999.
+fn year_string(year: i64, iso: bool) -> String {
+    if iso && !(0..=9999).contains(&year) {
+        let sign = if year < 0 { "-" } else { "+" };
+        return format!("{}{:06}", sign, year.abs());
+    }
+    let sign = if year < 0 { "-" } else { "" };
+    format!("{}{:04}", sign, year.abs())
+}
+
+
+/// A time as by toString, or as by toIso8601String with a T between
+/// the date and the time. Microseconds are left out when they are 0.
+pub fn format(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__DATETIME_FORMAT", 3, args.len(), fnode, state);
+    let micros = micros_arg("__DATETIME_FORMAT", &args[0], fnode, state);
+    let is_utc = bool_arg("__DATETIME_FORMAT", &args[1], fnode, state);
+    let iso = bool_arg("__DATETIME_FORMAT", &args[2], fnode, state);
+
+    let local = micros + local_time(micros, is_utc).offset * MICROS_PER_SECOND;
+    let days = local.div_euclid(MICROS_PER_DAY);
+    let time = local.rem_euclid(MICROS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+
+    let mut s = format!(
+        "{}-{:02}-{:02}{}{:02}:{:02}:{:02}.{:03}",
+        year_string(year, iso),
+        month,
+        day,
+        if iso { "T" } else { " " },
+        time / 3_600_000_000,
+        time / 60_000_000 % 60,
+        time / 1_000_000 % 60,
+        time / 1000 % 1000
+    );
+    if time % 1000 != 0 {
+        s.push_str(&format!("{:03}", time % 1000));
+    }
+    if is_utc {
+        s.push('Z');
+    }
+    Object::String(s)
+}
+
+
+/// A duration as Dart writes it, as 1:02:03.000000.
+pub fn format_duration(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__DATETIME_FORMAT_DURATION", 1, args.len(), fnode, state);
+    let micros = micros_arg("__DATETIME_FORMAT_DURATION", &args[0], fnode, state);
+
+    let sign = if micros < 0 { "-" } else { "" };
+    let m = (micros as i128).abs();
+    Object::String(format!(
+        "{}{}:{:02}:{:02}.{:06}",
+        sign,
+        m / 3_600_000_000,
+        m / 60_000_000 % 60,
+        m / 1_000_000 % 60,
+        m % 1_000_000
+    ))
+}
+
+
+/// Parse a date as DateTime.parse does, giving a list of the
+/// microseconds since the epoch and whether it is in UTC, or
+/// null if the string is not a date.
+pub fn parse(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__DATETIME_PARSE", 1, args.len(), fnode, state);
+    let s = match &args[0] {
+        Object::String(s) => s.clone(),
+        x => evalerror(format!("Expected String for __DATETIME_PARSE(). Got: {}", x), state, fnode)
+    };
+
+    // The year has 4 to 6 digits, so try the longest first.
+    let parsed = (4..=6).rev().find_map(|n| parse_iso(s.as_bytes(), n));
+    match parsed {
+        Some((parts, offset)) => {
+            let is_utc = offset.is_some();
+            let mut parts = parts;
+            parts[4] -= offset.unwrap_or(0);
+            match from_fields(is_utc, &parts) {
+                Some(micros) => create_list(vec![Object::Int(micros), Object::Bool(is_utc)], state),
+                None => Object::Null
+            }
+        }
+        None => Object::Null
+    }
+}
+
+
+// A subset of ISO 8601, as taken by Dart:
+//
+//   [+-]yyyy[yy][-]MM[-]dd
+//   followed by an optional time: [T ]HH[[:]mm[[:]ss[(.|,)ffffff]]]
+//   followed by an optional zone: [ ](z|Z|(+|-)HH[[:]mm])
+//
+// Gives the parts of the date and time, and the offset of the zone
+// in minutes, if given. Fractions of seconds past microseconds are
+// ignored.
+fn parse_iso(s: &[u8], year_digits: usize) -> Option<(Vec<i64>, Option<i64>)> {
+
+    let mut pos = 0;
+    let digits = |pos: &mut usize, n: usize| -> Option<i64> {
+        let part = s.get(*pos..*pos + n)?;
+        if !part.iter().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        *pos += n;
+        std::str::from_utf8(part).ok()?.parse().ok()
+    };
+    let eat = |pos: &mut usize, c: u8| -> bool {
+        if s.get(*pos) == Some(&c) {
+            *pos += 1;
+            return true;
+        }
+        false
+    };
+
+    let sign = if eat(&mut pos, b'-') { -1 } else { eat(&mut pos, b'+'); 1 };
+    let mut parts = vec![sign * digits(&mut pos, year_digits)?, 1, 1, 0, 0, 0, 0, 0];
+    eat(&mut pos, b'-');
+    parts[1] = digits(&mut pos, 2)?;
+    eat(&mut pos, b'-');
+    parts[2] = digits(&mut pos, 2)?;
+
+    let mut offset = None;
+    if pos < s.len() {
+        if !eat(&mut pos, b'T') && !eat(&mut pos, b' ') {
+            return None;
+        }
+        parts[3] = digits(&mut pos, 2)?;
+        let more = |pos: &mut usize| matches!(s.get(*pos), Some(c) if c.is_ascii_digit() || *c == b':');
+        if more(&mut pos) {
+            eat(&mut pos, b':');
+            parts[4] = digits(&mut pos, 2)?;
+            if more(&mut pos) {
+                eat(&mut pos, b':');
+                parts[5] = digits(&mut pos, 2)?;
+                if eat(&mut pos, b'.') || eat(&mut pos, b',') {
+                    let start = pos;
+                    while matches!(s.get(pos), Some(c) if c.is_ascii_digit()) {
+                        pos += 1;
+                    }
+                    if pos == start {
+                        return None;
+                    }
+                    let mut frac = 0;
+                    for i in 0..6 {
+                        frac = frac * 10 + s.get(start + i).filter(|_| start + i < pos).map_or(0, |c| (c - b'0') as i64);
+                    }
+                    parts[6] = frac / 1000;
+                    parts[7] = frac % 1000;
+                }
+            }
+        }
+
+        if pos < s.len() {
+            eat(&mut pos, b' ');
+            if eat(&mut pos, b'z') || eat(&mut pos, b'Z') {
+                offset = Some(0);
+            }
+            else {
+                let sign = if eat(&mut pos, b'-') { -1 } else if eat(&mut pos, b'+') { 1 } else { return None };
+                let hours = digits(&mut pos, 2)?;
+                eat(&mut pos, b':');
+                let minutes = if pos < s.len() { digits(&mut pos, 2)? } else { 0 };
+                offset = Some(sign * (hours * 60 + minutes));
+            }
+        }
+    }
+    if pos != s.len() {
+        return None;
+    }
+    Some((parts, offset))
+}
@@ -0,0 +1,139 @@
+use crate::{node::Node, object::Object, state::State, types};
+use crate::error::evalerror;
+use crate::evalhelp::{call_function_with, get_field, MaybeRef};
+use crate::eventloop::{self, Task};
+use crate::generator;
+
+
+// FUTURE
+
+/// A pending future. The type of its value is given as a Type,
+/// or is dynamic.
+pub fn create(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let valuetype = match args.first() {
+        Some(obj) => match types::type_of_type_object(obj, state) {
+            Some(t) => t,
+            None => evalerror(format!("Expected a type for __ASYNC_FUTURE(). Got: {}", obj), state, fnode)
+        },
+        None => String::from("dynamic")
+    };
+    eventloop::new_future(&valuetype, state)
+}
+
+
+/// Future.value, Future.error, Future.delayed and Future.wait. They
+/// are written as top level functions of the future library.
+pub fn call_static(
+    fnode: &Node,
+    name: &str,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let fname = match name {
+        "value" => "_futureValue",
+        "error" => "_futureError",
+        "delayed" => "_futureDelayed",
+        "wait" => "_futureWait",
+        _ => evalerror(
+            format!("Method not found: 'Future.{}'.", name),
+            state,
+            fnode
+        )
+    };
+    let func = eventloop::library_function("auto:future.dart", fname, state);
+    call_function_with(MaybeRef::None, &func, args, fnode, state)
+}
+
+
+// EVENT LOOP
+
+/// Queue a call of the function with the rest of the arguments.
+pub fn microtask(
+    fnode: &Node,
+    mut args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if args.is_empty() {
+        evalerror("Expected a function for __ASYNC_MICROTASK().", state, fnode);
+    }
+    let func = args.remove(0);
+    state.eventloop.add_microtask(Task { func, args, node: fnode.clone() });
+    Object::Null
+}
+
+
+/// Queue a call of the function with the rest of the arguments,
+/// to run once the delay in microseconds has passed. Gives the
+/// id of the timer.
+pub fn timer(
+    fnode: &Node,
+    mut args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if args.len() < 2 {
+        evalerror("Expected a delay and a function for __ASYNC_TIMER().", state, fnode);
+    }
+    let delay = match args.remove(0) {
+        Object::Int(n) => n.max(0) as u64,
+        x => evalerror(format!("Expected int delay for __ASYNC_TIMER(). Got: {}", x), state, fnode)
+    };
+    let func = args.remove(0);
+    let id = eventloop::add_timer(delay, Task { func, args, node: fnode.clone() }, state);
+    Object::Int(id as i64)
+}
+
+
+/// Cancel a timer, given its id.
+pub fn cancel_timer(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    match args.first() {
+        Some(Object::Int(id)) if args.len() == 1 => {
+            eventloop::cancel_timer(*id as u64, state);
+            Object::Null
+        }
+        _ => evalerror("Expected a timer id for __ASYNC_TIMER_CANCEL().", state, fnode)
+    }
+}
+
+
+/// Resume an async or async* body, given as __ASYNC_RESUME(body, source)
+/// with the future it awaited, or as __ASYNC_RESUME(body) after a yield.
+pub fn resume(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if args.is_empty() || args.len() > 2 {
+        evalerror(
+            format!("Expected 1 or 2 arguments for __ASYNC_RESUME(). Got: {}", args.len()),
+            state,
+            fnode
+        );
+    }
+
+    let gen_rk = match &args[0] {
+        Object::Reference(gen_rk) if state.objsys.has_generator(gen_rk) => gen_rk.clone(),
+        x => evalerror(format!("Expected async body for __ASYNC_RESUME(). Got: {}", x), state, fnode)
+    };
+
+    let outcome = match args.get(1) {
+        Some(source) => {
+            let result = get_field(source.clone(), "__result", state, fnode);
+            match get_field(source.clone(), "__state", state, fnode) {
+                Object::Int(2) => Some(Err(result)),
+                _ => Some(Ok(result))
+            }
+        }
+        None => None
+    };
+
+    generator::resume_async(gen_rk, outcome, fnode, state);
+    Object::Null
+}
@@ -0,0 +1,102 @@
+use crate::{node::Node, object::Object, state::State};
+use crate::error::evalerror;
+use crate::evalhelp::{call_function_with, MaybeRef};
+use crate::eventloop;
+use crate::isolate;
+
+
+/// Isolate.spawn and Isolate.run. They are written as
+/// top level functions of the isolate library.
+pub fn call_static(
+    fnode: &Node,
+    name: &str,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if state.program.is_none() {
+        // As Dart, without an import of dart:isolate.
+        evalerror("Undefined name 'Isolate'.", state, fnode);
+    }
+    let fname = match name {
+        "spawn" => "_isolateSpawn",
+        "run" => "_isolateRun",
+        _ => evalerror(
+            format!("Method not found: 'Isolate.{}'.", name),
+            state,
+            fnode
+        )
+    };
+    let func = eventloop::library_function("dart:isolate", fname, state);
+    call_function_with(MaybeRef::None, &func, args, fnode, state)
+}
+
+
+/// Run the entry function with the message in a new isolate,
+/// with the id of a port for its errors, if given.
+pub fn spawn(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let errors = match args.get(2) {
+        Some(Object::Int(id)) if args.len() == 3 => Some(*id as u64),
+        None if args.len() == 2 => None,
+        _ => evalerror("Expected an entry function, a message and an optional port id for __ISOLATE_SPAWN().", state, fnode)
+    };
+    match &args[0] {
+        Object::Function(..) => {},
+        x => evalerror(
+            format!("Expected a top level function for __ISOLATE_SPAWN(). Got: {}", x),
+            state,
+            fnode
+        )
+    }
+    isolate::spawn(&args[0], &args[1], errors, fnode, state);
+    Object::Null
+}
+
+
+/// Open a port delivering to the ReceivePort, giving its id.
+pub fn open(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    match args.first() {
+        Some(port @ Object::Reference(_)) if args.len() == 1 => {
+            Object::Int(isolate::open_port(port.clone(), fnode, state) as i64)
+        }
+        _ => evalerror("Expected a ReceivePort for __ISOLATE_OPEN().", state, fnode)
+    }
+}
+
+
+pub fn close(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    match args.first() {
+        Some(Object::Int(id)) if args.len() == 1 => {
+            isolate::close_port(*id as u64, state);
+            Object::Null
+        }
+        _ => evalerror("Expected a port id for __ISOLATE_CLOSE().", state, fnode)
+    }
+}
+
+
+/// Send a copy of the message to the port with the id.
+pub fn send(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    match args.first() {
+        Some(Object::Int(id)) if args.len() == 2 => {
+            isolate::send(*id as u64, &args[1], fnode, state);
+            Object::Null
+        }
+        _ => evalerror("Expected a port id and a message for __ISOLATE_SEND().", state, fnode)
+    }
+}
@@ -1,4 +1,10 @@
+use std::cmp::Ordering;
 use crate::{error::evalerror, node::Node, object::Object, state::State};
+use crate::error::{dartthrow, err_arg_type};
+use crate::evalhelp::{call_function_with, create_list, get_field, iterable_elements, to_dart_string, MaybeRef};
+use crate::evaluator::operator_equals;
+use crate::types::type_name;
+use crate::api::num;
 
 
 // GETTERS
@@ -16,24 +22,30 @@ pub fn get_length(
 
 
 pub fn get_first(
+    fnode: &Node,
     args: Vec<Object>,
     state: &mut State) -> Object {
 
     if let Object::Reference(rk) = &args[0] {
-        let ilist = state.objsys.get_list(rk);
-        return ilist.els.first().unwrap().clone();
+        return match state.objsys.get_list(rk).els.first() {
+            Some(el) => el.clone(),
+            None => dartthrow("Bad state: No element", state, fnode)
+        };
     }
     panic!("Unexpected type of internal argument for List.get_first(): {}", &args[0])
 }
 
 
 pub fn get_last(
+    fnode: &Node,
     args: Vec<Object>,
     state: &mut State) -> Object {
 
     if let Object::Reference(rk) = &args[0] {
-        let ilist = state.objsys.get_list(rk);
-        return ilist.els.last().unwrap().clone();
+        return match state.objsys.get_list(rk).els.last() {
+            Some(el) => el.clone(),
+            None => dartthrow("Bad state: No element", state, fnode)
+        };
     }
     panic!("Unexpected type of internal argument for List.get_last(): {}", &args[0])
 }
@@ -72,38 +84,10 @@ pub fn add_all(
     }
 
     if let Object::Reference(rk1) = &args[0] {
-
-        if let Object::Reference(rk2) = &args[1] {
-
-            let arg_list_inst = state.objsys.get_instance(rk2);
-            if !arg_list_inst.has_field("__list") {
-                evalerror(
-                    format!("Unexpected argument type for List.addAll(): {}",
-                            arg_list_inst.classname),
-                    state,
-                    &argnodes[1]
-                )
-            }
-
-            let arg_ilist_ref = arg_list_inst.get_field("__list");
-
-            if let Object::Reference(arg_ilist_rk) = arg_ilist_ref {
-
-                if !state.objsys.has_list(&arg_ilist_rk) {
-                    panic!("Could not find list in __list reference: {}", arg_ilist_rk)
-                }        
-                let new_els = state.objsys.get_list(&arg_ilist_rk).els.clone();
-                let ilist = state.objsys.get_list_mut(rk1);                            
-                ilist.add_all(new_els);
-                return Object::Null;
-            }
-            panic!("Internal __list field was not a reference: {}", arg_ilist_ref)
-        }
-        evalerror(
-            format!("Unexpected argument for List.addAll(): {}", &args[1]),
-            state,
-            &argnodes[1]
-        )
+        let new_els = list_elements("List.addAll", &args[1], &argnodes[1], state);
+        let ilist = state.objsys.get_list_mut(rk1);
+        ilist.add_all(new_els);
+        return Object::Null;
     }
     panic!("Unexpected internal argument for List.addAll: {}", &args[0])
 
@@ -333,13 +317,527 @@ pub fn to_string(
     }
 
     if let Object::Reference(rk) = &args[0]  {
-        let ilist = state.objsys.get_list(rk);
-        return Object::String(ilist.to_string());
+        let els = state.objsys.get_list(rk).els.clone();
+        let strs: Vec<String> = els.into_iter()
+            .map(|el| to_dart_string(el, state, fnode))
+            .collect();
+        return Object::String(format!("[{}]", strs.join(", ")));
     }
     panic!("Unexpected internal argument for List.toString(): {}", args[0])
 }
 
 
+pub fn sort(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let compare = args[1].clone();
+        if !matches!(compare, Object::Function(_, _, _, _, _, _) | Object::Null) {
+            err_arg_type("List.sort", "Function", &compare, &argnodes[1], state)
+        }
+
+        // Sort a copy, so that the comparator can't pull the list out
+        // from under us. Its elements stay alive through the list for
+        // as long as the list is not modified.
+        let els = state.objsys.get_list(rk).els.clone();
+        let modcount = state.objsys.get_list(rk).modcount;
+
+        let sorted = merge_sort(els, &mut |a, b| {
+            let ord = match &compare {
+                Object::Null => compare_default(a, b, fnode, state),
+                f => {
+                    let res = call_function_with(MaybeRef::None, f, vec![a.clone(), b.clone()], fnode, state);
+                    match res {
+                        Object::Int(n) => n.cmp(&0),
+                        x => dartthrow(
+                            format!("type '{}' is not a subtype of type 'int'", type_name(&x, state)),
+                            state,
+                            fnode
+                        )
+                    }
+                }
+            };
+            check_modcount(rk, modcount, fnode, state);
+            ord
+        });
+
+        state.objsys.get_list_mut(rk).set_elements(sorted);
+        return Object::Null;
+    }
+    panic!("Unexpected internal argument for List.sort(): {}", &args[0])
+}
+
+
+pub fn sublist(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let len = state.objsys.get_list(rk).els.len();
+        let start = int_arg("List.sublist", &args[1], &argnodes[1], state);
+        let end = match &args[2] {
+            Object::Null => len as i64,
+            x => int_arg("List.sublist", x, &argnodes[2], state)
+        };
+        check_valid_range(start, end, len, fnode, state);
+
+        let els = state.objsys.get_list(rk).els[start as usize .. end as usize].to_vec();
+        return create_list(els, state);
+    }
+    panic!("Unexpected internal argument for List.sublist(): {}", &args[0])
+}
+
+
+pub fn index_of(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let mut start = int_arg("List.indexOf", &args[2], &argnodes[2], state);
+        if start < 0 {
+            start = 0;
+        }
+        let mut i = start as usize;
+        while let Some(el) = state.objsys.get_list(rk).els.get(i).cloned() {
+            if operator_equals(el, args[1].clone(), None, fnode, state) {
+                return Object::Int(i as i64);
+            }
+            i += 1;
+        }
+        return Object::Int(-1);
+    }
+    panic!("Unexpected internal argument for List.indexOf(): {}", &args[0])
+}
+
+
+pub fn last_index_of(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let len = state.objsys.get_list(rk).els.len() as i64;
+        let start = match &args[2] {
+            Object::Null => len - 1,
+            x => int_arg("List.lastIndexOf", x, &argnodes[2], state).min(len - 1)
+        };
+
+        let mut i = start;
+        while i >= 0 {
+            if let Some(el) = state.objsys.get_list(rk).els.get(i as usize).cloned() {
+                if operator_equals(el, args[1].clone(), None, fnode, state) {
+                    return Object::Int(i);
+                }
+            }
+            i -= 1;
+        }
+        return Object::Int(-1);
+    }
+    panic!("Unexpected internal argument for List.lastIndexOf(): {}", &args[0])
+}
+
+
+pub fn index_where(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let test = function_arg("List.indexWhere", &args[1], &argnodes[1], state);
+        let mut i = int_arg("List.indexWhere", &args[2], &argnodes[2], state).max(0) as usize;
+
+        // The test may change the list, so look it up each time.
+        while i < state.objsys.get_list(rk).els.len() {
+            let el = state.objsys.get_list(rk).get_el(i);
+            if call_test(&test, el, fnode, state) {
+                return Object::Int(i as i64);
+            }
+            i += 1;
+        }
+        return Object::Int(-1);
+    }
+    panic!("Unexpected internal argument for List.indexWhere(): {}", &args[0])
+}
+
+
+pub fn last_index_where(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let test = function_arg("List.lastIndexWhere", &args[1], &argnodes[1], state);
+        let len = state.objsys.get_list(rk).els.len() as i64;
+        let mut i = match &args[2] {
+            Object::Null => len - 1,
+            x => int_arg("List.lastIndexWhere", x, &argnodes[2], state).min(len - 1)
+        };
+
+        while i >= 0 {
+            if i as usize >= state.objsys.get_list(rk).els.len() {
+                i -= 1;
+                continue;
+            }
+            let el = state.objsys.get_list(rk).get_el(i as usize);
+            if call_test(&test, el, fnode, state) {
+                return Object::Int(i);
+            }
+            i -= 1;
+        }
+        return Object::Int(-1);
+    }
+    panic!("Unexpected internal argument for List.lastIndexWhere(): {}", &args[0])
+}
+
+
+pub fn remove(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let mut i = 0;
+        while let Some(el) = state.objsys.get_list(rk).els.get(i).cloned() {
+            if operator_equals(el, args[1].clone(), None, fnode, state) {
+                state.objsys.get_list_mut(rk).remove_at(i);
+                return Object::Bool(true);
+            }
+            i += 1;
+        }
+        return Object::Bool(false);
+    }
+    panic!("Unexpected internal argument for List.remove(): {}", &args[0])
+}
+
+
+pub fn remove_where(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    filter("List.removeWhere", false, fnode, argnodes, args, state)
+}
+
+
+pub fn retain_where(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    filter("List.retainWhere", true, fnode, argnodes, args, state)
+}
+
+
+pub fn insert_all(
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let len = state.objsys.get_list(rk).els.len();
+        let index = int_arg("List.insertAll", &args[1], &argnodes[1], state);
+        check_in_range("index", index, 0, len as i64, &argnodes[1], state);
+
+        let new_els = list_elements("List.insertAll", &args[2], &argnodes[2], state);
+        let index = index as usize;
+        state.objsys.get_list_mut(rk).replace_range(index, index, new_els);
+        return Object::Null;
+    }
+    panic!("Unexpected internal argument for List.insertAll(): {}", &args[0])
+}
+
+
+pub fn set_all(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let len = state.objsys.get_list(rk).els.len();
+        let index = int_arg("List.setAll", &args[1], &argnodes[1], state);
+        let new_els = list_elements("List.setAll", &args[2], &argnodes[2], state);
+        check_valid_range(index, index + new_els.len() as i64, len, fnode, state);
+
+        let index = index as usize;
+        let end = index + new_els.len();
+        state.objsys.get_list_mut(rk).replace_range(index, end, new_els);
+        return Object::Null;
+    }
+    panic!("Unexpected internal argument for List.setAll(): {}", &args[0])
+}
+
+
+pub fn set_range(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let len = state.objsys.get_list(rk).els.len();
+        let start = int_arg("List.setRange", &args[1], &argnodes[1], state);
+        let end = int_arg("List.setRange", &args[2], &argnodes[2], state);
+        check_valid_range(start, end, len, fnode, state);
+
+        let count = (end - start) as usize;
+        if count == 0 {
+            return Object::Null;
+        }
+
+        let skip = int_arg("List.setRange", &args[4], &argnodes[4], state);
+        if skip < 0 {
+            dartthrow(
+                format!("RangeError (skipCount): Invalid value: Not greater than or equal to 0: {}", skip),
+                state,
+                &argnodes[4]
+            );
+        }
+
+        let other = list_elements("List.setRange", &args[3], &argnodes[3], state);
+        let skip = skip as usize;
+        if skip + count > other.len() {
+            dartthrow("Bad state: Too few elements", state, fnode);
+        }
+
+        let new_els = other[skip .. skip + count].to_vec();
+        let start = start as usize;
+        state.objsys.get_list_mut(rk).replace_range(start, start + count, new_els);
+        return Object::Null;
+    }
+    panic!("Unexpected internal argument for List.setRange(): {}", &args[0])
+}
+
+
+pub fn replace_range(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let len = state.objsys.get_list(rk).els.len();
+        let start = int_arg("List.replaceRange", &args[1], &argnodes[1], state);
+        let end = int_arg("List.replaceRange", &args[2], &argnodes[2], state);
+        check_valid_range(start, end, len, fnode, state);
+
+        let new_els = list_elements("List.replaceRange", &args[3], &argnodes[3], state);
+        state.objsys.get_list_mut(rk).replace_range(start as usize, end as usize, new_els);
+        return Object::Null;
+    }
+    panic!("Unexpected internal argument for List.replaceRange(): {}", &args[0])
+}
+
+
+pub fn fill_range(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let len = state.objsys.get_list(rk).els.len();
+        let start = int_arg("List.fillRange", &args[1], &argnodes[1], state);
+        let end = int_arg("List.fillRange", &args[2], &argnodes[2], state);
+        check_valid_range(start, end, len, fnode, state);
+
+        let fill = vec![args[3].clone(); (end - start) as usize];
+        state.objsys.get_list_mut(rk).replace_range(start as usize, end as usize, fill);
+        return Object::Null;
+    }
+    panic!("Unexpected internal argument for List.fillRange(): {}", &args[0])
+}
+
+
+// HELPERS
+
+/// The elements of a Dart List argument.
+pub fn list_elements(fname: &str, arg: &Object, argnode: &Node, state: &mut State) -> Vec<Object> {
+
+    if let Object::Reference(rk) = arg {
+        if state.objsys.get_instance(rk).has_field("__list") {
+            if let Object::Reference(ilist_rk) = get_field(arg.clone(), "__list", state, argnode) {
+                return state.objsys.get_list(&ilist_rk).els.clone();
+            }
+        }
+        let class = state.objsys.get_class(&state.objsys.get_instance(rk).classname);
+        if class.has_getter("iterator", state) {
+            return iterable_elements(arg, argnode, state);
+        }
+    }
+    err_arg_type(fname, "Iterable", arg, argnode, state)
+}
+
+
+/// Keep the elements for which test returns keep.
+fn filter(
+    fname: &str,
+    keep: bool,
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let test = function_arg(fname, &args[1], &argnodes[1], state);
+        let els = state.objsys.get_list(rk).els.clone();
+        let modcount = state.objsys.get_list(rk).modcount;
+
+        let mut kept = Vec::new();
+        for el in els {
+            let res = call_test(&test, el.clone(), fnode, state);
+            check_modcount(rk, modcount, fnode, state);
+            if res == keep {
+                kept.push(el);
+            }
+        }
+        state.objsys.get_list_mut(rk).set_elements(kept);
+        return Object::Null;
+    }
+    panic!("Unexpected internal argument for {}(): {}", fname, &args[0])
+}
+
+
+fn merge_sort(mut els: Vec<Object>, cmp: &mut dyn FnMut(&Object, &Object) -> Ordering) -> Vec<Object> {
+
+    if els.len() < 2 {
+        return els;
+    }
+
+    let right = els.split_off(els.len() / 2);
+    let left = merge_sort(els, cmp);
+    let right = merge_sort(right, cmp);
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        if cmp(a, b) == Ordering::Greater {
+            merged.push(right.next().unwrap());
+        }
+        else {
+            merged.push(left.next().unwrap());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    merged
+}
+
+
+/// Comparable.compare, for sorting without a comparator.
+fn compare_default(a: &Object, b: &Object, fnode: &Node, state: &mut State) -> Ordering {
+
+    match (a, b) {
+        (Object::Int(_) | Object::Double(_), Object::Int(_) | Object::Double(_)) => num::compare(a, b),
+        (Object::String(s1), Object::String(s2)) => s1.encode_utf16().cmp(s2.encode_utf16()),
+        (Object::Reference(rk), _) => {
+            let inst = state.objsys.get_instance(rk);
+            let c = state.objsys.get_class(&inst.classname);
+            let meth = c.get_method("compareTo", state, fnode);
+            match call_function_with(MaybeRef::Ref(rk.clone()), &meth, vec![b.clone()], fnode, state) {
+                Object::Int(n) => n.cmp(&0),
+                x => dartthrow(
+                    format!("type '{}' is not a subtype of type 'int'", type_name(&x, state)),
+                    state,
+                    fnode
+                )
+            }
+        }
+        _ => dartthrow(
+            format!("type '{}' is not a subtype of type 'Comparable<dynamic>'", type_name(a, state)),
+            state,
+            fnode
+        )
+    }
+}
+
+
+fn call_test(test: &Object, el: Object, fnode: &Node, state: &mut State) -> bool {
+
+    match call_function_with(MaybeRef::None, test, vec![el], fnode, state) {
+        Object::Bool(b) => b,
+        x => dartthrow(
+            format!("type '{}' is not a subtype of type 'bool'", type_name(&x, state)),
+            state,
+            fnode
+        )
+    }
+}
+
+
+fn check_modcount(rk: &crate::objsys::RefKey, modcount: usize, fnode: &Node, state: &State) {
+
+    let ilist = state.objsys.get_list(rk);
+    if ilist.modcount != modcount {
+        dartthrow(
+            format!("Concurrent modification during iteration: Instance(length:{}) of '_GrowableList'.", ilist.els.len()),
+            state,
+            fnode
+        );
+    }
+}
+
+
+fn check_valid_range(start: i64, end: i64, len: usize, fnode: &Node, state: &State) {
+    check_in_range("start", start, 0, len as i64, fnode, state);
+    check_in_range("end", end, start, len as i64, fnode, state);
+}
+
+
+fn check_in_range(name: &str, val: i64, min: i64, max: i64, node: &Node, state: &State) {
+    if val < min || val > max {
+        dartthrow(
+            format!("RangeError ({}): Invalid value: Not in inclusive range {}..{}: {}", name, min, max, val),
+            state,
+            node
+        );
+    }
+}
+
+
+fn int_arg(fname: &str, arg: &Object, argnode: &Node, state: &State) -> i64 {
+    if let Object::Int(n) = arg {
+        return *n;
+    }
+    err_arg_type(fname, "int", arg, argnode, state)
+}
+
+
+fn function_arg(fname: &str, arg: &Object, argnode: &Node, state: &State) -> Object {
+    if let Object::Function(_, _, _, _, _, _) = arg {
+        return arg.clone();
+    }
+    err_arg_type(fname, "Function", arg, argnode, state)
+}
+
+
 // INTERNAL
 
 // pub fn get_el(
@@ -0,0 +1,125 @@
+use crate::{error::{check_argc, dartthrow}, node::Node, object::Object, objsys::RefKey, state::State, types};
+use crate::evalhelp::{create_list, get_field};
+use crate::evaluator::operator_equals;
+
+
+/// The index of a key among the keys of a Map, found through the slots
+/// of its hash table, or -1 less the free slot for it, when it is not there.
+/// A new map has no slots yet, and is rehashed first.
+pub fn find(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__MAP_FIND", 2, args.len(), fnode, state);
+    let (keys, values, slots) = internal_lists(&args[0], fnode, state);
+    if state.objsys.get_list(&slots).els.is_empty() {
+        rehash_lists(&keys, &values, &slots, fnode, state);
+    }
+    match probe(&keys, &slots, &args[1], fnode, state) {
+        Ok(i) => Object::Int(i as i64),
+        Err(j) => Object::Int(-1 - j as i64)
+    }
+}
+
+
+/// Rebuild the slots of a Map, at least twice as many as there are keys.
+/// Keys that are == are merged, keeping the first key and the last value,
+/// as for keys given more than once in a literal.
+pub fn rehash(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__MAP_REHASH", 1, args.len(), fnode, state);
+    rehash_map(&args[0], fnode, state);
+    Object::Null
+}
+
+
+pub fn rehash_map(map: &Object, node: &Node, state: &mut State) {
+    let (keys, values, slots) = internal_lists(map, node, state);
+    rehash_lists(&keys, &values, &slots, node, state);
+}
+
+
+fn rehash_lists(keys: &RefKey, values: &RefKey, slots: &RefKey, fnode: &Node, state: &mut State) {
+
+    // The entries are kept in a list from the garbage collector, while
+    // hashCode and == are called on the keys as they are put back.
+    let oldkeys = state.objsys.get_list(keys).els.clone();
+    let oldvalues = state.objsys.get_list(values).els.clone();
+    let held = create_list([oldkeys.clone(), oldvalues.clone()].concat(), state);
+    if let Object::Reference(rk) = &held {
+        state.constructing.push(rk.clone());
+    }
+    let mut size = 8;
+    while size < oldkeys.len() * 2 {
+        size *= 2;
+    }
+    state.objsys.get_list_mut(slots).set_elements(vec![Object::Int(0); size]);
+    state.objsys.get_list_mut(keys).set_elements(Vec::new());
+    state.objsys.get_list_mut(values).set_elements(Vec::new());
+
+    for (key, value) in oldkeys.into_iter().zip(oldvalues) {
+        match probe(keys, slots, &key, fnode, state) {
+            Ok(i) => state.objsys.get_list_mut(values).els[i] = value,
+            Err(j) => {
+                state.objsys.get_list_mut(keys).add(key);
+                state.objsys.get_list_mut(values).add(value);
+                let n = state.objsys.get_list(keys).els.len();
+                state.objsys.get_list_mut(slots).els[j] = Object::Int(n as i64);
+            }
+        }
+    }
+    state.constructing.pop();
+}
+
+
+/// The index of the key, or the free slot for it. The key is compared
+/// with == to the keys in the slots from its hash code on. The slots hold
+/// one more than the index of a key, or 0 where free.
+fn probe(keys: &RefKey, slots: &RefKey, key: &Object, fnode: &Node, state: &mut State) -> Result<usize, usize> {
+
+    let hash = match get_field(key.clone(), "hashCode", state, fnode) {
+        Object::Int(n) => n,
+        x => dartthrow(format!("type '{}' is not a subtype of type 'int'", types::type_name(&x, state)), state, fnode)
+    };
+    let mask = state.objsys.get_list(slots).els.len() - 1;
+    let mut j = hash as usize & mask;
+
+    loop {
+        let i = match state.objsys.get_list(slots).els[j] {
+            Object::Int(0) => return Err(j),
+            Object::Int(n) => n as usize - 1,
+            ref x => panic!("Unexpected slot of Map: {}", x)
+        };
+        let other = state.objsys.get_list(keys).els[i].clone();
+
+        // A user defined == could change the map from under us.
+        let modcount = state.objsys.get_list(keys).modcount;
+        let found = operator_equals(key.clone(), other, None, fnode, state);
+        if state.objsys.get_list(keys).modcount != modcount {
+            dartthrow("Concurrent modification during map lookup", state, fnode);
+        }
+        if found {
+            return Ok(i);
+        }
+        j = (j + 1) & mask;
+    }
+}
+
+
+/// The internal lists of the keys, values and slots of a Map.
+fn internal_lists(map: &Object, fnode: &Node, state: &mut State) -> (RefKey, RefKey, RefKey) {
+
+    let mut lists = Vec::new();
+    for field in ["__keys", "__values", "__slots"] {
+        let list = get_field(map.clone(), field, state, fnode);
+        match get_field(list, "__list", state, fnode) {
+            Object::Reference(rk) => lists.push(rk),
+            x => panic!("Internal __list field was not a reference: {}", x)
+        }
+    }
+    (lists[0].clone(), lists[1].clone(), lists[2].clone())
+}
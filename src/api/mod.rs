@@ -1,4 +1,16 @@
 pub mod top;
 pub mod list;
+pub mod map;
 pub mod math;
 pub mod io;
+pub mod num;
+pub mod regexp;
+pub mod string;
+pub mod stringbuffer;
+pub mod syncstar;
+pub mod future;
+pub mod stream;
+pub mod isolate;
+pub mod timer;
+pub mod datetime;
+pub mod convert;
@@ -0,0 +1,843 @@
+use std::cmp::Ordering;
+use crate::{node::{Node, NodeType}, object::{Object, fmt_double}, state::State};
+use crate::error::{check_argc, err_arg_type, dartthrow, evalerror};
+
+
+// Statics.
+
+
+pub fn int_parse(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let (source, radix) = parse_args("int.parse", fnode, argnodes, args, state);
+
+    if let Some(n) = parse_int(&source, radix) {
+        return Object::Int(n);
+    }
+    dartthrow(
+        format!("FormatException: Invalid radix-{} number (at character 1)\n{}\n^\n", radix.unwrap_or(10), source),
+        state,
+        fnode
+    )
+}
+
+
+pub fn int_try_parse(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let (source, radix) = parse_args("int.tryParse", fnode, argnodes, args, state);
+
+    if let Some(n) = parse_int(&source, radix) {
+        return Object::Int(n);
+    }
+    Object::Null
+}
+
+
+pub fn double_parse(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let source = string_arg("double.parse", fnode, argnodes, &args, state);
+
+    if let Some(x) = parse_double(&source) {
+        return Object::Double(x);
+    }
+    dartthrow(
+        format!("FormatException: Invalid double\n{}", source),
+        state,
+        fnode
+    )
+}
+
+
+pub fn double_try_parse(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let source = string_arg("double.tryParse", fnode, argnodes, &args, state);
+
+    if let Some(x) = parse_double(&source) {
+        return Object::Double(x);
+    }
+    Object::Null
+}
+
+
+pub fn num_parse(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let source = string_arg("num.parse", fnode, argnodes, &args, state);
+
+    if let Some(n) = parse_int(&source, None) {
+        return Object::Int(n);
+    }
+    if let Some(x) = parse_double(&source) {
+        return Object::Double(x);
+    }
+    dartthrow(format!("FormatException: {}", source), state, fnode)
+}
+
+
+pub fn num_try_parse(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let source = string_arg("num.tryParse", fnode, argnodes, &args, state);
+
+    if let Some(n) = parse_int(&source, None) {
+        return Object::Int(n);
+    }
+    if let Some(x) = parse_double(&source) {
+        return Object::Double(x);
+    }
+    Object::Null
+}
+
+
+pub fn double_constant(name: &str) -> Option<Object> {
+    match name {
+        "nan" => Some(Object::Double(f64::NAN)),
+        "infinity" => Some(Object::Double(f64::INFINITY)),
+        "negativeInfinity" => Some(Object::Double(f64::NEG_INFINITY)),
+        "minPositive" => Some(Object::Double(5e-324)),
+        "maxFinite" => Some(Object::Double(f64::MAX)),
+        _ => None
+    }
+}
+
+
+// Getters.
+
+
+pub fn sign(this: &Object) -> Object {
+    match this {
+        Object::Int(n) => Object::Int(n.signum()),
+        Object::Double(x) => {
+            if x.is_nan() || *x == 0.0 {
+                return Object::Double(*x);
+            }
+            Object::Double(x.signum())
+        }
+        _ => panic!("Expected number.")
+    }
+}
+
+
+pub fn is_nan(this: &Object) -> Object {
+    Object::Bool(as_double(this).is_nan())
+}
+
+
+pub fn is_finite(this: &Object) -> Object {
+    Object::Bool(as_double(this).is_finite())
+}
+
+
+pub fn is_infinite(this: &Object) -> Object {
+    Object::Bool(as_double(this).is_infinite())
+}
+
+
+pub fn is_negative(this: &Object) -> Object {
+    match this {
+        Object::Int(n) => Object::Bool(*n < 0),
+        Object::Double(x) => Object::Bool(x.is_sign_negative() && !x.is_nan()),
+        _ => panic!("Expected number.")
+    }
+}
+
+
+pub fn is_even(this: &Object) -> Object {
+    Object::Bool(as_int(this) & 1 == 0)
+}
+
+
+pub fn is_odd(this: &Object) -> Object {
+    Object::Bool(as_int(this) & 1 == 1)
+}
+
+
+pub fn bit_length(this: &Object) -> Object {
+    let n = as_int(this);
+    if n < 0 {
+        return Object::Int(64 - (!n).leading_zeros() as i64);
+    }
+    Object::Int(64 - n.leading_zeros() as i64)
+}
+
+
+// Methods.
+
+
+pub fn abs(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.abs", 0, args.len(), fnode, state);
+
+    match this {
+        Object::Int(n) => Object::Int(n.wrapping_abs()),
+        Object::Double(x) => Object::Double(x.abs()),
+        _ => panic!("Expected number.")
+    }
+}
+
+
+pub fn to_int(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.toInt", 0, args.len(), fnode, state);
+    to_integer(this.clone(), f64::trunc, fnode, state)
+}
+
+
+pub fn to_double(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.toDouble", 0, args.len(), fnode, state);
+    Object::Double(as_double(this))
+}
+
+
+pub fn to_string(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.toString", 0, args.len(), fnode, state);
+    Object::String(format!("{}", this))
+}
+
+
+pub fn round(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.round", 0, args.len(), fnode, state);
+    to_integer(this.clone(), f64::round, fnode, state)
+}
+
+
+pub fn floor(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.floor", 0, args.len(), fnode, state);
+    to_integer(this.clone(), f64::floor, fnode, state)
+}
+
+
+pub fn ceil(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.ceil", 0, args.len(), fnode, state);
+    to_integer(this.clone(), f64::ceil, fnode, state)
+}
+
+
+pub fn truncate(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.truncate", 0, args.len(), fnode, state);
+    to_integer(this.clone(), f64::trunc, fnode, state)
+}
+
+
+pub fn round_to_double(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.roundToDouble", 0, args.len(), fnode, state);
+    Object::Double(as_double(this).round())
+}
+
+
+pub fn floor_to_double(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.floorToDouble", 0, args.len(), fnode, state);
+    Object::Double(as_double(this).floor())
+}
+
+
+pub fn ceil_to_double(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.ceilToDouble", 0, args.len(), fnode, state);
+    Object::Double(as_double(this).ceil())
+}
+
+
+pub fn truncate_to_double(fnode: &Node, this: &Object, args: Vec<Object>, state: &mut State) -> Object {
+
+    check_argc("num.truncateToDouble", 0, args.len(), fnode, state);
+    Object::Double(as_double(this).trunc())
+}
+
+
+pub fn compare_to(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("num.compareTo", 1, args.len(), fnode, state);
+    let other = num_arg("num.compareTo", &args[0], &argnodes[0], state);
+
+    match compare(this, &other) {
+        Ordering::Less => Object::Int(-1),
+        Ordering::Equal => Object::Int(0),
+        Ordering::Greater => Object::Int(1)
+    }
+}
+
+
+pub fn remainder(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("num.remainder", 1, args.len(), fnode, state);
+    let other = num_arg("num.remainder", &args[0], &argnodes[0], state);
+
+    if let (Object::Int(a), Object::Int(b)) = (this, &other) {
+        if *b == 0 {
+            dartthrow("IntegerDivisionByZeroException", state, fnode);
+        }
+        return Object::Int(a.wrapping_rem(*b));
+    }
+    Object::Double(as_double(this) % as_double(&other))
+}
+
+
+pub fn clamp(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("num.clamp", 2, args.len(), fnode, state);
+    let lower = num_arg("num.clamp", &args[0], &argnodes[0], state);
+    let upper = num_arg("num.clamp", &args[1], &argnodes[1], state);
+
+    if compare(&lower, &upper) == Ordering::Greater {
+        dartthrow(format!("Invalid argument(s): {}", lower), state, &argnodes[0]);
+    }
+    if as_double(&lower).is_nan() {
+        return lower;
+    }
+    if compare(this, &lower) == Ordering::Less {
+        return lower;
+    }
+    if compare(this, &upper) == Ordering::Greater {
+        return upper;
+    }
+    this.clone()
+}
+
+
+pub fn to_string_as_fixed(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("num.toStringAsFixed", 1, args.len(), fnode, state);
+    let digits = int_arg("num.toStringAsFixed", &args[0], &argnodes[0], state);
+    check_range("fractionDigits", digits, 0, 20, &argnodes[0], state);
+
+    let x = as_double(this);
+    if !x.is_finite() || x.abs() >= 1e21 {
+        return Object::String(fmt_double(x));
+    }
+
+    let exact = format!("{:.1074}", x.abs());
+    let (intpart, fracpart) = exact.split_once('.').unwrap();
+    let digits = digits as usize;
+    let mut s: Vec<u8> = intpart.bytes().chain(fracpart.bytes().take(digits)).collect();
+
+    if fracpart.as_bytes()[digits] >= b'5' {
+        increment(&mut s);
+    }
+    if digits > 0 {
+        s.insert(s.len() - digits, b'.');
+    }
+    Object::String(with_sign(x, String::from_utf8(s).unwrap()))
+}
+
+
+pub fn to_string_as_precision(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("num.toStringAsPrecision", 1, args.len(), fnode, state);
+    let precision = int_arg("num.toStringAsPrecision", &args[0], &argnodes[0], state);
+    check_range("precision", precision, 1, 21, &argnodes[0], state);
+
+    let x = as_double(this);
+    if !x.is_finite() {
+        return Object::String(fmt_double(x));
+    }
+
+    let p = precision as usize;
+    let (digits, exp) = significant_digits(x.abs(), p);
+
+    if exp < -6 || exp >= p as i32 {
+        return Object::String(with_sign(x, exponential(&digits, exp)));
+    }
+
+    let s = if exp >= 0 {
+        let point = exp as usize + 1;
+        if point < digits.len() {
+            format!("{}.{}", &digits[..point], &digits[point..])
+        }
+        else {
+            digits
+        }
+    }
+    else {
+        format!("0.{}{}", "0".repeat((-exp - 1) as usize), digits)
+    };
+    Object::String(with_sign(x, s))
+}
+
+
+pub fn to_string_as_exponential(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if args.len() > 1 {
+        check_argc("num.toStringAsExponential", 1, args.len(), fnode, state);
+    }
+
+    let x = as_double(this);
+    let mut fraction_digits: Option<usize> = None;
+
+    if args.len() == 1 && !matches!(args[0], Object::Null) {
+        let d = int_arg("num.toStringAsExponential", &args[0], &argnodes[0], state);
+        check_range("fractionDigits", d, 0, 20, &argnodes[0], state);
+        fraction_digits = Some(d as usize);
+    }
+
+    if !x.is_finite() {
+        return Object::String(fmt_double(x));
+    }
+
+    let (digits, exp) = match fraction_digits {
+        Some(d) => significant_digits(x.abs(), d + 1),
+        None => {
+            // Shortest representation that round trips.
+            let s = format!("{:e}", x.abs());
+            let (mantissa, exponent) = s.split_once('e').unwrap();
+            (mantissa.replace('.', ""), exponent.parse::<i32>().unwrap())
+        }
+    };
+    Object::String(with_sign(x, exponential(&digits, exp)))
+}
+
+
+pub fn to_radix_string(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("int.toRadixString", 1, args.len(), fnode, state);
+    let radix = int_arg("int.toRadixString", &args[0], &argnodes[0], state);
+    check_range("radix", radix, 2, 36, &argnodes[0], state);
+
+    let n = as_int(this);
+    let mut rest = (n as i128).unsigned_abs();
+    let mut s: Vec<char> = Vec::new();
+
+    loop {
+        s.push(std::char::from_digit((rest % radix as u128) as u32, radix as u32).unwrap());
+        rest /= radix as u128;
+        if rest == 0 {
+            break;
+        }
+    }
+    if n < 0 {
+        s.push('-');
+    }
+    Object::String(s.iter().rev().collect())
+}
+
+
+pub fn gcd(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("int.gcd", 1, args.len(), fnode, state);
+    let other = int_arg("int.gcd", &args[0], &argnodes[0], state);
+
+    let mut a = as_int(this).unsigned_abs();
+    let mut b = other.unsigned_abs();
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    Object::Int(a as i64)
+}
+
+
+pub fn mod_pow(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("int.modPow", 2, args.len(), fnode, state);
+    let exponent = int_arg("int.modPow", &args[0], &argnodes[0], state);
+    let modulus = int_arg("int.modPow", &args[1], &argnodes[1], state);
+
+    if exponent < 0 {
+        dartthrow(
+            format!("RangeError (exponent): Invalid value: Not greater than or equal to 0: {}", exponent),
+            state,
+            &argnodes[0]
+        );
+    }
+    if modulus < 1 {
+        dartthrow(
+            format!("RangeError (modulus): Invalid value: Not greater than or equal to 1: {}", modulus),
+            state,
+            &argnodes[1]
+        );
+    }
+
+    let m = modulus as u128;
+    let mut base = (as_int(this) as i128).rem_euclid(modulus as i128) as u128;
+    let mut e = exponent as u64;
+    let mut result: u128 = 1 % m;
+
+    while e > 0 {
+        if e & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        e >>= 1;
+    }
+    Object::Int(result as i64)
+}
+
+
+// Helpers.
+
+
+fn as_double(obj: &Object) -> f64 {
+    match obj {
+        Object::Int(n) => *n as f64,
+        Object::Double(x) => *x,
+        _ => panic!("Expected number.")
+    }
+}
+
+
+fn as_int(obj: &Object) -> i64 {
+    match obj {
+        Object::Int(n) => *n,
+        _ => panic!("Expected int.")
+    }
+}
+
+
+fn num_arg(fname: &str, arg: &Object, argnode: &Node, state: &State) -> Object {
+    match arg {
+        Object::Int(_) |
+        Object::Double(_) => arg.clone(),
+        _ => err_arg_type(fname, "num", arg, argnode, state)
+    }
+}
+
+
+fn int_arg(fname: &str, arg: &Object, argnode: &Node, state: &State) -> i64 {
+    match arg {
+        Object::Int(n) => *n,
+        _ => err_arg_type(fname, "int", arg, argnode, state)
+    }
+}
+
+
+fn string_arg(
+    fname: &str,
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: &Vec<Object>,
+    state: &State) -> String {
+
+    check_argc(fname, 1, args.len(), fnode, state);
+
+    if let NodeType::NamedArg(name, _, _) = &argnodes[0].nodetype {
+        evalerror(format!("No named parameter with the name '{}'.", name), state, &argnodes[0]);
+    }
+    match &args[0] {
+        Object::String(s) => s.clone(),
+        x => err_arg_type(fname, "String", x, &argnodes[0], state)
+    }
+}
+
+
+/// Arguments of int.parse and int.tryParse: the source
+/// string and an optional named radix.
+fn parse_args(
+    fname: &str,
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &State) -> (String, Option<i64>) {
+
+    let mut source: Option<String> = None;
+    let mut radix: Option<i64> = None;
+    let mut positional = 0;
+
+    for (argnode, arg) in argnodes.iter().zip(args.iter()) {
+
+        if let NodeType::NamedArg(name, _, _) = &argnode.nodetype {
+            if name != "radix" {
+                evalerror(format!("No named parameter with the name '{}'.", name), state, argnode);
+            }
+            if let Object::Null = arg {
+                continue;
+            }
+            let r = int_arg(fname, arg, argnode, state);
+            check_range("radix", r, 2, 36, argnode, state);
+            radix = Some(r);
+            continue;
+        }
+
+        positional += 1;
+        match arg {
+            Object::String(s) => source = Some(s.clone()),
+            x => err_arg_type(fname, "String", x, argnode, state)
+        }
+    }
+    check_argc(fname, 1, positional, fnode, state);
+    (source.unwrap(), radix)
+}
+
+
+fn check_range(name: &str, val: i64, min: i64, max: i64, node: &Node, state: &State) {
+    if val < min || val > max {
+        dartthrow(
+            format!("RangeError ({}): Invalid value: Not in inclusive range {}..{}: {}", name, min, max, val),
+            state,
+            node
+        );
+    }
+}
+
+
+fn parse_int(source: &str, radix: Option<i64>) -> Option<i64> {
+
+    let s = source.trim();
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s))
+    };
+
+    if radix.is_none() {
+        if let Some(hex) = s.strip_prefix("0x").or(s.strip_prefix("0X")) {
+            // Like literals, hex may use all 64 bits.
+            if hex.is_empty() || hex.starts_with('+') {
+                return None;
+            }
+            let n = u64::from_str_radix(hex, 16).ok()? as i64;
+            return Some(if negative { n.wrapping_neg() } else { n });
+        }
+    }
+
+    if s.is_empty() || s.starts_with('+') {
+        return None;
+    }
+    let n = i128::from_str_radix(s, radix.unwrap_or(10) as u32).ok()?;
+    let n = if negative { -n } else { n };
+
+    if n < i64::MIN as i128 || n > i64::MAX as i128 {
+        return None;
+    }
+    Some(n as i64)
+}
+
+
+fn parse_double(source: &str) -> Option<f64> {
+
+    let s = source.trim();
+    let unsigned = s.strip_prefix('-').or(s.strip_prefix('+')).unwrap_or(s);
+
+    match unsigned {
+        "NaN" => return Some(f64::NAN),
+        "Infinity" => return Some(if s.starts_with('-') { f64::NEG_INFINITY } else { f64::INFINITY }),
+        _ => {}
+    }
+
+    // Rust accepts spellings like "inf" that Dart does not,
+    // so check the shape of the number first.
+    let chars: Vec<char> = unsigned.chars().collect();
+    let mut i = 0;
+    let mut mantissa_digits = 0;
+
+    while i < chars.len() && chars[i].is_ascii_digit() {
+        i += 1;
+        mantissa_digits += 1;
+    }
+    if i < chars.len() && chars[i] == '.' {
+        i += 1;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+            mantissa_digits += 1;
+        }
+    }
+    if mantissa_digits == 0 {
+        return None;
+    }
+    if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+        i += 1;
+        if i < chars.len() && (chars[i] == '+' || chars[i] == '-') {
+            i += 1;
+        }
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == start {
+            return None;
+        }
+    }
+    if i != chars.len() {
+        return None;
+    }
+    s.parse::<f64>().ok()
+}
+
+
+/// Compare numbers the way num.compareTo does, where
+/// -0.0 is less than 0.0 and NaN is greater than everything.
+pub fn compare(a: &Object, b: &Object) -> Ordering {
+
+    if let (Object::Int(x), Object::Int(y)) = (a, b) {
+        return x.cmp(y);
+    }
+
+    let x = as_double(a);
+    let y = as_double(b);
+
+    if x < y {
+        return Ordering::Less;
+    }
+    if x > y {
+        return Ordering::Greater;
+    }
+    if x == y {
+        if x == 0.0 {
+            return y.is_sign_negative().cmp(&x.is_sign_negative());
+        }
+        return Ordering::Equal;
+    }
+    if x.is_nan() {
+        return if y.is_nan() { Ordering::Equal } else { Ordering::Greater };
+    }
+    Ordering::Less
+}
+
+
+fn to_integer(this: Object, f: fn(f64) -> f64, fnode: &Node, state: &State) -> Object {
+    match this {
+        Object::Int(_) => this,
+        Object::Double(x) => {
+            if !x.is_finite() {
+                dartthrow(format!("Unsupported operation: {}", fmt_double(x)), state, fnode);
+            }
+            Object::Int(f(x) as i64)
+        }
+        _ => panic!("Expected number.")
+    }
+}
+
+
+/// The first n significant decimal digits of a finite,
+/// non-negative double, rounded half up from its exact value,
+/// and the decimal exponent of the first digit.
+fn significant_digits(x: f64, n: usize) -> (String, i32) {
+
+    if x == 0.0 {
+        return ("0".repeat(n), 0);
+    }
+
+    let exact = format!("{:.1074}", x);
+    let (intpart, fracpart) = exact.split_once('.').unwrap();
+
+    let (all, exp) = if intpart != "0" {
+        (format!("{}{}", intpart, fracpart), intpart.len() as i32 - 1)
+    }
+    else {
+        let zeros = fracpart.len() - fracpart.trim_start_matches('0').len();
+        (String::from(&fracpart[zeros..]), -(zeros as i32) - 1)
+    };
+
+    let mut digits: Vec<u8> = all.bytes().take(n).collect();
+    while digits.len() < n {
+        digits.push(b'0');
+    }
+    if all.len() > n && all.as_bytes()[n] >= b'5' && increment(&mut digits) {
+        digits.pop();
+        return (String::from_utf8(digits).unwrap(), exp + 1);
+    }
+    (String::from_utf8(digits).unwrap(), exp)
+}
+
+
+/// Add one to a string of decimal digits. Returns true
+/// if this carried into a new leading digit.
+fn increment(digits: &mut Vec<u8>) -> bool {
+
+    for d in digits.iter_mut().rev() {
+        if *d == b'9' {
+            *d = b'0';
+        }
+        else {
+            *d += 1;
+            return false;
+        }
+    }
+    digits.insert(0, b'1');
+    true
+}
+
+
+fn exponential(digits: &str, exp: i32) -> String {
+
+    let mut s = String::from(&digits[..1]);
+    if digits.len() > 1 {
+        s.push('.');
+        s.push_str(&digits[1..]);
+    }
+    if exp < 0 {
+        return format!("{}e{}", s, exp);
+    }
+    format!("{}e+{}", s, exp)
+}
+
+
+fn with_sign(x: f64, s: String) -> String {
+    if x.is_sign_negative() {
+        return format!("-{}", s);
+    }
+    s
+}
@@ -0,0 +1,331 @@
+use crate::{heapobjs::InternalRegExp, node::Node, object::Object, state::State};
+use crate::error::{dartthrow, err_arg_type};
+use crate::evalhelp::{create_list, get_field};
+use crate::objsys::RefKey;
+use crate::regexp::{Captures, Regex};
+
+
+// REGEXP
+
+pub fn construct(
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    // First arg is internal and hidden from user.
+    if let Object::Reference(rk) = &args[0] {
+
+        let source = match &args[1] {
+            Object::String(s) => s.clone(),
+            _ => err_arg_type("RegExp", "String", &args[1], &argnodes[1], state)
+        };
+
+        let mut flags = Vec::new();
+        for i in 2..6 {
+            match &args[i] {
+                Object::Bool(b) => flags.push(*b),
+                _ => err_arg_type("RegExp", "bool", &args[i], &argnodes[i], state)
+            }
+        }
+
+        let regex = match Regex::new(&source, flags[0], flags[1], flags[2], flags[3]) {
+            Ok(regex) => regex,
+            Err(msg) => dartthrow(
+                format!("FormatException: {}\n{}", msg, source),
+                state,
+                &argnodes[1]
+            )
+        };
+        let internal_rk = state.objsys.register_regexp(InternalRegExp::new(regex));
+
+        let dregexp = state.objsys.get_instance_mut(rk);
+        dregexp.set_field(String::from("__regexp"), internal_rk);
+        dregexp.set_field(String::from("pattern"), Object::String(source));
+        dregexp.set_field(String::from("isMultiLine"), Object::Bool(flags[0]));
+        dregexp.set_field(String::from("isCaseSensitive"), Object::Bool(flags[1]));
+        dregexp.set_field(String::from("isUnicode"), Object::Bool(flags[2]));
+        dregexp.set_field(String::from("isDotAll"), Object::Bool(flags[3]));
+        return Object::Reference(rk.clone());
+    }
+    panic!("Unexpected internal arg: {}", &args[0])
+}
+
+
+pub fn has_match(
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+        let input = utf16_arg("RegExp.hasMatch", &args[1], &argnodes[1], state);
+        let regex = &state.objsys.get_regexp(rk).regex;
+        return Object::Bool(regex.exec(&input, 0).is_some());
+    }
+    panic!("Unexpected type of internal argument for RegExp.hasMatch(): {}", &args[0])
+}
+
+
+pub fn first_match(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let input = utf16_arg("RegExp.firstMatch", &args[1], &argnodes[1], state);
+    let rk = internal_regexp(&args[0], state, fnode);
+
+    let regex = &state.objsys.get_regexp(&rk).regex;
+    match regex.exec(&input, 0) {
+        Some(caps) => {
+            let names = regex.names.clone();
+            create_match(&args[0], &input, caps, names, state)
+        }
+        None => Object::Null
+    }
+}
+
+
+/// Check the start given to allMatches, before any match is looked for.
+pub fn check_start_arg(
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let input = utf16_arg("RegExp.allMatches", &args[0], &argnodes[0], state);
+    let start = match &args[1] {
+        Object::Int(n) => *n,
+        _ => err_arg_type("RegExp.allMatches", "int", &args[1], &argnodes[1], state)
+    };
+    check_start(start, input.len(), &argnodes[1], state);
+    Object::Null
+}
+
+
+/// The next match for allMatches, from pos on, or from the
+/// character after pos when the match before was empty.
+pub fn next_match(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let input = utf16_arg("RegExp.allMatches", &args[1], &argnodes[1], state);
+    let pos = match &args[2] {
+        Object::Int(n) => *n as usize,
+        _ => err_arg_type("RegExp.allMatches", "int", &args[2], &argnodes[2], state)
+    };
+
+    let rk = internal_regexp(&args[0], state, fnode);
+    let regex = &state.objsys.get_regexp(&rk).regex;
+    let pos = match args[3] {
+        Object::Bool(true) => regex.advance(&input, pos),
+        _ => pos
+    };
+    if pos > input.len() {
+        return Object::Null;
+    }
+    let names = regex.names.clone();
+    match regex.exec(&input, pos) {
+        Some(caps) => create_match(&args[0], &input, caps, names, state),
+        None => Object::Null
+    }
+}
+
+
+pub fn string_match(
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+        let input = utf16_arg("RegExp.stringMatch", &args[1], &argnodes[1], state);
+        let regex = &state.objsys.get_regexp(rk).regex;
+        return match regex.exec(&input, 0) {
+            Some(caps) => substring(&input, caps[0]),
+            None => Object::Null
+        };
+    }
+    panic!("Unexpected type of internal argument for RegExp.stringMatch(): {}", &args[0])
+}
+
+
+pub fn to_string(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let this = args[0].clone();
+    let pattern = get_field(this.clone(), "pattern", state, fnode);
+
+    let mut flags = String::new();
+    for (field, flag, when) in [
+        ("isMultiLine", 'm', true),
+        ("isCaseSensitive", 'i', false),
+        ("isUnicode", 'u', true),
+        ("isDotAll", 's', true)] {
+
+        if let Object::Bool(b) = get_field(this.clone(), field, state, fnode) {
+            if b == when {
+                flags.push(flag);
+            }
+        }
+    }
+    Object::String(format!("RegExp/{}/{}", pattern, flags))
+}
+
+
+// REGEXPMATCH
+
+pub fn match_group(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let groups = match_list(&args[0], "__groups", state, fnode);
+
+    if let Object::Int(n) = &args[1] {
+        if *n < 0 || *n as usize >= groups.len() {
+            dartthrow(
+                format!("RangeError: Invalid value: Not in inclusive range 0..{}: {}", groups.len() - 1, n),
+                state,
+                &argnodes[1]
+            );
+        }
+        return groups[*n as usize].clone();
+    }
+    err_arg_type("RegExpMatch.group", "int", &args[1], &argnodes[1], state)
+}
+
+
+pub fn match_named_group(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let groups = match_list(&args[0], "__groups", state, fnode);
+    let names = match_list(&args[0], "__names", state, fnode);
+
+    if let Object::String(name) = &args[1] {
+        for (i, n) in names.iter().enumerate() {
+            if let Object::String(n) = n {
+                if n == name {
+                    return groups[i].clone();
+                }
+            }
+        }
+        dartthrow(
+            format!("Invalid argument (name): Not a capture group name: \"{}\"", name),
+            state,
+            &argnodes[1]
+        );
+    }
+    err_arg_type("RegExpMatch.namedGroup", "String", &args[1], &argnodes[1], state)
+}
+
+
+pub fn match_group_names(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let names = match_list(&args[0], "__names", state, fnode)
+        .into_iter()
+        .filter(|n| matches!(n, Object::String(_)))
+        .collect();
+    create_list(names, state)
+}
+
+
+// HELPERS
+
+/// The compiled regex of a Dart RegExp instance, if the object is one.
+pub fn as_regexp(obj: &Object, state: &mut State, node: &Node) -> Option<RefKey> {
+
+    if let Object::Reference(rk) = obj {
+        if state.objsys.get_instance(rk).classname == "RegExp" {
+            return Some(internal_regexp(obj, state, node));
+        }
+    }
+    None
+}
+
+
+fn internal_regexp(obj: &Object, state: &mut State, node: &Node) -> RefKey {
+    if let Object::Reference(rk) = get_field(obj.clone(), "__regexp", state, node) {
+        return rk;
+    }
+    panic!("RegExp has no internal regexp: {}", obj)
+}
+
+
+fn create_match(
+    regexp: &Object,
+    input: &[u16],
+    caps: Captures,
+    names: Vec<Option<String>>,
+    state: &mut State) -> Object {
+
+    let (start, end) = caps[0].unwrap();
+
+    let groups = caps.iter().map(|span| {
+        match span {
+            Some(_) => substring(input, *span),
+            None => Object::Null
+        }
+    }).collect();
+    let groups = create_list(groups, state);
+
+    let names = names.into_iter().map(|name| {
+        match name {
+            Some(n) => Object::String(n),
+            None => Object::Null
+        }
+    }).collect();
+    let names = create_list(names, state);
+
+    let class = state.objsys.get_class("RegExpMatch");
+    let mut inst = class.instantiate();
+    inst.set_field(String::from("input"), Object::String(String::from_utf16_lossy(input)));
+    inst.set_field(String::from("pattern"), regexp.clone());
+    inst.set_field(String::from("start"), Object::Int(start as i64));
+    inst.set_field(String::from("end"), Object::Int(end as i64));
+    inst.set_field(String::from("__groups"), groups);
+    inst.set_field(String::from("__names"), names);
+    state.objsys.register_instance(*inst)
+}
+
+
+fn match_list(obj: &Object, field: &str, state: &mut State, node: &Node) -> Vec<Object> {
+    let list = get_field(obj.clone(), field, state, node);
+    if let Object::Reference(ilist_rk) = get_field(list, "__list", state, node) {
+        return state.objsys.get_list(&ilist_rk).els.clone();
+    }
+    panic!("RegExpMatch has no internal list: {}", field)
+}
+
+
+pub fn utf16_arg(fname: &str, arg: &Object, argnode: &Node, state: &State) -> Vec<u16> {
+    if let Object::String(s) = arg {
+        return s.encode_utf16().collect();
+    }
+    err_arg_type(fname, "String", arg, argnode, state)
+}
+
+
+pub fn substring(input: &[u16], span: Option<(usize, usize)>) -> Object {
+    let (s, e) = span.unwrap();
+    Object::String(String::from_utf16_lossy(&input[s..e]))
+}
+
+
+pub fn check_start(start: i64, len: usize, argnode: &Node, state: &State) {
+    if start < 0 || start as usize > len {
+        dartthrow(
+            format!("RangeError: Invalid value: Not in inclusive range 0..{}: {}", len, start),
+            state,
+            argnode
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{node::Node, object::Object, state::State};
+use crate::error::evalerror;
+use crate::evalhelp::{call_function_with, MaybeRef};
+use crate::eventloop;
+
+
+/// Stream.fromIterable, Stream.periodic, Stream.value, Stream.error and
+/// Stream.empty. They are written as top level functions of the stream
+/// library.
+pub fn call_static(
+    fnode: &Node,
+    name: &str,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    let fname = match name {
+        "fromIterable" => "_streamFromIterable",
+        "periodic" => "_streamPeriodic",
+        "value" => "_streamValue",
+        "error" => "_streamError",
+        "empty" => "_streamEmpty",
+        _ => evalerror(
+            format!("Method not found: 'Stream.{}'.", name),
+            state,
+            fnode
+        )
+    };
+    let func = eventloop::library_function("auto:stream.dart", fname, state);
+    call_function_with(MaybeRef::None, &func, args, fnode, state)
+}
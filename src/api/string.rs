@@ -0,0 +1,157 @@
+use crate::{node::Node, object::Object, state::State};
+use crate::error::{check_argc, err_arg_type};
+use crate::evalhelp::create_list;
+use crate::api::regexp::{as_regexp, check_start, utf16_arg};
+
+
+// METHODS
+
+pub fn contains(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if args.is_empty() || args.len() > 2 {
+        check_argc("String.contains", 1, args.len(), fnode, state);
+    }
+
+    let input = utf16_arg("String.contains", this, fnode, state);
+    let start = match args.get(1) {
+        Some(Object::Int(n)) => *n,
+        Some(obj) => err_arg_type("String.contains", "int", obj, &argnodes[1], state),
+        None => 0
+    };
+    check_start(start, input.len(), &argnodes[argnodes.len() - 1], state);
+
+    let spans = pattern_matches("String.contains", &args[0], &argnodes[0], &input, start as usize, state);
+    Object::Bool(!spans.is_empty())
+}
+
+
+pub fn split(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("String.split", 1, args.len(), fnode, state);
+
+    let input = utf16_arg("String.split", this, fnode, state);
+
+    if let Object::String(p) = &args[0] {
+        if p.is_empty() {
+            // Splits into code units.
+            let units = input.iter()
+                .map(|u| Object::String(String::from_utf16_lossy(&[*u])))
+                .collect();
+            return create_list(units, state);
+        }
+    }
+
+    let spans = pattern_matches("String.split", &args[0], &argnodes[0], &input, 0, state);
+    let len = input.len();
+
+    if len == 0 && !spans.is_empty() {
+        // A matched empty string gives an empty list.
+        return create_list(vec![], state);
+    }
+
+    let mut parts = Vec::new();
+    let mut matches = spans.into_iter();
+    let mut start = 0;
+    let mut previous = 0;
+
+    loop {
+        let next = if start == len { None } else { matches.next() };
+
+        let (s, e) = match next {
+            Some(span) if span.0 != len => span,
+            _ => {
+                parts.push(utf16_string(&input[previous..len]));
+                break;
+            }
+        };
+        if start == e && e == previous {
+            // Skip an empty match at the start of a part.
+            start += 1;
+            continue;
+        }
+        parts.push(utf16_string(&input[previous..s]));
+        start = e;
+        previous = e;
+    }
+    create_list(parts, state)
+}
+
+
+pub fn replace_all(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    this: &Object,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("String.replaceAll", 2, args.len(), fnode, state);
+
+    let input = utf16_arg("String.replaceAll", this, fnode, state);
+    let replace = utf16_arg("String.replaceAll", &args[1], &argnodes[1], state);
+    let spans = pattern_matches("String.replaceAll", &args[0], &argnodes[0], &input, 0, state);
+
+    let mut result: Vec<u16> = Vec::new();
+    let mut start = 0;
+
+    for (s, e) in spans {
+        result.extend_from_slice(&input[start..s]);
+        result.extend_from_slice(&replace);
+        start = e;
+    }
+    result.extend_from_slice(&input[start..]);
+    utf16_string(&result)
+}
+
+
+// HELPERS
+
+/// The spans of all matches of a String or RegExp pattern from start.
+fn pattern_matches(
+    fname: &str,
+    pattern: &Object,
+    argnode: &Node,
+    input: &[u16],
+    start: usize,
+    state: &mut State) -> Vec<(usize, usize)> {
+
+    if let Object::String(p) = pattern {
+        let p: Vec<u16> = p.encode_utf16().collect();
+        let mut spans = Vec::new();
+        let mut pos = start;
+
+        while pos + p.len() <= input.len() {
+            if input[pos..pos + p.len()] == p[..] {
+                spans.push((pos, pos + p.len()));
+                pos += p.len().max(1);
+            }
+            else {
+                pos += 1;
+            }
+        }
+        return spans;
+    }
+
+    if let Some(rk) = as_regexp(pattern, state, argnode) {
+        return state.objsys.get_regexp(&rk).regex
+            .find_all(input, start)
+            .into_iter()
+            .map(|caps| caps[0].unwrap())
+            .collect();
+    }
+    err_arg_type(fname, "Pattern", pattern, argnode, state)
+}
+
+
+fn utf16_string(units: &[u16]) -> Object {
+    Object::String(String::from_utf16_lossy(units))
+}
@@ -0,0 +1,146 @@
+use crate::{heapobjs::InternalStringBuffer, node::Node, object::Object, state::State};
+use crate::error::{dartthrow, err_arg_type};
+use crate::evalhelp::to_dart_string;
+use crate::api::list::list_elements;
+
+
+// GETTERS
+
+pub fn get_length(
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+        let ibuf = state.objsys.get_buffer(rk);
+        return Object::Int(ibuf.length as i64);
+    }
+    panic!("Unexpected type of internal argument for StringBuffer.length: {}", &args[0])
+}
+
+
+// METHODS
+
+pub fn construct(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    // First arg is internal and hidden from user.
+    if let Object::Reference(rk) = &args[0] {
+        let content = to_dart_string(args[1].clone(), state, fnode);
+        let mut ibuf = InternalStringBuffer::new();
+        ibuf.write(&content);
+        let internal_rk = state.objsys.register_buffer(ibuf);
+
+        let dbuf = state.objsys.get_instance_mut(rk);
+        dbuf.set_field(String::from("__buffer"), internal_rk);
+        return Object::Reference(rk.clone());
+    }
+    panic!("Unexpected internal arg: {}", &args[0])
+}
+
+
+pub fn write(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+        let s = to_dart_string(args[1].clone(), state, fnode);
+        state.objsys.get_buffer_mut(rk).write(&s);
+        return Object::Null;
+    }
+    panic!("Unexpected type of internal argument for StringBuffer.write(): {}", &args[0])
+}
+
+
+pub fn write_all(
+    fnode: &Node,
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let els = list_elements("StringBuffer.writeAll", &args[1], &argnodes[1], state);
+        let separator = to_dart_string(args[2].clone(), state, fnode);
+
+        for (i, el) in els.into_iter().enumerate() {
+            let s = to_dart_string(el, state, fnode);
+            let ibuf = state.objsys.get_buffer_mut(rk);
+            if i > 0 {
+                ibuf.write(&separator);
+            }
+            ibuf.write(&s);
+        }
+        return Object::Null;
+    }
+    panic!("Unexpected type of internal argument for StringBuffer.writeAll(): {}", &args[0])
+}
+
+
+pub fn writeln(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+        let s = to_dart_string(args[1].clone(), state, fnode);
+        let ibuf = state.objsys.get_buffer_mut(rk);
+        ibuf.write(&s);
+        ibuf.write_char('\n');
+        return Object::Null;
+    }
+    panic!("Unexpected type of internal argument for StringBuffer.writeln(): {}", &args[0])
+}
+
+
+pub fn write_char_code(
+    argnodes: &Vec<Node>,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        if let Object::Int(n) = &args[1] {
+            if *n < 0 || *n > 0x10FFFF {
+                dartthrow(
+                    format!("RangeError: Invalid value: Not in inclusive range 0..1114111: {}", n),
+                    state,
+                    &argnodes[1]
+                );
+            }
+            // Lone surrogates can't be held in a Rust string.
+            let c = char::from_u32(*n as u32).unwrap_or('\u{FFFD}');
+            state.objsys.get_buffer_mut(rk).write_char(c);
+            return Object::Null;
+        }
+        err_arg_type("StringBuffer.writeCharCode", "int", &args[1], &argnodes[1], state)
+    }
+    panic!("Unexpected type of internal argument for StringBuffer.writeCharCode(): {}", &args[0])
+}
+
+
+pub fn clear(
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+        state.objsys.get_buffer_mut(rk).clear();
+        return Object::Null;
+    }
+    panic!("Unexpected type of internal argument for StringBuffer.clear(): {}", &args[0])
+}
+
+
+pub fn to_string(
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+        let ibuf = state.objsys.get_buffer(rk);
+        return Object::String(ibuf.buf.clone());
+    }
+    panic!("Unexpected type of internal argument for StringBuffer.toString(): {}", &args[0])
+}
+
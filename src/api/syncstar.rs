@@ -0,0 +1,48 @@
+use crate::{node::Node, object::Object, state::State};
+use crate::evalhelp::get_field;
+use crate::generator;
+
+
+// SYNC* ITERABLE
+
+pub fn iterator(
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+        // Each iterator runs the body from the start.
+        let gen = state.objsys.get_generator(rk).restart();
+        let gen_rk = state.objsys.register_generator(gen);
+
+        let class = state.objsys.get_class("_SyncStarIterator");
+        let mut inst = class.instantiate();
+        inst.set_field(String::from("__generator"), gen_rk);
+        return state.objsys.register_instance(*inst);
+    }
+    panic!("Unexpected type of internal argument for sync* iterator: {}", &args[0])
+}
+
+
+// SYNC* ITERATOR
+
+pub fn move_next(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = &args[0] {
+
+        let gen_rk = match get_field(args[0].clone(), "__generator", state, fnode) {
+            Object::Reference(gen_rk) => gen_rk,
+            x => panic!("Internal __generator field was not a reference: {}", x)
+        };
+
+        let (current, more) = match generator::resume(&gen_rk, fnode, state) {
+            Some(val) => (val, true),
+            None => (Object::Null, false)
+        };
+        state.objsys.get_instance_mut(rk).set_field(String::from("current"), current);
+        return Object::Bool(more);
+    }
+    panic!("Unexpected type of internal argument for sync* moveNext(): {}", &args[0])
+}
@@ -0,0 +1,30 @@
+use crate::{node::Node, object::Object, state::State};
+use crate::error::evalerror;
+use crate::evalhelp::{call_function_with, MaybeRef};
+use crate::eventloop;
+
+
+/// Timer.periodic and Timer.run. They are written as
+/// top level functions of the async library.
+pub fn call_static(
+    fnode: &Node,
+    name: &str,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    if !state.looktables.contains_key("dart:async") {
+        // As Dart, without an import of dart:async.
+        evalerror("Undefined name 'Timer'.", state, fnode);
+    }
+    let fname = match name {
+        "periodic" => "_timerPeriodic",
+        "run" => "_timerRun",
+        _ => evalerror(
+            format!("Method not found: 'Timer.{}'.", name),
+            state,
+            fnode
+        )
+    };
+    let func = eventloop::library_function("dart:async", fname, state);
+    call_function_with(MaybeRef::None, &func, args, fnode, state)
+}
@@ -1,4 +1,6 @@
-use crate::{error::{check_argc, evalerror}, evalhelp::{call_function, MaybeRef}, node::{Node, NodeType}, object::Object, state::State};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use crate::{error::{check_argc, dartthrow, evalerror}, evalhelp::{call_function, equals, throw_no_such_method, MaybeRef}, node::{Node, NodeType}, eventloop, object::Object, state::State, types};
 
 
 
@@ -71,7 +73,7 @@ pub fn print(
         let m = c.get_method("toString", state, &fakenode);
 
         match &m {
-            Object::Function(_, _, _, _) => {
+            Object::Function(_, _, _, _, _, _) => {
                 let tostring_args = Node::new(
                     NodeType::ArgList(
                         fnode.children[0].find_node_position().0,
@@ -93,3 +95,122 @@ pub fn print(
     return Object::Null;
 }
 
+
+
+/// Throws an exception from library code, like StateError or RangeError.
+pub fn throw(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__CORE_THROW", 1, args.len(), fnode, state);
+    dartthrow(format!("{}", &args[0]), state, fnode)
+}
+
+
+/// Microseconds since the start of the program, on the clock
+/// kept by the event loop, as for Stopwatch.
+pub fn clock(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__CORE_CLOCK", 0, args.len(), fnode, state);
+    Object::Int(eventloop::now(state) as i64)
+}
+
+
+/// Whether the arguments are the same object. Values
+/// that are not references are identical when equal.
+pub fn identical(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("identical", 2, args.len(), fnode, state);
+    Object::Bool(match (&args[0], &args[1]) {
+        (Object::Reference(k1), Object::Reference(k2)) => k1 == k2,
+        (Object::Reference(_), _) |
+        (_, Object::Reference(_)) => false,
+        (a, b) => equals(a, b) == Some(true)
+    })
+}
+
+
+/// A hash code that is the same for identical objects.
+pub fn identity_hash_code(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("identityHashCode", 1, args.len(), fnode, state);
+    Object::Int(hash_code(&args[0]))
+}
+
+
+/// A hash code by identity for references, and by value for others.
+pub fn hash_code(obj: &Object) -> i64 {
+
+    match obj {
+        Object::Int(n) => return *n,
+        // Equal numbers have the same hash code, as 1 and 1.0 do.
+        Object::Double(x) if x.fract() == 0.0 && x.abs() < 9e18 => return *x as i64,
+        _ => ()
+    }
+    let mut hasher = DefaultHasher::new();
+    match obj {
+        Object::Reference(rk) => rk.0.hash(&mut hasher),
+        x => x.to_string().hash(&mut hasher)
+    }
+    // Dart hash codes fit in 30 bits.
+    (hasher.finish() & 0x3fffffff) as i64
+}
+
+
+/// The Type object for the runtime type of an object.
+pub fn runtime_type(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__CORE_RUNTIME_TYPE", 1, args.len(), fnode, state);
+    let typ = types::type_name(&args[0], state);
+    types::type_object(&typ, state)
+}
+
+
+/// The default noSuchMethod, which throws a NoSuchMethodError
+/// for the Invocation.
+pub fn no_such_method(
+    fnode: &Node,
+    args: Vec<Object>,
+    state: &mut State) -> Object {
+
+    check_argc("__CORE_NO_SUCH_METHOD", 2, args.len(), fnode, state);
+
+    let invocation = match &args[1] {
+        Object::Reference(rk) => state.objsys.get_instance(rk),
+        x => panic!("Expected Invocation. Got: {}", x)
+    };
+    let name = match invocation.get_field("memberName") {
+        Object::Reference(rk) => state.objsys.get_instance(&rk).get_field("__name").to_string(),
+        x => panic!("Expected Symbol. Got: {}", x)
+    };
+    let positional = match invocation.get_field("positionalArguments") {
+        Object::Reference(rk) => match state.objsys.get_instance(&rk).get_field("__list") {
+            Object::Reference(lrk) => state.objsys.get_list(&lrk).els.clone(),
+            x => panic!("Expected list. Got: {}", x)
+        },
+        x => panic!("Expected List. Got: {}", x)
+    };
+    let kind = if matches!(invocation.get_field("isGetter"), Object::Bool(true)) {
+        "getter"
+    }
+    else if matches!(invocation.get_field("isSetter"), Object::Bool(true)) {
+        "setter"
+    }
+    else {
+        "method"
+    };
+    throw_no_such_method(&args[0], kind, name.trim_end_matches('='), &positional, state, fnode)
+}
@@ -1,28 +1,68 @@
 use crate::state::State;
-use crate::object::Object;
-use crate::node::Node;
-use crate::evalhelp::argnodes_to_argobjs;
+use crate::object::{Object, ParamObj};
+use crate::node::{Node, NodeType};
+use crate::evalhelp::{argnodes_to_argobjs, reject_named_args};
+use crate::error::evalerror;
 use crate::api;
 
 
 pub fn has_function(name: &str) -> bool {
     match name {
         "assert" |
+        "identical" |
+        "identityHashCode" |
         "print" |
+        "__ASYNC_FUTURE" |
+        "__ASYNC_MICROTASK" |
+        "__ASYNC_RESUME" |
+        "__ASYNC_TIMER" |
+        "__ASYNC_TIMER_CANCEL" |
+        "__CORE_CLOCK" |
+        "__CORE_NO_SUCH_METHOD" |
+        "__CORE_RUNTIME_TYPE" |
+        "__CORE_THROW" |
+        "__DATETIME_FIELDS" |
+        "__DATETIME_FORMAT" |
+        "__DATETIME_FORMAT_DURATION" |
+        "__DATETIME_FROM_FIELDS" |
+        "__DATETIME_NOW" |
+        "__DATETIME_PARSE" |
         "__IO_FILE_CONSTRUCT" |
         "__IO_FILE_READ_AS_STRING" |
+        "__ISOLATE_CLOSE" |
+        "__ISOLATE_OPEN" |
+        "__ISOLATE_SEND" |
+        "__ISOLATE_SPAWN" |
+        "__JSON_DECODE" |
+        "__JSON_ENCODE" |
         "__LIST_ADD" |
         "__LIST_ADDALL" |
         "__LIST_CLEAR" |
+        "__LIST_FILLRANGE" |
         "__LIST_GET_FIRST"  |
         "__LIST_GET_LAST"   |
         "__LIST_GET_LENGTH" |
+        "__LIST_INDEXOF" |
+        "__LIST_INDEXWHERE" |
         "__LIST_INSERT" |
+        "__LIST_INSERTALL" |
+        "__LIST_LASTINDEXOF" |
+        "__LIST_LASTINDEXWHERE" |
+        "__LIST_REMOVE" |
         "__LIST_REMOVEAT" |
         "__LIST_REMOVELAST" |
         "__LIST_REMOVERANGE" |
+        "__LIST_REMOVEWHERE" |
+        "__LIST_REPLACERANGE" |
+        "__LIST_RETAINWHERE" |
+        "__LIST_SETALL" |
+        "__LIST_SETRANGE" |
         "__LIST_SHUFFLE" |
+        "__LIST_SORT" |
+        "__LIST_SUBLIST" |
         "__LIST_TOSTRING" |
+        "__MAP_FIND" |
+        "__MAP_REHASH" |
         "__MATH_ACOS" |
         "__MATH_ASIN" |
         "__MATH_ATAN" |
@@ -38,7 +78,27 @@ pub fn has_function(name: &str) -> bool {
         "__MATH_TAN" |
         "__MATH_NEXT_BOOL" |
         "__MATH_NEXT_DOUBLE" |
-        "__MATH_NEXT_INT"
+        "__MATH_NEXT_INT" |
+        "__REGEXP_CHECKSTART" |
+        "__REGEXP_CONSTRUCT" |
+        "__REGEXP_FIRSTMATCH" |
+        "__REGEXP_HASMATCH" |
+        "__REGEXP_NEXTMATCH" |
+        "__REGEXP_STRINGMATCH" |
+        "__REGEXP_TOSTRING" |
+        "__REGEXPMATCH_GET_GROUPNAMES" |
+        "__REGEXPMATCH_GROUP" |
+        "__REGEXPMATCH_NAMEDGROUP" |
+        "__STRINGBUFFER_CLEAR" |
+        "__STRINGBUFFER_CONSTRUCT" |
+        "__STRINGBUFFER_GET_LENGTH" |
+        "__STRINGBUFFER_TOSTRING" |
+        "__STRINGBUFFER_WRITE" |
+        "__STRINGBUFFER_WRITEALL" |
+        "__STRINGBUFFER_WRITECHARCODE" |
+        "__STRINGBUFFER_WRITELN" |
+        "__SYNCSTAR_ITERATOR" |
+        "__SYNCSTAR_MOVENEXT"
         => true,
         _ => false
     }
@@ -55,15 +115,84 @@ pub fn call(fnode: &Node, name: &str, state: &mut State) -> Object {
         "assert" => {
             api::top::assert(fnode, argnodes, args, state)
         }
+        "identical" => {
+            api::top::identical(fnode, args, state)
+        }
+        "identityHashCode" => {
+            api::top::identity_hash_code(fnode, args, state)
+        }
         "print" => {
             api::top::print(fnode, argnodes, args, state)
         }
+        "__ASYNC_FUTURE" => {
+            api::future::create(fnode, args, state)
+        }
+        "__ASYNC_MICROTASK" => {
+            api::future::microtask(fnode, args, state)
+        }
+        "__ASYNC_RESUME" => {
+            api::future::resume(fnode, args, state)
+        }
+        "__ASYNC_TIMER" => {
+            api::future::timer(fnode, args, state)
+        }
+        "__ASYNC_TIMER_CANCEL" => {
+            api::future::cancel_timer(fnode, args, state)
+        }
+        "__CORE_CLOCK" => {
+            api::top::clock(fnode, args, state)
+        }
+        "__CORE_NO_SUCH_METHOD" => {
+            api::top::no_such_method(fnode, args, state)
+        }
+        "__CORE_RUNTIME_TYPE" => {
+            api::top::runtime_type(fnode, args, state)
+        }
+        "__CORE_THROW" => {
+            api::top::throw(fnode, args, state)
+        }
+        "__DATETIME_FIELDS" => {
+            api::datetime::fields(fnode, args, state)
+        }
+        "__DATETIME_FORMAT" => {
+            api::datetime::format(fnode, args, state)
+        }
+        "__DATETIME_FORMAT_DURATION" => {
+            api::datetime::format_duration(fnode, args, state)
+        }
+        "__DATETIME_FROM_FIELDS" => {
+            api::datetime::from_parts(fnode, args, state)
+        }
+        "__DATETIME_NOW" => {
+            api::datetime::now(fnode, args, state)
+        }
+        "__DATETIME_PARSE" => {
+            api::datetime::parse(fnode, args, state)
+        }
         "__IO_FILE_CONSTRUCT" => {
             api::io::file_construct(fnode, argnodes, args, state)
         }
         "__IO_FILE_READ_AS_STRING" => {
             api::io::file_read_as_string(fnode, args, state)
         }
+        "__ISOLATE_CLOSE" => {
+            api::isolate::close(fnode, args, state)
+        }
+        "__ISOLATE_OPEN" => {
+            api::isolate::open(fnode, args, state)
+        }
+        "__ISOLATE_SEND" => {
+            api::isolate::send(fnode, args, state)
+        }
+        "__ISOLATE_SPAWN" => {
+            api::isolate::spawn(fnode, args, state)
+        }
+        "__JSON_DECODE" => {
+            api::convert::decode(fnode, args, state)
+        }
+        "__JSON_ENCODE" => {
+            api::convert::encode(fnode, args, state)
+        }
         "__LIST_ADD" => {
             api::list::add(fnode, args, state)
         }
@@ -73,18 +202,39 @@ pub fn call(fnode: &Node, name: &str, state: &mut State) -> Object {
         "__LIST_CLEAR" => {
             api::list::clear(fnode, args, state)
         }
+        "__LIST_FILLRANGE" => {
+            api::list::fill_range(fnode, argnodes, args, state)
+        }
         "__LIST_GET_FIRST" => {
-            api::list::get_first(args, state)
+            api::list::get_first(fnode, args, state)
         }
         "__LIST_GET_LAST" => {
-            api::list::get_last(args, state)
+            api::list::get_last(fnode, args, state)
         }
         "__LIST_GET_LENGTH" => {
             api::list::get_length(args, state)
         }
+        "__LIST_INDEXOF" => {
+            api::list::index_of(fnode, argnodes, args, state)
+        }
+        "__LIST_INDEXWHERE" => {
+            api::list::index_where(fnode, argnodes, args, state)
+        }
         "__LIST_INSERT" => {
             api::list::insert(fnode, argnodes, args, state)
         }
+        "__LIST_INSERTALL" => {
+            api::list::insert_all(argnodes, args, state)
+        }
+        "__LIST_LASTINDEXOF" => {
+            api::list::last_index_of(fnode, argnodes, args, state)
+        }
+        "__LIST_LASTINDEXWHERE" => {
+            api::list::last_index_where(fnode, argnodes, args, state)
+        }
+        "__LIST_REMOVE" => {
+            api::list::remove(fnode, args, state)
+        }
         "__LIST_REMOVEAT" => {
             api::list::remove_at(fnode, argnodes, args, state)
         }
@@ -94,12 +244,39 @@ pub fn call(fnode: &Node, name: &str, state: &mut State) -> Object {
         "__LIST_REMOVERANGE" => {
             api::list::remove_range(fnode, argnodes, args, state)
         }
+        "__LIST_REMOVEWHERE" => {
+            api::list::remove_where(fnode, argnodes, args, state)
+        }
+        "__LIST_REPLACERANGE" => {
+            api::list::replace_range(fnode, argnodes, args, state)
+        }
+        "__LIST_RETAINWHERE" => {
+            api::list::retain_where(fnode, argnodes, args, state)
+        }
+        "__LIST_SETALL" => {
+            api::list::set_all(fnode, argnodes, args, state)
+        }
+        "__LIST_SETRANGE" => {
+            api::list::set_range(fnode, argnodes, args, state)
+        }
         "__LIST_SHUFFLE" => {
             api::list::shuffle(fnode, args, state)
         }
+        "__LIST_SORT" => {
+            api::list::sort(fnode, argnodes, args, state)
+        }
+        "__LIST_SUBLIST" => {
+            api::list::sublist(fnode, argnodes, args, state)
+        }
         "__LIST_TOSTRING" => {
             api::list::to_string(fnode, args, state)
         }
+        "__MAP_FIND" => {
+            api::map::find(fnode, args, state)
+        }
+        "__MAP_REHASH" => {
+            api::map::rehash(fnode, args, state)
+        }
         "__MATH_ACOS" => {
             api::math::acos(fnode, argnodes, args, state)
         }
@@ -148,8 +325,331 @@ pub fn call(fnode: &Node, name: &str, state: &mut State) -> Object {
         "__MATH_NEXT_INT" => {
             api::math::next_int(fnode, argnodes, args, state)
         }
+        "__REGEXP_CHECKSTART" => {
+            api::regexp::check_start_arg(argnodes, args, state)
+        }
+        "__REGEXP_CONSTRUCT" => {
+            api::regexp::construct(argnodes, args, state)
+        }
+        "__REGEXP_FIRSTMATCH" => {
+            api::regexp::first_match(fnode, argnodes, args, state)
+        }
+        "__REGEXP_HASMATCH" => {
+            api::regexp::has_match(argnodes, args, state)
+        }
+        "__REGEXP_NEXTMATCH" => {
+            api::regexp::next_match(fnode, argnodes, args, state)
+        }
+        "__REGEXP_STRINGMATCH" => {
+            api::regexp::string_match(argnodes, args, state)
+        }
+        "__REGEXP_TOSTRING" => {
+            api::regexp::to_string(fnode, args, state)
+        }
+        "__REGEXPMATCH_GET_GROUPNAMES" => {
+            api::regexp::match_group_names(fnode, args, state)
+        }
+        "__REGEXPMATCH_GROUP" => {
+            api::regexp::match_group(fnode, argnodes, args, state)
+        }
+        "__REGEXPMATCH_NAMEDGROUP" => {
+            api::regexp::match_named_group(fnode, argnodes, args, state)
+        }
+        "__STRINGBUFFER_CLEAR" => {
+            api::stringbuffer::clear(args, state)
+        }
+        "__STRINGBUFFER_CONSTRUCT" => {
+            api::stringbuffer::construct(fnode, args, state)
+        }
+        "__STRINGBUFFER_GET_LENGTH" => {
+            api::stringbuffer::get_length(args, state)
+        }
+        "__STRINGBUFFER_TOSTRING" => {
+            api::stringbuffer::to_string(args, state)
+        }
+        "__STRINGBUFFER_WRITE" => {
+            api::stringbuffer::write(fnode, args, state)
+        }
+        "__STRINGBUFFER_WRITEALL" => {
+            api::stringbuffer::write_all(fnode, argnodes, args, state)
+        }
+        "__STRINGBUFFER_WRITECHARCODE" => {
+            api::stringbuffer::write_char_code(argnodes, args, state)
+        }
+        "__STRINGBUFFER_WRITELN" => {
+            api::stringbuffer::writeln(fnode, args, state)
+        }
+        "__SYNCSTAR_ITERATOR" => {
+            api::syncstar::iterator(args, state)
+        }
+        "__SYNCSTAR_MOVENEXT" => {
+            api::syncstar::move_next(fnode, args, state)
+        }
 
         _ => panic!("Unknown command: {}", name)
     }
 }
 
+
+
+/// The hash code of a value that is not an instance, like an int or a string.
+pub fn hash_code(obj: &Object) -> Object {
+    Object::Int(api::top::hash_code(obj))
+}
+
+
+/// A built-in function as a value, as in list.forEach(print). It is
+/// a function that passes its parameters on to the built-in.
+pub fn tear_off(name: &str, state: &State) -> Option<Object> {
+
+    let params = match name {
+        "print" => vec![("Object?", "object")],
+        _ => return None
+    };
+
+    let mut args = Node::new(NodeType::ArgList(0, 0));
+    for (_, pname) in &params {
+        args.children.push(Node::new(NodeType::Name(pname.to_string(), 0, 0)));
+    }
+    let mut call = Node::new(NodeType::FunCall(name.to_string(), 0, 0));
+    call.children.push(args);
+    let mut ret = Node::new(NodeType::Return(0, 0));
+    ret.children.push(call);
+    let mut body = Node::new(NodeType::Block(0, 0));
+    body.children.push(ret);
+
+    let params = params.iter().map(|(ptype, pname)| ParamObj {
+        typ: ptype.to_string(),
+        name: pname.to_string(),
+        fieldinit: false,
+        optional: false,
+        named: false,
+        default: None
+    }).collect();
+    Some(Object::Function(name.to_string(), state.filepath.clone(), body, params, Vec::new(), String::from("void")))
+}
+
+
+/// Whether a name is one of the built-in types with
+/// static members, like int.parse and Future.value.
+pub fn has_statics(name: &str) -> bool {
+    matches!(name, "int" | "double" | "num" | "Future" | "Stream" | "Isolate" | "Timer" | "DateTime" | "Duration" | "JsonEncoder")
+}
+
+
+pub fn call_static(fnode: &Node, typename: &str, name: &str, state: &mut State) -> Object {
+
+    if typename == "DateTime" {
+        // Some take named arguments.
+        return api::datetime::call_static(fnode, name, state);
+    }
+
+    let argnodes = &fnode.children[0].children;
+    let args = argnodes_to_argobjs(argnodes, state);
+
+    return match (typename, name) {
+
+        ("int", "parse") => {
+            api::num::int_parse(fnode, argnodes, args, state)
+        }
+        ("int", "tryParse") => {
+            api::num::int_try_parse(fnode, argnodes, args, state)
+        }
+        ("double", "parse") => {
+            api::num::double_parse(fnode, argnodes, args, state)
+        }
+        ("double", "tryParse") => {
+            api::num::double_try_parse(fnode, argnodes, args, state)
+        }
+        ("num", "parse") => {
+            api::num::num_parse(fnode, argnodes, args, state)
+        }
+        ("num", "tryParse") => {
+            api::num::num_try_parse(fnode, argnodes, args, state)
+        }
+        ("Future", _) => {
+            api::future::call_static(fnode, name, args, state)
+        }
+        ("Stream", _) => {
+            api::stream::call_static(fnode, name, args, state)
+        }
+        ("Isolate", _) => {
+            api::isolate::call_static(fnode, name, args, state)
+        }
+        ("Timer", _) => {
+            api::timer::call_static(fnode, name, args, state)
+        }
+        ("JsonEncoder", _) => {
+            api::convert::call_static(fnode, name, args, state)
+        }
+
+        _ => evalerror(
+            format!("Method not found: '{}.{}'.", typename, name),
+            state,
+            fnode
+        )
+    }
+}
+
+
+pub fn get_static(node: &Node, typename: &str, name: &str, state: &mut State) -> Object {
+
+    if typename == "double" {
+        if let Some(obj) = api::num::double_constant(name) {
+            return obj;
+        }
+    }
+    if let Some(obj) = api::datetime::constant(node, typename, name, state) {
+        return obj;
+    }
+    evalerror(
+        format!("Getter not found: '{}'.", name),
+        state,
+        node
+    )
+}
+
+
+/// Call a method on a number.
+pub fn call_method(fnode: &Node, this: &Object, name: &str, state: &mut State) -> Object {
+
+    let argnodes = &fnode.children[0].children;
+    reject_named_args(&fnode.children[0], state);
+
+    let args = argnodes_to_argobjs(argnodes, state);
+    let is_int = matches!(this, Object::Int(_));
+
+    return match name {
+
+        "abs" => {
+            api::num::abs(fnode, this, args, state)
+        }
+        "ceil" => {
+            api::num::ceil(fnode, this, args, state)
+        }
+        "ceilToDouble" => {
+            api::num::ceil_to_double(fnode, this, args, state)
+        }
+        "clamp" => {
+            api::num::clamp(fnode, argnodes, this, args, state)
+        }
+        "compareTo" => {
+            api::num::compare_to(fnode, argnodes, this, args, state)
+        }
+        "floor" => {
+            api::num::floor(fnode, this, args, state)
+        }
+        "floorToDouble" => {
+            api::num::floor_to_double(fnode, this, args, state)
+        }
+        "gcd" if is_int => {
+            api::num::gcd(fnode, argnodes, this, args, state)
+        }
+        "modPow" if is_int => {
+            api::num::mod_pow(fnode, argnodes, this, args, state)
+        }
+        "remainder" => {
+            api::num::remainder(fnode, argnodes, this, args, state)
+        }
+        "round" => {
+            api::num::round(fnode, this, args, state)
+        }
+        "roundToDouble" => {
+            api::num::round_to_double(fnode, this, args, state)
+        }
+        "toDouble" => {
+            api::num::to_double(fnode, this, args, state)
+        }
+        "toInt" => {
+            api::num::to_int(fnode, this, args, state)
+        }
+        "toRadixString" if is_int => {
+            api::num::to_radix_string(fnode, argnodes, this, args, state)
+        }
+        "toString" => {
+            api::num::to_string(fnode, this, args, state)
+        }
+        "toStringAsExponential" => {
+            api::num::to_string_as_exponential(fnode, argnodes, this, args, state)
+        }
+        "toStringAsFixed" => {
+            api::num::to_string_as_fixed(fnode, argnodes, this, args, state)
+        }
+        "toStringAsPrecision" => {
+            api::num::to_string_as_precision(fnode, argnodes, this, args, state)
+        }
+        "truncate" => {
+            api::num::truncate(fnode, this, args, state)
+        }
+        "truncateToDouble" => {
+            api::num::truncate_to_double(fnode, this, args, state)
+        }
+
+        _ => evalerror(
+            format!("The method '{}' isn't defined for the class '{}'.", name, num_type(this)),
+            state,
+            fnode
+        )
+    }
+}
+
+
+/// Call a method on a string.
+pub fn call_string_method(fnode: &Node, this: &Object, name: &str, state: &mut State) -> Object {
+
+    let argnodes = &fnode.children[0].children;
+    reject_named_args(&fnode.children[0], state);
+
+    let args = argnodes_to_argobjs(argnodes, state);
+
+    return match name {
+
+        "contains" => {
+            api::string::contains(fnode, argnodes, this, args, state)
+        }
+        "replaceAll" => {
+            api::string::replace_all(fnode, argnodes, this, args, state)
+        }
+        "split" => {
+            api::string::split(fnode, argnodes, this, args, state)
+        }
+
+        _ => evalerror(
+            format!("The method '{}' isn't defined for the class 'String'.", name),
+            state,
+            fnode
+        )
+    }
+}
+
+
+/// Get a property of a number.
+pub fn get_property(node: &Node, this: &Object, name: &str, state: &mut State) -> Object {
+
+    let is_int = matches!(this, Object::Int(_));
+
+    return match name {
+        "bitLength" if is_int => api::num::bit_length(this),
+        "isEven" if is_int => api::num::is_even(this),
+        "isFinite" => api::num::is_finite(this),
+        "isInfinite" => api::num::is_infinite(this),
+        "isNaN" => api::num::is_nan(this),
+        "isNegative" => api::num::is_negative(this),
+        "isOdd" if is_int => api::num::is_odd(this),
+        "sign" => api::num::sign(this),
+
+        _ => evalerror(
+            format!("The getter '{}' isn't defined for the class '{}'.", name, num_type(this)),
+            state,
+            node
+        )
+    }
+}
+
+
+fn num_type(obj: &Object) -> &'static str {
+    match obj {
+        Object::Int(_) => "int",
+        _ => "double"
+    }
+}
@@ -0,0 +1,269 @@
+// An instant in time, such as July 20, 1969, 8:18pm GMT.
+//
+// A DateTime is kept as microseconds since the epoch, and is either in
+// UTC or in the local time zone. The local time zone is given by the TZ
+// environment variable, or is that of the system.
+class DateTime {
+    int __micros = 0;
+    // True if this DateTime is set to UTC time.
+    bool isUtc = false;
+    // The parts of the date and time, once asked for.
+    List? __parts;
+
+
+    // Constructs a DateTime instance specified in the local time zone.
+    DateTime(int year, [int month = 1, int day = 1, int hour = 0, int minute = 0, int second = 0, int millisecond = 0, int microsecond = 0]) {
+        __micros = _dateTimeMicros(false, year, month, day, hour, minute, second, millisecond, microsecond);
+    }
+
+
+    List __fields() {
+        List? parts = __parts;
+        if (parts == null) {
+            parts = __DATETIME_FIELDS(__micros, isUtc);
+            __parts = parts;
+        }
+        return parts!;
+    }
+
+
+    // The year.
+    int get year {
+        return __fields()[0];
+    }
+
+
+    // The month [1..12].
+    int get month {
+        return __fields()[1];
+    }
+
+
+    // The day of the month [1..31].
+    int get day {
+        return __fields()[2];
+    }
+
+
+    // The hour of the day, expressed as in a 24-hour clock [0..23].
+    int get hour {
+        return __fields()[3];
+    }
+
+
+    // The minute [0...59].
+    int get minute {
+        return __fields()[4];
+    }
+
+
+    // The second [0...59].
+    int get second {
+        return __fields()[5];
+    }
+
+
+    // The millisecond [0...999].
+    int get millisecond {
+        return __fields()[6];
+    }
+
+
+    // The microsecond [0...999].
+    int get microsecond {
+        return __fields()[7];
+    }
+
+
+    // The day of the week, from monday (1) to sunday (7).
+    int get weekday {
+        return __fields()[8];
+    }
+
+
+    // The time zone offset, which is the difference between local time and UTC.
+    Duration get timeZoneOffset {
+        return Duration(seconds: __fields()[9]);
+    }
+
+
+    // The time zone name.
+    String get timeZoneName {
+        return __fields()[10];
+    }
+
+
+    // The number of milliseconds since the "Unix epoch" 1970-01-01T00:00:00Z (UTC).
+    int get millisecondsSinceEpoch {
+        return __micros ~/ 1000;
+    }
+
+
+    // The number of microseconds since the "Unix epoch" 1970-01-01T00:00:00Z (UTC).
+    int get microsecondsSinceEpoch {
+        return __micros;
+    }
+
+
+    // Returns a new DateTime instance with duration added to this DateTime.
+    DateTime add(Duration duration) {
+        return _dateTime(__micros + duration.inMicroseconds, isUtc);
+    }
+
+
+    // Returns a new DateTime instance with duration subtracted from this DateTime.
+    DateTime subtract(Duration duration) {
+        return _dateTime(__micros - duration.inMicroseconds, isUtc);
+    }
+
+
+    // Returns a Duration with the difference when subtracting other from this DateTime.
+    Duration difference(DateTime other) {
+        return Duration(microseconds: __micros - other.__micros);
+    }
+
+
+    // Whether this DateTime occurs before other.
+    bool isBefore(DateTime other) {
+        return __micros < other.__micros;
+    }
+
+
+    // Whether this DateTime occurs after other.
+    bool isAfter(DateTime other) {
+        return __micros > other.__micros;
+    }
+
+
+    // Whether this DateTime occurs at the same moment as other.
+    bool isAtSameMomentAs(DateTime other) {
+        return __micros == other.__micros;
+    }
+
+
+    // Compares this DateTime object to other, returning zero if the values are equal.
+    int compareTo(DateTime other) {
+        if (__micros < other.__micros) {
+            return -1;
+        }
+        if (__micros > other.__micros) {
+            return 1;
+        }
+        return 0;
+    }
+
+
+    // Returns this DateTime value in the UTC time zone.
+    DateTime toUtc() {
+        if (isUtc) {
+            return this;
+        }
+        return _dateTime(__micros, true);
+    }
+
+
+    // Returns this DateTime value in the local time zone.
+    DateTime toLocal() {
+        if (isUtc) {
+            return _dateTime(__micros, false);
+        }
+        return this;
+    }
+
+
+    // Whether other is a DateTime at the same moment and in the same time zone.
+    bool operator ==(Object other) {
+        return other is DateTime && other.__micros == __micros && other.isUtc == isUtc;
+    }
+
+
+    int get hashCode {
+        return __micros;
+    }
+
+
+    // Returns an ISO-8601 full-precision extended format representation.
+    String toIso8601String() {
+        return __DATETIME_FORMAT(__micros, isUtc, true);
+    }
+
+
+    // Returns a human-readable string for this instance.
+    String toString() {
+        return __DATETIME_FORMAT(__micros, isUtc, false);
+    }
+}
+
+
+int _dateTimeMicros(bool isUtc, int year, int month, int day, int hour, int minute, int second, int millisecond, int microsecond) {
+    int? micros = __DATETIME_FROM_FIELDS(isUtc, year, month, day, hour, minute, second, millisecond, microsecond);
+    if (micros == null) {
+        __CORE_THROW("Invalid argument(s): DateTime is outside valid range");
+    }
+    return micros!;
+}
+
+
+// A DateTime at the microseconds since the epoch.
+DateTime _dateTime(int micros, bool isUtc) {
+    if (micros > 8640000000000000000 || micros < -8640000000000000000) {
+        __CORE_THROW("Invalid argument(s): DateTime is outside valid range: $micros");
+    }
+    DateTime time = DateTime(1970);
+    time.__micros = micros;
+    time.isUtc = isUtc;
+    return time;
+}
+
+
+// The static methods of DateTime.
+
+// DateTime.now
+DateTime _dateTimeNow() {
+    return _dateTime(__DATETIME_NOW(), false);
+}
+
+
+// DateTime.timestamp
+DateTime _dateTimeTimestamp() {
+    return _dateTime(__DATETIME_NOW(), true);
+}
+
+
+// DateTime.utc
+DateTime _dateTimeUtc(int year, [int month = 1, int day = 1, int hour = 0, int minute = 0, int second = 0, int millisecond = 0, int microsecond = 0]) {
+    return _dateTime(_dateTimeMicros(true, year, month, day, hour, minute, second, millisecond, microsecond), true);
+}
+
+
+// DateTime.parse
+DateTime _dateTimeParse(String formattedString) {
+    DateTime? time = _dateTimeTryParse(formattedString);
+    if (time == null) {
+        __CORE_THROW("FormatException: Invalid date format\n$formattedString");
+    }
+    return time!;
+}
+
+
+// DateTime.tryParse
+DateTime? _dateTimeTryParse(String formattedString) {
+    List? parsed = __DATETIME_PARSE(formattedString);
+    if (parsed == null) {
+        return null;
+    }
+    List p = parsed!;
+    return _dateTime(p[0], p[1]);
+}
+
+
+// DateTime.fromMillisecondsSinceEpoch
+DateTime _dateTimeFromMilliseconds(int millisecondsSinceEpoch, {bool isUtc = false}) {
+    return _dateTime(millisecondsSinceEpoch * 1000, isUtc);
+}
+
+
+// DateTime.fromMicrosecondsSinceEpoch
+DateTime _dateTimeFromMicroseconds(int microsecondsSinceEpoch, {bool isUtc = false}) {
+    return _dateTime(microsecondsSinceEpoch, isUtc);
+}
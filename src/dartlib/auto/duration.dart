@@ -0,0 +1,150 @@
+// A span of time, such as 27 days, 4 hours, 12 minutes, and 3 seconds.
+class Duration {
+    int __micros = 0;
+
+
+    // Creates a new Duration object whose value is the sum of all individual parts.
+    Duration({int days = 0, int hours = 0, int minutes = 0, int seconds = 0, int milliseconds = 0, int microseconds = 0}) {
+        __micros = ((((days * 24 + hours) * 60 + minutes) * 60 + seconds) * 1000 + milliseconds) * 1000 + microseconds;
+    }
+
+
+    // The number of entire days spanned by this Duration.
+    int get inDays {
+        return __micros ~/ 86400000000;
+    }
+
+
+    // The number of entire hours spanned by this Duration.
+    int get inHours {
+        return __micros ~/ 3600000000;
+    }
+
+
+    // The number of whole minutes spanned by this Duration.
+    int get inMinutes {
+        return __micros ~/ 60000000;
+    }
+
+
+    // The number of entire seconds spanned by this Duration.
+    int get inSeconds {
+        return __micros ~/ 1000000;
+    }
+
+
+    // The number of entire milliseconds spanned by this Duration.
+    int get inMilliseconds {
+        return __micros ~/ 1000;
+    }
+
+
+    // The number of entire microseconds spanned by this Duration.
+    int get inMicroseconds {
+        return __micros;
+    }
+
+
+    // Whether this Duration is negative.
+    bool get isNegative {
+        return __micros < 0;
+    }
+
+
+    // Adds this Duration and other and returns the sum as a new Duration object.
+    Duration operator +(Duration other) {
+        return Duration(microseconds: __micros + other.__micros);
+    }
+
+
+    // Subtracts other from this Duration and returns the difference as a new Duration object.
+    Duration operator -(Duration other) {
+        return Duration(microseconds: __micros - other.__micros);
+    }
+
+
+    // Multiplies this Duration by the given factor and returns the result as a new Duration object.
+    Duration operator *(num factor) {
+        return Duration(microseconds: (__micros * factor).round());
+    }
+
+
+    // Divides this Duration by the given quotient and returns the truncated result as a new Duration object.
+    Duration operator ~/(int quotient) {
+        return Duration(microseconds: __micros ~/ quotient);
+    }
+
+
+    // Creates a new Duration with the opposite direction of this Duration.
+    Duration operator -() {
+        return Duration(microseconds: 0 - __micros);
+    }
+
+
+    // Whether this Duration is shorter than other.
+    bool operator <(Duration other) {
+        return __micros < other.__micros;
+    }
+
+
+    // Whether this Duration is longer than other.
+    bool operator >(Duration other) {
+        return __micros > other.__micros;
+    }
+
+
+    // Whether this Duration is shorter than or equal to other.
+    bool operator <=(Duration other) {
+        return __micros <= other.__micros;
+    }
+
+
+    // Whether this Duration is longer than or equal to other.
+    bool operator >=(Duration other) {
+        return __micros >= other.__micros;
+    }
+
+
+    // Whether this Duration has the same length as other.
+    bool operator ==(Object other) {
+        return other is Duration && other.__micros == __micros;
+    }
+
+
+    int get hashCode {
+        return __micros;
+    }
+
+
+    // Compares this Duration to other, returning zero if the values are equal.
+    int compareTo(Duration other) {
+        if (__micros < other.__micros) {
+            return -1;
+        }
+        if (__micros > other.__micros) {
+            return 1;
+        }
+        return 0;
+    }
+
+
+    // Creates a new Duration representing the absolute length of this Duration.
+    Duration abs() {
+        if (__micros < 0) {
+            return -this;
+        }
+        return this;
+    }
+
+
+    // Returns a string representation of this Duration, as 1:02:03.000000.
+    String toString() {
+        return __DATETIME_FORMAT_DURATION(__micros);
+    }
+}
+
+
+// Duration.zero
+Duration _durationZero() {
+    return Duration();
+}
@@ -0,0 +1,257 @@
+// The result of an asynchronous computation.
+//
+// A future is pending until it completes, once, with a value or with an
+// error. Callbacks registered by then, catchError and whenComplete, and
+// async functions awaiting the future, run as microtasks once it has
+// completed. Futures made by the interpreter, as for async functions,
+// have their fields set without running the initializers.
+class Future<T> {
+    // 0 while pending, 1 with a value, 2 with an error.
+    int __state = 0;
+    dynamic __result;
+    List<_FutureListener> __listeners = [];
+    // Whether anything listens, so that an error is not reported.
+    bool __handled = false;
+
+
+    // Creates a future completed with the result of calling computation,
+    // which is done from a timer.
+    Future(Function computation) {
+        __ASYNC_TIMER(0, _completeDelayed, this, computation);
+    }
+
+
+    // Register callbacks to be called when this future completes.
+    Future<R> then<R>(Function onValue, {Function? onError}) {
+        Future<R> result = __ASYNC_FUTURE(R);
+        _FutureListener listener = _FutureListener(result, "then");
+        listener.onValue = onValue;
+        listener.onError = onError;
+        __listen(listener);
+        return result;
+    }
+
+
+    // Handles errors emitted by this future.
+    Future<T> catchError(Function onError) {
+        Future<T> result = __ASYNC_FUTURE(T);
+        _FutureListener listener = _FutureListener(result, "catchError");
+        listener.onError = onError;
+        __listen(listener);
+        return result;
+    }
+
+
+    // Registers a function to be called when this future completes,
+    // with a value or an error.
+    Future<T> whenComplete(Function action) {
+        Future<T> result = __ASYNC_FUTURE(T);
+        _FutureListener listener = _FutureListener(result, "whenComplete");
+        listener.onValue = action;
+        __listen(listener);
+        return result;
+    }
+
+
+    void __listen(_FutureListener listener) {
+        __handled = true;
+        if (__state == 0) {
+            __listeners.add(listener);
+        }
+        else {
+            __ASYNC_MICROTASK(_runListener, listener, this);
+        }
+    }
+
+
+    // Completes with the value, or as the value does if it is a future.
+    void __complete(dynamic value) {
+        if (value is Future) {
+            value.__listen(_FutureListener(this, "chain"));
+            return;
+        }
+        __state = 1;
+        __result = value;
+        __notify();
+    }
+
+
+    void __completeError(dynamic error) {
+        __state = 2;
+        __result = error;
+        if (!__handled) {
+            // Reported unless something listens before the check.
+            __ASYNC_MICROTASK(_checkHandled, this);
+        }
+        __notify();
+    }
+
+
+    void __notify() {
+        for (_FutureListener listener in __listeners) {
+            __ASYNC_MICROTASK(_runListener, listener, this);
+        }
+        __listeners = [];
+    }
+}
+
+
+// What to do with the outcome of a future, and the future
+// completed with the result.
+//
+// kind is one of then, catchError, whenComplete, chain (complete as
+// the future did), await (resume an async function, which has no
+// result) and wait (one of the futures of Future.wait).
+class _FutureListener {
+    Future? result;
+    String kind;
+    Function? onValue;
+    Function? onError;
+    _FutureWait? wait;
+    int index = 0;
+    __InternalGenerator? __coroutine;
+
+    _FutureListener(this.result, this.kind);
+}
+
+
+// The values of Future.wait, as they arrive.
+class _FutureWait {
+    List values;
+    int remaining;
+    bool failed = false;
+
+    _FutureWait(this.values, this.remaining);
+}
+
+
+// Passes the outcome of a completed future to a listener.
+void _runListener(_FutureListener listener, Future source) {
+    if (listener.kind == "await") {
+        __ASYNC_RESUME(listener.__coroutine, source);
+        return;
+    }
+
+    dynamic value = source.__result;
+    bool failed = source.__state == 2;
+    Future result = listener.result!;
+    String kind = listener.kind;
+
+    if (kind == "wait") {
+        _FutureWait wait = listener.wait!;
+        if (failed) {
+            // The first error completes the result.
+            if (!wait.failed) {
+                wait.failed = true;
+                result.__completeError(value);
+            }
+        }
+        else {
+            List values = wait.values;
+            values[listener.index] = value;
+            wait.remaining = wait.remaining - 1;
+            if (wait.remaining == 0 && !wait.failed) {
+                result.__complete(wait.values);
+            }
+        }
+    }
+    else if (kind == "whenComplete") {
+        Function action = listener.onValue!;
+        action();
+        _completeAs(result, failed, value);
+    }
+    else if (failed && listener.onError != null) {
+        Function onError = listener.onError!;
+        result.__complete(onError(value));
+    }
+    else if (!failed && kind == "then") {
+        Function onValue = listener.onValue!;
+        result.__complete(onValue(value));
+    }
+    else {
+        _completeAs(result, failed, value);
+    }
+}
+
+
+void _completeAs(Future result, bool failed, dynamic value) {
+    if (failed) {
+        result.__completeError(value);
+    }
+    else {
+        result.__complete(value);
+    }
+}
+
+
+// Reports an error that nothing has listened for.
+void _checkHandled(Future future) {
+    if (!future.__handled) {
+        __CORE_THROW("${future.__result}");
+    }
+}
+
+
+void _completeDelayed(Future result, Function? computation) {
+    if (computation == null) {
+        result.__complete(null);
+    }
+    else {
+        Function f = computation!;
+        result.__complete(f());
+    }
+}
+
+
+// The static methods of Future.
+
+// Future.value
+Future _futureValue([dynamic value]) {
+    Future result = __ASYNC_FUTURE();
+    if (value != null && value is! Future) {
+        result = __ASYNC_FUTURE(value.runtimeType);
+    }
+    result.__complete(value);
+    return result;
+}
+
+
+// Future.error
+Future _futureError(Object error) {
+    Future result = __ASYNC_FUTURE();
+    result.__completeError(error);
+    return result;
+}
+
+
+// Future.delayed
+Future _futureDelayed(Duration duration, [Function? computation]) {
+    Future result = __ASYNC_FUTURE();
+    __ASYNC_TIMER(duration.inMicroseconds, _completeDelayed, result, computation);
+    return result;
+}
+
+
+// Future.wait
+Future<List> _futureWait(List futures) {
+    Future<List> result = __ASYNC_FUTURE(List);
+    List values = [];
+    for (var f in futures) {
+        values.add(null);
+    }
+    if (futures.isEmpty) {
+        result.__complete(values);
+        return result;
+    }
+
+    _FutureWait wait = _FutureWait(values, futures.length);
+    int i = 0;
+    for (Future f in futures) {
+        _FutureListener listener = _FutureListener(result, "wait");
+        listener.wait = wait;
+        listener.index = i;
+        f.__listen(listener);
+        i++;
+    }
+    return result;
+}
@@ -0,0 +1,592 @@
+// An interface for getting items, one at a time, from an object.
+//
+// Subclasses implement bool moveNext() and a current field or getter.
+class Iterator<E> {
+
+    Iterator();
+
+
+    // The current element.
+    E get current {
+        __CORE_THROW("UnimplementedError: current");
+    }
+
+
+    // Advances the iterator to the next element of the iteration.
+    bool moveNext() {
+        __CORE_THROW("UnimplementedError: moveNext");
+    }
+}
+
+
+// A collection of values, or "elements", that can be accessed sequentially.
+//
+// Subclasses implement the iterator getter, everything else is
+// built on top of it.
+class Iterable<E> {
+
+    Iterable();
+
+
+    // A new Iterator that allows iterating the elements of this Iterable.
+    Iterator<E> get iterator {
+        __CORE_THROW("UnimplementedError: iterator");
+    }
+
+
+    // The current elements of this iterable modified by toElement.
+    Iterable<T> map<T>(T toElement(E e)) {
+        return _MappedIterable<E, T>(this, toElement);
+    }
+
+
+    // Creates a new lazy Iterable with all elements that satisfy the predicate test.
+    Iterable<E> where(bool test(E element)) {
+        return _WhereIterable<E>(this, test);
+    }
+
+
+    // Expands each element of this Iterable into zero or more elements.
+    Iterable<T> expand<T>(Iterable<T> toElements(E element)) {
+        return _ExpandIterable<E, T>(this, toElements);
+    }
+
+
+    // Creates a lazy iterable of the count first elements of this iterable.
+    Iterable<E> take(int count) {
+        _checkNotNegative(count, "count");
+        return _TakeIterable<E>(this, count);
+    }
+
+
+    // Creates an Iterable that provides all but the first count elements.
+    Iterable<E> skip(int count) {
+        _checkNotNegative(count, "count");
+        return _SkipIterable<E>(this, count);
+    }
+
+
+    // Creates a lazy iterable of the leading elements satisfying test.
+    Iterable<E> takeWhile(bool test(E value)) {
+        return _TakeWhileIterable<E>(this, test);
+    }
+
+
+    // Creates an Iterable that skips leading elements while test is satisfied.
+    Iterable<E> skipWhile(bool test(E value)) {
+        return _SkipWhileIterable<E>(this, test);
+    }
+
+
+    // Creates the lazy concatenation of this iterable and other.
+    Iterable<E> followedBy(Iterable<E> other) {
+        return _FollowedByIterable<E>(this, other);
+    }
+
+
+    // Reduces a collection to a single value by iteratively combining each
+    // element of the collection with an existing value.
+    T fold<T>(T initialValue, T combine(T previousValue, E element)) {
+        var value = initialValue;
+        for (var element in this) {
+            value = combine(value, element);
+        }
+        return value;
+    }
+
+
+    // Reduces a collection to a single value by iteratively combining
+    // elements of the collection using the provided function.
+    E reduce(E combine(E value, E element)) {
+        var it = iterator;
+        if (!it.moveNext()) {
+            __CORE_THROW("Bad state: No element");
+        }
+        var value = it.current;
+        while (it.moveNext()) {
+            value = combine(value, it.current);
+        }
+        return value;
+    }
+
+
+    // Checks whether any element of this iterable satisfies test.
+    bool any(bool test(E element)) {
+        for (var element in this) {
+            if (test(element)) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+
+    // Checks whether every element of this iterable satisfies test.
+    bool every(bool test(E element)) {
+        for (var element in this) {
+            if (!test(element)) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+
+    // Whether the collection contains an element equal to element.
+    bool contains(Object? element) {
+        for (var e in this) {
+            if (e == element) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+
+    // Invokes action on each element of this iterable in iteration order.
+    void forEach(void action(E element)) {
+        for (var element in this) {
+            action(element);
+        }
+    }
+
+
+    // Converts each element to a String and concatenates the strings.
+    String join([String separator = ""]) {
+        var buffer = StringBuffer();
+        buffer.writeAll(this, separator);
+        return buffer.toString();
+    }
+
+
+    // Creates a List containing the elements of this Iterable. Unlike
+    // in Dart, the list can grow even when growable is false.
+    List<E> toList({bool growable = true}) {
+        var list = <E>[];
+        list.addAll(this);
+        return list;
+    }
+
+
+    // Creates a Set containing the same elements as this Iterable.
+    Set<E> toSet() {
+        Set<E> set = Set<E>();
+        set.addAll(this);
+        return set;
+    }
+
+
+    // The number of elements in this Iterable.
+    int get length {
+        var count = 0;
+        var it = iterator;
+        while (it.moveNext()) {
+            count = count + 1;
+        }
+        return count;
+    }
+
+
+    // Whether this collection has no elements.
+    bool get isEmpty {
+        return !iterator.moveNext();
+    }
+
+
+    // Whether this collection has at least one element.
+    bool get isNotEmpty {
+        return iterator.moveNext();
+    }
+
+
+    // The first element.
+    E get first {
+        var it = iterator;
+        if (!it.moveNext()) {
+            __CORE_THROW("Bad state: No element");
+        }
+        return it.current;
+    }
+
+
+    // The last element.
+    E get last {
+        var it = iterator;
+        if (!it.moveNext()) {
+            __CORE_THROW("Bad state: No element");
+        }
+        var result = it.current;
+        while (it.moveNext()) {
+            result = it.current;
+        }
+        return result;
+    }
+
+
+    // Checks that this iterable has only one element, and returns that element.
+    E get single {
+        var it = iterator;
+        if (!it.moveNext()) {
+            __CORE_THROW("Bad state: No element");
+        }
+        var result = it.current;
+        if (it.moveNext()) {
+            __CORE_THROW("Bad state: Too many elements");
+        }
+        return result;
+    }
+
+
+    // The first element that satisfies the given predicate test.
+    E firstWhere(bool test(E element), {E orElse()?}) {
+        for (var element in this) {
+            if (test(element)) {
+                return element;
+            }
+        }
+        if (orElse == null) {
+            __CORE_THROW("Bad state: No element");
+        }
+        return orElse();
+    }
+
+
+    // The last element that satisfies the given predicate test.
+    E lastWhere(bool test(E element), {E orElse()?}) {
+        var found = false;
+        var result = null;
+        for (var element in this) {
+            if (test(element)) {
+                found = true;
+                result = element;
+            }
+        }
+        if (found) {
+            return result;
+        }
+        if (orElse == null) {
+            __CORE_THROW("Bad state: No element");
+        }
+        return orElse();
+    }
+
+
+    // Returns the indexth element.
+    E elementAt(int index) {
+        _checkNotNegative(index, "index");
+        var count = 0;
+        for (var element in this) {
+            if (index == count) {
+                return element;
+            }
+            count = count + 1;
+        }
+        __CORE_THROW("RangeError (index): Index out of range: index should be less than $count: $index");
+    }
+
+
+    // Returns a string representation of (some of) the elements of this.
+    String toString() {
+        return "(" + join(", ") + ")";
+    }
+
+
+    void _checkNotNegative(int value, String name) {
+        if (value < 0) {
+            __CORE_THROW("RangeError ($name): Invalid value: Not greater than or equal to 0: $value");
+        }
+    }
+}
+
+
+class _MappedIterable<S, T> extends Iterable<T> {
+    Iterable<S> _source;
+    Function _f;
+
+    _MappedIterable(this._source, this._f);
+
+    Iterator get iterator {
+        return _MappedIterator<S, T>(_source.iterator, _f);
+    }
+}
+
+
+class _MappedIterator<S, T> extends Iterator<T> {
+    Iterator<S> _source;
+    Function _f;
+    T? current;
+
+    _MappedIterator(this._source, this._f);
+
+    bool moveNext() {
+        if (_source.moveNext()) {
+            var f = _f;
+            current = f(_source.current);
+            return true;
+        }
+        current = null;
+        return false;
+    }
+}
+
+
+class _WhereIterable<E> extends Iterable<E> {
+    Iterable<E> _source;
+    Function _f;
+
+    _WhereIterable(this._source, this._f);
+
+    Iterator get iterator {
+        return _WhereIterator<E>(_source.iterator, _f);
+    }
+}
+
+
+class _WhereIterator<E> extends Iterator<E> {
+    Iterator<E> _source;
+    Function _f;
+    E? current;
+
+    _WhereIterator(this._source, this._f);
+
+    bool moveNext() {
+        var f = _f;
+        while (_source.moveNext()) {
+            if (f(_source.current)) {
+                current = _source.current;
+                return true;
+            }
+        }
+        current = null;
+        return false;
+    }
+}
+
+
+class _ExpandIterable<S, T> extends Iterable<T> {
+    Iterable<S> _source;
+    Function _f;
+
+    _ExpandIterable(this._source, this._f);
+
+    Iterator get iterator {
+        return _ExpandIterator<S, T>(_source.iterator, _f);
+    }
+}
+
+
+class _ExpandIterator<S, T> extends Iterator<T> {
+    Iterator<S> _source;
+    Function _f;
+    Iterator<T>? _inner = null;
+    T? current;
+
+    _ExpandIterator(this._source, this._f);
+
+    bool moveNext() {
+        var f = _f;
+        while (_inner == null || !_inner!.moveNext()) {
+            if (!_source.moveNext()) {
+                _inner = null;
+                current = null;
+                return false;
+            }
+            _inner = f(_source.current).iterator;
+        }
+        current = _inner!.current;
+        return true;
+    }
+}
+
+
+class _TakeIterable<E> extends Iterable<E> {
+    Iterable<E> _source;
+    int _count;
+
+    _TakeIterable(this._source, this._count);
+
+    Iterator get iterator {
+        return _TakeIterator<E>(_source.iterator, _count);
+    }
+}
+
+
+class _TakeIterator<E> extends Iterator<E> {
+    Iterator<E> _source;
+    int _remaining;
+    E? current;
+
+    _TakeIterator(this._source, this._remaining);
+
+    bool moveNext() {
+        _remaining = _remaining - 1;
+        if (_remaining >= 0 && _source.moveNext()) {
+            current = _source.current;
+            return true;
+        }
+        _remaining = -1;
+        current = null;
+        return false;
+    }
+}
+
+
+class _SkipIterable<E> extends Iterable<E> {
+    Iterable<E> _source;
+    int _count;
+
+    _SkipIterable(this._source, this._count);
+
+    Iterator get iterator {
+        return _SkipIterator<E>(_source.iterator, _count);
+    }
+}
+
+
+class _SkipIterator<E> extends Iterator<E> {
+    Iterator<E> _source;
+    int _skipCount;
+    E? current;
+
+    _SkipIterator(this._source, this._skipCount);
+
+    bool moveNext() {
+        while (_skipCount > 0) {
+            _skipCount = _skipCount - 1;
+            _source.moveNext();
+        }
+        if (_source.moveNext()) {
+            current = _source.current;
+            return true;
+        }
+        current = null;
+        return false;
+    }
+}
+
+
+class _TakeWhileIterable<E> extends Iterable<E> {
+    Iterable<E> _source;
+    Function _f;
+
+    _TakeWhileIterable(this._source, this._f);
+
+    Iterator get iterator {
+        return _TakeWhileIterator<E>(_source.iterator, _f);
+    }
+}
+
+
+class _TakeWhileIterator<E> extends Iterator<E> {
+    Iterator<E> _source;
+    Function _f;
+    bool _done = false;
+    E? current;
+
+    _TakeWhileIterator(this._source, this._f);
+
+    bool moveNext() {
+        var f = _f;
+        if (!_done && _source.moveNext() && f(_source.current)) {
+            current = _source.current;
+            return true;
+        }
+        _done = true;
+        current = null;
+        return false;
+    }
+}
+
+
+class _SkipWhileIterable<E> extends Iterable<E> {
+    Iterable<E> _source;
+    Function _f;
+
+    _SkipWhileIterable(this._source, this._f);
+
+    Iterator get iterator {
+        return _SkipWhileIterator<E>(_source.iterator, _f);
+    }
+}
+
+
+class _SkipWhileIterator<E> extends Iterator<E> {
+    Iterator<E> _source;
+    Function _f;
+    bool _skipping = true;
+    E? current;
+
+    _SkipWhileIterator(this._source, this._f);
+
+    bool moveNext() {
+        var f = _f;
+        while (_source.moveNext()) {
+            if (!_skipping || !f(_source.current)) {
+                _skipping = false;
+                current = _source.current;
+                return true;
+            }
+        }
+        current = null;
+        return false;
+    }
+}
+
+
+class _FollowedByIterable<E> extends Iterable<E> {
+    Iterable<E> _first;
+    Iterable<E> _second;
+
+    _FollowedByIterable(this._first, this._second);
+
+    Iterator get iterator {
+        return _FollowedByIterator<E>(_first.iterator, _second);
+    }
+}
+
+
+class _FollowedByIterator<E> extends Iterator<E> {
+    Iterator<E> _current;
+    Iterable<E>? _next;
+    E? current;
+
+    _FollowedByIterator(this._current, this._next);
+
+    bool moveNext() {
+        if (_current.moveNext()) {
+            current = _current.current;
+            return true;
+        }
+        if (_next != null) {
+            _current = _next!.iterator;
+            _next = null;
+            if (_current.moveNext()) {
+                current = _current.current;
+                return true;
+            }
+        }
+        current = null;
+        return false;
+    }
+}
+
+
+// The Iterable returned by a sync* function.
+class _SyncStarIterable<E> extends Iterable<E> {
+    __InternalGenerator __generator;
+
+    Iterator get iterator {
+        return __SYNCSTAR_ITERATOR(__generator);
+    }
+}
+
+
+class _SyncStarIterator<E> extends Iterator<E> {
+    __InternalGenerator __generator;
+    E? current;
+
+    // Runs the body of the sync* function to the next yield.
+    bool moveNext() {
+        return __SYNCSTAR_MOVENEXT(this);
+    }
+}
@@ -1,5 +1,5 @@
 
-class List {
+class List<E> extends Iterable<E> {
     __InternalList __list;
 
 
@@ -21,32 +21,42 @@ class List {
     }
 
 
+    // A new Iterator that allows iterating the elements of this list.
+    Iterator<E> get iterator {
+        return _ListIterator<E>(this);
+    }
+
+
+    // An Iterable of the objects in this list in reverse order.
+    Iterable<E> get reversed {
+        return _ReversedListIterable<E>(this);
+    }
+
+
     // Adds value to the end of this list, extending the length by one. 
-    void add(el) {
-        __LIST_ADD(__list, el);
+    void add(E value) {
+        __LIST_ADD(__list, value);
     }
 
 
     // Appends all objects of iterable to the end of this list.
-    //
-    // TODO
-    // void addAll(Iterable<E> iterable) {
-    void addAll(List iterable) {
+    void addAll(Iterable<E> iterable) {
         __LIST_ADDALL(__list, iterable);
     }
 
 
-    // An unmodifiable Map view of this list. 
-    // Map<int, E> asMap() {
-    //     assert(false, "Not implemented: List.asMap()");
-    // }
-
-
-    // Returns a view of this list as a list of R instances.
-    // override
-    // List<R> cast<R>() {
-    //     assert(false, "Not implemented: List.cast<R>()");
-    // }
+    // A Map of the indices of this list to its elements. Unlike in Dart,
+    // it is a copy rather than a view, so later changes to the list
+    // don't show in it.
+    Map<int, E> asMap() {
+        Map<int, E> map = Map<int, E>();
+        int i = 0;
+        for (E element in this) {
+            map[i] = element;
+            i++;
+        }
+        return map;
+    }
 
 
     // Removes all objects from this list; the length of the list becomes zero. 
@@ -56,27 +66,29 @@ class List {
 
 
     // Overwrites a range of elements with fillValue. 
-    // void fillRange(int start, int end, [E? fillValue]) {
-    //     assert(false, "Not implemented: List.fillRange()");
-    // }
+    void fillRange(int start, int end, [E? fillValue]) {
+        __LIST_FILLRANGE(__list, start, end, fillValue);
+    }
 
 
     // Creates an Iterable that iterates over a range of elements. 
-    // Iterable<E> getRange(int start, int end) {
-    //     assert(false, "Not implemented: List.getRange()");
-    // }
+    Iterable<E> getRange(int start, int end) {
+        _checkInRange("start", start, 0, length);
+        _checkInRange("end", end, start, length);
+        return skip(start).take(end - start);
+    }
 
 
     // The first index of element in this list. 
-    // int indexOf(E element, [int start = 0]) {
-    //     assert(false, "Not implemented: List.indexOf()");
-    // }
+    int indexOf(E element, [int start = 0]) {
+        return __LIST_INDEXOF(__list, element, start);
+    }
 
 
     // The first index in the list that satisfies the provided test. 
-    // int indexWhere(bool test(E element), [int start = 0]) {
-    //     assert(false, "Not implemented: List.indexWhere()");
-    // }
+    int indexWhere(bool test(E element), [int start = 0]) {
+        return __LIST_INDEXWHERE(__list, test, start);
+    }
 
 
     // Inserts element at position index in this list. 
@@ -86,32 +98,32 @@ class List {
 
 
     // Inserts all objects of iterable at position index in this list. 
-    // void insertAll(int index, Iterable<E> iterable) {
-    //     assert(false, "Not implemented: List.insertAll()");
-    // }
+    void insertAll(int index, Iterable<E> iterable) {
+        __LIST_INSERTALL(__list, index, iterable);
+    }
 
 
     // The last index of element in this list. 
-    // int lastIndexOf(E element, [int? start]) {
-    //     assert(false, "Not implemented: List.lastIndexOf()");
-    // }
+    int lastIndexOf(E element, [int? start]) {
+        return __LIST_LASTINDEXOF(__list, element, start);
+    }
 
 
     // The last index in the list that satisfies the provided test. 
-    // int lastIndexWhere(bool test(E element), [int? start]) {
-    //     assert(false, "Not implemented: List.lastIndexWhere()");
-    // }
+    int lastIndexWhere(bool test(E element), [int? start]) {
+        return __LIST_LASTINDEXWHERE(__list, test, start);
+    }
 
 
     // Removes the first occurrence of value from this list. 
-    // bool remove(Object? value) {
-    //     assert(false, "Not implemented: List.remove()");
-    // }
+    bool remove(Object? value) {
+        return __LIST_REMOVE(__list, value);
+    }
 
 
     // Removes the object at position index from this list. 
     E removeAt(int index) {
-        __LIST_REMOVERANGE(__list, index, index + 1);
+        return __LIST_REMOVEAT(__list, index);
     }
 
     // Removes and returns the last object in this list. 
@@ -127,54 +139,52 @@ class List {
 
 
     // Removes all objects from this list that satisfy test. 
-    // void removeWhere(bool test(E element)) {
-    //     assert(false, "Not implemented: List.removeWhere()");
-    // }
+    void removeWhere(bool test(E element)) {
+        __LIST_REMOVEWHERE(__list, test);
+    }
 
 
     // Replaces a range of elements with the elements of replacements. 
-    // void replaceRange(int start, int end, Iterable<E> replacements) {
-    //     assert(false, "Not implemented: List.replaceRange()");
-    // }
+    void replaceRange(int start, int end, Iterable<E> replacements) {
+        __LIST_REPLACERANGE(__list, start, end, replacements);
+    }
 
 
     // Removes all objects from this list that fail to satisfy test. 
-    // void retainWhere(bool test(E element)) {
-    //     assert(false, "Not implemented: List.retainWhere()");
-    // }
+    void retainWhere(bool test(E element)) {
+        __LIST_RETAINWHERE(__list, test);
+    }
 
 
     // Overwrites elements with the objects of iterable. 
-    // void setAll(int index, Iterable<E> iterable) {
-    //     assert(false, "Not implemented: List.setAll()");
-    // }
+    void setAll(int index, Iterable<E> iterable) {
+        __LIST_SETALL(__list, index, iterable);
+    }
 
 
     // Writes some elements of iterable into a range of this list. 
-    // void setRange(int start, int end, Iterable<E> iterable, [int skipCount = 0]) {
-    //     assert(false, "Not implemented: List.setRange()");
-    // }
+    void setRange(int start, int end, Iterable<E> iterable, [int skipCount = 0]) {
+        __LIST_SETRANGE(__list, start, end, iterable, skipCount);
+    }
 
 
-    // Shuffles the elements of this list randomly.
-    // 
-    // TODO
-    // void shuffle([Random? random]) {
+    // Shuffles the elements of this list randomly. Unlike in Dart,
+    // no Random can be given, as dart:math is not imported here.
     void shuffle() {
         __LIST_SHUFFLE(__list);
     }
 
 
     // Sorts this list according to the order specified by the compare function. 
-    // void sort([int compare(E a, E b)?]) {
-    //     assert(false, "Not implemented: List.sort()");
-    // }
+    void sort([int compare(E a, E b)?]) {
+        __LIST_SORT(__list, compare);
+    }
 
 
     // Returns a new list containing the elements between start and end. 
-    // List<E> sublist(int start, [int? end]) {
-    //     assert(false, "Not implemented: List.sublist()");
-    // }
+    List<E> sublist(int start, [int? end]) {
+        return __LIST_SUBLIST(__list, start, end);
+    }
 
 
     // Return a string representation of the list.
@@ -183,9 +193,74 @@ class List {
     }
 
 
-    // Creates a new lazy Iterable with all elements that have type T. 
-    // Iterable<T> whereType<T>() {
-    //     assert(false, "Not implemented: List.whereType<T>()");
-    // }
+    void _checkInRange(String name, int value, int min, int max) {
+        if (value < min || value > max) {
+            __CORE_THROW("RangeError ($name): Invalid value: Not in inclusive range $min..$max: $value");
+        }
+    }
+}
+
+
+
+
+class _ListIterator<E> extends Iterator<E> {
+    List<E> _list;
+    int _length;
+    int _index = 0;
+    E? current;
+
+    _ListIterator(List<E> list) : _list = list, _length = list.length;
+
+    bool moveNext() {
+        if (!(_list.length == _length)) {
+            __CORE_THROW("Concurrent modification during iteration: Instance(length:${_list.length}) of '_GrowableList'.");
+        }
+        if (_index >= _length) {
+            current = null;
+            return false;
+        }
+        current = _list[_index];
+        _index = _index + 1;
+        return true;
+    }
+}
+
+
+// The elements of a list from the last to the first,
+// read from the list as they are iterated.
+class _ReversedListIterable<E> extends Iterable<E> {
+    List<E> _list;
+
+    _ReversedListIterable(this._list);
+
+    Iterator<E> get iterator {
+        return _ReversedListIterator<E>(_list);
+    }
+
+    int get length {
+        return _list.length;
+    }
 }
 
+
+class _ReversedListIterator<E> extends Iterator<E> {
+    List<E> _list;
+    int _length;
+    int _index;
+    E? current;
+
+    _ReversedListIterator(List<E> list) : _list = list, _length = list.length, _index = list.length;
+
+    bool moveNext() {
+        if (!(_list.length == _length)) {
+            __CORE_THROW("Concurrent modification during iteration: Instance(length:${_list.length}) of '_GrowableList'.");
+        }
+        if (_index <= 0) {
+            current = null;
+            return false;
+        }
+        _index = _index - 1;
+        current = _list[_index];
+        return true;
+    }
+}
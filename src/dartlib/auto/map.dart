@@ -0,0 +1,196 @@
+// A collection of key/value pairs, from which a value is retrieved using its key.
+//
+// The keys are kept in the order they were first added, as in the
+// LinkedHashMap of dart. Keys are found by their hashCode and compared
+// with ==, through the slots of a hash table, which are rebuilt as the
+// map grows or loses a key.
+class Map<K, V> {
+    List<K> __keys = <K>[];
+    List<V> __values = <V>[];
+    List<int> __slots = <int>[];
+
+
+    // Creates an empty Map.
+    Map();
+
+
+    // The value for the given key, or null if key is not in the map.
+    V? operator [](Object? key) {
+        int i = __MAP_FIND(this, key);
+        if (i < 0) {
+            return null;
+        }
+        return __values[i];
+    }
+
+
+    // Associates the key with the given value.
+    void operator []=(K key, V value) {
+        __put(key, value);
+    }
+
+
+    void __put(K key, V value) {
+        int i = __MAP_FIND(this, key);
+        if (i >= 0) {
+            __values[i] = value;
+            return;
+        }
+        __keys.add(key);
+        __values.add(value);
+        __slots[-1 - i] = __keys.length;
+        if (__keys.length * 2 > __slots.length) {
+            __MAP_REHASH(this);
+        }
+    }
+
+
+    // The number of key/value pairs in the map.
+    int get length {
+        return __keys.length;
+    }
+
+
+    // Whether there is no key/value pair in the map.
+    bool get isEmpty {
+        return __keys.isEmpty;
+    }
+
+
+    // Whether there is at least one key/value pair in the map.
+    bool get isNotEmpty {
+        return __keys.isNotEmpty;
+    }
+
+
+    // The keys of this Map, in the order they were added.
+    Iterable<K> get keys {
+        return _MapIterable<K>(__keys.toList());
+    }
+
+
+    // The values of this Map, in the order of their keys.
+    Iterable<V> get values {
+        return _MapIterable<V>(__values.toList());
+    }
+
+
+    // The map entries of this Map.
+    Iterable<MapEntry<K, V>> get entries {
+        var entries = <MapEntry<K, V>>[];
+        for (int i = 0; i < __keys.length; i++) {
+            entries.add(MapEntry<K, V>(__keys[i], __values[i]));
+        }
+        return _MapIterable<MapEntry<K, V>>(entries);
+    }
+
+
+    // Whether this map contains the given key.
+    bool containsKey(Object? key) {
+        return __MAP_FIND(this, key) >= 0;
+    }
+
+
+    // Whether this map contains the given value.
+    bool containsValue(Object? value) {
+        return __values.indexOf(value) >= 0;
+    }
+
+
+    // Look up the value of key, or add a new entry if it isn't there.
+    V putIfAbsent(K key, V ifAbsent()) {
+        int i = __MAP_FIND(this, key);
+        if (i >= 0) {
+            return __values[i];
+        }
+        V value = ifAbsent();
+        __put(key, value);
+        return value;
+    }
+
+
+    // Adds all key/value pairs of other to this map.
+    void addAll(Map<K, V> other) {
+        for (int i = 0; i < other.__keys.length; i++) {
+            __put(other.__keys[i], other.__values[i]);
+        }
+    }
+
+
+    // Removes key and its associated value, if present, from the map.
+    V? remove(Object? key) {
+        int i = __MAP_FIND(this, key);
+        if (i < 0) {
+            return null;
+        }
+        __keys.removeAt(i);
+        V value = __values.removeAt(i);
+        __MAP_REHASH(this);
+        return value;
+    }
+
+
+    // Removes all entries from the map.
+    void clear() {
+        __keys.clear();
+        __values.clear();
+        __slots.clear();
+    }
+
+
+    // Applies action to each key/value pair of the map.
+    void forEach(void action(K key, V value)) {
+        for (int i = 0; i < __keys.length; i++) {
+            action(__keys[i], __values[i]);
+        }
+    }
+
+
+    // Return a string representation of the map.
+    String toString() {
+        var buffer = StringBuffer();
+        buffer.write("{");
+        for (int i = 0; i < __keys.length; i++) {
+            if (i > 0) {
+                buffer.write(", ");
+            }
+            buffer.write("${__keys[i]}: ${__values[i]}");
+        }
+        buffer.write("}");
+        return buffer.toString();
+    }
+}
+
+
+
+
+// A key/value pair representing an entry in a Map.
+class MapEntry<K, V> {
+    K key;
+    V value;
+
+    // Creates an entry with key and value.
+    MapEntry(this.key, this.value);
+
+
+    String toString() {
+        return "MapEntry($key: $value)";
+    }
+}
+
+
+
+// The keys, values or entries of a Map, as they were when asked for.
+class _MapIterable<E> extends Iterable<E> {
+    List<E> _elements;
+
+    _MapIterable(this._elements);
+
+    Iterator<E> get iterator {
+        return _elements.iterator;
+    }
+
+    int get length {
+        return _elements.length;
+    }
+}
@@ -0,0 +1,85 @@
+// The base class for all Dart objects except null.
+//
+// Classes declared without extends inherit from Object.
+class Object {
+
+    Object();
+
+
+    // Whether this and other are equal. By default, only the same object is.
+    bool operator ==(Object other) {
+        return identical(this, other);
+    }
+
+
+    // A hash code for this object, the same for objects that are equal.
+    int get hashCode {
+        return identityHashCode(this);
+    }
+
+
+    // A representation of the runtime type of the object.
+    Type get runtimeType {
+        return __CORE_RUNTIME_TYPE(this);
+    }
+
+
+    // A string representation of this object.
+    String toString() {
+        return "Instance of '" + runtimeType.toString() + "'";
+    }
+
+
+    // Invoked when a nonexistent method or property is accessed,
+    // as through a dynamic receiver. By default it throws.
+    dynamic noSuchMethod(Invocation invocation) {
+        __CORE_NO_SUCH_METHOD(this, invocation);
+    }
+}
+
+
+// The name of a member, like the memberName of an Invocation.
+class Symbol {
+    String __name;
+
+
+    Symbol(this.__name);
+
+
+    bool operator ==(Object other) {
+        if (other is Symbol) {
+            return __name == other.__name;
+        }
+        return false;
+    }
+
+
+    int get hashCode {
+        return identityHashCode(__name);
+    }
+
+
+    String toString() {
+        return 'Symbol("' + __name + '")';
+    }
+}
+
+
+// An invocation of a member that the receiver lacks, as given to noSuchMethod.
+class Invocation {
+    Symbol memberName;
+    List<Object?> positionalArguments;
+    Map<Symbol, Object?> namedArguments;
+    bool isMethod;
+    bool isGetter;
+    bool isSetter;
+
+
+    Invocation(this.memberName, this.positionalArguments, this.namedArguments, this.isMethod, this.isGetter, this.isSetter);
+
+
+    // Whether the invocation was a getter or a setter.
+    bool get isAccessor {
+        return isGetter || isSetter;
+    }
+}
@@ -0,0 +1,128 @@
+class RegExp {
+    __InternalRegExp __regexp;
+    late String pattern;
+    late bool isMultiLine;
+    late bool isCaseSensitive;
+    late bool isUnicode;
+    late bool isDotAll;
+
+
+    // Constructs a regular expression.
+    RegExp(String source, {bool multiLine = false, bool caseSensitive = true, bool unicode = false, bool dotAll = false}) {
+        // This sets __regexp, pattern and the flag fields.
+        __REGEXP_CONSTRUCT(this, source, multiLine, caseSensitive, unicode, dotAll);
+    }
+
+
+    // Whether the regular expression has a match in the string input.
+    bool hasMatch(String input) {
+        return __REGEXP_HASMATCH(__regexp, input);
+    }
+
+
+    // Finds the first match of the regular expression in the string input.
+    //
+    // Returns null if there is no match.
+    RegExpMatch? firstMatch(String input) {
+        return __REGEXP_FIRSTMATCH(this, input);
+    }
+
+
+    // Matches this pattern against the string repeatedly. The
+    // matches are found one at a time, as they are iterated.
+    Iterable<RegExpMatch> allMatches(String input, [int start = 0]) {
+        __REGEXP_CHECKSTART(input, start);
+        return _RegExpMatches(this, input, start);
+    }
+
+
+    // Returns the first substring match of this regular expression in input.
+    String? stringMatch(String input) {
+        return __REGEXP_STRINGMATCH(__regexp, input);
+    }
+
+
+    String toString() {
+        return __REGEXP_TOSTRING(this);
+    }
+}
+
+
+class RegExpMatch {
+    late String input;
+    late RegExp pattern;
+    late int start;
+    late int end;
+    late List __groups;
+    late List __names;
+
+
+    // Returns the number of captured groups in the match.
+    int get groupCount {
+        return __groups.length - 1;
+    }
+
+
+    // The names of the named capture groups of pattern.
+    //
+    // TODO
+    // Iterable<String> get groupNames {
+    List get groupNames {
+        return __REGEXPMATCH_GET_GROUPNAMES(this);
+    }
+
+
+    // The string matched by the given group.
+    //
+    // Group 0 is the entire match. Returns null for a group that did not participate.
+    String? group(int group) {
+        return __REGEXPMATCH_GROUP(this, group);
+    }
+
+
+    // The string captured by the named capture group name.
+    String? namedGroup(String name) {
+        return __REGEXPMATCH_NAMEDGROUP(this, name);
+    }
+}
+
+
+class _RegExpMatches extends Iterable<RegExpMatch> {
+    RegExp _regexp;
+    String _input;
+    int _start;
+
+    _RegExpMatches(this._regexp, this._input, this._start);
+
+    Iterator<RegExpMatch> get iterator {
+        return _RegExpMatchIterator(_regexp, _input, _start);
+    }
+}
+
+
+class _RegExpMatchIterator extends Iterator<RegExpMatch> {
+    RegExp _regexp;
+    String _input;
+    int _pos;
+    bool _afterEmpty = false;
+    bool _done = false;
+    RegExpMatch? current;
+
+    _RegExpMatchIterator(this._regexp, this._input, this._pos);
+
+    // After an empty match, the next one is looked for from the next character.
+    bool moveNext() {
+        if (_done) {
+            return false;
+        }
+        RegExpMatch? m = __REGEXP_NEXTMATCH(_regexp, _input, _pos, _afterEmpty);
+        current = m;
+        if (m == null) {
+            _done = true;
+            return false;
+        }
+        _pos = m.end;
+        _afterEmpty = m.start == m.end;
+        return true;
+    }
+}
@@ -0,0 +1,117 @@
+// A collection of objects in which each object can occur only once.
+//
+// The elements are kept in the order they were first added, as in the
+// LinkedHashSet of dart. The elements are the keys of a Map, where they
+// are found by their hashCode and compared with ==.
+class Set<E> extends Iterable<E> {
+    Map<E, bool> __map = <E, bool>{};
+
+
+    // Creates an empty Set.
+    Set();
+
+
+    // A new Iterator that allows iterating the elements of this Set.
+    Iterator<E> get iterator {
+        return __map.__keys.iterator;
+    }
+
+
+    // The number of elements in the set.
+    int get length {
+        return __map.length;
+    }
+
+
+    // Whether value is in the set.
+    bool contains(Object? value) {
+        return __map.containsKey(value);
+    }
+
+
+    // Adds value to the set. Returns whether it was not already in it.
+    bool add(E value) {
+        if (contains(value)) {
+            return false;
+        }
+        __map[value] = true;
+        return true;
+    }
+
+
+    // Adds all elements to this set.
+    void addAll(Iterable<E> elements) {
+        for (E element in elements) {
+            add(element);
+        }
+    }
+
+
+    // Removes value from the set. Returns whether it was in the set.
+    bool remove(Object? value) {
+        return __map.remove(value) != null;
+    }
+
+
+    // Removes all elements from the set.
+    void clear() {
+        __map.clear();
+    }
+
+
+    // Whether this set contains all the elements of other.
+    bool containsAll(Iterable<Object?> other) {
+        for (var element in other) {
+            if (!contains(element)) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+
+    // A new set with the elements of this and other.
+    Set<E> union(Set<E> other) {
+        Set<E> result = toSet();
+        result.addAll(other);
+        return result;
+    }
+
+
+    // A new set with the elements of this that are also in other.
+    Set<E> intersection(Set<Object?> other) {
+        Set<E> result = Set<E>();
+        for (E element in this) {
+            if (other.contains(element)) {
+                result.add(element);
+            }
+        }
+        return result;
+    }
+
+
+    // A new set with the elements of this that are not in other.
+    Set<E> difference(Set<Object?> other) {
+        Set<E> result = Set<E>();
+        for (E element in this) {
+            if (!other.contains(element)) {
+                result.add(element);
+            }
+        }
+        return result;
+    }
+
+
+    // Creates a Set with the same elements as this.
+    Set<E> toSet() {
+        Set<E> result = Set<E>();
+        result.__map.addAll(__map);
+        return result;
+    }
+
+
+    // Return a string representation of the set.
+    String toString() {
+        return "{" + __map.__keys.join(", ") + "}";
+    }
+}
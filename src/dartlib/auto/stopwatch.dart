@@ -0,0 +1,81 @@
+// A stopwatch which measures time while it's running.
+//
+// Time is read from the clock of the event loop, so with a virtual
+// clock, only timers make time pass.
+class Stopwatch {
+    // The time counted before the stopwatch was last started.
+    int __elapsed = 0;
+    // The clock when the stopwatch was started, while it runs.
+    int? __start;
+
+
+    Stopwatch();
+
+
+    // The frequency of the elapsed counter in Hz.
+    int get frequency {
+        return 1000000;
+    }
+
+
+    // Starts the Stopwatch.
+    void start() {
+        if (__start == null) {
+            __start = __CORE_CLOCK();
+        }
+    }
+
+
+    // Stops the Stopwatch.
+    void stop() {
+        int? started = __start;
+        if (started != null) {
+            __elapsed = __elapsed + __CORE_CLOCK() - started!;
+            __start = null;
+        }
+    }
+
+
+    // Resets the elapsed count to zero.
+    void reset() {
+        __elapsed = 0;
+        if (__start != null) {
+            __start = __CORE_CLOCK();
+        }
+    }
+
+
+    // The elapsed number of clock ticks since calling start while the
+    // Stopwatch is running.
+    int get elapsedTicks {
+        int? started = __start;
+        if (started == null) {
+            return __elapsed;
+        }
+        return __elapsed + __CORE_CLOCK() - started!;
+    }
+
+
+    // The elapsedTicks counter converted to a Duration.
+    Duration get elapsed {
+        return Duration(microseconds: elapsedTicks);
+    }
+
+
+    // The elapsedTicks counter converted to microseconds.
+    int get elapsedMicroseconds {
+        return elapsedTicks;
+    }
+
+
+    // The elapsedTicks counter converted to milliseconds.
+    int get elapsedMilliseconds {
+        return elapsedTicks ~/ 1000;
+    }
+
+
+    // Whether the Stopwatch is currently running.
+    bool get isRunning {
+        return __start != null;
+    }
+}
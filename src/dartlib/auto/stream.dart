@@ -0,0 +1,672 @@
+// A source of asynchronous events.
+//
+// A stream delivers data events, error events and a last done event to
+// the one subscription listening to it. The events are added through
+// the StreamController of the stream, and delivered one at a time, as
+// microtasks. Events added before the stream is listened to, or while
+// the subscription is paused, are kept until they can be delivered.
+class Stream<T> {
+    StreamController<T> __controller;
+
+    Stream(this.__controller);
+
+
+    // Adds a subscription to this stream.
+    StreamSubscription<T> listen(Function? onData, {Function? onError, Function? onDone, bool? cancelOnError}) {
+        StreamController<T> controller = __controller;
+        if (controller.__subscription != null) {
+            __CORE_THROW("Bad state: Stream has already been listened to.");
+        }
+        StreamSubscription<T> subscription = StreamSubscription<T>(controller);
+        subscription.__onData = onData;
+        subscription.__onError = onError;
+        subscription.__onDone = onDone;
+        subscription.__cancelOnError = cancelOnError == true;
+        controller.__subscription = subscription;
+        controller.__hook(controller.onListen);
+        controller.__schedule();
+        return subscription;
+    }
+
+
+    // Transforms each element of this stream into a new stream event.
+    Stream<R> map<R>(Function convert) {
+        StreamController<R> out = StreamController<R>();
+        _StreamTransform transform = _StreamTransform(this, out, "map");
+        transform.f = convert;
+        return out.stream;
+    }
+
+
+    // Creates a new stream from this stream that discards some elements.
+    Stream<T> where(Function test) {
+        StreamController<T> out = StreamController<T>();
+        _StreamTransform transform = _StreamTransform(this, out, "where");
+        transform.f = test;
+        return out.stream;
+    }
+
+
+    // Provides at most the first count data events of this stream.
+    Stream<T> take(int count) {
+        StreamController<T> out = StreamController<T>();
+        _StreamTransform transform = _StreamTransform(this, out, "take");
+        transform.count = count;
+        return out.stream;
+    }
+
+
+    // Collects all elements of this stream in a List.
+    Future<List<T>> toList() async {
+        List<T> values = [];
+        await for (T value in this) {
+            values.add(value);
+        }
+        return values;
+    }
+
+
+    // The first element of this stream.
+    Future<T> get first async {
+        StreamIterator<T> iterator = StreamIterator<T>(this);
+        if (!await iterator.moveNext()) {
+            __CORE_THROW("Bad state: No element");
+        }
+        T value = iterator.current as T;
+        await iterator.cancel();
+        return value;
+    }
+
+
+    // Executes action on each element of this stream.
+    Future forEach(Function action) async {
+        await for (T value in this) {
+            action(value);
+        }
+    }
+}
+
+
+// A controller with the stream it controls.
+//
+// The on-callbacks are called as the stream is listened to, and as
+// its subscription is paused, resumed and cancelled.
+class StreamController<T> {
+    late Stream<T> stream;
+    Function? onListen;
+    Function? onPause;
+    Function? onResume;
+    Function? onCancel;
+    bool isClosed = false;
+    StreamSubscription<T>? __subscription;
+    // Events waiting to be delivered.
+    List<_StreamEvent> __queue = [];
+    // Whether a delivery is queued as a microtask.
+    bool __scheduled = false;
+    // Completed once the done event is delivered, or the
+    // subscription is cancelled.
+    Future __done = __ASYNC_FUTURE();
+
+
+    StreamController({this.onListen, this.onPause, this.onResume, this.onCancel}) {
+        stream = Stream<T>(this);
+    }
+
+
+    // Whether there is a subscription on the stream.
+    bool get hasListener {
+        StreamSubscription<T>? subscription = __subscription;
+        return subscription != null && !subscription.__cancelled;
+    }
+
+
+    // Whether the subscription would have to buffer events.
+    bool get isPaused {
+        StreamSubscription<T>? subscription = __subscription;
+        return subscription == null || subscription.isPaused;
+    }
+
+
+    // Sends a data event.
+    void add(T event) {
+        __add(_StreamEvent(0, event));
+    }
+
+
+    // Sends an error event.
+    void addError(Object error) {
+        __add(_StreamEvent(1, error));
+    }
+
+
+    // Closes the stream. The future completes once the done
+    // event has been delivered, or the subscription cancelled.
+    Future close() {
+        if (!isClosed) {
+            __queue.add(_StreamEvent(2, null));
+            isClosed = true;
+            __schedule();
+        }
+        return __done;
+    }
+
+
+    void __add(_StreamEvent event) {
+        if (isClosed) {
+            __CORE_THROW("Bad state: Cannot add event after closing");
+        }
+        __queue.add(event);
+        __schedule();
+    }
+
+
+    // Queues delivery of the next event, if it can be delivered.
+    void __schedule() {
+        StreamSubscription<T>? subscription = __subscription;
+        if (__scheduled || __queue.isEmpty || subscription == null) {
+            return;
+        }
+        if (subscription.isPaused || subscription.__cancelled) {
+            return;
+        }
+        __scheduled = true;
+        __ASYNC_MICROTASK(__deliver);
+    }
+
+
+    void __deliver() {
+        __scheduled = false;
+        StreamSubscription<T> subscription = __subscription!;
+        if (subscription.isPaused || subscription.__cancelled || __queue.isEmpty) {
+            return;
+        }
+        _StreamEvent event = __queue.removeAt(0);
+        subscription.__dispatch(event);
+        __schedule();
+    }
+
+
+    void __finish() {
+        if (__done.__state == 0) {
+            __done.__complete(null);
+        }
+    }
+
+
+    void __hook(Function? callback) {
+        if (callback != null) {
+            Function f = callback!;
+            f();
+        }
+    }
+}
+
+
+// A subscription on events from a Stream.
+class StreamSubscription<T> {
+    StreamController<T> __controller;
+    Function? __onData;
+    Function? __onError;
+    Function? __onDone;
+    bool __cancelOnError = false;
+    int __pauses = 0;
+    bool __cancelled = false;
+
+    StreamSubscription(this.__controller);
+
+
+    // Whether the subscription is currently paused.
+    bool get isPaused {
+        return __pauses > 0;
+    }
+
+
+    // Replaces the data event handler of this subscription.
+    void onData(Function? handleData) {
+        __onData = handleData;
+    }
+
+
+    // Replaces the error event handler of this subscription.
+    void onError(Function? handleError) {
+        __onError = handleError;
+    }
+
+
+    // Replaces the done event handler of this subscription.
+    void onDone(Function? handleDone) {
+        __onDone = handleDone;
+    }
+
+
+    // Requests that the stream pauses events until further notice.
+    // Given a future, resumes once it completes.
+    void pause([Future? resumeSignal]) {
+        if (__cancelled) {
+            return;
+        }
+        __pauses = __pauses + 1;
+        if (__pauses == 1) {
+            __controller.__hook(__controller.onPause);
+        }
+        if (resumeSignal != null) {
+            Future signal = resumeSignal!;
+            signal.whenComplete(resume);
+        }
+    }
+
+
+    // Resumes after a pause.
+    void resume() {
+        if (__cancelled || __pauses == 0) {
+            return;
+        }
+        __pauses = __pauses - 1;
+        if (__pauses == 0) {
+            __controller.__hook(__controller.onResume);
+            __controller.__schedule();
+        }
+    }
+
+
+    // Cancels this subscription. No further events are delivered.
+    Future cancel() {
+        if (!__cancelled) {
+            __cancelled = true;
+            __controller.__queue = [];
+            __controller.__finish();
+            __controller.__hook(__controller.onCancel);
+        }
+        return Future.value();
+    }
+
+
+    void __dispatch(_StreamEvent event) {
+        if (event.kind == 0) {
+            if (__onData != null) {
+                Function f = __onData!;
+                f(event.value);
+            }
+        }
+        else if (event.kind == 1) {
+            if (__onError == null) {
+                // Not handled, so reported as for a future.
+                __CORE_THROW("${event.value}");
+            }
+            Function f = __onError!;
+            f(event.value);
+            if (__cancelOnError) {
+                cancel();
+            }
+        }
+        else {
+            __cancelled = true;
+            __controller.__finish();
+            if (__onDone != null) {
+                Function f = __onDone!;
+                f();
+            }
+        }
+    }
+}
+
+
+// An event of a stream. kind is 0 for data, 1 for an error
+// and 2 for done.
+class _StreamEvent {
+    int kind;
+    dynamic value;
+
+    _StreamEvent(this.kind, this.value);
+}
+
+
+// An iterator over the events of a stream, as used by await for.
+//
+// The stream is listened to on the first moveNext, and paused
+// between calls.
+class StreamIterator<T> {
+    Stream<T> __stream;
+    StreamSubscription<T>? __subscription;
+    Future<bool>? __next;
+    // The current element.
+    T? current;
+
+    StreamIterator(this.__stream);
+
+
+    // Waits for the next element of the stream. Completes with
+    // false once the stream is done.
+    Future<bool> moveNext() {
+        Future<bool> next = __ASYNC_FUTURE(bool);
+        __next = next;
+        StreamSubscription<T>? subscription = __subscription;
+        if (subscription == null) {
+            __subscription = __stream.listen(__onData, onError: __onError, onDone: __onDone);
+        }
+        else {
+            subscription.resume();
+        }
+        return next;
+    }
+
+
+    // Cancels the subscription on the stream.
+    Future cancel() {
+        StreamSubscription<T>? subscription = __subscription;
+        if (subscription == null) {
+            return Future.value();
+        }
+        return subscription.cancel();
+    }
+
+
+    void __onData(T value) {
+        StreamSubscription<T> subscription = __subscription!;
+        subscription.pause();
+        current = value;
+        Future<bool> next = __next!;
+        next.__complete(true);
+    }
+
+
+    void __onError(Object error) {
+        StreamSubscription<T> subscription = __subscription!;
+        subscription.cancel();
+        current = null;
+        Future<bool> next = __next!;
+        next.__completeError(error);
+    }
+
+
+    void __onDone() {
+        current = null;
+        Future<bool> next = __next!;
+        next.__complete(false);
+    }
+}
+
+
+// Runs map, where and take on the events of a source stream, adding the
+// results to the controller of a new stream. The source is listened to
+// as the new stream is, and paused, resumed and cancelled with it.
+class _StreamTransform {
+    Stream source;
+    StreamController out;
+    String kind;
+    Function? f;
+    int count = 0;
+    StreamSubscription? subscription;
+
+    _StreamTransform(this.source, this.out, this.kind) {
+        out.onListen = start;
+        out.onPause = pause;
+        out.onResume = resume;
+        out.onCancel = cancel;
+    }
+
+
+    void start() {
+        if (kind == "take" && count <= 0) {
+            out.close();
+            return;
+        }
+        subscription = source.listen(onData, onError: out.addError, onDone: out.close);
+    }
+
+
+    void onData(dynamic value) {
+        Function? g = f;
+        if (kind == "map") {
+            Function convert = g!;
+            out.add(convert(value));
+        }
+        else if (kind == "where") {
+            Function test = g!;
+            if (test(value)) {
+                out.add(value);
+            }
+        }
+        else {
+            out.add(value);
+            count = count - 1;
+            if (count == 0) {
+                cancel();
+                out.close();
+            }
+        }
+    }
+
+
+    void pause() {
+        StreamSubscription s = subscription!;
+        s.pause();
+    }
+
+
+    void resume() {
+        StreamSubscription s = subscription!;
+        s.resume();
+    }
+
+
+    void cancel() {
+        StreamSubscription? s = subscription;
+        if (s != null) {
+            s.cancel();
+        }
+    }
+}
+
+
+// Adds the elements of an iterable to a stream, one at a time, while
+// the stream is listened to and not paused.
+class _IterableFeed {
+    Iterator iterator;
+    StreamController out;
+    // Whether the feed stopped for a pause.
+    bool waiting = false;
+
+    _IterableFeed(this.iterator, this.out) {
+        out.onListen = next;
+        out.onResume = resume;
+    }
+
+
+    void next() {
+        if (!out.hasListener) {
+            return;
+        }
+        if (out.isPaused) {
+            waiting = true;
+            return;
+        }
+        if (iterator.moveNext()) {
+            out.add(iterator.current);
+            __ASYNC_MICROTASK(next);
+        }
+        else {
+            out.close();
+        }
+    }
+
+
+    void resume() {
+        if (waiting) {
+            waiting = false;
+            __ASYNC_MICROTASK(next);
+        }
+    }
+}
+
+
+// Adds an event to a stream each period, while it is listened
+// to and not paused.
+class _PeriodicFeed {
+    int micros;
+    Function? computation;
+    StreamController out;
+    int count = 0;
+    // Whether the feed stopped for a pause.
+    bool waiting = false;
+
+    _PeriodicFeed(this.micros, this.computation, this.out) {
+        out.onListen = start;
+        out.onResume = resume;
+    }
+
+
+    void start() {
+        __ASYNC_TIMER(micros, tick);
+    }
+
+
+    void tick() {
+        if (!out.hasListener) {
+            return;
+        }
+        if (out.isPaused) {
+            waiting = true;
+            return;
+        }
+        Function? g = computation;
+        if (g == null) {
+            out.add(null);
+        }
+        else {
+            Function f = g!;
+            out.add(f(count));
+        }
+        count++;
+        start();
+    }
+
+
+    void resume() {
+        if (waiting) {
+            waiting = false;
+            start();
+        }
+    }
+}
+
+
+// Runs the body of an async* function for its stream. The body starts
+// once the stream is listened to, and runs on after each yield unless
+// the subscription is paused or cancelled.
+class _AsyncStarFeed<T> {
+    __InternalGenerator __coroutine;
+    StreamController<T> out;
+    // Whether the body stopped at a yield for a pause.
+    bool waiting = false;
+
+    _AsyncStarFeed(this.__coroutine, this.out) {
+        out.onListen = start;
+        out.onResume = resume;
+    }
+
+
+    Stream<T> get stream {
+        return out.stream;
+    }
+
+
+    void start() {
+        __ASYNC_MICROTASK(next);
+    }
+
+
+    void next() {
+        // A cancelled body is left where it is.
+        if (out.hasListener) {
+            __ASYNC_RESUME(__coroutine);
+        }
+    }
+
+
+    void resume() {
+        if (waiting) {
+            waiting = false;
+            start();
+        }
+    }
+
+
+    // Called as the body yields.
+    void __yield(T event) {
+        if (!out.hasListener) {
+            return;
+        }
+        out.add(event);
+        if (out.isPaused) {
+            waiting = true;
+        }
+        else {
+            start();
+        }
+    }
+
+
+    // Called as the body returns.
+    void __close() {
+        out.close();
+    }
+
+
+    // Called as an error ends the body.
+    void __fail(Object error) {
+        out.addError(error);
+        out.close();
+    }
+}
+
+
+_AsyncStarFeed<T> _asyncStarFeed<T>(__InternalGenerator body) {
+    return _AsyncStarFeed<T>(body, StreamController<T>());
+}
+
+
+StreamIterator _streamIterator(Stream stream) {
+    return StreamIterator(stream);
+}
+
+
+// The static methods of Stream.
+
+// Stream.fromIterable
+Stream<T> _streamFromIterable<T>(Iterable<T> elements) {
+    StreamController<T> out = StreamController<T>();
+    _IterableFeed(elements.iterator, out);
+    return out.stream;
+}
+
+
+// Stream.periodic
+Stream _streamPeriodic(Duration period, [Function? computation]) {
+    StreamController out = StreamController();
+    _PeriodicFeed(period.inMicroseconds, computation, out);
+    return out.stream;
+}
+
+
+// Stream.value
+Stream _streamValue(dynamic value) {
+    return _streamFromIterable([value]);
+}
+
+
+// Stream.error
+Stream _streamError(Object error) {
+    StreamController out = StreamController();
+    out.addError(error);
+    out.close();
+    return out.stream;
+}
+
+
+// Stream.empty
+Stream _streamEmpty() {
+    StreamController out = StreamController();
+    out.close();
+    return out.stream;
+}
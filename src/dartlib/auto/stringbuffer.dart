@@ -0,0 +1,64 @@
+class StringBuffer {
+    __InternalStringBuffer __buffer;
+
+
+    // Creates a string buffer containing the provided content.
+    StringBuffer([Object content = ""]) {
+        // This sets __buffer
+        __STRINGBUFFER_CONSTRUCT(this, content);
+    }
+
+
+    // Returns the length of the content that has been accumulated so far.
+    int get length {
+        return __STRINGBUFFER_GET_LENGTH(__buffer);
+    }
+
+
+    // Returns whether the buffer is empty.
+    bool get isEmpty {
+        return length == 0;
+    }
+
+
+    // Returns whether the buffer is not empty.
+    bool get isNotEmpty {
+        return !isEmpty;
+    }
+
+
+    // Appends the string representation of object to the buffer.
+    void write(Object? object) {
+        __STRINGBUFFER_WRITE(__buffer, object);
+    }
+
+
+    // Writes all objects separated by separator.
+    void writeAll(Iterable objects, [String separator = ""]) {
+        __STRINGBUFFER_WRITEALL(__buffer, objects, separator);
+    }
+
+
+    // Writes the string representation of obj followed by a newline.
+    void writeln([Object? obj = ""]) {
+        __STRINGBUFFER_WRITELN(__buffer, obj);
+    }
+
+
+    // Writes a string containing the character with code point charCode.
+    void writeCharCode(int charCode) {
+        __STRINGBUFFER_WRITECHARCODE(__buffer, charCode);
+    }
+
+
+    // Clears the string buffer.
+    void clear() {
+        __STRINGBUFFER_CLEAR(__buffer);
+    }
+
+
+    // Returns the contents of buffer as a single string.
+    String toString() {
+        return __STRINGBUFFER_TOSTRING(__buffer);
+    }
+}
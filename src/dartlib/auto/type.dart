@@ -0,0 +1,13 @@
+
+
+class Type {
+    String __name;
+
+
+    Type(this.__name);
+
+
+    String toString() {
+        return __name;
+    }
+}
@@ -0,0 +1,74 @@
+// Support for asynchronous programming. Future and Stream are
+// included in every program, so this only adds Timer.
+
+
+// A countdown timer that can be configured to fire once or repeatedly.
+//
+// A periodic timer fires at multiples of its duration from when it was
+// made. If it falls behind, ticks are skipped rather than run late.
+class Timer {
+    Function __callback;
+    int __micros = 0;
+    int __start = 0;
+    int __id = 0;
+    bool __periodic = false;
+    bool __active = true;
+    // The number of durations preceding the most recent timer event.
+    int tick = 0;
+
+
+    // Creates a new timer. The callback is called once, after duration.
+    Timer(Duration duration, this.__callback) {
+        __micros = duration.inMicroseconds;
+        __start = __CORE_CLOCK();
+        __id = __ASYNC_TIMER(__micros, __fire);
+    }
+
+
+    // Returns whether the timer is still active.
+    bool get isActive {
+        return __active;
+    }
+
+
+    // Cancels the timer.
+    void cancel() {
+        if (__active) {
+            __active = false;
+            __ASYNC_TIMER_CANCEL(__id);
+        }
+    }
+
+
+    void __fire() {
+        Function callback = __callback;
+        if (!__periodic) {
+            __active = false;
+            tick = 1;
+            callback();
+            return;
+        }
+        int elapsed = __CORE_CLOCK() - __start;
+        tick++;
+        if (__micros > 0 && elapsed ~/ __micros > tick) {
+            tick = elapsed ~/ __micros;
+        }
+        // Scheduled before the callback, which may cancel it.
+        __id = __ASYNC_TIMER((tick + 1) * __micros - elapsed, __fire);
+        callback(this);
+    }
+}
+
+
+// Timer.periodic
+Timer _timerPeriodic(Duration duration, Function callback) {
+    Timer timer = Timer(duration, callback);
+    timer.__periodic = true;
+    return timer;
+}
+
+
+// Timer.run
+Timer _timerRun(Function callback) {
+    return Timer(Duration(), callback);
+}
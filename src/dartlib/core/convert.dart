@@ -0,0 +1,93 @@
+// Encoders and decoders for converting between JSON and Dart objects.
+//
+// JSON objects are decoded as Map<String, dynamic> and arrays as
+// List<dynamic>. Numbers without a fraction or exponent are decoded
+// as int, and others as double.
+
+
+// Converts object to a JSON string.
+//
+// Objects that are not numbers, booleans, null, strings, lists or maps
+// with string keys are converted by toEncodable, or without it, by
+// calling their toJson method.
+String jsonEncode(Object? object, {Function? toEncodable}) {
+    return JsonEncoder(toEncodable).convert(object);
+}
+
+
+// Parses the string and returns the resulting Json object.
+//
+// The reviver, if given, is called for each value, with the key or
+// index it has in its map or list, from the inside out. The value it
+// returns takes the place of the original. It is called last for the
+// whole result, with the key null.
+dynamic jsonDecode(String source, {Function? reviver}) {
+    return JsonDecoder(reviver).convert(source);
+}
+
+
+// Converts objects to JSON strings.
+class JsonEncoder {
+    // The string used for indentation, or null for no line breaks at all.
+    String? indent;
+    Function? __toEncodable;
+
+
+    // Creates a JSON encoder, with toEncodable for objects that are not directly encodable.
+    JsonEncoder([Function? toEncodable]) {
+        __toEncodable = toEncodable;
+    }
+
+
+    // Converts object to a JSON string.
+    String convert(Object? object) {
+        return __JSON_ENCODE(object, indent, __toEncodable);
+    }
+}
+
+
+// JsonEncoder.withIndent
+JsonEncoder _jsonEncoderWithIndent(String? indent, [Function? toEncodable]) {
+    JsonEncoder encoder = JsonEncoder(toEncodable);
+    encoder.indent = indent;
+    return encoder;
+}
+
+
+// Converts JSON strings to objects.
+class JsonDecoder {
+    Function? __reviver;
+
+
+    // Creates a JSON decoder, with an optional reviver, as for jsonDecode.
+    JsonDecoder([Function? reviver]) {
+        __reviver = reviver;
+    }
+
+
+    // Converts the given JSON string input to its corresponding object.
+    dynamic convert(String input) {
+        dynamic result = __JSON_DECODE(input);
+        Function? reviver = __reviver;
+        if (reviver == null) {
+            return result;
+        }
+        return _jsonRevive(null, result, reviver!);
+    }
+}
+
+
+// The value as given by the reviver, after the values it holds.
+dynamic _jsonRevive(Object? key, dynamic value, Function reviver) {
+    if (value is List) {
+        for (int i = 0; i < value.length; i++) {
+            value[i] = _jsonRevive(i, value[i], reviver);
+        }
+    }
+    else if (value is Map) {
+        for (var k in value.keys) {
+            value[k] = _jsonRevive(k, value[k], reviver);
+        }
+    }
+    return reviver(key, value);
+}
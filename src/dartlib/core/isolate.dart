@@ -0,0 +1,95 @@
+// Isolates, which run in parallel and share no objects.
+//
+// An isolate runs a function with a message, on a thread of its own,
+// and can only talk to other isolates by sending messages to their
+// ports. Messages are copied, so the receiver gets objects equal to
+// but not identical to those sent.
+
+
+class Isolate {
+
+    Isolate();
+}
+
+
+// Sends messages to the ReceivePort it was made by, which
+// may be in another isolate.
+class SendPort {
+    int __id;
+
+    SendPort(this.__id);
+
+
+    // Sends a copy of message to the ReceivePort.
+    void send(dynamic message) {
+        __ISOLATE_SEND(__id, message);
+    }
+
+
+    bool operator ==(Object other) {
+        return other is SendPort && other.__id == __id;
+    }
+
+
+    int get hashCode {
+        return __id;
+    }
+}
+
+
+// A stream of the messages sent to its sendPort.
+//
+// The isolate is kept alive as long as the port is open.
+class ReceivePort extends Stream<dynamic> {
+    int __id = 0;
+    late SendPort sendPort;
+
+    ReceivePort() : super(StreamController()) {
+        __id = __ISOLATE_OPEN(this);
+        sendPort = SendPort(__id);
+    }
+
+
+    // Closes the port. Messages sent to it later are dropped.
+    void close() {
+        __ISOLATE_CLOSE(__id);
+        __controller.close();
+    }
+
+
+    void __receive(dynamic message) {
+        __controller.add(message);
+    }
+}
+
+
+// Isolate.spawn
+Future<Isolate> _isolateSpawn(Function entryPoint, dynamic message) {
+    __ISOLATE_SPAWN(entryPoint, message);
+    return Future.value(Isolate());
+}
+
+
+// Isolate.run
+//
+// The isolate sends back a list of the result, or if it ends with
+// an unhandled exception, a list of the exception and a stack trace.
+Future _isolateRun(Function computation) async {
+    ReceivePort port = ReceivePort();
+    __ISOLATE_SPAWN(_isolateRunAndSend, [computation, port.sendPort], port.sendPort.__id);
+    List response = await port.first;
+    port.close();
+    if (response.length == 2) {
+        return Future.error(response[0]);
+    }
+    return response[0];
+}
+
+
+// Runs the computation of Isolate.run, and sends back its result.
+void _isolateRunAndSend(List args) async {
+    Function computation = args[0];
+    SendPort port = args[1];
+    dynamic result = await computation();
+    port.send([result]);
+}
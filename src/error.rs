@@ -3,6 +3,7 @@ use crate::object::Object;
 use crate::token::Token;
 use crate::State;
 use crate::node::Node;
+use crate::isolate;
 
 
 pub fn lexerror<S: Into<String>>(msg: S, line: usize, column: usize, filepath: &str) -> ! {
@@ -39,6 +40,30 @@ pub fn evalerror<S: Into<String>>(msg: S, state: &State, node: &Node) -> ! {
 }
 
 
+/// Report a thrown Dart exception and exit.
+///
+/// Exceptions can not be caught yet, so this behaves
+/// like an unhandled exception in Dart. In a spawned
+/// isolate, only the isolate is ended.
+pub fn dartthrow<S: Into<String>>(exception: S, state: &State, node: &Node) -> ! {
+
+    let (linenum, symnum) = node.find_node_position();
+
+    if state.debug {
+        panic!("{}:{}:{}: Unhandled exception:\n{}", state.filepath, linenum, symnum, exception.into());
+    }
+    else if state.spawned {
+        let exception = exception.into();
+        let report = format!("{}:{}:{}: Unhandled exception:\n{}", state.filepath, linenum, symnum, exception);
+        isolate::end(exception, report);
+    }
+    else {
+        println!("{}:{}:{}: Unhandled exception:\n{}", state.filepath, linenum, symnum, exception.into());
+        process::exit(255);
+    }
+}
+
+
 pub fn err_arg_count(
     fname: &str,
     expected: usize,
@@ -1,10 +1,13 @@
 use crate::evaluator::eval;
 use crate::heapobjs::instance::MaybeObject;
+use crate::heapobjs::InternalList;
 use crate::node::{Node, NodeType};
 use crate::object::{Object, ParamObj};
 use crate::objsys::RefKey;
 use crate::state::State;
-use crate::error::evalerror;
+use crate::error::{evalerror, dartthrow};
+use crate::builtin;
+use crate::types;
 
 
 pub enum MaybeRef {
@@ -17,6 +20,13 @@ pub fn get_name(name_node: &Node, state: &mut State) -> Object {
 
     if let NodeType::Name(s, linenum, symnum) = &name_node.nodetype {
 
+        // Constants of the number types, like double.nan.
+        if name_node.children.len() > 0 {
+            if let Some(typename) = static_type_name(&name_node.children[0], state) {
+                return builtin::get_static(name_node, &typename, s, state);
+            }
+        }
+
         if state.in_const {
             evalerror(
                 "Not a constant expression.",
@@ -27,19 +37,31 @@ pub fn get_name(name_node: &Node, state: &mut State) -> Object {
 
         // For Name, having a child means having an owner.
         if name_node.children.len() > 0 {
+            if let NodeType::SuperRef(cname, _, _) = &name_node.children[0].nodetype {
+                return get_super(cname, s, state, name_node);
+            }
+            if let Some(cons) = constructor_tear_off(&name_node.children[0], s, state) {
+                return cons;
+            }
             // Run parent through the loop for lookup.
             let owner = eval(&name_node.children[0], state);
             return get_field(owner, s, state, name_node);
         }
 
-        // Don't access stack or this if we are lazy evaling a topvar.
-        if state.eval_var == "" {
-            if state.stack.has(s) {
-                return state.stack.get(s).clone();
-            }
-            else if state.objsys.has_this() {
-                return get_field(state.objsys.get_this_object(), s, state, name_node);
+        if state.stack.has(s) {
+            if let Object::Late(init) = state.stack.get(s) {
+                let init = init.clone();
+                return init_late_local(s, init, state, name_node);
             }
+            return state.stack.get(s).clone();
+        }
+        else if state.objsys.has_this() && has_member(&state.objsys.get_this_object(), s, state) {
+            return get_field(state.objsys.get_this_object(), s, state, name_node);
+        }
+        else if state.objsys.has_this() && state.objsys.get_class(&state.objsys.get_this_instance().classname).has_method(s, state) {
+            let this = state.objsys.get_this();
+            let method = state.objsys.get_class(&state.objsys.get_this_instance().classname).get_method(s, state, name_node);
+            return bind_method(&this, method, state);
         }
 
         if state.has_global(s) {
@@ -57,11 +79,20 @@ pub fn get_name(name_node: &Node, state: &mut State) -> Object {
                             &n
                         );
                     }
+                    if let NodeType::Late(_, _) = n.children[0].nodetype {
+                        // As dart.
+                        dartthrow(
+                            format!("LateInitializationError: Field '{}' has not been initialized.", name),
+                            state,
+                            name_node
+                        );
+                    }
                     if state.eval_var.len() == 0 {
                         state.eval_var = name.clone();
                     }
 
-                    let res = eval(&n.children[0], state);
+                    let res = eval_top_init(&n.children[0], state);
+                    check_type(&res, typ, "", state, &n);
                     state.eval_var = String::from("");
                     let resolved_node = Node::new(NodeType::TopVar(
                         typ.clone(),
@@ -92,7 +123,8 @@ pub fn get_name(name_node: &Node, state: &mut State) -> Object {
                     }
 
                     state.in_const = true;
-                    let res = eval(&n.children[0], state);
+                    let res = eval_top_init(&n.children[0], state);
+                    check_type(&res, typ, "", state, &n);
                     state.in_const = false;
                     state.eval_var = String::from("");
                     let resolved_node = Node::new(NodeType::ConstTopVar(
@@ -110,9 +142,24 @@ pub fn get_name(name_node: &Node, state: &mut State) -> Object {
                     return *val.clone();
                 }
 
+                NodeType::FunDef(_, _, _, _, _) => {
+                    return create_function(&n);
+                }
+
+                NodeType::Constructor(_, _, _, _, _, _, _) if state.objsys.has_class(s) => {
+                    let typ = types::type_literal(s, state).unwrap();
+                    return types::type_object(&typ, state);
+                }
+
                 _ => panic!("Unexpected node type in globals: {}", n)
             }
         }
+        if let Some(func) = builtin::tear_off(s, state) {
+            return func;
+        }
+        if let Some(typ) = types::type_literal(s, state) {
+            return types::type_object(&typ, state);
+        }
         if state.debug {
             state.stack.printstack();
         }
@@ -127,11 +174,112 @@ pub fn get_name(name_node: &Node, state: &mut State) -> Object {
 }
 
 
+/// Evaluate the initializer of a top level variable outside of any call-frame
+/// or instance, as it may be first read from anywhere.
+pub fn eval_top_init(init: &Node, state: &mut State) -> Object {
+    let this = state.objsys.get_this();
+    state.objsys.set_this(RefKey(String::from("")));
+    state.stack.push_call();
+    let res = eval(init, state);
+    state.stack.pop_call();
+    state.objsys.set_this(this);
+    res
+}
+
+
+/// Run the initializer of a late local variable on its first read.
+fn init_late_local(name: &str, init: Option<Node>, state: &mut State, node: &Node) -> Object {
+
+    match init {
+        Some(initexpr) => {
+            let val = eval(&initexpr, state);
+            set_name(node, val.clone(), state);
+            val
+        }
+        // As dart.
+        None => dartthrow(
+            format!("LateInitializationError: Local '{}' has not been initialized.", name),
+            state,
+            node
+        )
+    }
+}
+
+
+/// Run the initializer of a late field on its first read,
+/// with the instance as this.
+fn init_late_field(rk: &RefKey, field: &str, init: Option<Node>, state: &mut State, node: &Node) -> Object {
+
+    match init {
+        Some(initexpr) => {
+            let oldthis = state.objsys.get_this();
+            state.objsys.set_this(rk.clone());
+            let val = eval(&initexpr, state);
+            state.objsys.set_this(oldthis);
+            set_field(Object::Reference(rk.clone()), field, val.clone(), state, node);
+            val
+        }
+        // As dart.
+        None => dartthrow(
+            format!("LateInitializationError: Field '{}' has not been initialized.", field),
+            state,
+            node
+        )
+    }
+}
+
+
+/// Throw as Dart does when a member is accessed on null. The kind is
+/// method, getter or setter, and tried is how it was accessed.
+pub fn null_receiver(kind: &str, member: &str, tried: &str, state: &State, node: &Node) -> ! {
+    dartthrow(
+        format!("NoSuchMethodError: The {} '{}' was called on null.\nReceiver: null\nTried calling: {}",
+            kind, member, tried),
+        state,
+        node
+    )
+}
+
+
+/// Throw as Dart does when the receiver lacks the member.
+pub fn throw_no_such_method(receiver: &Object, kind: &str, name: &str, args: &[Object], state: &State, node: &Node) -> ! {
+
+    let argstr = args.iter().map(|a| safe_to_string(a, state)).collect::<Vec<String>>().join(", ");
+    let (member, tried) = match kind {
+        "getter" => (name.to_string(), name.to_string()),
+        "setter" => (format!("{}=", name), format!("{}={}", name, argstr)),
+        _ => (name.to_string(), format!("{}({})", name, argstr))
+    };
+    // As dart.
+    dartthrow(
+        format!("NoSuchMethodError: Class '{}' has no instance {} '{}'.\nReceiver: {}\nTried calling: {}",
+            types::type_name(receiver, state), kind, member, safe_to_string(receiver, state), tried),
+        state,
+        node
+    )
+}
+
+
+/// An object as shown in error messages, without running its toString.
+pub fn safe_to_string(obj: &Object, state: &State) -> String {
+    match obj {
+        Object::String(s) => format!("\"{}\"", s),
+        Object::Reference(_) => format!("Instance of '{}'", types::type_name(obj, state)),
+        x => x.to_string()
+    }
+}
+
+
 pub fn set_name(name_node: &Node, val: Object, state: &mut State) {
 
     if let NodeType::Name(name, linenum, symnum) = &name_node.nodetype {
 
         if name_node.children.len() > 0 {
+            if let NodeType::SuperRef(cname, _, _) = &name_node.children[0].nodetype {
+                let part = super_part(cname, name, "setter", state, name_node);
+                set_field(part, &name, val, state, &name_node);
+                return;
+            }
             let left_obj = eval(&name_node.children[0], state);
             set_field(left_obj, &name, val, state, &name_node);
             return;
@@ -139,17 +287,20 @@ pub fn set_name(name_node: &Node, val: Object, state: &mut State) {
 
         // Look on the stack.
         if state.stack.has(&name) {
+            let key = types::var_key(name);
+            if state.stack.has(&key) {
+                if let Object::String(typ) = state.stack.get(&key).clone() {
+                    check_type(&val, &typ, "", state, name_node);
+                }
+            }
             state.stack.update(&name, val);
             return;
         }
 
-        // Look in 'this' instance.
-        if state.objsys.has_this() {
-            let this = state.objsys.get_this_instance_mut();
-            if this.has_field(&name) {
-                set_field(state.objsys.get_this_object(), &name, val, state, &name_node);
-                return;
-            }
+        // Look in 'this' instance, and the parts it inherits.
+        if state.objsys.has_this() && has_member(&state.objsys.get_this_object(), name, state) {
+            set_field(state.objsys.get_this_object(), &name, val, state, &name_node);
+            return;
         }
 
         // Look for globals.
@@ -161,6 +312,7 @@ pub fn set_name(name_node: &Node, val: Object, state: &mut State) {
 
                 NodeType::TopVarLazy(typ, _, _, _) |
                 NodeType::TopVar(typ, _, _, _, _) => {
+                    check_type(&val, typ, "", state, name_node);
                     let newval = Node::new(
                         NodeType::TopVar(
                             typ.clone(),
@@ -200,14 +352,44 @@ pub fn set_name(name_node: &Node, val: Object, state: &mut State) {
 }
 
 
+/// The name of a built-in type with static members, if the node
+/// refers to one rather than to a variable that shadows it.
+pub fn static_type_name(node: &Node, state: &State) -> Option<String> {
+
+    if let NodeType::Name(s, _, _) = &node.nodetype {
+        if node.children.is_empty()
+            && builtin::has_statics(s)
+            && !state.stack.has(s)
+            && (!state.has_global(s) || state.objsys.has_class(s)) {
+            return Some(s.clone());
+        }
+    }
+    None
+}
+
+
 pub fn get_field(obj: Object, field: &str, state: &mut State, node: &Node) -> Object {
 
+    if field == "runtimeType" && !has_member(&obj, field, state) {
+        let typ = types::type_name(&obj, state);
+        return types::type_object(&typ, state);
+    }
+    if field == "hashCode" && !matches!(obj, Object::Reference(_)) {
+        return builtin::hash_code(&obj);
+    }
+
+    if let Object::Int(_) | Object::Double(_) = obj {
+        return builtin::get_property(node, &obj, field, state);
+    }
+
     if let Object::Reference(rk) = obj {
         let inst = state.objsys.get_instance(&rk);
 
         let c = state.objsys.get_class(&inst.classname);
 
-        if c.has_getter(field) {
+        // Own fields shadow inherited getters, but inherited
+        // getters still run with this object as receiver.
+        if c.getters.contains_key(field) || (!inst.has_field(field) && c.has_getter(field, state)) {
             let g = c.get_getter(field, state, node);
             return call_function(
                 MaybeRef::Ref(rk.clone()), 
@@ -218,9 +400,20 @@ pub fn get_field(obj: Object, field: &str, state: &mut State, node: &Node) -> Ob
         }
 
         if inst.has_field(field) {
+            if let Object::Late(init) = inst.get_field(field) {
+                return init_late_field(&rk, field, init, state, node);
+            }
             return inst.get_field(field);
         }
 
+        if c.has_method(field, state) {
+            let method = c.get_method(field, state, node);
+            return bind_method(&rk, method, state);
+        }
+
+        if !has_member(&Object::Reference(rk.clone()), field, state) {
+            return no_such_method(&rk, "getter", field, Vec::new(), Vec::new(), state, node);
+        }
         if let MaybeObject::Some(p) = &inst.parent {
             return get_field(p.clone(), field, state, node);
         }
@@ -230,15 +423,147 @@ pub fn get_field(obj: Object, field: &str, state: &mut State, node: &Node) -> Ob
             node
         )
     }
+
+    if let Object::Null = obj {
+        null_receiver("getter", field, field, state, node);
+    }
     panic!("Not a reference: {}", obj);
 }
 
 
+/// A method torn off an instance. Its types are those of the instance,
+/// as in (int) => void for the add method of a List<int>.
+fn bind_method(rk: &RefKey, mut method: Object, state: &State) -> Object {
+
+    if let Object::Function(_, _, _, params, typeparams, rettype) = &mut method {
+        // Type parameters of the method shadow those of the class.
+        let mut bindings = types::instance_bindings(rk, state);
+        for p in typeparams.iter() {
+            bindings.remove(&p.name);
+        }
+        for p in params.iter_mut() {
+            p.typ = types::substitute(&p.typ, &bindings);
+        }
+        *rettype = types::substitute(rettype, &bindings);
+    }
+    Object::BoundMethod(rk.clone(), Box::new(method))
+}
+
+
+/// A member of the parent of the named class, as read by super.name.
+/// Getters and methods run with this as receiver, so that
+/// members they use are still those of the most derived object.
+fn get_super(cname: &str, name: &str, state: &mut State, node: &Node) -> Object {
+
+    let this = state.objsys.get_this();
+    let parent = state.objsys.get_class(cname).parent.clone();
+
+    if parent != "" {
+        let class = state.objsys.get_class(&parent);
+
+        if class.has_getter(name, state) {
+            let g = class.get_getter(name, state, node);
+            return call_function(MaybeRef::Ref(this), &g, &Node::new(NodeType::ArgList(0, 0)), state);
+        }
+        if class.has_method(name, state) {
+            let method = class.get_method(name, state, node);
+            return bind_method(&this, method, state);
+        }
+    }
+    let part = super_part(cname, name, "getter", state, node);
+    get_field(part, name, state, node)
+}
+
+
+/// The part of this object made by the parent of the named class,
+/// which holds the fields declared there and above.
+pub fn super_part(cname: &str, name: &str, kind: &str, state: &State, node: &Node) -> Object {
+
+    let parent = &state.objsys.get_class(cname).parent;
+    let mut part = state.objsys.get_this_object();
+
+    while let Object::Reference(rk) = &part {
+        let inst = state.objsys.get_instance(rk);
+        if inst.classname == *parent {
+            if has_member(&part, name, state) {
+                return part;
+            }
+            break;
+        }
+        part = match &inst.parent {
+            MaybeObject::Some(p) => p.clone(),
+            MaybeObject::None => break
+        };
+    }
+    // As dart.
+    evalerror(
+        format!("Superclass has no {} named '{}'.", kind, name),
+        state,
+        node
+    )
+}
+
+
+/// The constructor of the class named by owner, as torn off by Point.new.
+fn constructor_tear_off(owner: &Node, name: &str, state: &State) -> Option<Object> {
+
+    if let NodeType::Name(cname, _, _) = &owner.nodetype {
+        if name != "new" || !owner.children.is_empty() || state.stack.has(cname) || !state.has_global(cname) {
+            return None;
+        }
+        let consnode = state.get_global_ref(cname);
+        if !matches!(consnode.nodetype, NodeType::Constructor(_, _, _, _, _, _, _)) {
+            return None;
+        }
+        let mut cons = create_constructor(consnode);
+
+        // Initializing formals have the type of their field.
+        if let Object::Constructor(_, _, params, _, _) = &mut cons {
+            let class = state.objsys.get_class(cname);
+            for p in params.iter_mut().filter(|p| p.fieldinit) {
+                if let Some((ftype, _, _)) = class.fields.iter().find(|(_, fname, _)| *fname == p.name) {
+                    p.typ = ftype.clone();
+                }
+            }
+        }
+        return Some(cons);
+    }
+    None
+}
+
+
+/// Whether an instance, or one of its parents, has a field or getter by the name.
+pub fn has_member(obj: &Object, name: &str, state: &State) -> bool {
+
+    if let Object::Reference(rk) = obj {
+        let inst = state.objsys.get_instance(rk);
+
+        if inst.has_field(name) || state.objsys.get_class(&inst.classname).has_getter(name, state) {
+            return true;
+        }
+        if let MaybeObject::Some(p) = &inst.parent {
+            return has_member(p, name, state);
+        }
+    }
+    false
+}
+
+
 pub fn set_field(obj: Object, field: &str, val: Object, state: &mut State, node: &Node) {
 
     if let Object::Reference(rk) = &obj {
+
+        if state.objsys.get_instance(rk).has_field(field) {
+            if let Some(ftype) = types::field_type(rk, field, state) {
+                check_resolved(&val, &ftype, "", state, node);
+            }
+        }
+        if !has_member(&obj, field, state) {
+            no_such_method(rk, "setter", field, vec![val], Vec::new(), state, node);
+            return;
+        }
         let inst = state.objsys.get_instance_mut(&rk);
-        
+
         if inst.has_field(field) {
             inst.set_field(String::from(field), val);
             return;
@@ -253,6 +578,10 @@ pub fn set_field(obj: Object, field: &str, val: Object, state: &mut State, node:
             node
         )
     }
+
+    if let Object::Null = obj {
+        null_receiver("setter", &format!("{}=", field), &format!("{}={}", field, val), state, node);
+    }
     panic!("Not a reference: {}", obj);
 }
 
@@ -277,6 +606,241 @@ pub fn set_list_element(ulist_ref: Object, index: Object, value: Object, state:
 }
 
 
+/// The string representation of an object, as given by its toString method.
+pub fn to_dart_string(obj: Object, state: &mut State, node: &Node) -> String {
+
+    if let Object::Reference(rk) = &obj {
+        let inst = state.objsys.get_instance(rk);
+        let c = state.objsys.get_class(&inst.classname);
+        let meth_obj = c.get_method("toString", state, node);
+        let str_obj = call_function(
+            MaybeRef::Ref(rk.clone()),
+            &meth_obj,
+            &Node::new(NodeType::ArgList(0, 0)),
+            state
+        );
+        return format!("{}", str_obj);
+    }
+    format!("{}", obj)
+}
+
+
+/// Equality as by the == operator, or None if it
+/// is not implemented for the objects.
+pub fn equals(left: &Object, right: &Object) -> Option<bool> {
+
+    Some(match (left, right) {
+        (Object::Int(n1), Object::Int(n2)) => n1 == n2,
+        (Object::Int(n1), Object::Double(x2)) => (*n1 as f64) == *x2,
+        (Object::Double(x1), Object::Int(n2)) => *x1 == (*n2 as f64),
+        (Object::Double(x1), Object::Double(x2)) => x1 == x2,
+        (Object::Bool(b1), Object::Bool(b2)) => b1 == b2,
+        (Object::String(s1), Object::String(s2)) => s1 == s2,
+        (Object::Reference(k1), Object::Reference(k2)) => k1 == k2,
+        (Object::Null, Object::Null) => true,
+        // Functions are equal when they are the same function,
+        // and torn off methods when also of the same instance.
+        (Object::Function(name1, file1, _, _, _, _), Object::Function(name2, file2, _, _, _, _)) => {
+            name1 == name2 && file1 == file2
+        }
+        (Object::BoundMethod(k1, m1), Object::BoundMethod(k2, m2)) => {
+            k1 == k2 && equals(m1, m2) == Some(true)
+        }
+        (Object::Function(_, _, _, _, _, _), _) |
+        (Object::BoundMethod(_, _), _) |
+        (Object::Int(_), _) |
+        (Object::Double(_), _) |
+        (Object::Bool(_), _) |
+        (Object::String(_), _) |
+        (Object::Reference(_), _) |
+        (Object::Null, _) |
+        (_, Object::Null) => false,
+        _ => return None
+    })
+}
+
+
+/// The method as declared by the class or a class it inherits from, or
+/// None when only Object declares it, so the builtin behaviour applies.
+pub fn overridden(cname: &str, name: &str, state: &State) -> Option<Object> {
+
+    let mut class = state.objsys.get_class(cname);
+
+    while class.name != "Object" {
+        if let Some(m) = class.methods.get(name) {
+            return Some(m.clone());
+        }
+        class = state.objsys.get_class(&class.parent);
+    }
+    None
+}
+
+
+/// Call a method on an object with already evaluated arguments.
+pub fn call_method_with(
+    obj: &Object,
+    name: &str,
+    argobjs: Vec<Object>,
+    node: &Node,
+    state: &mut State) -> Object {
+
+    if let Object::Reference(rk) = obj {
+        let inst = state.objsys.get_instance(rk);
+        let c = state.objsys.get_class(&inst.classname);
+        let meth = c.get_method(name, state, node);
+        return call_function_with(MaybeRef::Ref(rk.clone()), &meth, argobjs, node, state);
+    }
+    evalerror(
+        format!("The method '{}' isn't defined for '{}'.", name, obj),
+        state,
+        node
+    )
+}
+
+
+/// Run an Iterable's iterator to the end and collect the elements.
+pub fn iterable_elements(obj: &Object, node: &Node, state: &mut State) -> Vec<Object> {
+
+    let iterator = get_field(obj.clone(), "iterator", state, node);
+    let elements = create_list(vec![], state);
+
+    // Keep the iterator and the elements so far on the
+    // stack, where the garbage collector can see them.
+    state.stack.push_lex();
+    state.stack.add_new("__iterator", iterator.clone());
+    state.stack.add_new("__elements", elements.clone());
+
+    let ilist_rk = match get_field(elements, "__list", state, node) {
+        Object::Reference(rk) => rk,
+        x => panic!("Internal __list field was not a reference: {}", x)
+    };
+
+    while move_next(&iterator, node, state) {
+        let current = get_field(iterator.clone(), "current", state, node);
+        state.objsys.get_list_mut(&ilist_rk).add(current);
+    }
+    state.stack.pop_lex();
+    state.objsys.get_list(&ilist_rk).els.clone()
+}
+
+
+/// Advance an iterator, telling if there is a current element.
+pub fn move_next(iterator: &Object, node: &Node, state: &mut State) -> bool {
+
+    match call_method_with(iterator, "moveNext", vec![], node, state) {
+        Object::Bool(b) => b,
+        x => evalerror(
+            format!("Expected bool from moveNext(). Got: {}", x),
+            state,
+            node
+        )
+    }
+}
+
+
+/// Create a Dart List holding the given values.
+pub fn create_list(vals: Vec<Object>, state: &mut State) -> Object {
+
+    let class = state.objsys.get_class("List");
+    let mut inst = class.instantiate();
+
+    let mut ilist = InternalList::new();
+    ilist.set_elements(vals);
+
+    inst.set_field(String::from("__list"), Object::Reference(ilist.id.clone()));
+    state.objsys.register_list(ilist);
+
+    state.objsys.register_instance(*inst)
+}
+
+
+/// Create a Dart Map of the key and value types, holding the given
+/// entries. A key given more than once keeps its first place and last value.
+pub fn create_map(entries: Vec<(Object, Object)>, keytype: &str, valtype: &str, state: &mut State) -> Object {
+
+    let mut keys: Vec<Object> = Vec::new();
+    let mut vals: Vec<Object> = Vec::new();
+    for (key, val) in entries {
+        match keys.iter().position(|k| equals(k, &key) == Some(true)) {
+            Some(i) => vals[i] = val,
+            None => {
+                keys.push(key);
+                vals.push(val);
+            }
+        }
+    }
+
+    // The slots are left empty, to be filled when a key is first looked up.
+    let keylist = create_list(keys, state);
+    let vallist = create_list(vals, state);
+    let slots = create_list(vec![], state);
+    for (list, typ) in [(&keylist, keytype), (&vallist, valtype), (&slots, "int")] {
+        if let Object::Reference(rk) = list {
+            state.objsys.get_instance_mut(rk).typeargs = vec![String::from(typ)];
+        }
+    }
+
+    let class = state.objsys.get_class("Map");
+    let mut inst = class.instantiate();
+    inst.set_field(String::from("__keys"), keylist);
+    inst.set_field(String::from("__values"), vallist);
+    inst.set_field(String::from("__slots"), slots);
+    inst.typeargs = vec![String::from(keytype), String::from(valtype)];
+
+    state.objsys.register_instance(*inst)
+}
+
+
+/// Create a Dart Set of the element type, kept in the given map
+/// of its elements to true.
+pub fn create_set(map: Object, elemtype: &str, state: &mut State) -> Object {
+
+    let class = state.objsys.get_class("Set");
+    let mut inst = class.instantiate();
+    inst.set_field(String::from("__map"), map);
+    inst.typeargs = vec![String::from(elemtype)];
+
+    state.objsys.register_instance(*inst)
+}
+
+
+/// Throw as Dart does when a value does not fit the type declared
+/// for it. The name is given for parameters.
+pub fn check_type(obj: &Object, typ: &str, name: &str, state: &State, node: &Node) {
+
+    if !state.runtime_checks {
+        return;
+    }
+    check_resolved(obj, &types::resolve(typ, state), name, state, node);
+}
+
+
+/// As check_type, for a resolved type, like that of a field.
+pub fn check_resolved(obj: &Object, typ: &str, name: &str, state: &State, node: &Node) {
+
+    if !state.runtime_checks {
+        return;
+    }
+    if let Some(actual) = types::mismatch(obj, typ, state) {
+        let mut msg = format!("type '{}' is not a subtype of type '{}'", types::display(&actual), types::display(typ));
+        if name != "" {
+            msg = format!("{} of '{}'", msg, name);
+        }
+        dartthrow(msg, state, node);
+    }
+}
+
+
+/// Add a variable to the current lex-frame. With runtime checks,
+/// its declared type is kept next to it, for checking assignments.
+pub fn add_typed(name: &str, typ: &str, val: Object, state: &mut State) {
+    state.stack.add_new(name, val);
+    if state.runtime_checks {
+        state.stack.add_new(&types::var_key(name), Object::String(String::from(typ)));
+    }
+}
+
+
 pub fn create_function(funcnode: &Node) -> Object {
 
     match &funcnode.nodetype {
@@ -284,18 +848,12 @@ pub fn create_function(funcnode: &Node) -> Object {
         NodeType::FunDef(typ, fname, filename, _, _) => {
             let paramnodes = &funcnode.children[0];
             let bodynode = &funcnode.children[1];
-            let mut paramobjs: Vec<ParamObj> = Vec::new();
-
-            for i in 0..paramnodes.children.len() {
-                let p = &paramnodes.children[i];
-                match &p.nodetype {
-                    NodeType::Name(s, _, _) => {
-                        paramobjs.push(ParamObj { typ: String::from("var"), name: s.clone(), fieldinit: false });
-                    }
-                    x => panic!("Invalid parameter: {}", x)
-                }
+            let paramobjs = ParamObj::from_paramlist(paramnodes);
+            let mut typeparams = Vec::new();
+            if let Some(Node { nodetype: NodeType::TypeParams(params, _, _), .. }) = funcnode.children.get(2) {
+                typeparams = params.clone();
             }
-            return Object::Function(fname.clone(), filename.clone(), bodynode.clone(), paramobjs);
+            return Object::Function(fname.clone(), filename.clone(), bodynode.clone(), paramobjs, typeparams, typ.clone());
         }
         _ => panic!("Invalid node type.")
     }
@@ -307,66 +865,159 @@ pub fn call_function(
     func: &Object,
     args: &Node,
     state: &mut State) -> Object {
+
+    call_generic(instance, func, args, Vec::new(), state)
+}
+
+
+/// Call a function with type arguments. Without them,
+/// they are inferred from the arguments.
+pub fn call_generic(
+    instance: MaybeRef,
+    func: &Object,
+    args: &Node,
+    typeargs: Vec<String>,
+    state: &mut State) -> Object {
     
     match func {
 
-        Object::Function(funcname, filename, body, params) => {
+        Object::Function(funcname, _, _, params, _, _) => {
 
-            if args.children.len() != params.len() {
-                evalerror(
-                    format!("In method call {}, {} arguments expected but {} given.",
-                        funcname, params.len(), args.children.len()),
-                    state,
-                    args
-                );
-            }
-    
-            let mut argobjs = argnodes_to_argobjs(
-                &args.children,
-                state
-            );
-    
             // Argtrees must be evaluated in the callers context,
-            // but stored in the new call frame.
-    
-            state.stack.push_call();
+            // but stored in the new call frame. The receiver may be held
+            // nowhere else, so it is kept from the garbage collector meanwhile.
+            if let MaybeRef::Ref(rk) = &instance {
+                state.constructing.push(rk.clone());
+            }
+            let argobjs = bind_args(funcname, params, args, state);
+            if let MaybeRef::Ref(_) = &instance {
+                state.constructing.pop();
+            }
+            run_function(instance, func, argobjs, typeargs, state)
+        }
 
-            // Loop params backwards so that we can use pop().
-            let mut i = params.len() as isize;
-            loop {
-                i -= 1;
-                if i < 0 {
-                    break;
+        Object::BoundMethod(rk, method) => {
+            call_generic(MaybeRef::Ref(rk.clone()), method, args, typeargs, state)
+        }
+
+        Object::Reference(rk) => {
+            match call_method_of(rk, state, args) {
+                Some(method) => call_generic(MaybeRef::Ref(rk.clone()), &method, args, typeargs, state),
+                None => {
+                    let (argobjs, named) = invocation_args(&args.children, state);
+                    no_such_method(rk, "method", "call", argobjs, named, state, args)
                 }
-                state.stack.add_new(params[i as usize].name.as_str(), argobjs.pop().unwrap());
             }
-    
-            let oldfilename = state.filepath.clone();
-            state.filepath = filename.clone();
+        }
+
+        x => panic!("Called a non-function object: {}", x)
+    }
+}
+
+
+/// The call method of an instance called as a function, if it has one.
+fn call_method_of(rk: &RefKey, state: &State, node: &Node) -> Option<Object> {
+    let class = state.objsys.get_class(&state.objsys.get_instance(rk).classname);
+    if !class.has_method("call", state) {
+        return None;
+    }
+    Some(class.get_method("call", state, node))
+}
 
-            let mut oldthis = MaybeRef::None;
-            if let MaybeRef::Ref(rk) = instance {
-                oldthis = MaybeRef::Ref(state.objsys.get_this());
-                state.objsys.set_this(rk);
+
+/// Hand an access of a member the object lacks, as through a dynamic
+/// receiver, to its noSuchMethod. The one of Object throws.
+pub fn no_such_method(
+    rk: &RefKey,
+    kind: &str,
+    name: &str,
+    args: Vec<Object>,
+    named: Vec<(Object, Object)>,
+    state: &mut State,
+    node: &Node) -> Object {
+
+    let cname = state.objsys.get_instance(rk).classname.clone();
+    let method = match overridden(&cname, "noSuchMethod", state) {
+        Some(m) => m,
+        None => throw_no_such_method(&Object::Reference(rk.clone()), kind, name, &args, state, node)
+    };
+
+    // Setters are named like wings=.
+    let symname = if kind == "setter" { format!("{}=", name) } else { String::from(name) };
+    let mut symbol = state.objsys.get_class("Symbol").instantiate();
+    symbol.set_field(String::from("__name"), Object::String(symname));
+    let symbol = state.objsys.register_instance(*symbol);
+    let positional = create_list(args, state);
+    let named = create_map(named, "Symbol", "Object?", state);
+
+    let mut invocation = state.objsys.get_class("Invocation").instantiate();
+    invocation.set_field(String::from("memberName"), symbol);
+    invocation.set_field(String::from("positionalArguments"), positional);
+    invocation.set_field(String::from("namedArguments"), named);
+    invocation.set_field(String::from("isMethod"), Object::Bool(kind == "method"));
+    invocation.set_field(String::from("isGetter"), Object::Bool(kind == "getter"));
+    invocation.set_field(String::from("isSetter"), Object::Bool(kind == "setter"));
+    let invocation = state.objsys.register_instance(*invocation);
+
+    call_function_with(MaybeRef::Ref(rk.clone()), &method, vec![invocation], node, state)
+}
+
+
+/// Call a function with already evaluated positional arguments,
+/// as when a builtin calls back into Dart code.
+pub fn call_function_with(
+    instance: MaybeRef,
+    func: &Object,
+    argobjs: Vec<Object>,
+    node: &Node,
+    state: &mut State) -> Object {
+
+    call_generic_with(instance, func, argobjs, Vec::new(), node, state)
+}
+
+
+/// Call a function with already evaluated positional arguments
+/// and type arguments. Without them, they are inferred.
+pub fn call_generic_with(
+    instance: MaybeRef,
+    func: &Object,
+    mut argobjs: Vec<Object>,
+    typeargs: Vec<String>,
+    node: &Node,
+    state: &mut State) -> Object {
+
+    match func {
+
+        Object::Function(funcname, _, _, params, _, _) => {
+
+            let required = params.iter().filter(|p| !p.optional).count();
+            let positional = params.iter().filter(|p| !p.named).count();
+
+            if argobjs.len() < required || argobjs.len() > positional {
+                evalerror(
+                    format!("In method call {}, {} arguments expected but {} given.",
+                        funcname, required, argobjs.len()),
+                    state,
+                    node
+                );
             }
-    
-            let result = eval(&body, state);
-    
-            if let MaybeRef::Ref(old_rk) = oldthis {
-                state.objsys.set_this(old_rk);
+            for p in &params[argobjs.len()..] {
+                argobjs.push(match &p.default {
+                    Some(defnode) => eval(defnode, state),
+                    None => Object::Null
+                });
             }
+            run_function(instance, func, argobjs, typeargs, state)
+        }
 
-            state.filepath = oldfilename;
-            state.stack.pop_call();
-    
-            return match result {
-                Object::Return(v) => {
-                    *v.clone()
-                }
-    
-                _ => {
-                    result.clone()
-                }
+        Object::BoundMethod(rk, method) => {
+            call_generic_with(MaybeRef::Ref(rk.clone()), method, argobjs, typeargs, node, state)
+        }
+
+        Object::Reference(rk) => {
+            match call_method_of(rk, state, node) {
+                Some(method) => call_generic_with(MaybeRef::Ref(rk.clone()), &method, argobjs, typeargs, node, state),
+                None => no_such_method(rk, "method", "call", argobjs, Vec::new(), state, node)
             }
         }
 
@@ -375,29 +1026,81 @@ pub fn call_function(
 }
 
 
+fn run_function(
+    instance: MaybeRef,
+    func: &Object,
+    mut argobjs: Vec<Object>,
+    mut typeargs: Vec<String>,
+    state: &mut State) -> Object {
+
+    if let Object::Function(_, filename, body, params, typeparams, returntype) = func {
+
+        if typeargs.len() != typeparams.len() {
+            let pairs: Vec<(String, String)> = params.iter().zip(argobjs.iter())
+                .map(|(p, a)| (p.typ.clone(), types::type_name(a, state)))
+                .collect();
+            typeargs = types::infer(typeparams, &pairs, state);
+        }
+
+        state.stack.push_call();
+
+        let oldfilename = state.filepath.clone();
+        state.filepath = filename.clone();
+
+        for (p, t) in typeparams.iter().zip(typeargs) {
+            state.stack.add_new(&types::frame_key(&p.name), Object::String(t));
+        }
+
+        // The receiver is this before the parameters are checked,
+        // so that type parameters of its class resolve.
+        let mut oldthis = MaybeRef::None;
+        if let MaybeRef::Ref(rk) = instance {
+            // Keep the receiver in the call frame, so the garbage
+            // collector sees it while nested calls replace this.
+            state.stack.add_new("this", Object::Reference(rk.clone()));
+            oldthis = MaybeRef::Ref(state.objsys.get_this());
+            state.objsys.set_this(rk);
+        }
+
+        // Loop params backwards so that we can use pop().
+        let mut i = params.len() as isize;
+        loop {
+            i -= 1;
+            if i < 0 {
+                break;
+            }
+            let param = &params[i as usize];
+            let arg = argobjs.pop().unwrap();
+            check_type(&arg, &param.typ, &param.name, state, body);
+            add_typed(&param.name, &param.typ, arg, state);
+        }
+
+        let result = match eval(&body, state) {
+            Object::Return(v) => *v,
+            x => x
+        };
+        check_type(&result, returntype, "", state, body);
+
+        if let MaybeRef::Ref(old_rk) = oldthis {
+            state.objsys.set_this(old_rk);
+        }
+
+        state.filepath = oldfilename;
+        state.stack.pop_call();
+
+        return result;
+    }
+    panic!("Called a non-function object: {}", func)
+}
+
+
 pub fn create_constructor(funcnode: &Node) -> Object {
 
     match &funcnode.nodetype {
 
         NodeType::Constructor(cname, paramlist, initlist, body, filename, _, _) => {
 
-            let mut paramobjs : Vec<ParamObj> = Vec::new();
-
-            for i in 0..paramlist.children.len() {
-                let p = &paramlist.children[i];
-                match &p.nodetype {
-                    NodeType::Name(s, _, _) => {
-                        paramobjs.push(ParamObj{ typ: String::from(""), name: s.clone(), fieldinit: false });
-                    }
-                    NodeType::TypedVar(t, s, _, _) => {
-                        paramobjs.push(ParamObj{ typ: t.clone(), name: s.clone(), fieldinit: false });
-                    }
-                    NodeType::ThisFieldInit(s, _, _) => {
-                        paramobjs.push(ParamObj{ typ: String::from(""), name: s.clone(), fieldinit: true });
-                    }
-                    x => panic!("Invalid parameter: {}", x)
-                }
-            }
+            let paramobjs = ParamObj::from_paramlist(paramlist);
             return Object::Constructor(cname.to_string(), filename.clone(), paramobjs, *initlist.clone(), *body.clone());
         }
         _ => panic!("Invalid node type.")
@@ -405,19 +1108,34 @@ pub fn create_constructor(funcnode: &Node) -> Object {
 }
 
 
+/// Call a constructor with the type arguments of the new instance.
+/// Without them, they are inferred from the arguments.
 pub fn call_constructor(
     funcobj: &Object,
     args: &Node,
+    typeargs: Vec<String>,
+    state: &mut State) -> Object {
+
+    construct(funcobj, args, typeargs, &[], state)
+}
+
+
+/// Run a constructor. When constructing the parent part of an object,
+/// derived holds the parts already made, the most derived first. They
+/// are linked to the new part before any constructor body runs, and the
+/// bodies run with the most derived object as this.
+fn construct(
+    funcobj: &Object,
+    args: &Node,
+    mut typeargs: Vec<String>,
+    derived: &[RefKey],
     state: &mut State) -> Object {
 
     match funcobj {
 
         Object::Constructor(cname, filename, params, initlist, body) => {
 
-            let args = argnodes_to_argobjs(
-                &args.children,
-                state
-            );
+            let args = bind_args(cname, params, args, state);
 
             // Argtrees must be evaluated in callers context, but stored in new context.
 
@@ -430,33 +1148,64 @@ pub fn call_constructor(
             let class = state.objsys.get_class(cname.as_str());
             let mut inst = class.instantiate();
             let parent_name = class.parent.clone();
-
-            // Evaluate the initial field values.
-
+            let parent_typeargs = class.parent_typeargs.clone();
             let field_nodes = class.fields.clone();
-            for (_, fname, initexpr) in &field_nodes {
-                inst.set_field(fname.clone(), eval(initexpr, state));
+
+            if typeargs.len() != class.typeparams.len() {
+                // Initializing formals have the type of their field.
+                let pairs: Vec<(String, String)> = params.iter().zip(args.iter()).map(|(p, a)| {
+                    let mut typ = p.typ.clone();
+                    if p.fieldinit {
+                        if let Some((ftype, _, _)) = field_nodes.iter().find(|(_, fname, _)| *fname == p.name) {
+                            typ = ftype.clone();
+                        }
+                    }
+                    (typ, types::type_name(a, state))
+                }).collect();
+                typeargs = types::infer(&class.typeparams, &pairs, state);
             }
+            inst.typeargs = typeargs;
 
             let instref = state.objsys.register_instance(*inst).clone();
 
             match &instref {
                 Object::Reference(refid) => {
 
+                    if let Some(child) = derived.last() {
+                        state.objsys.get_instance_mut(child).parent = MaybeObject::Some(instref.clone());
+                    }
+
                     state.constructing.push(refid.clone());
                     let oldthis = state.objsys.get_this();
                     state.objsys.set_this(refid.clone());
 
-                    let inst = state.objsys.get_this_instance_mut();
+                    // Evaluate the initial field values. This is done with the
+                    // new instance as this, so type parameters of the class resolve.
+
+                    for (_, fname, initexpr) in &field_nodes {
+                        let val = eval(initexpr, state);
+                        // Fields without an initializer start out as null,
+                        // and are given a value by the constructor.
+                        let implicit = matches!(initexpr.nodetype, NodeType::Null(_, _));
+                        if let Some(ftype) = types::field_type(refid, fname, state).filter(|_| !implicit) {
+                            check_resolved(&val, &ftype, "", state, initexpr);
+                        }
+                        state.objsys.get_this_instance_mut().set_field(fname.clone(), val);
+                    }
 
                     for i in 0..params.len() {
                         // Set fields from params that uses "this" to auto-init.
                         // Ie Bike(this.gears)
                         if params[i].fieldinit {
+                            if let Some(ftype) = types::field_type(refid, &params[i].name, state) {
+                                check_resolved(&args[i], &ftype, &params[i].name, state, body);
+                            }
+                            let inst = state.objsys.get_this_instance_mut();
                             inst.set_field(params[i].name.clone(),args[i].clone());
                         }
                         else {
-                            state.stack.add_new(params[i].name.as_str(), args[i].clone());
+                            check_type(&args[i], &params[i].typ, &params[i].name, state, body);
+                            add_typed(&params[i].name, &params[i].typ, args[i].clone(), state);
                         }
                     }
 
@@ -472,6 +1221,9 @@ pub fn call_constructor(
                             if let NodeType::Name(fieldname, _, _) = &initter.children[0].nodetype {
 
                                 let fieldval = eval(&initter.children[1], state);
+                                if let Some(ftype) = types::field_type(refid, fieldname, state) {
+                                    check_resolved(&fieldval, &ftype, "", state, &initter.children[1]);
+                                }
                                 let inst = state.objsys.get_this_instance_mut();
                                 inst.set_field(fieldname.clone(), fieldval)
                             }
@@ -487,10 +1239,11 @@ pub fn call_constructor(
                         }
                     }
                      
-                    // Initialize parent if it exists.
+                    // Initialize parent if it exists. Object has
+                    // no fields, so no part is made for it.
                     // TODO: initializer list
 
-                    if parent_name != "" {
+                    if parent_name != "" && parent_name != "Object" {
 
                         if state.has_global(parent_name.as_str()) {
 
@@ -498,9 +1251,10 @@ pub fn call_constructor(
 
                             match parent_cons.nodetype {
                                 NodeType::Constructor(_, _, _, _, _, _, _) => {
-                                    let parent_rk = call_constructor(&create_constructor(parent_cons), parent_args, state);
-                                    let inst = state.objsys.get_this_instance_mut();
-                                    inst.parent = MaybeObject::Some(parent_rk);
+                                    let parent_typeargs = parent_typeargs.iter().map(|t| types::resolve(t, state)).collect();
+                                    let mut parts = derived.to_vec();
+                                    parts.push(refid.clone());
+                                    construct(&create_constructor(parent_cons), parent_args, parent_typeargs, &parts, state);
                                 }
                                 _ => panic!("Not a constructor.")
                             }
@@ -508,6 +1262,9 @@ pub fn call_constructor(
                     }                    
 
                     // Run body
+                    if let Some(receiver) = derived.first() {
+                        state.objsys.set_this(receiver.clone());
+                    }
                     eval(&body, state);
 
                     state.objsys.set_this(oldthis);
@@ -538,3 +1295,127 @@ pub fn argnodes_to_argobjs(
     ).collect()
 }
 
+
+
+/// Evaluate the arguments of a call, for an Invocation: the positional
+/// ones, and the named ones with Symbols of their names.
+pub fn invocation_args(argnodes: &[Node], state: &mut State) -> (Vec<Object>, Vec<(Object, Object)>) {
+
+    let mut positional = Vec::new();
+    let mut named = Vec::new();
+    for argnode in argnodes {
+        let val = eval(argnode, state);
+        match &argnode.nodetype {
+            NodeType::NamedArg(name, _, _) => {
+                let mut symbol = state.objsys.get_class("Symbol").instantiate();
+                symbol.set_field(String::from("__name"), Object::String(name.clone()));
+                named.push((state.objsys.register_instance(*symbol), val));
+            }
+            _ => positional.push(val)
+        }
+    }
+    (positional, named)
+}
+
+
+/// Evaluate the arguments of a call and order them like
+/// the parameters, filling in defaults for omitted ones.
+fn bind_args(funcname: &str, params: &Vec<ParamObj>, args: &Node, state: &mut State) -> Vec<Object> {
+
+    let positional: Vec<&ParamObj> = params.iter().filter(|p| !p.named).collect();
+    let required = positional.iter().filter(|p| !p.optional).count();
+    let given = args.children.iter().filter(
+        |a| !matches!(a.nodetype, NodeType::NamedArg(_, _, _))
+    ).count();
+
+    if given < required || given > positional.len() {
+        let expected = if required == positional.len() {
+            format!("{}", required)
+        }
+        else {
+            format!("{} to {}", required, positional.len())
+        };
+        evalerror(
+            format!("In method call {}, {} arguments expected but {} given.",
+                funcname, expected, given),
+            state,
+            args
+        );
+    }
+
+    // Check names before evaluating anything.
+    let mut seen: Vec<&String> = Vec::new();
+    for argnode in &args.children {
+        if let NodeType::NamedArg(name, _, _) = &argnode.nodetype {
+            if !params.iter().any(|p| p.named && p.name == *name) {
+                evalerror(
+                    format!("No named parameter with the name '{}'.", name),
+                    state,
+                    argnode
+                );
+            }
+            if seen.contains(&name) {
+                evalerror(
+                    format!("Duplicated named argument '{}'.", name),
+                    state,
+                    argnode
+                );
+            }
+            seen.push(name);
+        }
+    }
+    for p in params {
+        if p.named && !p.optional && !seen.contains(&&p.name) {
+            evalerror(
+                format!("Required named parameter '{}' must be provided.", p.name),
+                state,
+                args
+            );
+        }
+    }
+
+    let argobjs = argnodes_to_argobjs(&args.children, state);
+
+    let mut positional_objs: Vec<Object> = Vec::new();
+    let mut named_objs: Vec<(&String, Object)> = Vec::new();
+    for (argnode, obj) in args.children.iter().zip(argobjs) {
+        match &argnode.nodetype {
+            NodeType::NamedArg(name, _, _) => named_objs.push((name, obj)),
+            _ => positional_objs.push(obj)
+        }
+    }
+
+    let mut positional_objs = positional_objs.into_iter();
+    let mut bound: Vec<Object> = Vec::new();
+
+    for p in params {
+        let val = if p.named {
+            named_objs.iter().find(|(name, _)| **name == p.name).map(|(_, obj)| obj.clone())
+        }
+        else {
+            positional_objs.next()
+        };
+        bound.push(match (val, &p.default) {
+            (Some(obj), _) => obj,
+            (None, Some(defnode)) => eval(defnode, state),
+            (None, None) => Object::Null
+        });
+    }
+    bound
+}
+
+
+/// For callees without named parameters,
+/// any named argument is an error.
+pub fn reject_named_args(args: &Node, state: &State) {
+
+    for argnode in &args.children {
+        if let NodeType::NamedArg(name, _, _) = &argnode.nodetype {
+            evalerror(
+                format!("No named parameter with the name '{}'.", name),
+                state,
+                argnode
+            );
+        }
+    }
+}
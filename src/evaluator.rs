@@ -4,10 +4,14 @@ use std::time::Instant;
 use crate::state::State;
 use crate::node::{NodeType, Node};
 use crate::builtin;
-use crate::error::evalerror;
+use crate::api;
+use crate::error::{evalerror, dartthrow};
 use crate::object::Object;
 use crate::evalhelp::*;
-use crate::heapobjs::internallist::InternalList;
+use crate::generator;
+use crate::heapobjs::InternalList;
+use crate::objsys::RefKey;
+use crate::types;
 
 
 static GC_TIME: Duration = Duration::from_micros(400);
@@ -17,6 +21,18 @@ pub fn eval(
     node: &Node,
     state: &mut State) -> Object {
 
+    // Statements of async bodies note what they evaluate, to go on after an await.
+    if generator::is_noting(state) {
+        return generator::noted(Some(node), state, |state| eval_node(node, state));
+    }
+    eval_node(node, state)
+}
+
+
+pub fn eval_node(
+    node: &Node,
+    state: &mut State) -> Object {
+
     let t: &NodeType = &node.nodetype;
 
     match t {
@@ -32,7 +48,7 @@ pub fn eval(
                     return Object::Null;
                 }
 
-                NodeType::TypedVar(_, name, _, _) => {
+                NodeType::TypedVar(typ, name, _, _) => {
                     // TypedVar means we will allocate a new one on stack even if the name exists in a
                     // larger scope, like outside a loop or in a field. But fail if it's already on lex stack.
                     if state.stack.has_in_lexscope(name) {
@@ -43,13 +59,33 @@ pub fn eval(
                             &node.children[0]
                         );
                     }
-                    state.stack.add_new(name, right_obj);
+                    check_type(&right_obj, typ, "", state, &node.children[1]);
+                    add_typed(name, typ, right_obj, state);
 
                     return Object::Null;
                 }
 
                 NodeType::CollAccess(_, _) => {
 
+                    // An index operator of a user class, as of Map. The value and
+                    // the owner are kept from the garbage collector meanwhile.
+                    let kept = state.constructing.len();
+                    if let Some(rk) = right_obj.heap_ref() {
+                        state.constructing.push(rk.clone());
+                    }
+                    let owner = eval(&node.children[0].children[0], state);
+                    if let Some(rk) = owner.heap_ref().filter(|rk| state.objsys.has_instance(rk)).cloned() {
+                        let cname = state.objsys.get_instance(&rk).classname.clone();
+                        if let Some(meth) = overridden(&cname, "[]=", state) {
+                            state.constructing.push(rk.clone());
+                            let index = eval(&node.children[0].children[1], state);
+                            state.constructing.truncate(kept);
+                            call_function_with(MaybeRef::Ref(rk), &meth, vec![index, right_obj], node, state);
+                            return Object::Null;
+                        }
+                    }
+                    state.constructing.truncate(kept);
+
                     match &node.children[0].children[0].nodetype {
 
                         NodeType::Name(name, linenum, symnum) => {
@@ -109,7 +145,7 @@ pub fn eval(
                                             state.eval_var = topname.clone();
                                         }
 
-                                        let compval = eval(&n.children[0], state);
+                                        let compval = eval_top_init(&n.children[0], state);
 
                                         let wrapped = Node::new(NodeType::TopVar(
                                             typ.clone(),
@@ -171,6 +207,22 @@ pub fn eval(
             }
         }
 
+        NodeType::TypedVar(typ, name, _, _) => {
+            // Declared without an initializer. A nullable variable starts
+            // out as null, others must be assigned before they are read.
+            if state.stack.has_in_lexscope(name) {
+                // As dart.
+                evalerror(
+                    format!("'{}' is already declared in this scope.", name),
+                    state,
+                    node
+                );
+            }
+            let val = if types::is_nullable(&types::resolve(typ, state), state) { Object::Null } else { Object::Late(None) };
+            add_typed(name, typ, val, state);
+            return Object::Null;
+        }
+
         NodeType::Not(_, _) => {
 
             let obj = eval(&node.children[0], state);
@@ -193,6 +245,9 @@ pub fn eval(
 
             match left_obj {
 
+                // Short-circuit, as Dart.
+                Object::Bool(true) => Object::Bool(true),
+
                 Object::Bool(b1) => {
                     let right_obj = eval(&node.children[1], state);
 
@@ -221,6 +276,9 @@ pub fn eval(
 
             match left_obj {
 
+                // Short-circuit, as Dart.
+                Object::Bool(false) => Object::Bool(false),
+
                 Object::Bool(b1) => {
                     let right_obj = eval(&node.children[1], state);
 
@@ -247,6 +305,10 @@ pub fn eval(
 
             let left_obj = eval(&node.children[0], state);
 
+            if let Some(obj) = user_operator(&left_obj, "<", node, state) {
+                return obj;
+            }
+
             match left_obj {
 
                 Object::Int(n1) => {
@@ -296,6 +358,10 @@ pub fn eval(
 
             let left_obj = eval(&node.children[0], state);
 
+            if let Some(obj) = user_operator(&left_obj, ">", node, state) {
+                return obj;
+            }
+
             match left_obj {
 
                 Object::Int(n1) => {
@@ -345,6 +411,10 @@ pub fn eval(
 
             let left_obj = eval(&node.children[0], state);
 
+            if let Some(obj) = user_operator(&left_obj, "<=", node, state) {
+                return obj;
+            }
+
             match left_obj {
 
                 Object::Int(n1) => {
@@ -394,6 +464,10 @@ pub fn eval(
 
             let left_obj = eval(&node.children[0], state);
 
+            if let Some(obj) = user_operator(&left_obj, ">=", node, state) {
+                return obj;
+            }
+
             match left_obj {
 
                 Object::Int(n1) => {
@@ -442,48 +516,60 @@ pub fn eval(
         NodeType::Equal(_, _) => {
 
             let left_obj = eval(&node.children[0], state);
+
+            // The left operand is kept from the garbage collector while the right is evaluated.
+            let rooted = left_obj.heap_ref().cloned();
+            if let Some(rk) = &rooted {
+                state.constructing.push(rk.clone());
+            }
             let right_obj = eval(&node.children[1], state);
+            if rooted.is_some() {
+                state.constructing.pop();
+            }
 
-            return match left_obj {
+            let cname = match &node.children[0].nodetype {
+                NodeType::SuperRef(cname, _, _) => Some(state.objsys.get_class(cname).parent.clone()),
+                _ => None
+            };
+            return Object::Bool(operator_equals(left_obj, right_obj, cname, node, state));
+        }
 
-                Object::Int(n1) => {
-                    match right_obj {
-                        Object::Int(n2) => Object::Bool(n1 == n2),
-                        Object::Double(x2) => Object::Bool((n1 as f64) == x2),
-                        _ => Object::Bool(false)
-                    }
-                }
-                Object::Double(x1) => {
-                    match right_obj {
-                        Object::Int(n2) => Object::Bool(x1 == (n2 as f64)),
-                        Object::Double(x2) => Object::Bool(x1 == x2),
-                        _ => Object::Bool(false)
-                    }
-                }
-                Object::Bool(b1) => {
-                    match right_obj {
-                        Object::Bool(b2) => Object::Bool(b1 == b2),
-                        _ => Object::Bool(false)
-                    }
-                }
-                Object::String(s1) => {
-                    match right_obj {
-                        Object::String(s2) => Object::Bool(s1 == s2),
-                        _ => Object::Bool(false)
-                    }
-                }
-                Object::Reference(k1) => {
-                    match right_obj {
-                        Object::Reference(k2) => Object::Bool(k1 == k2),
-                        _ => Object::Bool(false)
-                    }
-                }
-                _ => evalerror(
-                    format!("Equality not implemented for object: {}", left_obj),
+        NodeType::Is(typ, negated, _, _) => {
+
+            let obj = eval(&node.children[0], state);
+            let is = types::is_subtype(&types::type_name(&obj, state), &types::resolve(typ, state), state);
+            return Object::Bool(is != *negated);
+        }
+
+        NodeType::As(typ, _, _) => {
+
+            let obj = eval(&node.children[0], state);
+            let objtype = types::type_name(&obj, state);
+            let target = types::resolve(typ, state);
+
+            if !types::is_subtype(&objtype, &target, state) {
+                dartthrow(
+                    format!("type '{}' is not a subtype of type '{}' in type cast", types::display(&objtype), types::display(&target)),
                     state,
-                    &node.children[0]
-                )
+                    node
+                );
+            }
+            return obj;
+        }
+
+        NodeType::NullCheck(_, _) => {
+
+            let obj = eval(&node.children[0], state);
+            if let Object::Null = obj {
+                // As dart.
+                dartthrow("Null check operator used on a null value", state, node);
             }
+            return obj;
+        }
+
+        NodeType::Late(_, _) => {
+            // Held by the variable until it is first read.
+            return Object::Late(node.children.first().cloned());
         }
 
         NodeType::BitAnd(_, _) => {
@@ -574,6 +660,10 @@ pub fn eval(
 
             let left_obj = eval(&node.children[0], state);
 
+            if let Some(obj) = user_operator(&left_obj, "+", node, state) {
+                return obj;
+            }
+
             match &left_obj {
                 Object::Int(s1) => {
 
@@ -581,7 +671,7 @@ pub fn eval(
 
                     match &right_obj {
                         Object::Int(s2) => {
-                            Object::Int(s1 + s2)
+                            Object::Int(s1.wrapping_add(*s2))
                         }
                         Object::Double(s2) => {
                             Object::Double(*s1 as f64 + s2)
@@ -638,10 +728,15 @@ pub fn eval(
 
             let left_obj = eval(&node.children[0], state);
 
+            let op = if node.children.len() == 1 { "unary-" } else { "-" };
+            if let Some(obj) = user_operator(&left_obj, op, node, state) {
+                return obj;
+            }
+
             if node.children.len() == 1 {
                 return match &left_obj {
                     Object::Int(n) => {
-                        Object::Int(-*n)
+                        Object::Int(n.wrapping_neg())
                     }
                     Object::Double(x) => {
                         Object::Double(-*x)
@@ -660,7 +755,7 @@ pub fn eval(
 
                     match &right_obj {
                         Object::Int(s2) => {
-                            Object::Int(s1 - s2)
+                            Object::Int(s1.wrapping_sub(*s2))
                         }
                         Object::Double(s2) => {
                             Object::Double(*s1 as f64 - s2)
@@ -701,13 +796,17 @@ pub fn eval(
 
             let left_obj = eval(&node.children[0], state);
 
+            if let Some(obj) = user_operator(&left_obj, "*", node, state) {
+                return obj;
+            }
+
             match &left_obj {
                 Object::Int(s1) => {
                     let right_obj = eval(&node.children[1], state);
 
                     match &right_obj {
                         Object::Int(s2) => {
-                            Object::Int(s1 * s2)
+                            Object::Int(s1.wrapping_mul(*s2))
                         }
                         Object::Double(s2) => {
                             Object::Double(*s1 as f64 * s2)
@@ -748,6 +847,10 @@ pub fn eval(
 
             let left_obj = eval(&node.children[0], state);
 
+            if let Some(obj) = user_operator(&left_obj, "/", node, state) {
+                return obj;
+            }
+
             match &left_obj {
                 Object::Int(s1) => {
                     let right_obj = eval(&node.children[1], state);
@@ -791,6 +894,51 @@ pub fn eval(
             }
         }
 
+        NodeType::TruncDiv(_, _) => {
+
+            let left_obj = eval(&node.children[0], state);
+
+            if let Some(obj) = user_operator(&left_obj, "~/", node, state) {
+                return obj;
+            }
+            let right_obj = eval(&node.children[1], state);
+
+            let (x1, x2) = match (&left_obj, &right_obj) {
+                (Object::Int(n1), Object::Int(n2)) => {
+                    if *n2 == 0 {
+                        // As Dart.
+                        dartthrow("IntegerDivisionByZeroException", state, node)
+                    }
+                    return Object::Int(n1.wrapping_div(*n2));
+                }
+                (Object::Int(n1), Object::Double(x2)) => (*n1 as f64, *x2),
+                (Object::Double(x1), Object::Int(n2)) => (*x1, *n2 as f64),
+                (Object::Double(x1), Object::Double(x2)) => (*x1, *x2),
+                (Object::Int(_), _) |
+                (Object::Double(_), _) => evalerror(
+                    format!("Illegal right operand for truncating division: {}", right_obj),
+                    state,
+                    &node.children[1]
+                ),
+                _ => evalerror(
+                    format!("Illegal left operand for truncating division: {}", left_obj),
+                    state,
+                    &node.children[0]
+                )
+            };
+
+            let res = (x1 / x2).trunc();
+            if !res.is_finite() {
+                // As Dart.
+                dartthrow(
+                    format!("Unsupported operation: Result of truncating division is {}", Object::Double(res)),
+                    state,
+                    node
+                )
+            }
+            return Object::Int(res as i64);
+        }
+
         NodeType::PreIncrement(_, _) => {
 
             let valnode = &node.children[0];
@@ -798,7 +946,7 @@ pub fn eval(
             let oldval = get_name(valnode, state);
             match oldval {
                 Object::Int(n) => {
-                    let newval = Object::Int(n + 1);
+                    let newval = Object::Int(n.wrapping_add(1));
                     set_name(valnode, newval.clone(), state);
                     return newval;
                 }
@@ -817,7 +965,7 @@ pub fn eval(
             let oldval = get_name(valnode, state);
             match oldval {
                 Object::Int(n) => {
-                    let newval = Object::Int(n - 1);
+                    let newval = Object::Int(n.wrapping_sub(1));
                     set_name(valnode, newval.clone(), state);
                     return newval;
                 }
@@ -836,7 +984,7 @@ pub fn eval(
             let oldval = get_name(valnode, state);
             match oldval {
                 Object::Int(n) => {
-                    let newval = Object::Int(n + 1);
+                    let newval = Object::Int(n.wrapping_add(1));
                     set_name(valnode, newval.clone(), state);
                     return oldval;
                 }
@@ -855,7 +1003,7 @@ pub fn eval(
             let oldval = get_name(valnode, state);
             match oldval {
                 Object::Int(n) => {
-                    let newval = Object::Int(n - 1);
+                    let newval = Object::Int(n.wrapping_sub(1));
                     set_name(valnode, newval.clone(), state);
                     return oldval;
                 }
@@ -880,38 +1028,17 @@ pub fn eval(
         },
 
         NodeType::Str(s, _, _) => {
+            Object::String(s.clone())
+        },
 
-            if node.children.is_empty() {
-                return Object::String(s.clone())
-            }
-
-            let mut evaled_itps = Vec::new();
-            for itp in &node.children {
-                evaled_itps.push(eval(itp, state));
-            }
-
-            let parts : Vec<&str> = s.as_str().split("$").collect();
-
-            let mut built : String = String::new();
+        NodeType::StrInterpol(parts, _, _) => {
 
-            for i in 0 .. evaled_itps.len() {
+            let mut built = String::new();
 
-                // If it's a reference, call the toString method on the object.
-                if let Object::Reference(rk) = &evaled_itps[i] {
-                    let inst = state.objsys.get_instance(&rk);
-                    let c = state.objsys.get_class(&inst.classname);
-                    let meth_obj = c.get_method("toString", state, &node.children[i]);
-                    let str_obj = call_function(
-                        MaybeRef::Ref(rk.clone()), 
-                        &meth_obj, 
-                        &node.children[0], 
-                        state
-                    );
-                    built = format!("{}{}{}", parts[0], built.clone(), str_obj);
-                }
-                else {
-                    built = format!("{}{}{}", parts[0], built.clone(), evaled_itps[i].clone());
-                }
+            for (i, itp) in node.children.iter().enumerate() {
+                built.push_str(&parts[i]);
+                let obj = eval(itp, state);
+                built.push_str(&to_dart_string(obj, state, itp));
             }
             built.push_str(parts.last().unwrap());
 
@@ -922,6 +1049,10 @@ pub fn eval(
             return get_name(node, state);
         }
 
+        NodeType::NamedArg(_, _, _) => {
+            return eval(&node.children[0], state);
+        }
+
         NodeType::Return(_, _) => {
             let retval = eval(&node.children[0], state);
             return Object::Return(Box::new(retval));
@@ -930,6 +1061,9 @@ pub fn eval(
         NodeType::CollAccess(_, _) => {
 
             let owner = eval(&node.children[0], state);
+            if let Some(result) = user_operator(&owner, "[]", node, state) {
+                return result;
+            }
             let index_obj = eval(&node.children[1], state);
             let ilist_ref = get_field(owner, "__list", state, &node.children[0]);
 
@@ -956,13 +1090,56 @@ pub fn eval(
                 )
             }
 
+            if let Some(typename) = static_type_name(owner, state) {
+                return builtin::call_static(node, &typename, name, state);
+            }
+
+            if let NodeType::SuperRef(cname, _, _) = &owner.nodetype {
+                // The method of the parent, with this as receiver.
+                let parent = state.objsys.get_class(cname).parent.clone();
+                if parent != "" && state.objsys.get_class(&parent).has_method(name, state) {
+                    let meth_obj = state.objsys.get_class(&parent).get_method(name, state, node);
+                    let typeargs = call_typeargs(node, state);
+                    return call_generic(MaybeRef::Ref(state.objsys.get_this()), &meth_obj, &node.children[0], typeargs, state);
+                }
+                // A field holding a function.
+                let part = super_part(cname, name, "method", state, node);
+                let funcobj = get_field(part, name, state, node);
+                return call_value(&funcobj, node, state);
+            }
+
             let reference: Object = eval(owner, state);
 
-            if let Object::Reference(refid) = reference {
+            if let Object::Reference(refid) = &reference {
+                let refid = refid.clone();
                 let instance = state.objsys.get_instance(&refid);
                 let c = state.objsys.get_class(&instance.classname);
+                if !c.has_method(name, state) && has_member(&reference, name, state) {
+                    // A field holding a function.
+                    let funcobj = get_field(reference, name, state, node);
+                    return call_value(&funcobj, node, state);
+                }
+                if !c.has_method(name, state) {
+                    let (argobjs, named) = invocation_args(&node.children[0].children, state);
+                    return no_such_method(&refid, "method", name, argobjs, named, state, node);
+                }
                 let meth_obj = c.get_method(name, state, node);
-                return call_function(MaybeRef::Ref(refid), &meth_obj, &node.children[0], state)
+                let typeargs = call_typeargs(node, state);
+                return call_generic(MaybeRef::Ref(refid), &meth_obj, &node.children[0], typeargs, state)
+            }
+            if let Object::Int(_) | Object::Double(_) = reference {
+                return builtin::call_method(node, &reference, name, state);
+            }
+            if let Object::String(_) = reference {
+                return builtin::call_string_method(node, &reference, name, state);
+            }
+            if let Object::Null = reference {
+                if name == "toString" && node.children[0].children.is_empty() {
+                    return Object::String(String::from("null"));
+                }
+                let args: Vec<String> = argnodes_to_argobjs(&node.children[0].children, state)
+                    .iter().map(|a| a.to_string()).collect();
+                null_receiver("method", name, &format!("{}({})", name, args.join(", ")), state, node);
             }
             panic!("Can't access {} of {}", name, owner);
         }
@@ -980,42 +1157,60 @@ pub fn eval(
             // First look in stack.
             if state.stack.has(s) {
                 let funcobj = state.stack.get(s).clone();
-
-                return match funcobj {
-                    Object::Function(_, _, _, _) => {
-                        call_function(MaybeRef::None, &funcobj, &node.children[0], state)
-                    }
-                    Object::Constructor(_, _, _, _, _) => {
-                        call_constructor(&funcobj, &node.children[0], state)
-                    }
-                    _ => panic!("Called non-callable.")
-                }
+                return call_value(&funcobj, node, state);
             }
 
             // Next we look at other functions available from current file.
             if state.has_global(s) {
 
                 let funcnode = state.get_global(s);
+                let typeargs = call_typeargs(node, state);
 
                 return match funcnode.nodetype {
                     NodeType::FunDef(_, _, _, _, _) => {
-                        call_function(
+                        call_generic(
                             MaybeRef::None,
                             &create_function(&funcnode),
                             &node.children[0],
+                            typeargs,
                             state)
                     }
                     NodeType::Constructor(_, _, _, _, _, _, _) => {
                         call_constructor(
                             &create_constructor(&funcnode),
                             &node.children[0],
+                            typeargs,
                             state)
                     }
+                    NodeType::TopVarLazy(_, _, _, _) |
+                    NodeType::TopVar(_, _, _, _, _) => {
+                        // A top level variable holding a function.
+                        let (linenum, symnum) = node.find_node_position();
+                        let funcobj = eval(&Node::new(NodeType::Name(s.clone(), linenum, symnum)), state);
+                        call_value(&funcobj, node, state)
+                    }
                     _ => panic!("Expected function definition or constructor.")
                 }
             }
 
-            // Third we check if we have a built-in function.
+            // Then methods called without an explicit this.
+            if state.objsys.has_this() {
+                let this = state.objsys.get_this();
+                let class = state.objsys.get_class(&state.objsys.get_instance(&this).classname);
+
+                if class.has_method(s, state) {
+                    let meth = class.get_method(s, state, node);
+                    let typeargs = call_typeargs(node, state);
+                    return call_generic(MaybeRef::Ref(this), &meth, &node.children[0], typeargs, state);
+                }
+                if has_member(&state.objsys.get_this_object(), s, state) {
+                    // A field holding a function.
+                    let funcobj = get_field(state.objsys.get_this_object(), s, state, node);
+                    return call_value(&funcobj, node, state);
+                }
+            }
+
+            // Last we check if we have a built-in function.
             if builtin::has_function(s) {
                 // let mut args = argnodes_to_argobjs(
                 //     &node.children[0].children,
@@ -1073,6 +1268,40 @@ pub fn eval(
             return Object::Null;
         }
 
+        NodeType::Switch(_, _) |
+        NodeType::SwitchExpr(_, _) => {
+
+            let subject = eval(&node.children[0], state);
+
+            // The subject lives on the stack, so it is not garbage collected.
+            state.stack.push_lex();
+            state.stack.add_new("__subject", subject.clone());
+
+            for case in &node.children[1..] {
+                for head in &case.children[1..] {
+                    // Variables bound by the pattern are scoped to the case.
+                    state.stack.push_lex();
+                    if match_head(head, &subject, state) {
+                        let ret = eval(&case.children[0], state);
+                        state.stack.pop_lex();
+                        state.stack.pop_lex();
+                        return ret;
+                    }
+                    state.stack.pop_lex();
+                }
+            }
+            state.stack.pop_lex();
+
+            if let NodeType::SwitchExpr(_, _) = node.nodetype {
+                dartthrow(
+                    format!("Bad state: No case of the switch expression matched '{}'.", to_dart_string(subject, state, node)),
+                    state,
+                    node
+                );
+            }
+            return Object::Null;
+        }
+
         NodeType::While(_, _) => {
 
             let boolnode = &node.children[0];
@@ -1084,7 +1313,10 @@ pub fn eval(
                 Object::Bool(mut v) => {
 
                     while v {
-                        eval(block, state);
+                        let retval = eval_body(block, state);
+                        if let Object::Return(_) = retval {
+                            return retval;
+                        }
                         cond = eval(boolnode, state);
 
                         match &cond {
@@ -1107,14 +1339,20 @@ pub fn eval(
             let block = &node.children[0];
             let boolnode = &node.children[1];
 
-            eval(block, state);
+            let retval = eval_body(block, state);
+            if let Object::Return(_) = retval {
+                return retval;
+            }
 
             let mut cond = eval(boolnode, state);
 
             if let Object::Bool(mut b) = cond {
 
                 while b {
-                    eval(block, state);
+                    let retval = eval_body(block, state);
+                    if let Object::Return(_) = retval {
+                        return retval;
+                    }
                     cond = eval(boolnode, state);
 
                     match &cond {
@@ -1143,39 +1381,59 @@ pub fn eval(
                 // First child is an the variable, second the
                 // free variable, and third the body block. 
                 let typedvar = &node.children[0];
-                let iter_ref = eval(&node.children[1], state);
+                let iterable = eval(&node.children[1], state);
                 let body = &node.children[2];
 
-                let ilist_ref = get_field(iter_ref, "__list", state, node);
+                let (typ, name) = match &typedvar.nodetype {
+                    NodeType::TypedVar(typ, name, _, _) => (typ, name),
+                    _ => panic!("For loop expecped typed var. Got: {}", &typedvar)
+                };
 
-                match &ilist_ref {
+                if let Object::Reference(rk) = &iterable {
 
-                    Object::Reference(ilist_rk) => {
-                        let ilist = state.objsys.get_list(&ilist_rk);
+                    if state.objsys.get_instance(rk).has_field("__list") {
+                        // Lists are iterated directly.
+                        if let Object::Reference(ilist_rk) = get_field(iterable.clone(), "__list", state, node) {
+                            let cloned = state.objsys.get_list(&ilist_rk).els.clone();
 
-                        // Put var on new, inner, stack frame
-                        let mut cloned = Vec::new();
-                        for obj in &ilist.els {
-                            cloned.push(obj.clone());
-                        }
-
-                        state.stack.push_lex();
+                            for c in cloned {
+                                // Put var on new, inner, stack frame
+                                check_type(&c, typ, "", state, typedvar);
+                                state.stack.push_lex();
+                                add_typed(name, typ, c, state);
+                                let retval = eval(body, state);
+                                state.stack.pop_lex();
 
-                        for c in cloned {
-                            match &typedvar.nodetype {
-                                NodeType::TypedVar(_, name, _, _) => {
-                                    state.stack.add_new(name, c);
-                                    eval(body, state);
-                                }
-                                _ => {
-                                    panic!("For loop expecped typed var. Got: {}", &typedvar);
+                                if let Object::Return(_) = retval {
+                                    return retval;
                                 }
                             }
+                            return Object::Null;
                         }
+                    }
+                }
+
+                // Anything else is driven through its iterator.
+                let iterator = get_field(iterable, "iterator", state, &node.children[1]);
+
+                // The iterator lives on the stack, so it is not garbage collected.
+                state.stack.push_lex();
+                state.stack.add_new("__iterator", iterator.clone());
+
+                while move_next(&iterator, &node.children[1], state) {
+                    let current = get_field(iterator.clone(), "current", state, &node.children[1]);
+                    check_type(&current, typ, "", state, typedvar);
+                    state.stack.push_lex();
+                    add_typed(name, typ, current, state);
+                    let retval = eval(body, state);
+                    state.stack.pop_lex();
+
+                    if let Object::Return(_) = retval {
                         state.stack.pop_lex();
+                        return retval;
                     }
-                    x => panic!("Expected reference, got: {}", x)
                 }
+                state.stack.pop_lex();
                 return Object::Null;
             }
             else if node.children.len() == 4 {
@@ -1186,6 +1444,8 @@ pub fn eval(
                 let mutexpr = &node.children[2];
                 let body = &node.children[3];
 
+                // The loop variable is scoped to the loop.
+                state.stack.push_lex();
                 eval(assign, state);
 
                 loop {
@@ -1197,7 +1457,11 @@ pub fn eval(
                             if !b {
                                 break;
                             }
-                            eval(body, state);
+                            let retval = eval_body(body, state);
+                            if let Object::Return(_) = retval {
+                                state.stack.pop_lex();
+                                return retval;
+                            }
                             eval(mutexpr, state);
                         }
                         _ => evalerror(
@@ -1207,6 +1471,7 @@ pub fn eval(
                         )
                     }
                 }
+                state.stack.pop_lex();
             }
             return Object::Null;
         }
@@ -1215,13 +1480,7 @@ pub fn eval(
 
             for c in &node.children {
 
-                if Instant::now() - state.last_gc > GC_TIME {
-                    let gc_start = state.start_time.elapsed();
-                    state.stack.garbagecollect(&mut state.objsys, &state.constructing, &state.globals);
-                    let gc_end = state.start_time.elapsed();
-                    state.last_gc = Instant::now();
-                    println!("Garbage collected in {}μs", (gc_end - gc_start).as_micros());
-                }
+                collect_garbage_if_due(state);
 
                 let retval = eval(c, state);
 
@@ -1232,25 +1491,104 @@ pub fn eval(
             return Object::Null;
         }
 
-        NodeType::List(_, _) => {
+        NodeType::List(elemtype, _, _) => {
+
+            let list = create_list(vec![], state);
+
+            // The list is kept on the stack while its elements
+            // are evaluated, where the garbage collector can see it.
+            state.stack.push_lex();
+            state.stack.add_new("__elements", list.clone());
+
+            let ilist_rk = match get_field(list.clone(), "__list", state, node) {
+                Object::Reference(rk) => rk,
+                x => panic!("Internal __list field was not a reference: {}", x)
+            };
+            for c in &node.children {
+                add_elements(c, &ilist_rk, false, state);
+            }
+            state.stack.pop_lex();
+
+            let els = state.objsys.get_list(&ilist_rk).els.clone();
+            let typearg = element_type(elemtype, &els, state);
+            if let Object::Reference(rk) = &list {
+                state.objsys.get_instance_mut(rk).typeargs = vec![typearg];
+            }
+            return list;
+        }
+
+        NodeType::Map(keytype, valtype, _, _) => {
+
+            // The keys and values are gathered in turn in a list, kept on the
+            // stack while they are evaluated, where the garbage collector can see it.
+            let list = create_list(vec![], state);
+            state.stack.push_lex();
+            state.stack.add_new("__entries", list.clone());
 
-            let class = state.objsys.get_class("List");
-            let mut inst = class.instantiate();
-            
-            let mut vals: Vec<Object> = Vec::new();
+            let ilist_rk = match get_field(list.clone(), "__list", state, node) {
+                Object::Reference(rk) => rk,
+                x => panic!("Internal __list field was not a reference: {}", x)
+            };
             for c in &node.children {
-                let v = eval(c, state);
-                vals.push(v);
+                add_elements(c, &ilist_rk, true, state);
             }
+            let entries: Vec<(Object, Object)> = state.objsys.get_list(&ilist_rk).els
+                .chunks(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect();
+
+            // Without type arguments, the key and value types are inferred from the entries.
+            let infer = |objs: Vec<&Object>, state: &mut State| {
+                objs.into_iter()
+                    .map(|obj| types::type_name(obj, state))
+                    .reduce(|a, b| types::upper_bound(&a, &b, state))
+                    .unwrap_or(String::from("dynamic"))
+            };
+            let (keytype, valtype) = if keytype.is_empty() {
+                (infer(entries.iter().map(|(k, _)| k).collect(), state), infer(entries.iter().map(|(_, v)| v).collect(), state))
+            }
+            else {
+                (types::resolve(keytype, state), types::resolve(valtype, state))
+            };
+            // Keys that are == by a user defined ==, and not only by identity,
+            // are merged as the map is hashed.
+            let map = create_map(entries, &keytype, &valtype, state);
+            state.stack.add_new("__map", map.clone());
+            api::map::rehash_map(&map, node, state);
+            state.stack.pop_lex();
+            return map;
+        }
+
+        NodeType::Set(elemtype, _, _) => {
 
-            let mut ilist = InternalList::new();
-            ilist.set_elements(vals);
+            // The elements are gathered as for a list literal, and
+            // become the keys of the map the set is kept in.
+            let list = create_list(vec![], state);
+            state.stack.push_lex();
+            state.stack.add_new("__elements", list.clone());
 
-            inst.set_field(String::from("__list"), Object::Reference(ilist.id.clone()));
-            state.objsys.register_list(ilist);
+            let ilist_rk = match get_field(list.clone(), "__list", state, node) {
+                Object::Reference(rk) => rk,
+                x => panic!("Internal __list field was not a reference: {}", x)
+            };
+            for c in &node.children {
+                add_elements(c, &ilist_rk, false, state);
+            }
+            let els = state.objsys.get_list(&ilist_rk).els.clone();
+            let typearg = element_type(elemtype, &els, state);
+
+            let entries = els.into_iter().map(|el| (el, Object::Bool(true))).collect();
+            let map = create_map(entries, &typearg, "bool", state);
+            state.stack.add_new("__map", map.clone());
+            api::map::rehash_map(&map, node, state);
+            let set = create_set(map, &typearg, state);
+            state.stack.pop_lex();
+            return set;
+        }
 
-            let instref = state.objsys.register_instance(*inst);
-            return instref;
+        NodeType::SuperRef(_, _, _) => {
+            // The receiver of a super operator call, as in super == other.
+            return Object::Reference(state.objsys.get_this());
         }
 
         NodeType::This(_, _) => {
@@ -1269,6 +1607,406 @@ pub fn eval(
             return Object::Null;
         }
 
+        NodeType::SyncStar(_, _) => {
+            // The body runs as the iterable is iterated.
+            return generator::create_iterable(node, state);
+        }
+
+        NodeType::Async(_, _, _) => {
+            // The body runs until it awaits, then the future is returned.
+            return generator::start_async(node, state);
+        }
+
+        NodeType::Await(_, _) => {
+            // Awaits in async bodies are evaluated as they are noted.
+            evalerror(
+                "Unsupported use of 'await'.",
+                state,
+                node
+            )
+        }
+
+        NodeType::AsyncStar(_, _, _) => {
+            // The body runs as the stream is listened to.
+            return generator::start_async_star(node, state);
+        }
+
         _ => panic!("Unknown node type: {}", t)
     }
 }
+
+
+/// The type arguments of a call, resolved where the call is made.
+fn call_typeargs(node: &Node, state: &State) -> Vec<String> {
+    match node.children.get(1) {
+        Some(Node { nodetype: NodeType::TypeArgs(args, _, _), .. }) => {
+            args.iter().map(|a| types::resolve(a, state)).collect()
+        }
+        _ => Vec::new()
+    }
+}
+
+
+/// Call a function or constructor held by a variable or field, or
+/// an instance of a class with a call method.
+fn call_value(funcobj: &Object, node: &Node, state: &mut State) -> Object {
+
+    let typeargs = call_typeargs(node, state);
+
+    match funcobj {
+        Object::Function(_, _, _, _, _, _) |
+        Object::BoundMethod(_, _) |
+        Object::Reference(_) => {
+            call_generic(MaybeRef::None, funcobj, &node.children[0], typeargs, state)
+        }
+        Object::Constructor(_, _, _, _, _) => {
+            call_constructor(funcobj, &node.children[0], typeargs, state)
+        }
+        Object::Null => null_receiver("method", "call", "call()", state, node),
+        x => {
+            // As dart.
+            dartthrow(
+                format!("NoSuchMethodError: Class '{}' has no instance method 'call'.", types::type_name(x, state)),
+                state,
+                node
+            )
+        }
+    }
+}
+
+
+/// The result of a user defined operator, as for a + b where a is a
+/// Duration, or None if the class of left doesn't define the operator.
+fn user_operator(left_obj: &Object, op: &str, node: &Node, state: &mut State) -> Option<Object> {
+
+    let rk = match left_obj {
+        Object::Reference(rk) if state.objsys.has_instance(rk) => rk.clone(),
+        _ => return None
+    };
+    // For super + x, the operator is looked up from the parent class.
+    let cname = match &node.children[0].nodetype {
+        NodeType::SuperRef(cname, _, _) => state.objsys.get_class(cname).parent.clone(),
+        _ => state.objsys.get_instance(&rk).classname.clone()
+    };
+    let meth = overridden(&cname, op, state)?;
+    // The left operand is kept from the garbage collector while the right is evaluated.
+    state.constructing.push(rk.clone());
+    let args = node.children[1..].iter().map(|n| eval(n, state)).collect();
+    state.constructing.pop();
+    Some(call_function_with(MaybeRef::Ref(rk), &meth, args, node, state))
+}
+
+
+/// Whether left == right. A user defined == of the class of left is called,
+/// or of cname if given, as for super == x. Comparing with null does not call it.
+pub fn operator_equals(left_obj: Object, right_obj: Object, cname: Option<String>, node: &Node, state: &mut State) -> bool {
+
+    // Type objects are equal when they stand for the same type.
+    if let (Some(t1), Some(t2)) = (
+        types::type_of_type_object(&left_obj, state),
+        types::type_of_type_object(&right_obj, state)) {
+        return t1 == t2;
+    }
+
+    if let (Object::Reference(rk), false) = (&left_obj, matches!(right_obj, Object::Null)) {
+        let cname = cname.unwrap_or_else(|| state.objsys.get_instance(rk).classname.clone());
+        if let Some(meth) = overridden(&cname, "==", state) {
+            return match call_function_with(MaybeRef::Ref(rk.clone()), &meth, vec![right_obj], node, state) {
+                Object::Bool(b) => b,
+                x => evalerror(format!("Expected bool from ==. Got: {}", x), state, node)
+            };
+        }
+    }
+
+    match equals(&left_obj, &right_obj) {
+        Some(b) => b,
+        None => evalerror(
+            format!("Equality not implemented for object: {}", left_obj),
+            state,
+            &node.children[0]
+        )
+    }
+}
+
+
+/// Whether the value matches the pattern of a case head, and the guard
+/// holds. Variables of the pattern are bound in the current scope.
+pub fn match_head(head: &Node, value: &Object, state: &mut State) -> bool {
+
+    if !match_pattern(&head.children[0], value, state) {
+        return false;
+    }
+    match head.children.get(1) {
+        Some(guard) => match eval(guard, state) {
+            Object::Bool(b) => b,
+            x => evalerror(format!("Expected bool from guard. Got: {}", x), state, guard)
+        },
+        None => true
+    }
+}
+
+
+fn match_pattern(pattern: &Node, value: &Object, state: &mut State) -> bool {
+
+    match &pattern.nodetype {
+
+        NodeType::VarPattern(typ, name, _, _) => {
+            if typ != "var" && !types::is_subtype(&types::type_name(value, state), &types::resolve(typ, state), state) {
+                return false;
+            }
+            if name != "_" {
+                add_typed(name, typ, value.clone(), state);
+            }
+            true
+        }
+
+        NodeType::ConstPattern(_, _) => {
+            let constant = eval(&pattern.children[0], state);
+            operator_equals(constant, value.clone(), None, pattern, state)
+        }
+
+        NodeType::ObjectPattern(typ, _, _) => {
+            if !types::is_subtype(&types::type_name(value, state), &types::resolve(typ, state), state) {
+                return false;
+            }
+            for field in &pattern.children {
+                if let NodeType::FieldPattern(name, _, _) = &field.nodetype {
+                    let fieldval = get_field(value.clone(), name, state, field);
+                    if !match_pattern(&field.children[0], &fieldval, state) {
+                        return false;
+                    }
+                }
+            }
+            true
+        }
+
+        x => panic!("Unexpected pattern: {}", x)
+    }
+}
+
+
+/// Run a loop body in a scope of its own, so that
+/// every iteration gets fresh variables.
+fn eval_body(body: &Node, state: &mut State) -> Object {
+    state.stack.push_lex();
+    let retval = eval(body, state);
+    state.stack.pop_lex();
+    retval
+}
+
+
+/// The element type of a list or set literal. Without a type
+/// argument, it is inferred from the elements.
+fn element_type(elemtype: &str, els: &[Object], state: &mut State) -> String {
+    if elemtype.is_empty() {
+        return els.iter()
+            .map(|el| types::type_name(el, state))
+            .reduce(|a, b| types::upper_bound(&a, &b, state))
+            .unwrap_or(String::from("dynamic"));
+    }
+    types::resolve(elemtype, state)
+}
+
+
+/// Add the elements an element of a collection literal stands
+/// for. Spreads, if and for elements can give any number of them.
+/// In a map, the keys and values of the entries are added in turn.
+fn add_elements(node: &Node, ilist_rk: &RefKey, in_map: bool, state: &mut State) {
+
+    // In async bodies, what a spread, if or for element adds is noted
+    // as a whole, and added again if the statement is evaluated again.
+    let compound = matches!(node.nodetype, NodeType::Spread(_, _, _) | NodeType::MapPair(_, _) |
+        NodeType::CollectionIf(_, _) | NodeType::CollectionFor(_, _));
+    if compound && generator::is_noting(state) {
+        let added = generator::noted(Some(node), state, |state| {
+            let added = state.objsys.register_list(InternalList::new());
+            if let Object::Reference(rk) = &added {
+                state.constructing.push(rk.clone());
+                add_element(node, rk, in_map, state);
+                state.constructing.pop();
+            }
+            added
+        });
+        if let Object::Reference(rk) = added {
+            for el in state.objsys.get_list(&rk).els.clone() {
+                state.objsys.get_list_mut(ilist_rk).add(el);
+            }
+        }
+        return;
+    }
+    add_element(node, ilist_rk, in_map, state);
+}
+
+
+fn add_element(node: &Node, ilist_rk: &RefKey, in_map: bool, state: &mut State) {
+
+    match &node.nodetype {
+
+        NodeType::Spread(nullaware, _, _) => {
+            let obj = eval(&node.children[0], state);
+            if *nullaware && matches!(obj, Object::Null) {
+                return;
+            }
+            let els = if in_map {
+                spread_entries(&obj, &node.children[0], state)
+            }
+            else {
+                spread_elements(&obj, &node.children[0], state)
+            };
+            for el in els {
+                state.objsys.get_list_mut(ilist_rk).add(el);
+            }
+        }
+
+        NodeType::MapPair(_, _) => {
+            let key = eval(&node.children[0], state);
+            state.objsys.get_list_mut(ilist_rk).add(key);
+            let val = eval(&node.children[1], state);
+            state.objsys.get_list_mut(ilist_rk).add(val);
+        }
+
+        NodeType::CollectionIf(_, _) => {
+            match eval(&node.children[0], state) {
+                Object::Bool(true) => add_elements(&node.children[1], ilist_rk, in_map, state),
+                Object::Bool(false) => {
+                    if node.children.len() == 3 {
+                        add_elements(&node.children[2], ilist_rk, in_map, state);
+                    }
+                }
+                x => evalerror(
+                    format!("Expected boolean expression. Got: {}", x),
+                    state,
+                    &node.children[0]
+                )
+            }
+        }
+
+        NodeType::CollectionFor(_, _) => {
+
+            if node.children.len() == 3 {
+                let (typ, name) = match &node.children[0].nodetype {
+                    NodeType::TypedVar(typ, name, _, _) => (typ, name),
+                    _ => panic!("For element expected typed var. Got: {}", &node.children[0])
+                };
+                let iterable = eval(&node.children[1], state);
+                let iterator = element_step(state, |state| get_field(iterable, "iterator", state, &node.children[1]));
+
+                state.stack.push_lex();
+                state.stack.add_new("__iterator", iterator.clone());
+
+                let more = |state: &mut State| Object::Bool(move_next(&iterator, &node.children[1], state));
+                while let Object::Bool(true) = element_step(state, more) {
+                    let current = element_step(state, |state| get_field(iterator.clone(), "current", state, &node.children[1]));
+                    check_type(&current, typ, "", state, &node.children[0]);
+                    state.stack.push_lex();
+                    add_typed(name, typ, current, state);
+                    add_elements(&node.children[2], ilist_rk, in_map, state);
+                    state.stack.pop_lex();
+                }
+                state.stack.pop_lex();
+            }
+            else {
+                // The loop variable is scoped to the element.
+                state.stack.push_lex();
+                eval(&node.children[0], state);
+
+                loop {
+                    match eval(&node.children[1], state) {
+                        Object::Bool(true) => {}
+                        Object::Bool(false) => break,
+                        x => evalerror(
+                            format!("Expected boolean expression. Got: {}", x),
+                            state,
+                            &node.children[1]
+                        )
+                    }
+                    state.stack.push_lex();
+                    add_elements(&node.children[3], ilist_rk, in_map, state);
+                    state.stack.pop_lex();
+                    eval(&node.children[2], state);
+                }
+                state.stack.pop_lex();
+            }
+        }
+
+        _ => {
+            let val = eval(node, state);
+            state.objsys.get_list_mut(ilist_rk).add(val);
+        }
+    }
+}
+
+
+/// A step of a for element that isn't an expression, noted
+/// like its expressions are in async bodies.
+fn element_step(state: &mut State, f: impl FnOnce(&mut State) -> Object) -> Object {
+    if generator::is_noting(state) {
+        return generator::noted(None, state, f);
+    }
+    f(state)
+}
+
+
+/// The keys and values of a spread in a map, in turn. It must be a Map.
+fn spread_entries(obj: &Object, node: &Node, state: &mut State) -> Vec<Object> {
+
+    if let Object::Reference(rk) = obj {
+        let cname = state.objsys.get_instance(rk).classname.clone();
+        if types::is_subtype(&cname, "Map", state) {
+            let mut lists = Vec::new();
+            for field in ["__keys", "__values"] {
+                let list = get_field(obj.clone(), field, state, node);
+                match get_field(list, "__list", state, node) {
+                    Object::Reference(ilist_rk) => lists.push(state.objsys.get_list(&ilist_rk).els.clone()),
+                    x => panic!("Internal __list field was not a reference: {}", x)
+                }
+            }
+            return lists[0].iter().zip(&lists[1])
+                .flat_map(|(k, v)| [k.clone(), v.clone()])
+                .collect();
+        }
+    }
+    dartthrow(
+        format!("type '{}' is not a subtype of type 'Map<dynamic, dynamic>'", types::type_name(obj, state)),
+        state,
+        node
+    )
+}
+
+
+/// The elements of a spread, which must be an Iterable.
+fn spread_elements(obj: &Object, node: &Node, state: &mut State) -> Vec<Object> {
+
+    if let Object::Reference(rk) = obj {
+        if state.objsys.get_instance(rk).has_field("__list") {
+            if let Object::Reference(ilist_rk) = get_field(obj.clone(), "__list", state, node) {
+                return state.objsys.get_list(&ilist_rk).els.clone();
+            }
+        }
+        let class = state.objsys.get_class(&state.objsys.get_instance(rk).classname);
+        if class.has_getter("iterator", state) {
+            return iterable_elements(obj, node, state);
+        }
+    }
+    dartthrow(
+        format!("type '{}' is not a subtype of type 'Iterable<dynamic>'", types::type_name(obj, state)),
+        state,
+        node
+    )
+}
+
+
+pub fn collect_garbage_if_due(state: &mut State) {
+
+    if Instant::now() - state.last_gc > GC_TIME {
+        let gc_start = state.start_time.elapsed();
+        // Queued callbacks and running async bodies are kept too.
+        let mut roots = state.constructing.clone();
+        roots.extend(state.eventloop.roots());
+        roots.extend(state.replaying.iter().flat_map(|replay| replay.references()));
+        state.stack.garbagecollect(&mut state.objsys, &roots, &state.globals);
+        let gc_end = state.start_time.elapsed();
+        state.last_gc = Instant::now();
+        println!("Garbage collected in {}μs", (gc_end - gc_start).as_micros());
+    }
+}
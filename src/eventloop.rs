@@ -0,0 +1,312 @@
+// The event loop, which runs the asynchronous parts of a program.
+//
+// Callbacks of completed futures are queued as microtasks. Timers, as
+// made by Future.delayed, are queued with the time they are due. Once
+// main returns, the microtasks run, then the earliest timer, then the
+// microtasks it queued, and so on until both queues are empty.
+//
+// With a virtual clock, as in tests, the loop doesn't sleep until the
+// next timer is due, but sets the clock forward to it. Runs are then
+// deterministic, and don't take longer than the program needs.
+//
+// Messages sent to the open ports of an isolate arrive in its inbox, and
+// are delivered between tasks. While ports are open, the loop waits for
+// messages when it has nothing else to do.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crate::evalhelp::{call_function_with, call_method_with, create_function, create_list, get_field, MaybeRef};
+use crate::isolate::{self, Delivery, Message};
+use crate::node::Node;
+use crate::object::Object;
+use crate::objsys::RefKey;
+use crate::state::State;
+use crate::types;
+
+
+/// A call of a Dart function with arguments, made later.
+pub struct Task {
+    pub func: Object,
+    pub args: Vec<Object>,
+    // Where the task was queued, for errors.
+    pub node: Node
+}
+
+
+struct Timer {
+    // Microseconds since the start of the program.
+    due: u64,
+    // Timers due at the same time run in the order they were made.
+    seq: u64,
+    task: Task
+}
+
+
+// microtasks:   Queued callbacks, run before any timer.
+// timers:       Pending timers, in no particular order.
+// now:          The virtual clock, in microseconds since the start.
+// virtual_time: Whether time is kept by the virtual clock.
+// running:      The async bodies being run, innermost last.
+// inbox:        Messages sent to the ports of the isolate, by port id.
+// ports:        The open ports, with their ReceivePort.
+pub struct EventLoop {
+    microtasks: VecDeque<Task>,
+    timers: Vec<Timer>,
+    seq: u64,
+    now: u64,
+    pub virtual_time: bool,
+    pub running: Vec<RefKey>,
+    inbox: (Sender<Delivery>, Receiver<Delivery>),
+    ports: HashMap<u64, (Object, Node)>
+}
+
+
+impl EventLoop {
+
+    pub fn new() -> EventLoop {
+        EventLoop {
+            microtasks: VecDeque::new(),
+            timers: Vec::new(),
+            seq: 0,
+            now: 0,
+            virtual_time: false,
+            running: Vec::new(),
+            inbox: channel(),
+            ports: HashMap::new()
+        }
+    }
+
+
+    pub fn add_microtask(&mut self, task: Task) {
+        self.microtasks.push_back(task);
+    }
+
+
+    /// References held by queued tasks and running async bodies,
+    /// for the garbage collector.
+    pub fn roots(&self) -> Vec<RefKey> {
+
+        let tasks = self.microtasks.iter().chain(self.timers.iter().map(|t| &t.task));
+        let mut refs: Vec<RefKey> = tasks
+            .flat_map(|t| std::iter::once(&t.func).chain(t.args.iter()))
+            .filter_map(|obj| obj.heap_ref().cloned())
+            .collect();
+
+        refs.extend(self.running.iter().cloned());
+        refs.extend(self.ports.values().filter_map(|(port, _)| port.heap_ref().cloned()));
+        refs
+    }
+
+
+    /// Where to send messages for the ports of this isolate.
+    pub fn inbox(&self) -> Sender<Delivery> {
+        self.inbox.0.clone()
+    }
+
+
+    pub fn add_port(&mut self, id: u64, port: Object, node: Node) {
+        self.ports.insert(id, (port, node));
+    }
+
+
+    pub fn remove_port(&mut self, id: u64) {
+        self.ports.remove(&id);
+    }
+
+
+    /// The timer that is due first.
+    fn next_timer(&self) -> Option<usize> {
+        (0..self.timers.len()).min_by_key(|&i| (self.timers[i].due, self.timers[i].seq))
+    }
+}
+
+
+/// Microseconds since the start of the program, on the clock in use.
+pub fn now(state: &State) -> u64 {
+    if state.eventloop.virtual_time {
+        return state.eventloop.now;
+    }
+    state.start_time.elapsed().as_micros() as u64
+}
+
+
+/// Queue a task to run once the delay, in microseconds, has passed.
+/// Gives an id for the timer, to cancel it by.
+pub fn add_timer(delay: u64, task: Task, state: &mut State) -> u64 {
+    let due = now(state) + delay;
+    let evl = &mut state.eventloop;
+    evl.seq += 1;
+    evl.timers.push(Timer { due, seq: evl.seq, task });
+    evl.seq
+}
+
+
+/// Remove a pending timer, so it neither runs nor keeps the loop going.
+pub fn cancel_timer(id: u64, state: &mut State) {
+    state.eventloop.timers.retain(|t| t.seq != id);
+}
+
+
+/// Run queued tasks until none are left, and no ports are open.
+pub fn run(state: &mut State) {
+
+    loop {
+        while let Some(task) = state.eventloop.microtasks.pop_front() {
+            run_task(task, state);
+        }
+
+        if let Ok((id, message)) = state.eventloop.inbox.1.try_recv() {
+            deliver(id, message, state);
+            continue;
+        }
+
+        let waiting = !state.eventloop.ports.is_empty();
+        let i = match state.eventloop.next_timer() {
+            Some(i) => i,
+            None if waiting => {
+                // Nothing to do until a message arrives. The sender
+                // kept by the inbox means this never disconnects.
+                let (id, message) = state.eventloop.inbox.1.recv().unwrap();
+                deliver(id, message, state);
+                continue;
+            }
+            None => break
+        };
+        let due = state.eventloop.timers[i].due;
+        let now = now(state);
+        if due > now {
+            if state.eventloop.virtual_time {
+                state.eventloop.now = due;
+            }
+            else if waiting {
+                // A message may arrive before the timer is due.
+                match state.eventloop.inbox.1.recv_timeout(Duration::from_micros(due - now)) {
+                    Ok((id, message)) => {
+                        deliver(id, message, state);
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Timeout) => {},
+                    Err(RecvTimeoutError::Disconnected) => unreachable!()
+                }
+            }
+            else {
+                thread::sleep(Duration::from_micros(due - now));
+            }
+        }
+        let timer = state.eventloop.timers.swap_remove(i);
+        run_task(timer.task, state);
+    }
+}
+
+
+/// Hand a message to the ReceivePort it was sent to. Messages
+/// arriving after the port was closed are dropped.
+fn deliver(id: u64, message: Message, state: &mut State) {
+    let (port, node) = match state.eventloop.ports.get(&id) {
+        Some((port, node)) => (port.clone(), node.clone()),
+        None => return
+    };
+    let msg = isolate::build(message, state);
+    let receive = get_field(port, "__receive", state, &node);
+    // The message is kept from the garbage collector until it is bound.
+    let kept = state.constructing.len();
+    if let Some(rk) = msg.heap_ref() {
+        state.constructing.push(rk.clone());
+    }
+    run_task(Task { func: receive, args: vec![msg], node }, state);
+    state.constructing.truncate(kept);
+}
+
+
+fn run_task(task: Task, state: &mut State) {
+    call_function_with(MaybeRef::None, &task.func, task.args, &task.node, state);
+}
+
+
+/// A top level function of an auto included library, as
+/// _futureValue of auto:future.dart for Future.value.
+pub fn library_function(library: &str, name: &str, state: &State) -> Object {
+    let i = state.looktables[library][name];
+    create_function(&state.globals[i])
+}
+
+
+/// A pending future, for values of the type.
+pub fn new_future(valuetype: &str, state: &mut State) -> Object {
+
+    let mut inst = state.objsys.get_class("Future").instantiate();
+    inst.typeargs = vec![String::from(valuetype)];
+
+    // Set as the field initializers of the class do.
+    let listeners = create_list(Vec::new(), state);
+    inst.set_field(String::from("__state"), Object::Int(0));
+    inst.set_field(String::from("__result"), Object::Null);
+    inst.set_field(String::from("__listeners"), listeners);
+    inst.set_field(String::from("__handled"), Object::Bool(false));
+    state.objsys.register_instance(*inst)
+}
+
+
+pub fn is_future(obj: &Object, state: &State) -> bool {
+    match obj {
+        Object::Reference(rk) if state.objsys.has_instance(rk) => {
+            types::is_subtype(&state.objsys.get_instance(rk).classname, "Future", state)
+        }
+        _ => false
+    }
+}
+
+
+/// The type of the value the future completes with, as int for Future<int>.
+pub fn value_type(future: &Object, state: &State) -> String {
+    match future {
+        Object::Reference(rk) => match state.objsys.get_instance(rk).typeargs.first() {
+            Some(t) => t.clone(),
+            None => String::from("dynamic")
+        },
+        x => panic!("Expected future. Got: {}", x)
+    }
+}
+
+
+/// Complete a future with a value, or as another future completes.
+pub fn complete(future: &Object, val: Object, node: &Node, state: &mut State) {
+    call_method_with(future, "__complete", vec![val], node, state);
+}
+
+
+pub fn complete_error(future: &Object, error: Object, node: &Node, state: &mut State) {
+    call_method_with(future, "__completeError", vec![error], node, state);
+}
+
+
+/// Resume the async body once the awaited value is ready. A value that
+/// is not a future is awaited as a future completed with it.
+pub fn await_value(rk: &RefKey, val: Object, node: &Node, state: &mut State) {
+
+    let source = if is_future(&val, state) {
+        val
+    }
+    else {
+        let typ = types::type_name(&val, state);
+        let source = new_future(&typ, state);
+        complete(&source, val, node, state);
+        source
+    };
+
+    let mut listener = state.objsys.get_class("_FutureListener").instantiate();
+    listener.set_field(String::from("result"), Object::Null);
+    listener.set_field(String::from("kind"), Object::String(String::from("await")));
+    listener.set_field(String::from("onValue"), Object::Null);
+    listener.set_field(String::from("onError"), Object::Null);
+    listener.set_field(String::from("wait"), Object::Null);
+    listener.set_field(String::from("index"), Object::Int(0));
+    listener.set_field(String::from("__coroutine"), Object::Reference(rk.clone()));
+    let listener = state.objsys.register_instance(*listener);
+
+    call_method_with(&source, "__listen", vec![listener], node, state);
+}
+
@@ -3,8 +3,9 @@ use crate::state::State;
 use crate::reader::Reader;
 use crate::token::Token;
 use crate::node::{NodeType, Node};
-use crate::parser::arglist;
+use crate::parser::{arglist, switch_expression, typeargs, typeargs_end, typename};
 use crate::error::parseerror;
+use crate::types::make_type;
 
 
 pub fn expression(reader: &mut Reader, ctx: &State) -> Node {
@@ -91,6 +92,18 @@ fn equality(reader: &mut Reader, state: &State) -> Node {
             eqnode
         }
 
+        Token::NotEqual(linenum, symnum) => {
+            // a != b is !(a == b).
+            reader.next();
+            let right = comparison(reader, state);
+            let mut eqnode = Node::new(NodeType::Equal(linenum, symnum));
+            eqnode.children.push(left);
+            eqnode.children.push(right);
+            let mut notnode = Node::new(NodeType::Not(linenum, symnum));
+            notnode.children.push(eqnode);
+            notnode
+        }
+
         _ => left
     }
 }
@@ -130,6 +143,25 @@ fn comparison(reader: &mut Reader, state: &State) -> Node {
             connode.children.push(right);
             connode
         }
+        Token::Is(linenum, symnum) => {
+            reader.next();
+            let mut negated = false;
+            if let Token::Not(_, _) = reader.tok() {
+                negated = true;
+                reader.next();
+            }
+            let typ = typename(reader, state);
+            let mut isnode = Node::new(NodeType::Is(typ, negated, linenum, symnum));
+            isnode.children.push(left);
+            isnode
+        }
+        Token::As(linenum, symnum) => {
+            reader.next();
+            let typ = typename(reader, state);
+            let mut asnode = Node::new(NodeType::As(typ, linenum, symnum));
+            asnode.children.push(left);
+            asnode
+        }
         Token::GreaterOrEq(linenum, symnum) => {
             reader.next();
             let right= bit_or(reader, state);
@@ -288,6 +320,15 @@ fn product_help(reader: &mut Reader, righties: &mut Queue<Node>, ops: &mut Queue
             node.children.push(righties.remove().unwrap());
             node
         }
+        Token::TruncDiv(linenum, symnum) => {
+            ops.add(Node::new(NodeType::TruncDiv(linenum, symnum))).ok();
+            reader.next();
+            let deeper = product_help(reader, righties, ops, ctx);
+            let mut node = ops.remove().unwrap();
+            node.children.push(deeper);
+            node.children.push(righties.remove().unwrap());
+            node
+        }
         _ => {
             righties.remove().unwrap()
         }
@@ -356,7 +397,8 @@ fn access_chain(reader: &mut Reader, ctx: &State) -> Node {
 
     match reader.tok() {
         Token::Access(_, _) |
-        Token::Brack1(_, _) => {
+        Token::Brack1(_, _) |
+        Token::Not(_, _) => {
             access_help(reader, n, ctx)
         }
         _ => n
@@ -400,6 +442,22 @@ pub fn access_help(reader: &mut Reader, owner: Node, ctx: &State) -> Node {
                             access_help(reader, funcall_node, ctx)
                         }
 
+                        Token::LessThan(_, _) if is_generic_call(reader) => {
+                            let targs = call_typeargs(reader, ctx);
+                            let node = arglist(reader, ctx);
+                            let mut funcall_node = Node::new(
+                                NodeType::MethodCall(
+                                    name.to_string(),
+                                    Box::new(owner),
+                                    ctx.filepath.clone(),
+                                    linenum,
+                                    symnum
+                            ));
+                            funcall_node.children.push(node);
+                            funcall_node.children.push(targs);
+                            access_help(reader, funcall_node, ctx)
+                        }
+
                         // FIXME, the next two are identical
 
                         Token::Brack1(_, _) => {
@@ -429,7 +487,7 @@ pub fn access_help(reader: &mut Reader, owner: Node, ctx: &State) -> Node {
                                 NodeType::Name(name.clone(), linenum, symnum)
                             );
                             node.children.push(owner);
-                            node
+                            access_help(reader, node, ctx)
                         }
                     }
                 }
@@ -454,13 +512,258 @@ pub fn access_help(reader: &mut Reader, owner: Node, ctx: &State) -> Node {
             if let Token::Access(_, _) = reader.tok() {
                 return access_help(reader, collaccess, ctx);
             }
+            if let Token::Not(_, _) = reader.tok() {
+                return access_help(reader, collaccess, ctx);
+            }
             collaccess
         }
+        Token::Not(linenum, symnum) => {
+            // The postfix null check, as in x!.length.
+            reader.next();
+            let mut node = Node::new(NodeType::NullCheck(linenum, symnum));
+            node.children.push(owner);
+            access_help(reader, node, ctx)
+        }
         _ => owner
     }
 }
 
 
+/// Whether the '<' at the reader starts the type arguments of a call,
+/// as in foo<int>(x), rather than being a less-than operator.
+fn is_generic_call(reader: &Reader) -> bool {
+    matches!(
+        typeargs_end(reader, reader.pos()).and_then(|e| reader.tokens().get(e)),
+        Some(Token::Paren1(_, _))
+    )
+}
+
+
+/// Whether the '<' at the reader starts the type arguments of a type
+/// literal, as in print(List<int>). It is when what follows the type
+/// arguments can't continue a comparison.
+fn is_type_literal(reader: &Reader) -> bool {
+    matches!(
+        typeargs_end(reader, reader.pos()).and_then(|e| reader.tokens().get(e)),
+        Some(Token::Paren2(_, _)) | Some(Token::Comma(_, _)) | Some(Token::EndSt(_, _)) |
+        Some(Token::Brack2(_, _)) | Some(Token::Block2(_, _)) | Some(Token::Equal(_, _)) |
+        Some(Token::NotEqual(_, _))
+    )
+}
+
+
+fn call_typeargs(reader: &mut Reader, state: &State) -> Node {
+    let (linenum, symnum) = reader.tok().find_token_position();
+    let args = typeargs(reader, state);
+    Node::new(NodeType::TypeArgs(args, linenum, symnum))
+}
+
+
+/// A list literal, from the '['.
+fn list_literal(elemtype: String, reader: &mut Reader, state: &State) -> Node {
+
+    let (linenum, symnum) = reader.tok().find_token_position();
+
+    reader.next();
+    let mut list_node = Node::new(NodeType::List(elemtype, linenum, symnum));
+    let mut expect_sep = false;
+
+    match reader.tok() {
+
+        Token::Brack2(_, _) => {
+            reader.next();
+            list_node
+        }
+
+        _ => {
+
+            while reader.pos() < reader.len() {
+
+                if expect_sep {
+                    match reader.tok() {
+
+                        Token::Comma(_, _) => {
+                            if !expect_sep {
+                                panic!("Expected an identifier, but got ','");
+                            }
+                            reader.next();
+                            expect_sep = false;
+                            continue;
+                        }
+
+                        Token::Brack2(_, _) => {
+                            reader.next();
+                            break;
+                        }
+                        _ => panic!("Unexpected token when parsing list: {}", reader.tok())
+                    }
+                }
+                expect_sep = true;
+                let entry = collection_element(&mut Some(false), reader, state);
+                list_node.children.push(entry);
+            }
+            list_node
+        }
+    }
+}
+
+
+/// A map or set literal, from the '{'. The children are the elements, or
+/// for a map key and value pairs, spreads of maps, and if and for elements
+/// giving pairs. With one type argument it is a set, and with two a map.
+/// Without, the first element that is not a spread tells which it is,
+/// and when there is none, as in {}, it is a map.
+fn map_or_set_literal(typeargs: Vec<String>, reader: &mut Reader, state: &State) -> Node {
+
+    let (linenum, symnum) = reader.tok().find_token_position();
+    let mut in_map = match typeargs.len() {
+        0 => None,
+        1 => Some(false),
+        _ => Some(true)
+    };
+
+    reader.next();
+    let mut children = Vec::new();
+
+    loop {
+        if let Token::Block2(_, _) = reader.tok() {
+            reader.next();
+            break;
+        }
+        children.push(collection_element(&mut in_map, reader, state));
+
+        match reader.tok() {
+            Token::Comma(_, _) => {
+                reader.next();
+            }
+            Token::Block2(_, _) => {}
+            x => parseerror(
+                format!("Expected ',' or '}}' in {} literal. Got: {}", if in_map == Some(false) { "set" } else { "map" }, x),
+                state,
+                reader.tok()
+            )
+        }
+    }
+
+    let mut node = if in_map == Some(false) {
+        Node::new(NodeType::Set(typeargs.first().cloned().unwrap_or_default(), linenum, symnum))
+    }
+    else {
+        let mut typeargs = typeargs.into_iter();
+        Node::new(NodeType::Map(typeargs.next().unwrap_or_default(), typeargs.next().unwrap_or_default(), linenum, symnum))
+    };
+    node.children = children;
+    node
+}
+
+
+/// An element of a collection literal: an expression, or in a map a key
+/// and value pair, a spread, or an if or for element producing zero or
+/// more elements. Whether it is in a map is None until a literal in
+/// braces has an element that tells.
+fn collection_element(in_map: &mut Option<bool>, reader: &mut Reader, state: &State) -> Node {
+
+    match reader.tok() {
+
+        Token::Spread(linenum, symnum) |
+        Token::SpreadNull(linenum, symnum) => {
+            let nullaware = matches!(reader.tok(), Token::SpreadNull(_, _));
+            reader.next();
+            let mut node = Node::new(NodeType::Spread(nullaware, linenum, symnum));
+            node.children.push(expression(reader, state));
+            node
+        }
+
+        Token::If(linenum, symnum) => {
+            reader.next();
+            reader.skip("(", state);
+            let condexpr = expression(reader, state);
+            reader.skip(")", state);
+
+            let mut node = Node::new(NodeType::CollectionIf(linenum, symnum));
+            node.children.push(condexpr);
+            node.children.push(collection_element(in_map, reader, state));
+
+            if let Token::Else(_, _) = reader.tok() {
+                reader.next();
+                node.children.push(collection_element(in_map, reader, state));
+            }
+            node
+        }
+
+        Token::For(linenum, symnum) => {
+            reader.next();
+            reader.skip("(", state);
+
+            let typvar = match (reader.tok(), reader.peek()) {
+                (Token::Name(typ, _, _), Token::Name(name, name_linenum, name_symnum)) => {
+                    reader.next();
+                    reader.next();
+                    Node::new(NodeType::TypedVar(typ, name, name_linenum, name_symnum))
+                }
+                (x, _) => parseerror(
+                    format!("Unexpected token in for element: {}", x),
+                    state,
+                    reader.tok()
+                )
+            };
+
+            // Same children as the for statement, with
+            // the element in place of the body.
+            let mut node = Node::new(NodeType::CollectionFor(linenum, symnum));
+
+            match reader.tok() {
+
+                Token::In(_, _) => {
+                    reader.next();
+                    let iterable = expression(reader, state);
+                    node.children.push(typvar);
+                    node.children.push(iterable);
+                }
+
+                Token::Assign(assign_linenum, assign_symnum) => {
+                    reader.next();
+                    let mut assign = Node::new(NodeType::Assign(assign_linenum, assign_symnum));
+                    assign.children.push(typvar);
+                    assign.children.push(expression(reader, state));
+                    reader.skip(";", state);
+                    let condexpr = expression(reader, state);
+                    reader.skip(";", state);
+                    let mutexpr = expression(reader, state);
+                    node.children.extend([assign, condexpr, mutexpr]);
+                }
+
+                x => parseerror(
+                    format!("Unexpected token in for element: {}", x),
+                    state,
+                    reader.tok()
+                )
+            }
+
+            reader.skip(")", state);
+            node.children.push(collection_element(in_map, reader, state));
+            node
+        }
+
+        _ => {
+            let expr = expression(reader, state);
+            if in_map.is_none() {
+                *in_map = Some(matches!(reader.tok(), Token::Colon(_, _)));
+            }
+            if *in_map == Some(false) {
+                return expr;
+            }
+            let (linenum, symnum) = reader.tok().find_token_position();
+            reader.skip(":", state);
+            let mut node = Node::new(NodeType::MapPair(linenum, symnum));
+            node.children.push(expr);
+            node.children.push(expression(reader, state));
+            node
+        }
+    }
+}
+
+
 fn term(reader: &mut Reader, state: &State) -> Node {
 
     match reader.tok() {
@@ -488,7 +791,7 @@ fn term(reader: &mut Reader, state: &State) -> Node {
             // This handles unary minus.
             reader.next();
             let mut unary = Node::new(NodeType::Sub(linenum, symnum));
-            let next = term(reader, state);
+            let next = access_chain(reader, state);
             unary.children.push(next);
             unary
         }
@@ -496,29 +799,54 @@ fn term(reader: &mut Reader, state: &State) -> Node {
         Token::Not(linenum, symnum) => {
             reader.next();
             let mut notnode = Node::new(NodeType::Not(linenum, symnum));
-            let next = term(reader, state);
+            let next = access_chain(reader, state);
             notnode.children.push(next);
             notnode
         }
 
-        Token::Str(ref s, interpols, linenum, symnum) => {
+        Token::Name(ref s, linenum, symnum) if s == "await" && reader.in_async => {
+            reader.next();
+            let mut awaitnode = Node::new(NodeType::Await(linenum, symnum));
+            let next = access_chain(reader, state);
+            awaitnode.children.push(next);
+            awaitnode
+        }
 
-            let mut node = Node::new(NodeType::Str(s.clone(), linenum, symnum));
+        Token::Name(ref s, _, _) if s == "await" && matches!(reader.peek(), Token::Name(_, _, _)) => {
+            // As dart.
+            parseerror(
+                "'await' can only be used in 'async' or 'async*' methods.",
+                state,
+                reader.tok()
+            );
+        }
+
+        Token::Str(parts, interpols, linenum, symnum) => {
+
+            reader.next();
 
             if interpols.is_empty() {
-                reader.next();
-                return node;
+                return Node::new(NodeType::Str(parts.concat(), linenum, symnum));
             }
 
+            let mut node = Node::new(NodeType::StrInterpol(parts, linenum, symnum));
+
             for itp in interpols {
+                // The interpolated expression is read as part of the body it is in.
                 let mut r = Reader::new(itp);
+                r.in_async = reader.in_async;
+                r.in_generator = reader.in_generator;
+                r.in_class = reader.in_class.clone();
                 let itpn = expression(&mut r, state);
+                if r.more() {
+                    parseerror(
+                        format!("Expected '}}' after interpolated expression. Got: {}", r.tok()),
+                        state,
+                        r.tok()
+                    );
+                }
                 node.children.push(itpn);
             }
-            // May be empty when inside interpol recursion.
-            if reader.more() {
-                reader.next();
-            }
             node
         }
 
@@ -527,6 +855,11 @@ fn term(reader: &mut Reader, state: &State) -> Node {
             Node::new(NodeType::Bool(v, linenum, symnum))
         }
 
+        Token::Null(linenum, symnum) => {
+            reader.next();
+            Node::new(NodeType::Null(linenum, symnum))
+        }
+
         Token::Name(ref s, linenum, symnum) => {
 
             if reader.more() {
@@ -540,6 +873,22 @@ fn term(reader: &mut Reader, state: &State) -> Node {
                         funcall_node.children.push(args_node);
                         return funcall_node;
                     }
+                    Token::LessThan(_, _) if is_generic_call(reader) => {
+                        // Call with type arguments, like foo<int>(x) or Box<int>(x).
+                        let targs = call_typeargs(reader, state);
+                        let args_node = arglist(reader, state);
+                        let mut funcall_node = Node::new(
+                            NodeType::FunCall(s.to_string(), linenum, symnum)
+                        );
+                        funcall_node.children.push(args_node);
+                        funcall_node.children.push(targs);
+                        return funcall_node;
+                    }
+                    Token::LessThan(_, _) if is_type_literal(reader) => {
+                        // A type literal with type arguments, like List<int>.
+                        let args = typeargs(reader, state);
+                        return Node::new(NodeType::Name(make_type(s, &args), linenum, symnum));
+                    }
                     Token::Brack1(_, _) => {
                         let node = Node::new(
                             NodeType::Name(s.clone(), linenum, symnum)
@@ -593,48 +942,44 @@ fn term(reader: &mut Reader, state: &State) -> Node {
             wnode
         }
 
-        Token::Brack1(linenum, symnum) => {
-
-            reader.next();
-            let mut list_node = Node::new(NodeType::List(linenum, symnum));
-            let mut expect_sep = false;
-
-            match reader.tok() {
-
-                Token::Brack2(_, _) => {
-                    reader.next();
-                    list_node
-                }
-
-                _ => {
-
-                    while reader.pos() < reader.len() {
+        Token::Brack1(_, _) => {
+            list_literal(String::new(), reader, state)
+        }
 
-                        if expect_sep {
-                            match reader.tok() {
+        Token::Block1(_, _) => {
+            map_or_set_literal(Vec::new(), reader, state)
+        }
 
-                                Token::Comma(_, _) => {
-                                    if !expect_sep {
-                                        panic!("Expected an identifier, but got ','");
-                                    }
-                                    reader.next();
-                                    expect_sep = false;
-                                    continue;
-                                }
+        Token::LessThan(_, _) => {
+            // A list or set literal with a type argument, like <int>[]
+            // or <int>{}, or a map literal with two, like <String, int>{}.
+            let tok = reader.tok();
+            let args = typeargs(reader, state);
 
-                                Token::Brack2(_, _) => {
-                                    reader.next();
-                                    break;
-                                }
-                                _ => panic!("Unexpected token when parsing list: {}", reader.tok())
-                            }
-                        }
-                        expect_sep = true;
-                        let entry = expression(reader, state);
-                        list_node.children.push(entry);
-                    }
-                    list_node
+            if let Token::Block1(_, _) = reader.tok() {
+                if args.len() > 2 {
+                    parseerror(
+                        format!("Map literal requires exactly two type arguments or none, but {} found.", args.len()),
+                        state,
+                        tok
+                    )
                 }
+                return map_or_set_literal(args, reader, state);
+            }
+            if args.len() != 1 {
+                parseerror(
+                    format!("List literal requires exactly one type argument or none, but {} found.", args.len()),
+                    state,
+                    tok
+                )
+            }
+            match reader.tok() {
+                Token::Brack1(_, _) => list_literal(args[0].clone(), reader, state),
+                x => parseerror(
+                    format!("Expected '[' after type arguments. Got: {}", x),
+                    state,
+                    reader.tok()
+                )
             }
         }
 
@@ -643,6 +988,23 @@ fn term(reader: &mut Reader, state: &State) -> Node {
             Node::new(NodeType::This(linenum, symnum))
         }
 
+        Token::Switch(_, _) => {
+            switch_expression(reader, state)
+        }
+
+        Token::Super(linenum, symnum) => {
+            if reader.in_class == "" {
+                // As dart.
+                parseerror(
+                    "Expected identifier, but got 'super'.",
+                    state,
+                    reader.tok()
+                );
+            }
+            reader.next();
+            Node::new(NodeType::SuperRef(reader.in_class.clone(), linenum, symnum))
+        }
+
         x => {
             panic!("Unexpected token: {}", x)
         }
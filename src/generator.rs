@@ -0,0 +1,772 @@
+// Resumable execution of sync*, async and async* function bodies.
+//
+// The evaluator is recursive, so it can't stop in the middle of a
+// function. Generator bodies are instead run one statement at a time
+// from an explicit list of steps, which is kept in the generator between
+// calls to moveNext. Statements that can't contain a yield are handed to
+// the evaluator as usual, so only the statements that nest other
+// statements need a step here.
+//
+// The call-frame of the body is taken off the stack when the body yields,
+// and put back when it resumes.
+//
+// Async bodies run the same way, and stop at await instead of yield.
+// An await can be deep in an expression, so the evaluator is left by
+// unwinding out of it, and the statement is evaluated again from the
+// start when the future completes. The results of the expressions
+// evaluated on the way are noted, and given back the next time in place
+// of evaluating them again, so each runs once and in order, up to the
+// await the body stopped at. The variables of the scopes the statement
+// had opened then get the values they had, and evaluation goes on.
+//
+// Async* bodies do both. Their yields are added to the stream of the
+// body, by a feed in the stream library that also decides when the
+// body runs on. An await for loop awaits moveNext of a StreamIterator
+// before each round.
+
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::error::{dartthrow, evalerror};
+use crate::evalhelp::{add_typed, call_function_with, call_generic_with, call_method_with, check_type, get_field, move_next, MaybeRef};
+use crate::evaluator::{collect_garbage_if_due, eval, match_head};
+use crate::eventloop;
+use crate::heapobjs::InternalGenerator;
+use crate::node::{Node, NodeType};
+use crate::object::Object;
+use crate::objsys::RefKey;
+use crate::state::State;
+use crate::types;
+
+
+pub enum Step {
+    // Run the statements of a block from the index.
+    Block(Node, usize),
+    // Check the condition of the branch at the index of an if statement.
+    Conditional(Node, usize),
+    // Leave a lexical scope, like that of a loop body.
+    PopLex,
+    While(Node),
+    DoWhile(Node),
+    // Check the condition of a for loop.
+    ForCond(Node),
+    // Run the update expression of a for loop.
+    ForNext(Node),
+    ForIn(Node),
+    // Pass on the elements of a yield* iterable.
+    YieldAll(Node),
+    // Wait for the next event of an await for loop,
+    // or of the stream of a yield* in async*.
+    AwaitFor(Node)
+}
+
+
+enum Flow {
+    Normal,
+    Yield(Object),
+    Await(Object),
+    Return(Object)
+}
+
+
+// The results noted while evaluating a statement, kept while the body
+// is suspended at an await in it.
+//
+// entries:  Results in the order the evaluations started, each with the
+//           index of the entry after those it started itself. An entry
+//           with no result is for one still being evaluated.
+// cursor:   Index of the entry of the next evaluation.
+// pending:  Entry of the await the body is suspended at.
+// awaiting: What that await is waiting for.
+// scopes:   Variables of the scopes the statement had opened.
+// level:    Call level of the body. Calls made from the statement
+//           evaluate without noting.
+// depth:    Lexical level the statement started at.
+// noting:   Off while evaluating an expression that can't suspend.
+pub struct Replay {
+    entries: Vec<(Option<Object>, usize)>,
+    cursor: usize,
+    pending: Option<usize>,
+    awaiting: Object,
+    scopes: Vec<HashMap<String, Object>>,
+    level: usize,
+    depth: usize,
+    noting: bool
+}
+
+
+impl Replay {
+
+    pub fn new() -> Replay {
+        Replay {
+            entries: Vec::new(),
+            cursor: 0,
+            pending: None,
+            awaiting: Object::Null,
+            scopes: Vec::new(),
+            level: 0,
+            depth: 0,
+            noting: true
+        }
+    }
+
+
+    /// References held by the noted results and the scopes.
+    pub fn references(&self) -> Vec<RefKey> {
+        let values = self.entries.iter().filter_map(|(val, _)| val.as_ref())
+            .chain(self.scopes.iter().flat_map(|scope| scope.values()))
+            .chain(std::iter::once(&self.awaiting));
+        values.filter_map(|obj| obj.heap_ref().cloned()).collect()
+    }
+}
+
+
+// Unwinds out of the evaluator when the body stops at an await.
+struct Suspended;
+
+
+/// Called in place of running a sync* body. Captures
+/// the call-frame and returns an Iterable for the body.
+pub fn create_iterable(node: &Node, state: &mut State) -> Object {
+
+    let body = node.children[0].clone();
+    let gen = InternalGenerator::new(body, state.filepath.clone(), state.stack.top_frame());
+    let gen_rk = state.objsys.register_generator(gen);
+
+    let class = state.objsys.get_class("_SyncStarIterable");
+    let mut inst = class.instantiate();
+    inst.set_field(String::from("__generator"), gen_rk);
+    state.objsys.register_instance(*inst)
+}
+
+
+/// Run the body until it yields a value, or return None when done.
+pub fn resume(rk: &RefKey, node: &Node, state: &mut State) -> Option<Object> {
+    match enter(rk, node, state) {
+        Flow::Yield(val) => Some(val),
+        _ => None
+    }
+}
+
+
+/// Called in place of running an async body. Runs the body until it
+/// first awaits, and returns the future that it completes.
+pub fn start_async(node: &Node, state: &mut State) -> Object {
+
+    let valuetype = match &node.nodetype {
+        NodeType::Async(t, _, _) => types::resolve(t, state),
+        x => panic!("Expected async body. Got: {}", x)
+    };
+    let future = eventloop::new_future(&valuetype, state);
+
+    let body = node.children[0].clone();
+    let mut gen = InternalGenerator::new(body.clone(), state.filepath.clone(), state.stack.top_frame());
+    gen.steps.push(Step::Block(body, 0));
+    gen.target = future.clone();
+    let gen_rk = register(gen, state);
+
+    resume_async(gen_rk, None, node, state);
+    future
+}
+
+
+/// Called in place of running an async* body. Returns the stream
+/// of the body, which starts running once the stream is listened to.
+pub fn start_async_star(node: &Node, state: &mut State) -> Object {
+
+    let eventtype = match &node.nodetype {
+        NodeType::AsyncStar(t, _, _) => types::resolve(t, state),
+        x => panic!("Expected async* body. Got: {}", x)
+    };
+
+    let body = node.children[0].clone();
+    let mut gen = InternalGenerator::new(body.clone(), state.filepath.clone(), state.stack.top_frame());
+    gen.steps.push(Step::Block(body, 0));
+    let gen_rk = register(gen, state);
+
+    // Kept from the garbage collector until the feed holds it.
+    state.constructing.push(gen_rk.clone());
+    let func = eventloop::library_function("auto:stream.dart", "_asyncStarFeed", state);
+    let feed = call_generic_with(MaybeRef::None, &func, vec![Object::Reference(gen_rk.clone())], vec![eventtype], node, state);
+    state.constructing.pop();
+    state.objsys.get_generator_mut(&gen_rk).target = feed.clone();
+    get_field(feed, "stream", state, node)
+}
+
+
+fn register(gen: InternalGenerator, state: &mut State) -> RefKey {
+    match state.objsys.register_generator(gen) {
+        Object::Reference(rk) => rk,
+        x => panic!("Expected reference to generator. Got: {}", x)
+    }
+}
+
+
+/// Run an async or async* body on from where it stopped, given the value
+/// or error of the future it awaited, until it awaits, yields or returns.
+pub fn resume_async(rk: RefKey, sent: Option<Result<Object, Object>>, node: &Node, state: &mut State) {
+
+    // A future to complete, or the feed of an async* body.
+    let target = state.objsys.get_generator(&rk).target.clone();
+    let is_async_star = !eventloop::is_future(&target, state);
+
+    match sent {
+        Some(Ok(val)) => {
+            let gen = state.objsys.get_generator_mut(&rk);
+            match gen.replay.pending {
+                Some(i) => gen.replay.entries[i].0 = Some(val),
+                None => gen.awaited = Some(val)
+            }
+        }
+        Some(Err(error)) => {
+            // Nothing catches the error, so it ends the body.
+            let gen = state.objsys.get_generator_mut(&rk);
+            gen.steps.clear();
+            gen.frame.clear();
+            gen.replay = Replay::new();
+            gen.awaited = None;
+            if is_async_star {
+                call_method_with(&target, "__fail", vec![error], node, state);
+            }
+            else {
+                eventloop::complete_error(&target, error, node, state);
+            }
+            return;
+        }
+        None => {}
+    }
+
+    state.eventloop.running.push(rk.clone());
+
+    match enter(&rk, node, state) {
+        Flow::Await(val) => {
+            eventloop::await_value(&rk, val, node, state);
+        }
+        Flow::Yield(val) => {
+            call_method_with(&target, "__yield", vec![val], node, state);
+        }
+        Flow::Return(_) if is_async_star => {
+            call_method_with(&target, "__close", Vec::new(), node, state);
+        }
+        Flow::Return(val) => {
+            if !eventloop::is_future(&val, state) {
+                // Checked as a return of the body.
+                let gen = state.objsys.get_generator(&rk);
+                let body = gen.body.clone();
+                let oldfilepath = std::mem::replace(&mut state.filepath, gen.filepath.clone());
+                check_type(&val, &eventloop::value_type(&target, state), "", state, &body);
+                state.filepath = oldfilepath;
+            }
+            eventloop::complete(&target, val, node, state);
+        }
+        Flow::Normal => {}
+    }
+
+    state.eventloop.running.pop();
+}
+
+
+/// Run the body from where it stopped, until it yields, awaits or returns.
+fn enter(rk: &RefKey, node: &Node, state: &mut State) -> Flow {
+
+    let gen = state.objsys.get_generator_mut(rk);
+
+    if gen.running {
+        dartthrow("Bad state: Generator is already running", state, node);
+    }
+    if gen.steps.is_empty() {
+        return Flow::Return(Object::Null);
+    }
+    gen.running = true;
+
+    let frame = std::mem::take(&mut gen.frame);
+    let mut steps = std::mem::take(&mut gen.steps);
+    let filepath = gen.filepath.clone();
+
+    // Methods run with their receiver as this.
+    let mut oldthis = None;
+    if let Some(Object::Reference(this_rk)) = frame[0].get("this") {
+        oldthis = Some(state.objsys.get_this());
+        state.objsys.set_this(this_rk.clone());
+    }
+    state.stack.push_frame(frame);
+    let oldfilepath = std::mem::replace(&mut state.filepath, filepath);
+
+    let result = run(rk, &mut steps, state);
+
+    state.filepath = oldfilepath;
+    let frame = state.stack.pop_frame();
+    if let Some(old_rk) = oldthis {
+        state.objsys.set_this(old_rk);
+    }
+
+    let gen = state.objsys.get_generator_mut(rk);
+    gen.frame = frame;
+    gen.steps = steps;
+    gen.running = false;
+    result
+}
+
+
+fn run(rk: &RefKey, steps: &mut Vec<Step>, state: &mut State) -> Flow {
+
+    while let Some(step) = steps.pop() {
+
+        match step {
+
+            Step::Block(node, i) => {
+                if i >= node.children.len() {
+                    continue;
+                }
+                collect_garbage_if_due(state);
+
+                let stmt = node.children[i].clone();
+                let mark = steps.len();
+                steps.push(Step::Block(node.clone(), i + 1));
+
+                match resumable(rk, &header(&stmt), state, |state| exec(rk, &stmt, steps, state)) {
+                    None => {
+                        steps.truncate(mark);
+                        steps.push(Step::Block(node, i));
+                        return suspend(rk, state);
+                    }
+                    Some(Flow::Normal) => {}
+                    Some(Flow::Return(val)) => {
+                        // The frame is dropped with any scopes still open.
+                        steps.clear();
+                        return Flow::Return(val);
+                    }
+                    Some(flow) => return flow
+                }
+            }
+
+            Step::Conditional(node, i) => {
+                if i >= node.children.len() {
+                    continue;
+                }
+                let branch = node.children[i].clone();
+                match branch.nodetype {
+                    NodeType::If(_, _) |
+                    NodeType::ElseIf(_, _) => {
+                        let cond = &branch.children[0];
+                        let holds = match resumable(rk, &[cond], state, |state| condition(cond, state)) {
+                            Some(holds) => holds,
+                            None => {
+                                steps.push(Step::Conditional(node, i));
+                                return suspend(rk, state);
+                            }
+                        };
+                        if holds {
+                            enter_body(branch.children[1].clone(), steps, state);
+                        }
+                        else {
+                            steps.push(Step::Conditional(node, i + 1));
+                        }
+                    }
+                    NodeType::Else(_, _) => {
+                        enter_body(branch.children[0].clone(), steps, state);
+                    }
+                    _ => panic!("Invalid node in conditional!")
+                }
+            }
+
+            Step::PopLex => {
+                state.stack.pop_lex();
+            }
+
+            Step::While(node) => {
+                let cond = &node.children[0];
+                let holds = match resumable(rk, &[cond], state, |state| condition(cond, state)) {
+                    Some(holds) => holds,
+                    None => {
+                        steps.push(Step::While(node));
+                        return suspend(rk, state);
+                    }
+                };
+                if holds {
+                    let body = node.children[1].clone();
+                    steps.push(Step::While(node));
+                    enter_body(body, steps, state);
+                }
+            }
+
+            Step::DoWhile(node) => {
+                let cond = &node.children[1];
+                let holds = match resumable(rk, &[cond], state, |state| condition(cond, state)) {
+                    Some(holds) => holds,
+                    None => {
+                        steps.push(Step::DoWhile(node));
+                        return suspend(rk, state);
+                    }
+                };
+                if holds {
+                    let body = node.children[0].clone();
+                    steps.push(Step::DoWhile(node));
+                    enter_body(body, steps, state);
+                }
+            }
+
+            Step::ForCond(node) => {
+                let cond = &node.children[1];
+                let holds = match resumable(rk, &[cond], state, |state| condition(cond, state)) {
+                    Some(holds) => holds,
+                    None => {
+                        steps.push(Step::ForCond(node));
+                        return suspend(rk, state);
+                    }
+                };
+                if holds {
+                    let body = node.children[3].clone();
+                    steps.push(Step::ForNext(node));
+                    enter_body(body, steps, state);
+                }
+            }
+
+            Step::ForNext(node) => {
+                let update = &node.children[2];
+                if resumable(rk, &[update], state, |state| eval(update, state)).is_none() {
+                    steps.push(Step::ForNext(node));
+                    return suspend(rk, state);
+                }
+                steps.push(Step::ForCond(node));
+            }
+
+            Step::ForIn(node) => {
+                let iterator = state.stack.get("__iterator").clone();
+
+                if move_next(&iterator, &node.children[1], state) {
+                    let (typ, name) = match &node.children[0].nodetype {
+                        NodeType::TypedVar(typ, name, _, _) => (typ.clone(), name.clone()),
+                        _ => panic!("For loop expecped typed var. Got: {}", &node.children[0])
+                    };
+                    let current = get_field(iterator, "current", state, &node.children[1]);
+                    check_type(&current, &typ, "", state, &node.children[0]);
+                    let body = node.children[2].clone();
+                    steps.push(Step::ForIn(node));
+                    enter_body(body, steps, state);
+                    add_typed(&name, &typ, current, state);
+                }
+            }
+
+            Step::YieldAll(node) => {
+                let iterator = state.stack.get("__iterator").clone();
+
+                if move_next(&iterator, &node.children[0], state) {
+                    let current = get_field(iterator, "current", state, &node.children[0]);
+                    steps.push(Step::YieldAll(node));
+                    return Flow::Yield(current);
+                }
+            }
+
+            Step::AwaitFor(node) => {
+                // The result of moveNext is awaited before each round.
+                let iterator = state.stack.get("__iterator").clone();
+
+                match state.objsys.get_generator_mut(rk).awaited.take() {
+                    None => {
+                        let next = call_method_with(&iterator, "moveNext", Vec::new(), &node, state);
+                        steps.push(Step::AwaitFor(node));
+                        return Flow::Await(next);
+                    }
+                    Some(Object::Bool(true)) => {
+                        let current = get_field(iterator, "current", state, &node);
+                        steps.push(Step::AwaitFor(node.clone()));
+
+                        if let NodeType::YieldStar(_, _) = node.nodetype {
+                            return Flow::Yield(current);
+                        }
+                        let (typ, name) = match &node.children[0].nodetype {
+                            NodeType::TypedVar(typ, name, _, _) => (typ.clone(), name.clone()),
+                            _ => panic!("For loop expecped typed var. Got: {}", &node.children[0])
+                        };
+                        check_type(&current, &typ, "", state, &node.children[0]);
+                        enter_body(node.children[2].clone(), steps, state);
+                        add_typed(&name, &typ, current, state);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Flow::Return(Object::Null)
+}
+
+
+/// Start a statement of the body. Statements holding other
+/// statements are set up as steps, anything else is evaluated.
+fn exec(rk: &RefKey, stmt: &Node, steps: &mut Vec<Step>, state: &mut State) -> Flow {
+
+    match &stmt.nodetype {
+
+        NodeType::Yield(_, _) => {
+            return Flow::Yield(eval(&stmt.children[0], state));
+        }
+
+        NodeType::YieldStar(_, _) => {
+            let source = eval(&stmt.children[0], state);
+            // An async* body yields the events of a stream.
+            if matches!(state.objsys.get_generator(rk).target, Object::Null) {
+                start_iteration(source, &stmt.children[0], state);
+                steps.push(Step::PopLex);
+                steps.push(Step::YieldAll(stmt.clone()));
+            }
+            else {
+                start_stream_iteration(source, &stmt.children[0], state);
+                steps.push(Step::PopLex);
+                steps.push(Step::AwaitFor(stmt.clone()));
+            }
+        }
+
+        NodeType::AwaitFor(_, _) => {
+            let stream = eval(&stmt.children[1], state);
+            start_stream_iteration(stream, &stmt.children[1], state);
+            steps.push(Step::PopLex);
+            steps.push(Step::AwaitFor(stmt.clone()));
+        }
+
+        NodeType::Block(_, _) => {
+            steps.push(Step::Block(stmt.clone(), 0));
+        }
+
+        NodeType::Conditional(_, _) => {
+            steps.push(Step::Conditional(stmt.clone(), 0));
+        }
+
+        NodeType::While(_, _) => {
+            steps.push(Step::While(stmt.clone()));
+        }
+
+        NodeType::DoWhile(_, _) => {
+            steps.push(Step::DoWhile(stmt.clone()));
+            enter_body(stmt.children[0].clone(), steps, state);
+        }
+
+        NodeType::For(_, _) => {
+
+            if stmt.children.len() == 3 {
+                let iterable = eval(&stmt.children[1], state);
+                start_iteration(iterable, &stmt.children[1], state);
+                steps.push(Step::PopLex);
+                steps.push(Step::ForIn(stmt.clone()));
+            }
+            else {
+                // The loop variable is scoped to the loop.
+                state.stack.push_lex();
+                eval(&stmt.children[0], state);
+                steps.push(Step::PopLex);
+                steps.push(Step::ForCond(stmt.clone()));
+            }
+        }
+
+        NodeType::Switch(_, _) => {
+            let subject = eval(&stmt.children[0], state);
+            state.stack.push_lex();
+            state.stack.add_new("__subject", subject.clone());
+            steps.push(Step::PopLex);
+
+            for case in &stmt.children[1..] {
+                for head in &case.children[1..] {
+                    // Variables bound by the pattern are scoped to the case.
+                    state.stack.push_lex();
+                    if match_head(head, &subject, state) {
+                        steps.push(Step::PopLex);
+                        steps.push(Step::Block(case.children[0].clone(), 0));
+                        return Flow::Normal;
+                    }
+                    state.stack.pop_lex();
+                }
+            }
+        }
+
+        NodeType::Return(_, _) => {
+            return Flow::Return(eval(&stmt.children[0], state));
+        }
+
+        _ => {
+            if let Object::Return(val) = eval(stmt, state) {
+                return Flow::Return(*val);
+            }
+        }
+    }
+    Flow::Normal
+}
+
+
+/// The expressions of a statement that are evaluated as it starts.
+/// Those of nested statements are evaluated by their own steps.
+fn header(stmt: &Node) -> Vec<&Node> {
+    match &stmt.nodetype {
+        NodeType::Block(_, _) |
+        NodeType::Conditional(_, _) |
+        NodeType::While(_, _) |
+        NodeType::DoWhile(_, _) |
+        NodeType::FunDef(_, _, _, _, _) => Vec::new(),
+        NodeType::For(_, _) if stmt.children.len() == 3 => vec![&stmt.children[1]],
+        NodeType::AwaitFor(_, _) => vec![&stmt.children[1]],
+        NodeType::For(_, _) |
+        NodeType::Switch(_, _) => vec![&stmt.children[0]],
+        _ => vec![stmt]
+    }
+}
+
+
+/// Evaluate the expressions of a statement with f, which the body can
+/// stop in at an await. Gives None when it does, and f is run again
+/// once the future completes, with the results noted so far.
+fn resumable<T>(
+    rk: &RefKey,
+    exprs: &[&Node],
+    state: &mut State,
+    f: impl FnOnce(&mut State) -> T) -> Option<T> {
+
+    if !exprs.iter().any(|e| has_await(e)) {
+        return Some(f(state));
+    }
+
+    let mut replay = std::mem::replace(&mut state.objsys.get_generator_mut(rk).replay, Replay::new());
+    replay.cursor = 0;
+    replay.level = state.stack.call_level;
+    replay.depth = state.stack.lex_level;
+    state.replaying.push(replay);
+    let constructing = state.constructing.len();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(state)));
+    let mut replay = state.replaying.pop().unwrap();
+
+    let result = match result {
+        Ok(val) => {
+            replay = Replay::new();
+            Some(val)
+        }
+        Err(payload) if payload.is::<Suspended>() => {
+            // The scopes the statement opened are left, keeping
+            // their variables for when it gets back to the await.
+            replay.scopes = state.stack.leave_lex(replay.depth);
+            for scope in &mut replay.scopes {
+                scope.retain(|name, _| !name.starts_with("__"));
+            }
+            state.constructing.truncate(constructing);
+            None
+        }
+        Err(payload) => panic::resume_unwind(payload)
+    };
+    state.objsys.get_generator_mut(rk).replay = replay;
+    result
+}
+
+
+/// Stop at the await the body is suspended at, for the future it awaits.
+fn suspend(rk: &RefKey, state: &mut State) -> Flow {
+    let replay = &mut state.objsys.get_generator_mut(rk).replay;
+    Flow::Await(std::mem::replace(&mut replay.awaiting, Object::Null))
+}
+
+
+/// Whether an evaluation is part of a statement that can stop at an await,
+/// and so has its result noted.
+pub fn is_noting(state: &State) -> bool {
+    match state.replaying.last() {
+        Some(replay) => replay.noting && replay.level == state.stack.call_level,
+        None => false
+    }
+}
+
+
+/// Evaluate with f, or give back the result noted for the evaluation
+/// when the statement is evaluated again. An await that has no value
+/// yet suspends the body. Node is None for steps of the evaluator that
+/// aren't expressions, like those of an iterator.
+pub fn noted(node: Option<&Node>, state: &mut State, f: impl FnOnce(&mut State) -> Object) -> Object {
+
+    let replay = state.replaying.last_mut().unwrap();
+    let i = replay.cursor;
+    replay.cursor += 1;
+
+    if i < replay.entries.len() {
+        if let (Some(val), end) = &replay.entries[i] {
+            let val = val.clone();
+            replay.cursor = *end;
+            if replay.pending == Some(i) {
+                // Back at the await, where the variables are as they were.
+                replay.pending = None;
+                let scopes = std::mem::take(&mut replay.scopes);
+                let depth = replay.depth;
+                for (scope, vars) in state.stack.lex_above(depth).iter_mut().zip(scopes) {
+                    scope.extend(vars);
+                }
+            }
+            return val;
+        }
+    }
+    else {
+        replay.entries.push((None, 0));
+    }
+
+    if let Some(node) = node {
+        if let NodeType::Await(_, _) = node.nodetype {
+            let future = eval(&node.children[0], state);
+            let replay = state.replaying.last_mut().unwrap();
+            replay.entries[i].1 = replay.cursor;
+            replay.pending = Some(i);
+            replay.awaiting = future;
+            panic::resume_unwind(Box::new(Suspended));
+        }
+    }
+
+    // Only what holds an await needs its parts noted.
+    let noting = node.is_some_and(has_await);
+    state.replaying.last_mut().unwrap().noting = noting;
+    let val = f(state);
+
+    let replay = state.replaying.last_mut().unwrap();
+    replay.noting = true;
+    replay.entries[i] = (Some(val.clone()), replay.cursor);
+    val
+}
+
+
+/// Whether the expression awaits. Nested functions await in bodies of their own.
+fn has_await(node: &Node) -> bool {
+    match &node.nodetype {
+        NodeType::FunDef(_, _, _, _, _) => false,
+        NodeType::Await(_, _) => true,
+        _ => node.children.iter().any(has_await)
+    }
+}
+
+
+/// Run a body in a scope of its own.
+fn enter_body(body: Node, steps: &mut Vec<Step>, state: &mut State) {
+    state.stack.push_lex();
+    steps.push(Step::PopLex);
+    steps.push(Step::Block(body, 0));
+}
+
+
+/// Open a scope holding the iterator of the iterable.
+fn start_iteration(iterable: Object, node: &Node, state: &mut State) {
+    let iterator = get_field(iterable, "iterator", state, node);
+    state.stack.push_lex();
+    state.stack.add_new("__iterator", iterator);
+}
+
+
+/// Open a scope holding a StreamIterator of the stream.
+fn start_stream_iteration(stream: Object, node: &Node, state: &mut State) {
+    let func = eventloop::library_function("auto:stream.dart", "_streamIterator", state);
+    let iterator = call_function_with(MaybeRef::None, &func, vec![stream], node, state);
+    state.stack.push_lex();
+    state.stack.add_new("__iterator", iterator);
+}
+
+
+fn condition(node: &Node, state: &mut State) -> bool {
+    match eval(node, state) {
+        Object::Bool(b) => b,
+        x => evalerror(
+            format!("Expected boolean expression. Got: {}", x),
+            state,
+            node
+        )
+    }
+}
@@ -13,6 +13,8 @@ pub struct Instance {
     pub id: RefKey,
     pub classname: String,
     pub parent: MaybeObject,
+    // Reified type arguments, if the class is generic.
+    pub typeargs: Vec<String>,
     pub fields: HashMap<String, Object>,
     pub marked: bool
 }
@@ -22,9 +24,10 @@ impl Instance {
 
     pub fn new(classname: String, parent: MaybeObject) -> Instance {
         Instance {
-            id: RefKey(nuid::next()),
+            id: RefKey::next(),
             classname,
             parent,
+            typeargs: Vec::new(),
             fields: HashMap::new(),
             marked: false
         }
@@ -14,7 +14,7 @@ impl InternalFile {
     pub fn new(filename: String) -> InternalFile {
         
         InternalFile {
-            id: RefKey(nuid::next()),
+            id: RefKey::next(),
             file: File::open(filename).unwrap(),
             marked: false
         }
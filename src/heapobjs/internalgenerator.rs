@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use crate::generator::{Replay, Step};
+use crate::node::Node;
+use crate::object::Object;
+use crate::objsys::RefKey;
+
+
+// The state of a sync*, async or async* function body.
+//
+// frame:   The call-frame of the body, with parameters and locals. It
+//          lives on the stack while the body runs, and here in between.
+// steps:   Where to continue, innermost statement last.
+// target:  The future an async body completes, or the feed an async*
+//          body adds its events through. Null for sync*.
+// replay:  What the statement the body is suspended in has evaluated.
+// awaited: Result of the moveNext of an await for loop, once it completes.
+// running: Set while the body runs, to catch re-entrant moveNext calls.
+pub struct InternalGenerator {
+    pub id: RefKey,
+    pub body: Node,
+    pub filepath: String,
+    pub frame: Vec<HashMap<String, Object>>,
+    pub steps: Vec<Step>,
+    pub target: Object,
+    pub replay: Replay,
+    pub awaited: Option<Object>,
+    pub running: bool,
+    pub marked: bool
+}
+
+
+impl InternalGenerator {
+
+    pub fn new(body: Node, filepath: String, frame: Vec<HashMap<String, Object>>) -> InternalGenerator {
+        InternalGenerator {
+            id: RefKey::next(),
+            body,
+            filepath,
+            frame,
+            steps: Vec::new(),
+            target: Object::Null,
+            replay: Replay::new(),
+            awaited: None,
+            running: false,
+            marked: false
+        }
+    }
+
+
+    /// A fresh run of the same body, as for a new iterator.
+    pub fn restart(&self) -> InternalGenerator {
+        let mut gen = InternalGenerator::new(self.body.clone(), self.filepath.clone(), self.frame.clone());
+        gen.steps.push(Step::Block(self.body.clone(), 0));
+        gen
+    }
+
+
+    /// References held by locals of the body, its target and awaited values.
+    pub fn references(&self) -> Vec<RefKey> {
+        let mut refs: Vec<RefKey> = self.target.heap_ref().cloned().into_iter().collect();
+        for lexframe in &self.frame {
+            for obj in lexframe.values() {
+                if let Some(rk) = obj.heap_ref() {
+                    refs.push(rk.clone());
+                }
+            }
+        }
+        if let Some(rk) = self.awaited.as_ref().and_then(|obj| obj.heap_ref()) {
+            refs.push(rk.clone());
+        }
+        refs.extend(self.replay.references());
+        refs
+    }
+}
@@ -8,6 +8,9 @@ use crate::objsys::RefKey;
 pub struct InternalList {
     pub id: RefKey,
     pub els: Vec<Object>,
+    // Bumped on every change, so that callbacks
+    // modifying the list can be detected.
+    pub modcount: usize,
     pub marked: bool
 }
 
@@ -15,8 +18,9 @@ impl InternalList {
     
     pub fn new() -> InternalList {
         InternalList {
-            id: RefKey(nuid::next()),
+            id: RefKey::next(),
             els: Vec::new(),
+            modcount: 0,
             marked: false
         }
     }
@@ -24,6 +28,7 @@ impl InternalList {
 
     pub fn set_elements(&mut self, new_els: Vec<Object>) {
         self.els = new_els;
+        self.modcount += 1;
     }
 
 
@@ -34,11 +39,13 @@ impl InternalList {
 
     pub fn set_el(&mut self, index: usize, val: Object) {
         self.els[index] = val;
+        self.modcount += 1;
     }
 
 
     pub fn add(&mut self, el: Object) {
         self.els.push(el);
+        self.modcount += 1;
     }
 
 
@@ -46,21 +53,25 @@ impl InternalList {
         for el in iterable {
             self.els.push(el);
         }
+        self.modcount += 1;
     }
 
 
     pub fn insert(&mut self, index: usize, el: Object) {
         self.els.insert(index, el);
+        self.modcount += 1;
     }
 
 
     pub fn remove_at(&mut self, index: usize) -> Object {
+        self.modcount += 1;
         self.els.remove(index)
     }
 
 
     pub fn remove_last(&mut self) -> Object {
         if self.els.len() > 0 {
+            self.modcount += 1;
             return self.els.pop().unwrap()
         }
         Object::Null
@@ -69,12 +80,20 @@ impl InternalList {
     
     pub fn remove_range(&mut self, start: usize, end: usize) {
         self.els.drain(start .. end);
+        self.modcount += 1;
+    }
+
+
+    pub fn replace_range(&mut self, start: usize, end: usize, replacements: Vec<Object>) {
+        self.els.splice(start .. end, replacements);
+        self.modcount += 1;
     }
 
 
     pub fn shuffle(&mut self) {
         let mut rng = thread_rng();
         self.els.shuffle(&mut rng);
+        self.modcount += 1;
     }
 
 
@@ -84,23 +103,4 @@ impl InternalList {
             f(obj);
         }
     }
-
-
-    pub fn to_string(&self) -> String {
-        let mut s = String::from("[");
-
-        if self.els.len() == 0 {
-            return String::from("[]");
-        }
-
-        let mut i = 0;
-        while i < self.els.len() - 1 {
-            s.push_str(format!("{}", self.els[i]).as_str());
-            s.push_str(", ");
-            i += 1;
-        }
-        s.push_str(format!("{}", self.els[i]).as_str());
-        s.push_str("]");
-        return s;
-    }
 }
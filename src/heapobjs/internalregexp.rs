@@ -0,0 +1,21 @@
+use crate::objsys::RefKey;
+use crate::regexp::Regex;
+
+
+pub struct InternalRegExp {
+    pub id: RefKey,
+    pub regex: Regex,
+    pub marked: bool
+}
+
+
+impl InternalRegExp {
+
+    pub fn new(regex: Regex) -> InternalRegExp {
+        InternalRegExp {
+            id: RefKey::next(),
+            regex,
+            marked: false
+        }
+    }
+}
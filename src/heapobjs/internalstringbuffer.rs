@@ -0,0 +1,41 @@
+use crate::objsys::RefKey;
+
+
+pub struct InternalStringBuffer {
+    pub id: RefKey,
+    pub buf: String,
+    // Length in UTF-16 code units, as Dart counts it.
+    pub length: usize,
+    pub marked: bool
+}
+
+
+impl InternalStringBuffer {
+
+    pub fn new() -> InternalStringBuffer {
+        InternalStringBuffer {
+            id: RefKey::next(),
+            buf: String::new(),
+            length: 0,
+            marked: false
+        }
+    }
+
+
+    pub fn write(&mut self, s: &str) {
+        self.buf.push_str(s);
+        self.length += s.encode_utf16().count();
+    }
+
+
+    pub fn write_char(&mut self, c: char) {
+        self.buf.push(c);
+        self.length += c.len_utf16();
+    }
+
+
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.length = 0;
+    }
+}
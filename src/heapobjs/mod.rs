@@ -1,7 +1,13 @@
 pub mod instance;
 pub mod internalfile;
+pub mod internalgenerator;
 pub mod internallist;
+pub mod internalregexp;
+pub mod internalstringbuffer;
 
 pub use crate::heapobjs::instance::Instance;
 pub use crate::heapobjs::internalfile::InternalFile;
+pub use crate::heapobjs::internalgenerator::InternalGenerator;
 pub use crate::heapobjs::internallist::InternalList;
+pub use crate::heapobjs::internalregexp::InternalRegExp;
+pub use crate::heapobjs::internalstringbuffer::InternalStringBuffer;
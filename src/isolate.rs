@@ -0,0 +1,312 @@
+// Isolates, which run parts of a program in parallel on threads.
+//
+// An isolate has a State of its own, with its own stack, heap and event
+// loop. It starts from a copy of the program as it was before main ran,
+// so top level variables are initialized anew in each isolate.
+//
+// Isolates share no objects. Messages sent through ports are copied out
+// of the heap of the sender into a Message, and built into the heap of
+// the receiver. Objects reached more than once, as through cycles, are
+// copied once and shared in the copy as they were in the original.
+//
+// An unhandled exception ends only the isolate it is thrown in. Its
+// thread unwinds to where the isolate started, and the exception is
+// reported, or sent to the port given for errors, as for Isolate.run.
+
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+
+use crate::error::dartthrow;
+use crate::evalhelp::{call_function_with, MaybeRef};
+use crate::eventloop;
+use crate::heapobjs::{instance::MaybeObject, InternalList};
+use crate::node::Node;
+use crate::object::Object;
+use crate::objsys::{ObjSys, RefKey};
+use crate::state::State;
+
+
+/// The program as it was before main ran, to start isolates from.
+pub struct Program {
+    globals: Vec<Node>,
+    looktables: HashMap<String, HashMap<String, usize>>,
+    objsys: ObjSys,
+    runtime_checks: bool,
+    virtual_time: bool
+}
+
+
+impl Program {
+
+    pub fn snapshot(state: &State) -> Program {
+        Program {
+            globals: state.globals.clone(),
+            looktables: state.looktables.clone(),
+            objsys: state.objsys.program(),
+            runtime_checks: state.runtime_checks,
+            virtual_time: state.eventloop.virtual_time
+        }
+    }
+
+
+    /// A fresh state for an isolate running the program.
+    fn start(program: &Arc<Program>) -> State {
+        let mut state = State::new();
+        state.globals = program.globals.clone();
+        state.looktables = program.looktables.clone();
+        state.objsys = program.objsys.program();
+        state.runtime_checks = program.runtime_checks;
+        state.eventloop.virtual_time = program.virtual_time;
+        state.program = Some(program.clone());
+        state.spawned = true;
+        state
+    }
+}
+
+
+/// An object copied out of a heap.
+pub enum Message {
+    // Null, bools, numbers, strings and functions, which hold no references.
+    Value(Object),
+    // Class, type arguments, fields and the parent part.
+    Instance(String, Vec<String>, Vec<(String, Message)>, Option<Box<Message>>),
+    // The elements of a list.
+    Elements(Vec<Message>),
+    // A method torn off a copied instance.
+    BoundMethod(Box<Message>, Object),
+    // An object copied before, by the order they were copied in.
+    Shared(usize)
+}
+
+
+/// A message, with the id of the port it was sent to.
+pub type Delivery = (u64, Message);
+
+
+// The ports of all isolates, by id, with the inbox of the isolate that
+// listens to them. A port is removed as it is closed.
+type Ports = HashMap<u64, Sender<Delivery>>;
+static PORTS: LazyLock<Mutex<Ports>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static NEXT_PORT: AtomicU64 = AtomicU64::new(1);
+
+
+/// An unhandled exception, ending the isolate it was thrown in.
+struct Uncaught {
+    exception: String,
+    report: String
+}
+
+
+/// Run the entry function with the message in a new isolate. An unhandled
+/// exception is sent to the errors port, if given, as a list of the
+/// exception and a stack trace, which is empty.
+pub fn spawn(entry: &Object, message: &Object, errors: Option<u64>, node: &Node, state: &mut State) {
+
+    let program = match &state.program {
+        Some(p) => p.clone(),
+        None => panic!("Isolate spawned without a program.")
+    };
+    let entry = copy(entry, node, state);
+    let message = copy(message, node, state);
+    let node = node.clone();
+
+    // With a stack as large as that of the main thread,
+    // as the evaluator recurses deeply.
+    thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(move || {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut state = Program::start(&program);
+                let entry = build(entry, &mut state);
+                let message = build(message, &mut state);
+                state.filepath = String::from(node_file(&entry));
+                state.stack.push_call();
+                call_function_with(MaybeRef::None, &entry, vec![message], &node, &mut state);
+                state.stack.pop_call();
+                eventloop::run(&mut state);
+            }));
+            if let Err(payload) = outcome {
+                match payload.downcast::<Uncaught>() {
+                    Ok(uncaught) => report(*uncaught, errors),
+                    Err(payload) => panic::resume_unwind(payload)
+                }
+            }
+        })
+        .expect("Could not start isolate thread.");
+}
+
+
+/// End the current isolate with an unhandled exception.
+pub fn end(exception: String, report: String) -> ! {
+    panic::resume_unwind(Box::new(Uncaught { exception, report }))
+}
+
+
+fn report(uncaught: Uncaught, errors: Option<u64>) {
+    match errors {
+        Some(id) => {
+            let elements = Message::Elements(vec![
+                Message::Value(Object::String(uncaught.exception)),
+                Message::Value(Object::String(String::new()))
+            ]);
+            let fields = vec![(String::from("__list"), elements)];
+            let message = Message::Instance(String::from("List"), Vec::new(), fields, None);
+            if let Some(inbox) = PORTS.lock().unwrap().get(&id) {
+                let _ = inbox.send((id, message));
+            }
+        }
+        None => println!("{}", uncaught.report)
+    }
+}
+
+
+fn node_file(entry: &Object) -> &str {
+    match entry {
+        Object::Function(_, filename, _, _, _, _) => filename,
+        _ => ""
+    }
+}
+
+
+/// Open a port for the ReceivePort, delivering to this isolate.
+pub fn open_port(port: Object, node: &Node, state: &mut State) -> u64 {
+    let id = NEXT_PORT.fetch_add(1, Ordering::Relaxed);
+    PORTS.lock().unwrap().insert(id, state.eventloop.inbox());
+    state.eventloop.add_port(id, port, node.clone());
+    id
+}
+
+
+pub fn close_port(id: u64, state: &mut State) {
+    PORTS.lock().unwrap().remove(&id);
+    state.eventloop.remove_port(id);
+}
+
+
+/// Send a copy of the object to the port. Messages
+/// to closed ports are dropped, as in Dart.
+pub fn send(id: u64, obj: &Object, node: &Node, state: &mut State) {
+    let message = copy(obj, node, state);
+    if let Some(inbox) = PORTS.lock().unwrap().get(&id) {
+        // The isolate may have ended, which also drops the message.
+        let _ = inbox.send((id, message));
+    }
+}
+
+
+/// Copy an object out of the heap.
+pub fn copy(obj: &Object, node: &Node, state: &State) -> Message {
+    let mut seen = HashMap::new();
+    copy_object(obj, &mut seen, node, state)
+}
+
+
+fn copy_object(obj: &Object, seen: &mut HashMap<RefKey, usize>, node: &Node, state: &State) -> Message {
+    match obj {
+        Object::Reference(rk) => copy_ref(rk, seen, node, state),
+        Object::BoundMethod(rk, method) => {
+            Message::BoundMethod(Box::new(copy_ref(rk, seen, node, state)), (**method).clone())
+        }
+        Object::Return(_) => panic!("Tried to copy Return object"),
+        x => Message::Value(x.clone())
+    }
+}
+
+
+fn copy_ref(rk: &RefKey, seen: &mut HashMap<RefKey, usize>, node: &Node, state: &State) -> Message {
+
+    if let Some(i) = seen.get(rk) {
+        return Message::Shared(*i);
+    }
+    let i = seen.len();
+    seen.insert(rk.clone(), i);
+
+    if state.objsys.has_instance(rk) {
+        let inst = state.objsys.get_instance(rk);
+        if inst.classname == "ReceivePort" {
+            // As dart.
+            dartthrow(
+                "Invalid argument(s): Illegal argument in isolate message: (object is a ReceivePort)",
+                state,
+                node
+            );
+        }
+        let fields = inst.fields.iter()
+            .map(|(name, val)| (name.clone(), copy_object(val, seen, node, state)))
+            .collect();
+        let parent = match &inst.parent {
+            MaybeObject::Some(p) => Some(Box::new(copy_object(p, seen, node, state))),
+            MaybeObject::None => None
+        };
+        return Message::Instance(inst.classname.clone(), inst.typeargs.clone(), fields, parent);
+    }
+    if state.objsys.has_list(rk) {
+        let els = state.objsys.get_list(rk).els.iter()
+            .map(|val| copy_object(val, seen, node, state))
+            .collect();
+        return Message::Elements(els);
+    }
+    // Generators, files, buffers and regexps are held by Rust.
+    dartthrow(
+        "Invalid argument(s): Illegal argument in isolate message: object is unsendable",
+        state,
+        node
+    )
+}
+
+
+/// Build a copied object into the heap.
+pub fn build(message: Message, state: &mut State) -> Object {
+    let mut made = Vec::new();
+    build_object(message, &mut made, state)
+}
+
+
+fn build_object(message: Message, made: &mut Vec<Object>, state: &mut State) -> Object {
+    match message {
+        Message::Value(obj) => obj,
+        Message::Shared(i) => made[i].clone(),
+        Message::Instance(classname, typeargs, fields, parent) => {
+            // Registered before its fields are built, as they may refer to it.
+            let mut inst = state.objsys.get_class(&classname).instantiate();
+            inst.typeargs = typeargs;
+            let obj = state.objsys.register_instance(*inst);
+            made.push(obj.clone());
+
+            let fields: Vec<(String, Object)> = fields.into_iter()
+                .map(|(name, val)| (name, build_object(val, made, state)))
+                .collect();
+            let parent = parent.map(|p| build_object(*p, made, state));
+
+            let rk = obj.heap_ref().unwrap().clone();
+            let inst = state.objsys.get_instance_mut(&rk);
+            for (name, val) in fields {
+                inst.set_field(name, val);
+            }
+            if let Some(p) = parent {
+                inst.parent = MaybeObject::Some(p);
+            }
+            obj
+        }
+        Message::Elements(els) => {
+            let list = InternalList::new();
+            let rk = list.id.clone();
+            let obj = state.objsys.register_list(list);
+            made.push(obj.clone());
+
+            let els = els.into_iter().map(|val| build_object(val, made, state)).collect();
+            state.objsys.get_list_mut(&rk).set_elements(els);
+            obj
+        }
+        Message::BoundMethod(receiver, method) => {
+            match build_object(*receiver, made, state) {
+                Object::Reference(rk) => Object::BoundMethod(rk, Box::new(method)),
+                x => panic!("Expected reference to receiver. Got: {}", x)
+            }
+        }
+    }
+}
@@ -27,12 +27,15 @@ fn read_word(tokens: &mut Vec<Token>, chars: &[char], start: usize, linenum: usi
         "import"  => Token::Import(linenum, symnum),
         "true"    => Token::Bool(true, linenum, symnum),
         "false"   => Token::Bool(false, linenum, symnum),
+        "null"    => Token::Null(linenum, symnum),
         "if"      => Token::If(linenum, symnum),
         "else"    => Token::Else(linenum, symnum),
         "while"   => Token::While(linenum, symnum),
         "do"      => Token::Do(linenum, symnum),
         "for"     => Token::For(linenum, symnum),
         "in"      => Token::In(linenum, symnum),
+        "is"      => Token::Is(linenum, symnum),
+        "as"      => Token::As(linenum, symnum),
         "get"     => Token::Get(linenum, symnum),
         "const"   => Token::Const(linenum, symnum),
         "return"  => Token::Return(linenum, symnum),
@@ -40,6 +43,9 @@ fn read_word(tokens: &mut Vec<Token>, chars: &[char], start: usize, linenum: usi
         "class"   => Token::Class(linenum, symnum),
         "this"    => Token::This(linenum, symnum),
         "extends" => Token::Extends(linenum, symnum),
+        "switch"  => Token::Switch(linenum, symnum),
+        "case"    => Token::Case(linenum, symnum),
+        "default" => Token::Default(linenum, symnum),
         _         => Token::Name(sym, linenum, symnum),
     });
     return len;
@@ -47,20 +53,25 @@ fn read_word(tokens: &mut Vec<Token>, chars: &[char], start: usize, linenum: usi
 
 
 pub fn lex(input: &str, filepath: &str) -> Reader {
-    let (tokens, pos) = lex_real(input, 0, 0, 1, 1, filepath);
+    let (tokens, pos, _, _) = lex_real(input, 0, 0, 1, 1, filepath);
     let reader = Reader::new(tokens);
     assert_eq!(pos, input.chars().count(), "Lexer with leftover input.");
     return reader;
 }
 
 
-fn lex_real(input: &str, startpos: usize, interpol: usize, mut linenum: usize, mut symnum: usize, filepath: &str) -> (Vec<Token>, usize) {
+/// Lex until end of input, or until the closing brace when lexing an
+/// interpolated expression. Returns the tokens along with the position,
+/// line and column following the last consumed char.
+fn lex_real(input: &str, startpos: usize, interpol: usize, mut linenum: usize, mut symnum: usize, filepath: &str) -> (Vec<Token>, usize, usize, usize) {
 
     let mut tokens: Vec<Token> = Vec::new();
     let chars: Vec<char> = input.chars().collect();
     let inp_length = chars.len();
     let mut i: usize = startpos;
     let mut c: char;
+    // Nesting of braces within an interpolated expression.
+    let mut depth: usize = 0;
 
     while i < inp_length {
 
@@ -68,85 +79,31 @@ fn lex_real(input: &str, startpos: usize, interpol: usize, mut linenum: usize, m
 
         match c {
 
-            ' ' => {}
+            ' ' | '\t' | '\r' => {}
 
             '\n' => {
-                assert_eq!(interpol, 0, "Unexpected newline in interpolation.");
                 linenum += 1;
                 symnum = 0;
             }
 
-            '"' => {
-                let mut s = String::new();
-                let mut closed = false;
-                let mut subs: Vec<Vec<Token>> = Vec::new();
-                i += 1;
-                symnum += 1;
-
-                while i < inp_length {
-
-                    let nc : char = chars[i];
-
-                    if nc == '"' {
-                        closed = true;
-                        i += 1;
-                        symnum += 1;
-                        break;
-                    }
-
-                    s.push(nc);
-                    i += 1;
-                    symnum += 1;
-
-                    if nc == '$' && chars[i] == '{' {
-                        let (sublex, new_pos) = lex_real(input, i + 1, interpol + 1, linenum, symnum, filepath);
-                        subs.push(sublex);
-                        // Assuming string interpol does not cross lines.
-                        symnum += new_pos - i;
-                        i = new_pos;
-                    }
-                }
-                if !closed {
-                    lexerror("Unclosed quote.", linenum, symnum, filepath)
-                }
-                tokens.push(Token::Str(s, subs, linenum, symnum));
+            '"' | '\'' => {
+                let (parts, subs, new_pos, new_linenum, new_symnum) =
+                    read_string(input, &chars, i, interpol, linenum, symnum, filepath);
+                push_string(&mut tokens, parts, subs, linenum, symnum);
+                i = new_pos;
+                linenum = new_linenum;
+                symnum = new_symnum;
                 continue;
             }
 
-            '\'' => {
-                let mut s = String::new();
-                let mut closed = false;
-                let mut subs: Vec<Vec<Token>> = Vec::new();
-                i += 1;
-                symnum += 1;
-
-                while i < inp_length {
-
-                    let nc : char = chars[i];
-
-                    if nc == '\'' {
-                        closed = true;
-                        i += 1;
-                        symnum += 1;
-                        break;
-                    }
-
-                    s.push(nc);
-                    i += 1;
-                    symnum += 1;
-
-                    if nc == '$' && chars[i] == '{' {
-                        let (sublex, new_pos) = lex_real(input, i + 1, interpol + 1, linenum, symnum, filepath);
-                        subs.push(sublex);
-                        // Assuming string interpol does not cross lines.
-                        symnum += new_pos - i;
-                        i = new_pos;
-                    }
-                }
-                if !closed {
-                    lexerror("Unclosed quote.", linenum, symnum, filepath)
-                }
-                tokens.push(Token::Str(s, subs, linenum, symnum));
+            'r' if i + 1 < inp_length && (chars[i + 1] == '"' || chars[i + 1] == '\'') => {
+                // Raw string.
+                let (parts, subs, new_pos, new_linenum, new_symnum) =
+                    read_string(input, &chars, i, interpol, linenum, symnum, filepath);
+                push_string(&mut tokens, parts, subs, linenum, symnum);
+                i = new_pos;
+                linenum = new_linenum;
+                symnum = new_symnum;
                 continue;
             }
 
@@ -207,12 +164,17 @@ fn lex_real(input: &str, startpos: usize, interpol: usize, mut linenum: usize, m
             }
 
             '{' => {
+                depth += 1;
                 tokens.push(Token::Block1(linenum, symnum));
             }
 
             '}' => {
-                if interpol > 0 {
-                    return (tokens, i+1);
+                if interpol > 0 && depth == 0 {
+                    tokens.push(Token::End(linenum, symnum));
+                    return (tokens, i + 1, linenum, symnum + 1);
+                }
+                if depth > 0 {
+                    depth -= 1;
                 }
                 tokens.push(Token::Block2(linenum, symnum));
             }
@@ -226,6 +188,25 @@ fn lex_real(input: &str, startpos: usize, interpol: usize, mut linenum: usize, m
             }
 
             '.' => {
+                if i + 1 < inp_length && chars[i + 1].is_ascii_digit() {
+                    // A double like .5
+                    let len = read_number(&mut tokens, &chars, i, linenum, symnum, filepath);
+                    i += len;
+                    symnum += len;
+                    continue;
+                }
+                if i + 2 < inp_length && chars[i + 1] == '.' && chars[i + 2] == '.' {
+                    if i + 3 < inp_length && chars[i + 3] == '?' {
+                        tokens.push(Token::SpreadNull(linenum, symnum));
+                        i += 4;
+                        symnum += 4;
+                        continue;
+                    }
+                    tokens.push(Token::Spread(linenum, symnum));
+                    i += 3;
+                    symnum += 3;
+                    continue;
+                }
                 tokens.push(Token::Access(linenum, symnum));
             }
 
@@ -248,6 +229,12 @@ fn lex_real(input: &str, startpos: usize, interpol: usize, mut linenum: usize, m
                     symnum += 2;
                     continue;
                 }
+                if chars[i+1] == '>' {
+                    tokens.push(Token::Arrow(linenum, symnum));
+                    i += 2;
+                    symnum += 2;
+                    continue;
+                }
                 tokens.push(Token::Assign(linenum, symnum));
             }
 
@@ -315,49 +302,38 @@ fn lex_real(input: &str, startpos: usize, interpol: usize, mut linenum: usize, m
                 tokens.push(Token::BitAnd(linenum, symnum));
             }
 
+            '~' => {
+                if i + 1 < inp_length && chars[i + 1] == '/' {
+                    tokens.push(Token::TruncDiv(linenum, symnum));
+                    i += 2;
+                    symnum += 2;
+                    continue;
+                }
+                lexerror("Unrecognized symbol: ~", linenum, symnum, filepath)
+            }
+
             '^' => {
                 tokens.push(Token::BitXor(linenum, symnum));
             }
 
             '!' => {
+                if i + 1 < inp_length && chars[i + 1] == '=' {
+                    tokens.push(Token::NotEqual(linenum, symnum));
+                    i += 2;
+                    symnum += 2;
+                    continue;
+                }
                 tokens.push(Token::Not(linenum, symnum));
             }
 
-            x if x.is_digit(10) => {
-                let mut nl = 1;
-                let mut nc: char;
-                let mut is_int: bool = true;
-
-                while i + nl < inp_length {
-                    nc = chars[i + nl];
-                    if nc.is_digit(10) {
-                        nl += 1;
-                        symnum += 1;
-                        continue;
-                    }
-                    else if nc == '.' {
-                        if !is_int {
-                            // Two '.' in one number.
-                            lexerror("Unexpected symbol: \".\"", linenum, symnum, filepath)
-                        }
-                        is_int = false;
-                        nl += 1;
-                        symnum += 1;
-                        continue;
-                    }
-                    break;
-                }
+            '?' => {
+                tokens.push(Token::Question(linenum, symnum));
+            }
 
-                let valstr: &str = input.get(i .. i + nl).unwrap();
-                if is_int {
-                    let val = valstr.parse::<i64>().unwrap();
-                    tokens.push(Token::Int(val, linenum, symnum));
-                }
-                else {
-                    let val : f64 = valstr.parse::<f64>().unwrap() as f64;
-                    tokens.push(Token::Double(val, linenum, symnum));
-                }
-                i += nl;
+            x if x.is_ascii_digit() => {
+                let len = read_number(&mut tokens, &chars, i, linenum, symnum, filepath);
+                i += len;
+                symnum += len;
                 continue;
             }
 
@@ -379,6 +355,373 @@ fn lex_real(input: &str, startpos: usize, interpol: usize, mut linenum: usize, m
         symnum += 1;
     }
 
+    if interpol > 0 {
+        lexerror("Expected '}' to end interpolation.", linenum, symnum, filepath)
+    }
+
     tokens.push(Token::End(linenum, symnum));
-    (tokens, i)
+    (tokens, i, linenum, symnum)
+}
+
+
+/// Read a number literal and push it as an Int or Double token.
+/// Returns the number of chars consumed.
+fn read_number(tokens: &mut Vec<Token>, chars: &[char], start: usize, linenum: usize, symnum: usize, filepath: &str) -> usize {
+
+    let inp_length = chars.len();
+    let mut i = start;
+
+    if chars[i] == '0' && i + 2 < inp_length && (chars[i + 1] == 'x' || chars[i + 1] == 'X') && chars[i + 2].is_ascii_hexdigit() {
+        i += 2;
+        let digits = read_digits(chars, &mut i, 16, linenum, symnum, filepath);
+        // As Dart, hex literals may use all 64 bits, so 0xFFFFFFFFFFFFFFFF is -1.
+        match u64::from_str_radix(&digits, 16) {
+            Ok(val) => tokens.push(Token::Int(val as i64, linenum, symnum)),
+            Err(_) => lexerror(
+                // As Dart.
+                format!("The integer literal {} can't be represented in 64 bits.", String::from_iter(&chars[start .. i])),
+                linenum,
+                symnum,
+                filepath
+            )
+        }
+        return i - start;
+    }
+
+    let mut valstr = read_digits(chars, &mut i, 10, linenum, symnum, filepath);
+    let mut is_int = true;
+
+    if i + 1 < inp_length && chars[i] == '.' && chars[i + 1].is_ascii_digit() {
+        i += 1;
+        is_int = false;
+        valstr.push('.');
+        valstr.push_str(&read_digits(chars, &mut i, 10, linenum, symnum, filepath));
+    }
+
+    if i < inp_length && (chars[i] == 'e' || chars[i] == 'E') {
+        let signed = i + 1 < inp_length && (chars[i + 1] == '+' || chars[i + 1] == '-');
+        let first = if signed { i + 2 } else { i + 1 };
+
+        if first < inp_length && chars[first].is_ascii_digit() {
+            is_int = false;
+            valstr.push('e');
+            if signed {
+                valstr.push(chars[i + 1]);
+            }
+            i = first;
+            valstr.push_str(&read_digits(chars, &mut i, 10, linenum, symnum, filepath));
+        }
+    }
+
+    if is_int {
+        match valstr.parse::<i64>() {
+            Ok(val) => tokens.push(Token::Int(val, linenum, symnum)),
+            Err(_) => lexerror(
+                // As Dart.
+                format!("The integer literal {} can't be represented in 64 bits.", valstr),
+                linenum,
+                symnum,
+                filepath
+            )
+        }
+    }
+    else {
+        if valstr.starts_with('.') {
+            valstr.insert(0, '0');
+        }
+        tokens.push(Token::Double(valstr.parse::<f64>().unwrap(), linenum, symnum));
+    }
+    i - start
+}
+
+
+/// Read digits in the given radix, skipping digit separators.
+fn read_digits(chars: &[char], i: &mut usize, radix: u32, linenum: usize, symnum: usize, filepath: &str) -> String {
+
+    let mut digits = String::new();
+
+    while *i < chars.len() {
+        let c = chars[*i];
+
+        if c.is_digit(radix) {
+            digits.push(c);
+            *i += 1;
+        }
+        else if c == '_' {
+            let mut j = *i;
+            while j < chars.len() && chars[j] == '_' {
+                j += 1;
+            }
+            if digits.is_empty() || j >= chars.len() || !chars[j].is_digit(radix) {
+                // As Dart.
+                lexerror(
+                    "Digit separators ('_') in a number literal can only be placed between two digits.",
+                    linenum,
+                    symnum,
+                    filepath
+                )
+            }
+            *i = j;
+        }
+        else {
+            break;
+        }
+    }
+    digits
+}
+
+
+/// Read a string literal, starting at the opening quote, or at the 'r'
+/// prefix of a raw string.
+///
+/// Returns the literal parts and the tokens of the interpolated expressions
+/// between them, so there is always one more part than interpolations.
+/// Also returns the position, line and column after the closing quote.
+fn read_string(
+    input: &str,
+    chars: &[char],
+    start: usize,
+    interpol: usize,
+    mut linenum: usize,
+    mut symnum: usize,
+    filepath: &str) -> (Vec<String>, Vec<Vec<Token>>, usize, usize, usize) {
+
+    let inp_length = chars.len();
+    let mut i = start;
+
+    let raw = chars[i] == 'r';
+    if raw {
+        i += 1;
+        symnum += 1;
+    }
+
+    let quote = chars[i];
+    let triple = i + 2 < inp_length && chars[i + 1] == quote && chars[i + 2] == quote;
+    let quote_len = if triple { 3 } else { 1 };
+    let (start_linenum, start_symnum) = (linenum, symnum);
+    i += quote_len;
+    symnum += quote_len;
+
+    if triple {
+        // As Dart, the rest of the first line is not part of
+        // a multi-line string if it only contains whitespace.
+        let mut j = i;
+        while j < inp_length && (chars[j] == ' ' || chars[j] == '\t') {
+            j += 1;
+        }
+        if j < inp_length && chars[j] == '\n' {
+            i = j + 1;
+            linenum += 1;
+            symnum = 1;
+        }
+    }
+
+    let mut parts: Vec<String> = vec![String::new()];
+    let mut subs: Vec<Vec<Token>> = Vec::new();
+
+    loop {
+
+        if i >= inp_length {
+            // As Dart.
+            let q = String::from_iter(&chars[start .. start + quote_len + raw as usize]);
+            lexerror(
+                format!("String starting with {} must end with {}.", q, String::from_iter(vec![quote; quote_len])),
+                start_linenum,
+                start_symnum,
+                filepath
+            )
+        }
+
+        let nc = chars[i];
+
+        if nc == quote && (!triple || (i + 2 < inp_length && chars[i + 1] == quote && chars[i + 2] == quote)) {
+            i += quote_len;
+            symnum += quote_len;
+            break;
+        }
+
+        match nc {
+
+            '\n' => {
+                if !triple {
+                    // As Dart.
+                    lexerror(
+                        format!("String starting with {} must end with {}.", quote, quote),
+                        start_linenum,
+                        start_symnum,
+                        filepath
+                    )
+                }
+                parts.last_mut().unwrap().push('\n');
+                i += 1;
+                linenum += 1;
+                symnum = 1;
+            }
+
+            '\\' if !raw => {
+                let (ec, len) = read_escape(chars, i + 1, triple, linenum, symnum, filepath);
+                parts.last_mut().unwrap().push(ec);
+                i += 1 + len;
+                if ec == '\n' && chars[i - 1] == '\n' {
+                    linenum += 1;
+                    symnum = 1;
+                }
+                else {
+                    symnum += 1 + len;
+                }
+            }
+
+            '$' if !raw => {
+
+                if i + 1 < inp_length && chars[i + 1] == '{' {
+                    let (sublex, new_pos, new_linenum, new_symnum) =
+                        lex_real(input, i + 2, interpol + 1, linenum, symnum + 2, filepath);
+                    subs.push(sublex);
+                    parts.push(String::new());
+                    i = new_pos;
+                    linenum = new_linenum;
+                    symnum = new_symnum;
+                }
+                else if i + 1 < inp_length && (chars[i + 1].is_alphabetic() || chars[i + 1] == '_') {
+                    // Interpolation of a plain identifier, like "$name".
+                    let mut sub: Vec<Token> = Vec::new();
+                    let len = read_word(&mut sub, chars, i + 1, linenum, symnum + 1);
+                    sub.push(Token::End(linenum, symnum + 1 + len));
+                    subs.push(sub);
+                    parts.push(String::new());
+                    i += 1 + len;
+                    symnum += 1 + len;
+                }
+                else {
+                    // As Dart.
+                    lexerror(
+                        "A '$' has special meaning inside a string, and must be followed by an identifier or an expression in curly braces ({}).",
+                        linenum,
+                        symnum,
+                        filepath
+                    )
+                }
+            }
+
+            x => {
+                parts.last_mut().unwrap().push(x);
+                i += 1;
+                symnum += 1;
+            }
+        }
+    }
+
+    (parts, subs, i, linenum, symnum)
+}
+
+
+/// Read the escape sequence following a backslash in a string.
+/// Returns the char it represents and the number of chars consumed.
+fn read_escape(chars: &[char], start: usize, triple: bool, linenum: usize, symnum: usize, filepath: &str) -> (char, usize) {
+
+    if start >= chars.len() {
+        lexerror("Unexpected end of input in escape sequence.", linenum, symnum, filepath)
+    }
+
+    match chars[start] {
+        'n' => ('\n', 1),
+        'r' => ('\r', 1),
+        't' => ('\t', 1),
+        'b' => ('\u{8}', 1),
+        'f' => ('\u{c}', 1),
+        'v' => ('\u{b}', 1),
+
+        'x' => {
+            match read_hex(chars, start + 1, 2) {
+                Some(code) => (to_char(code), 3),
+                // As Dart.
+                None => lexerror(
+                    "An escape sequence starting with '\\x' must be followed by 2 hexadecimal digits.",
+                    linenum,
+                    symnum,
+                    filepath
+                )
+            }
+        }
+
+        'u' => {
+            // As Dart.
+            let msg = "An escape sequence starting with '\\u' must be followed by 4 hexadecimal digits or from 1 to 6 digits between '{' and '}'.";
+
+            if start + 1 < chars.len() && chars[start + 1] == '{' {
+                let mut len = 0;
+                while start + 2 + len < chars.len() && chars[start + 2 + len].is_ascii_hexdigit() {
+                    len += 1;
+                }
+                if len == 0 || len > 6 || start + 2 + len >= chars.len() || chars[start + 2 + len] != '}' {
+                    lexerror(msg, linenum, symnum, filepath)
+                }
+                let code = read_hex(chars, start + 2, len).unwrap();
+                if code > 0x10FFFF {
+                    // As Dart.
+                    lexerror("The escape sequence is not a valid code point.", linenum, symnum, filepath)
+                }
+                return (to_char(code), len + 3);
+            }
+
+            match read_hex(chars, start + 1, 4) {
+                Some(high) if (0xD800 .. 0xDC00).contains(&high) => {
+                    // A surrogate pair written as two escapes, like "\uD83D\uDE00".
+                    if start + 10 < chars.len() && chars[start + 5] == '\\' && chars[start + 6] == 'u' {
+                        if let Some(low) = read_hex(chars, start + 7, 4) {
+                            if (0xDC00 .. 0xE000).contains(&low) {
+                                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                                return (to_char(code), 11);
+                            }
+                        }
+                    }
+                    (to_char(high), 5)
+                }
+                Some(code) => (to_char(code), 5),
+                None => lexerror(msg, linenum, symnum, filepath)
+            }
+        }
+
+        '\n' if !triple => {
+            // As Dart.
+            lexerror("Can't have a line break in a single-line string.", linenum, symnum, filepath)
+        }
+
+        // Any other char, including quotes, '$' and '\\', stands for itself.
+        x => (x, 1)
+    }
+}
+
+
+fn read_hex(chars: &[char], start: usize, len: usize) -> Option<u32> {
+    if start + len > chars.len() {
+        return None;
+    }
+    let digits = String::from_iter(&chars[start .. start + len]);
+    if !digits.chars().all(|d| d.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+
+/// Lone surrogates can not be represented in a Rust string,
+/// so they are replaced by U+FFFD.
+fn to_char(code: u32) -> char {
+    char::from_u32(code).unwrap_or('\u{FFFD}')
+}
+
+
+/// Push a string token. Adjacent string literals are
+/// concatenated into one, as in Dart.
+fn push_string(tokens: &mut Vec<Token>, mut parts: Vec<String>, subs: Vec<Vec<Token>>, linenum: usize, symnum: usize) {
+
+    if let Some(Token::Str(prev_parts, prev_subs, _, _)) = tokens.last_mut() {
+        let first = parts.remove(0);
+        prev_parts.last_mut().unwrap().push_str(&first);
+        prev_parts.extend(parts);
+        prev_subs.extend(subs);
+        return;
+    }
+    tokens.push(Token::Str(parts, subs, linenum, symnum));
 }
@@ -1,4 +1,3 @@
-extern crate nuid;
 extern crate queues;
 extern crate rand;
 
@@ -19,14 +18,21 @@ mod reader;
 mod dirs;
 mod heapobjs;
 mod evalhelp;
-// mod typechecker;
+mod generator;
+mod eventloop;
+mod types;
+mod typechecker;
 mod api;
+mod regexp;
+mod isolate;
+mod timezone;
 
 use std::io::prelude::*;
 use std::env;
 use std::fs::File;
 use std::collections::HashMap;
 use std::time::Instant;
+use std::sync::Arc;
 use crate::state::State;
 use crate::dirs::Dirs;
 use crate::node::NodeType;
@@ -35,7 +41,16 @@ use crate::error::evalerror;
 
 fn main() {
 
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // Skip checking values against declared types, for speed.
+    let runtime_checks = !args.iter().any(|a| a == "--no-runtime-checks");
+    args.retain(|a| a != "--no-runtime-checks");
+
+    // Keep time by a clock that is set forward to the next timer,
+    // so timers don't make runs slow or flaky. Always on for tests.
+    let virtual_time = args.iter().any(|a| a == "--virtual-time");
+    args.retain(|a| a != "--virtual-time");
 
     if args.len() < 2 {
         println!("Argument expected.");
@@ -51,14 +66,14 @@ fn main() {
                 println!("Error: File argument expected.");
                 return;
             }
-            do_task("lex", &args[2], &dirs);
+            do_task("lex", &args[2], &dirs, runtime_checks, virtual_time);
         }
         "parse" => {
             if args.len() < 3 {
                 println!("Error: File argument expected.");
                 return;
             }
-            do_task("parse", &args[2], &dirs);
+            do_task("parse", &args[2], &dirs, runtime_checks, virtual_time);
         }
         "test" => {
             if args.len() < 3 {
@@ -69,7 +84,7 @@ fn main() {
                     println!("Running test: {}", s);
                     println!("----------------------------------------------------");
                     let path = format!("{}/{}", dirs.testdir(), s);
-                    do_task("eval", &path, &dirs);
+                    do_task("eval", &path, &dirs, runtime_checks, true);
                 }
                 let end = Instant::now();
                 println!("____________________________________________________");
@@ -98,7 +113,7 @@ fn main() {
             }
 
             let filepath = testlist::get_filepath(nextarg.clone(), &dirs);
-            do_task(task, &filepath, &dirs);
+            do_task(task, &filepath, &dirs, runtime_checks, true);
         }
         "testfail" => {
             if args.len() < 3 {
@@ -128,7 +143,10 @@ fn main() {
             }
 
             let filepath = testlist::get_failfilepath(nextarg.clone(), &dirs);
-            do_task(task, &filepath, &dirs);
+            do_task(task, &filepath, &dirs, runtime_checks, true);
+        }
+        _ if a1.ends_with(".dart") => {
+            do_task("eval", a1, &dirs, runtime_checks, virtual_time);
         }
         _ => {
             println!("Illegal argument: {}", a1);
@@ -137,10 +155,12 @@ fn main() {
 }
 
 
-fn do_task(action: &str, filepath: &str, dirs: &Dirs) {
+fn do_task(action: &str, filepath: &str, dirs: &Dirs, runtime_checks: bool, virtual_time: bool) {
 
     let mut state = State::new();
     state.filepath = String::from(filepath);
+    state.runtime_checks = runtime_checks;
+    state.eventloop.virtual_time = virtual_time;
 
     match action {
         "lex" => {
@@ -231,10 +251,9 @@ fn filecurse(
 
     for s in imports {
 
-        if memo.contains_key(&s) {
-            continue;
+        if !memo.contains_key(&s) {
+            filecurse(basepath.clone(), s.clone(), memo, state, dirs);
         }
-        filecurse(basepath.clone(), s.clone(), memo, state, dirs);
 
         // For every import, merge its functions into this files looktable.
 
@@ -274,8 +293,12 @@ fn evaluate(filepath: String, state: &mut State, dirs: &Dirs) {
 
     filecurse(basepath.clone(), String::from(filename), &mut memo, state, dirs);
 
-    //typechecker::typecheck(state);
+    typechecker::typecheck(state, &memo);
 
+    // Isolates start from the program as it is before main runs.
+    if state.looktables.contains_key("dart:isolate") {
+        state.program = Some(Arc::new(isolate::Program::snapshot(state)));
+    }
 
     let toptable = &state.looktables[filename];
     if !toptable.contains_key("main") {
@@ -293,6 +316,7 @@ fn evaluate(filepath: String, state: &mut State, dirs: &Dirs) {
             state.stack.push_call();
             evaluator::eval(mainbody, state);
             state.stack.pop_call();
+            eventloop::run(state);
         }
         x => {
             panic!("Unexpected type of 'main': {}", x)
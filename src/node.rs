@@ -1,5 +1,6 @@
 use std::fmt;
 use crate::object::Object;
+use crate::types::TypeParam;
 
 
 #[derive(Clone)]
@@ -8,6 +9,7 @@ pub enum NodeType {
     Sub(usize, usize),
     Mul(usize, usize),
     Div(usize, usize),
+    TruncDiv(usize, usize),
     PreIncrement(usize, usize),
     PostIncrement(usize, usize),
     PreDecrement(usize, usize),
@@ -23,10 +25,14 @@ pub enum NodeType {
     LessOrEq(usize, usize),
     GreaterOrEq(usize, usize),
     Equal(usize, usize),
+    Is(String, bool, usize, usize),     // type, negated
+    As(String, usize, usize),
+    NullCheck(usize, usize),
     Assign(usize, usize),
     Int(i64, usize, usize),
     Double(f64, usize, usize),
     Str(String, usize, usize),
+    StrInterpol(Vec<String>, usize, usize), // literal parts
     Bool(bool, usize, usize),
     Name(String, usize, usize),
     TypedVar(String, String, usize, usize),  // type, name
@@ -42,19 +48,47 @@ pub enum NodeType {
     DoWhile(usize, usize),
     For(usize, usize),
     Block(usize, usize),
-    List(usize, usize),
+    List(String, usize, usize),     // element type, empty if inferred
+    Map(String, String, usize, usize),  // key and value types, empty if inferred
+    Set(String, usize, usize),      // element type, empty if inferred
+    MapPair(usize, usize),      // key and value of a map literal
+    Spread(bool, usize, usize),     // null-aware
+    Late(usize, usize),     // initializer, if any, run on first read
+    CollectionIf(usize, usize),
+    CollectionFor(usize, usize),
     CollAccess(usize, usize),
     This(usize, usize),
     Super(usize, usize),
+    SuperRef(String, usize, usize),     // class the reference occurs in
     FunDef(String, String, String, usize, usize), // typename, funcname, filename
     FunCall(String, usize, usize),
     MethodCall(String, Box<Node>, String, usize, usize),  // methodname, owner, filename
     ParamList(usize, usize),
+    OptionalParam(usize, usize),
+    NamedParam(bool, usize, usize), // required
     ArgList(usize, usize),
+    NamedArg(String, usize, usize),
     ThisFieldInit(String, usize, usize),
     InitList(usize, usize),
     Initializer(usize, usize),
     Return(usize, usize),
+    TypeParams(Vec<TypeParam>, usize, usize),
+    TypeArgs(Vec<String>, usize, usize),
+    SyncStar(usize, usize),     // generator body
+    Yield(usize, usize),
+    YieldStar(usize, usize),
+    Async(String, usize, usize), // async body, with the type of its value
+    Await(usize, usize),
+    AsyncStar(String, usize, usize), // async* body, with the type of its events
+    AwaitFor(usize, usize),     // loop variable, stream, body
+    Switch(usize, usize),       // subject, then cases
+    SwitchExpr(usize, usize),
+    SwitchCase(usize, usize),   // body, then the heads sharing it
+    CaseHead(usize, usize),     // pattern, and the guard if any
+    VarPattern(String, String, usize, usize),   // type, name
+    ConstPattern(usize, usize),
+    ObjectPattern(String, usize, usize),        // type
+    FieldPattern(String, usize, usize),         // field name
     Constructor(String, Box<Node>, Box<Node>, Box<Node>, String, usize, usize), // consname, paramlist, initlist, body, filename
     Null(usize, usize),
 }
@@ -68,6 +102,7 @@ impl fmt::Display for NodeType {
             NodeType::Sub(_, _)           => write!(f, "-"),
             NodeType::Mul(_, _)           => write!(f, "*"),
             NodeType::Div(_, _)           => write!(f, "/"),
+            NodeType::TruncDiv(_, _)      => write!(f, "~/"),
             NodeType::PreIncrement(_, _)  |
             NodeType::PostIncrement(_, _) => write!(f, "++"),
             NodeType::PreDecrement(_, _)  |
@@ -83,10 +118,15 @@ impl fmt::Display for NodeType {
             NodeType::LessOrEq(_, _)      => write!(f, "<="),
             NodeType::GreaterOrEq(_, _)   => write!(f, ">="),
             NodeType::Equal(_, _)         => write!(f, "=="),
+            NodeType::Is(typ, false, _, _) => write!(f, "is {}", typ),
+            NodeType::Is(typ, true, _, _)  => write!(f, "is! {}", typ),
+            NodeType::As(typ, _, _)       => write!(f, "as {}", typ),
+            NodeType::NullCheck(_, _)     => write!(f, "!"),
             NodeType::Assign(_, _)        => write!(f, "="),
             NodeType::Int(s, _, _)  => write!(f, "{}", s),
             NodeType::Double(s, _, _)  => write!(f, "{}", s),
             NodeType::Str(s, _, _)  => write!(f, "\"{}\"", s),
+            NodeType::StrInterpol(parts, _, _) => write!(f, "\"{}\"", parts.join("${}")),
             NodeType::Bool(v, _, _)   => write!(f, "{}", v),
             NodeType::Name(s, _, _) => write!(f, "{}", s),
             NodeType::TypedVar(typ, name, _, _)                       => write!(f, "{}:{}", name, typ),
@@ -102,19 +142,51 @@ impl fmt::Display for NodeType {
             NodeType::DoWhile(_, _)     => write!(f, "DoWhile"),
             NodeType::For(_, _)         => write!(f, "For"),
             NodeType::Block(_, _)       => write!(f, "Block"),
-            NodeType::List(_, _)        => write!(f, "[]"),
+            NodeType::List(_, _, _)     => write!(f, "[]"),
+            NodeType::Map(_, _, _, _)   => write!(f, "{{}}"),
+            NodeType::Set(_, _, _)      => write!(f, "{{}}"),
+            NodeType::MapPair(_, _)     => write!(f, ":"),
+            NodeType::Spread(false, _, _)   => write!(f, "..."),
+            NodeType::Spread(true, _, _)    => write!(f, "...?"),
+            NodeType::Late(_, _)            => write!(f, "late"),
+            NodeType::CollectionIf(_, _)    => write!(f, "CollectionIf"),
+            NodeType::CollectionFor(_, _)   => write!(f, "CollectionFor"),
             NodeType::CollAccess(_, _)  => write!(f, "T[n]"),
             NodeType::This(_, _)        => write!(f, "this"),
             NodeType::Super(_, _)       => write!(f, "super"),
+            NodeType::SuperRef(_, _, _) => write!(f, "super"),
             NodeType::FunDef(_, s, _filename, _, _)          => write!(f, "{}() {{}}", s),
             NodeType::FunCall(s, _, _)                             => write!(f, "{}()", s),
             NodeType::MethodCall(name, owner, _, _, _) => write!(f, "{}.{}()", name, owner),
             NodeType::ParamList(_, _)   => write!(f, "ParamList"),
+            NodeType::OptionalParam(_, _) => write!(f, "OptionalParam"),
+            NodeType::NamedParam(_, _, _) => write!(f, "NamedParam"),
             NodeType::ArgList(_, _)     => write!(f, "ArgList"),
+            NodeType::NamedArg(s, _, _) => write!(f, "{}:", s),
             NodeType::ThisFieldInit(s, _, _)                      => write!(f, "this.{}", s),
             NodeType::InitList(_, _)                                       => write!(f, "InitList"),
             NodeType::Initializer(_, _)                                    => write!(f, "Initializer"),
             NodeType::Return(_, _)                                         => write!(f, "Return"),
+            NodeType::TypeParams(params, _, _) => {
+                let names: Vec<String> = params.iter().map(|p| p.name.clone()).collect();
+                write!(f, "<{}>", names.join(", "))
+            }
+            NodeType::TypeArgs(args, _, _)                                 => write!(f, "<{}>", args.join(", ")),
+            NodeType::SyncStar(_, _)                                       => write!(f, "sync*"),
+            NodeType::Yield(_, _)                                          => write!(f, "yield"),
+            NodeType::YieldStar(_, _)                                      => write!(f, "yield*"),
+            NodeType::Async(t, _, _)                                       => write!(f, "async {}", t),
+            NodeType::Await(_, _)                                          => write!(f, "await"),
+            NodeType::AsyncStar(t, _, _)                                   => write!(f, "async* {}", t),
+            NodeType::AwaitFor(_, _)                                       => write!(f, "await for"),
+            NodeType::Switch(_, _)                                         => write!(f, "Switch"),
+            NodeType::SwitchExpr(_, _)                                     => write!(f, "SwitchExpr"),
+            NodeType::SwitchCase(_, _)                                     => write!(f, "SwitchCase"),
+            NodeType::CaseHead(_, _)                                       => write!(f, "CaseHead"),
+            NodeType::VarPattern(typ, name, _, _)                          => write!(f, "{} {}", typ, name),
+            NodeType::ConstPattern(_, _)                                   => write!(f, "ConstPattern"),
+            NodeType::ObjectPattern(typ, _, _)                             => write!(f, "{}()", typ),
+            NodeType::FieldPattern(name, _, _)                             => write!(f, "{}:", name),
             NodeType::Constructor(name, _, _, _, _, _, _) => write!(f, "Constructor({})", name),
             NodeType::Null(_, _)                                           => write!(f, "null"),
         }
@@ -189,6 +261,7 @@ impl Node {
             NodeType::Sub(l, i) |
             NodeType::Mul(l, i) |
             NodeType::Div(l, i) |
+            NodeType::TruncDiv(l, i) |
             NodeType::PreIncrement(l, i) |
             NodeType::PostIncrement(l, i) |
             NodeType::PreDecrement(l, i) |
@@ -204,10 +277,14 @@ impl Node {
             NodeType::LessOrEq(l, i) |
             NodeType::GreaterOrEq(l, i) |
             NodeType::Equal(l, i) |
+            NodeType::Is(_, _, l, i) |
+            NodeType::As(_, l, i) |
+            NodeType::NullCheck(l, i) |
             NodeType::Assign(l, i) |
             NodeType::Int(_, l, i) |
             NodeType::Double(_, l, i) |
             NodeType::Str(_, l, i) |
+            NodeType::StrInterpol(_, l, i) |
             NodeType::Bool(_, l, i) |
             NodeType::Name(_, l, i) |
             NodeType::TypedVar(_, _, l, i) |
@@ -223,19 +300,47 @@ impl Node {
             NodeType::DoWhile(l, i) |
             NodeType::For(l, i) |
             NodeType::Block(l, i) |
-            NodeType::List(l, i) |
+            NodeType::List(_, l, i) |
+            NodeType::Map(_, _, l, i) |
+            NodeType::Set(_, l, i) |
+            NodeType::MapPair(l, i) |
+            NodeType::Spread(_, l, i) |
+            NodeType::Late(l, i) |
+            NodeType::CollectionIf(l, i) |
+            NodeType::CollectionFor(l, i) |
             NodeType::CollAccess(l, i) |
             NodeType::This(l, i) |
             NodeType::Super(l, i) |
+            NodeType::SuperRef(_, l, i) |
             NodeType::FunDef(_, _, _, l, i) |
             NodeType::FunCall(_, l, i) |
             NodeType::MethodCall(_, _, _, l, i) |
             NodeType::ParamList(l, i) |
+            NodeType::OptionalParam(l, i) |
+            NodeType::NamedParam(_, l, i) |
             NodeType::ArgList(l, i) |
+            NodeType::NamedArg(_, l, i) |
             NodeType::ThisFieldInit(_, l, i) |
             NodeType::InitList(l, i) |
             NodeType::Initializer(l, i) |
             NodeType::Return(l, i) |
+            NodeType::TypeParams(_, l, i) |
+            NodeType::TypeArgs(_, l, i) |
+            NodeType::SyncStar(l, i) |
+            NodeType::Yield(l, i) |
+            NodeType::YieldStar(l, i) |
+            NodeType::Async(_, l, i) |
+            NodeType::Await(l, i) |
+            NodeType::AsyncStar(_, l, i) |
+            NodeType::AwaitFor(l, i) |
+            NodeType::Switch(l, i) |
+            NodeType::SwitchExpr(l, i) |
+            NodeType::SwitchCase(l, i) |
+            NodeType::CaseHead(l, i) |
+            NodeType::VarPattern(_, _, l, i) |
+            NodeType::ConstPattern(l, i) |
+            NodeType::ObjectPattern(_, l, i) |
+            NodeType::FieldPattern(_, l, i) |
             NodeType::Constructor(_, _, _, _, _, l, i) |
             NodeType::Null(l, i)
             => {
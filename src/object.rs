@@ -1,15 +1,56 @@
 use std::fmt;
-use crate::node::Node;
+use crate::node::{Node, NodeType};
 use crate::objsys::RefKey;
+use crate::types::{self, TypeParam};
 
 
 
-#[derive(Debug)]
 #[derive(Clone)]
 pub struct ParamObj {
     pub typ: String,
     pub name: String,
     pub fieldinit: bool,
+    pub optional: bool,
+    pub named: bool,
+    pub default: Option<Node>
+}
+
+
+impl ParamObj {
+
+    pub fn new(p: &Node) -> ParamObj {
+
+        match &p.nodetype {
+            NodeType::Name(s, _, _) => {
+                ParamObj { typ: String::from("var"), name: s.clone(), fieldinit: false, optional: false, named: false, default: None }
+            }
+            NodeType::TypedVar(t, s, _, _) => {
+                ParamObj { typ: t.clone(), name: s.clone(), fieldinit: false, optional: false, named: false, default: None }
+            }
+            NodeType::ThisFieldInit(s, _, _) => {
+                ParamObj { typ: String::from("var"), name: s.clone(), fieldinit: true, optional: false, named: false, default: None }
+            }
+            NodeType::OptionalParam(_, _) => {
+                let mut param = ParamObj::new(&p.children[0]);
+                param.optional = true;
+                param.default = p.children.get(1).cloned();
+                param
+            }
+            NodeType::NamedParam(required, _, _) => {
+                let mut param = ParamObj::new(&p.children[0]);
+                param.named = true;
+                param.optional = !required;
+                param.default = p.children.get(1).cloned();
+                param
+            }
+            x => panic!("Invalid parameter: {}", x)
+        }
+    }
+
+
+    pub fn from_paramlist(paramlist: &Node) -> Vec<ParamObj> {
+        paramlist.children.iter().map(ParamObj::new).collect()
+    }
 }
 
 
@@ -32,42 +73,87 @@ pub enum Object {
     Double(f64),
     Bool(bool),
     String(String),
-    // funcname, filename, body, params
-    Function(String, String, Node, Vec<ParamObj>),    // funcname, filename, body, params
+    Function(String, String, Node, Vec<ParamObj>, Vec<TypeParam>, String),    // funcname, filename, body, params, typeparams, returntype
     Constructor(String, String, Vec<ParamObj>, Node, Node), // consname, filename, params, initlist, body
+    BoundMethod(RefKey, Box<Object>),   // receiver, method, as torn off an instance
     Reference(RefKey),
     Null,
+    Late(Option<Node>),     // initializer, run on first read
     Return(Box<Object>)
 }
 
 
 
+impl Object {
+
+    /// The heap object this keeps alive, if any. A method
+    /// torn off an instance keeps the instance.
+    pub fn heap_ref(&self) -> Option<&RefKey> {
+        match self {
+            Object::Reference(rk) |
+            Object::BoundMethod(rk, _) => Some(rk),
+            _ => None
+        }
+    }
+}
+
+
 impl fmt::Display for Object {
 
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 
         match self {
             Object::Int(i) => write!(f, "{}", i),
-            Object::Double(x) => write!(f, "{}", x),
+            Object::Double(x) => write!(f, "{}", fmt_double(*x)),
             Object::Bool(b) => write!(f, "{}", b),
             Object::String(s) => write!(f, "{}", s),
-            Object::Function(_, _, _, _) => {
-                // Dart prints a function signature, like: (int) => String.
-                // But since the function will turn into a closure, it really prints
-                // Closure: (int) => String
-                // TODO
-                write!(f, "() => ?")
+            Object::Function(_, _, _, params, typeparams, rettype) => {
+                // Dart prints the signature of the closure, like: Closure: (int) => String
+                write!(f, "Closure: {}", types::display(&types::fn_type(rettype, typeparams, params)))
             },
-            Object::Constructor(_, _, _, _, _) => {
-                // TODO
-                write!(f, "() => ?")
+            Object::Constructor(consname, _, params, _, _) => {
+                write!(f, "Closure: {}", types::display(&types::fn_type(consname, &[], params)))
             },
+            Object::BoundMethod(_, method) => write!(f, "{}", method),
             Object::Reference(s) => {
                 // TODO, need lookup, dont have access.
                 write!(f, "Reference<{}>", s)
             },
             Object::Null => write!(f, "null"),
+            Object::Late(_) => write!(f, "late"),
             Object::Return(_) => panic!("Tried to display Return Object")
         }
     }
 }
+
+
+/// Format a double the way Dart's double.toString() does.
+///
+/// Integral values keep their ".0", and very large or small
+/// magnitudes use exponential notation, as in 1e+21 and 1e-7.
+pub fn fmt_double(x: f64) -> String {
+
+    if x.is_nan() {
+        return String::from("NaN");
+    }
+    if x.is_infinite() {
+        return String::from(if x > 0.0 { "Infinity" } else { "-Infinity" });
+    }
+
+    let abs = x.abs();
+
+    if abs != 0.0 && (abs >= 1e21 || abs < 1e-6) {
+        let s = format!("{:e}", x);
+        let (mantissa, exponent) = s.split_once('e').unwrap();
+        if exponent.starts_with('-') {
+            return format!("{}e{}", mantissa, exponent);
+        }
+        return format!("{}e+{}", mantissa, exponent);
+    }
+
+    let mut s = format!("{}", x);
+    if !s.contains('.') {
+        s.push_str(".0");
+    }
+    s
+}
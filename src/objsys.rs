@@ -1,20 +1,36 @@
 use std::fmt;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use crate::object::Object;
 use crate::node::Node;
 use crate::state::State;
 use crate::error::evalerror;
+use crate::types::TypeParam;
 use crate::heapobjs::{
     instance::MaybeObject,
     Instance,
     InternalFile,
-    InternalList
+    InternalGenerator,
+    InternalList,
+    InternalRegExp,
+    InternalStringBuffer
 };
 
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct RefKey(pub String);
 
+// Counts the keys given out, in all isolates. Taking
+// the next one needs no lock, so isolates don't wait
+// on each other to allocate.
+static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+
+impl RefKey {
+    pub fn next() -> RefKey {
+        RefKey(NEXT_KEY.fetch_add(1, Ordering::Relaxed).to_string())
+    }
+}
+
 impl fmt::Display for RefKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "RefKey<{}>", self.0)
@@ -23,12 +39,24 @@ impl fmt::Display for RefKey {
 
 
 
+#[derive(Clone)]
 pub struct Class {
     pub name: String,
     pub fields: Vec<(String, String, Node)>,
     pub methods: HashMap<String, Object>,
     pub getters: HashMap<String, Object>,
-    pub parent: String
+    pub parent: String,
+    pub typeparams: Vec<TypeParam>,
+    // The type arguments given to the parent, as in 'extends Base<T>'.
+    pub parent_typeargs: Vec<String>,
+    // One of sealed, base, final and interface, or empty.
+    pub modifier: String,
+    pub is_abstract: bool,
+    pub is_mixin: bool,
+    // The file the class was declared in, which is its library.
+    pub filename: String,
+    pub linenum: usize,
+    pub symnum: usize
 }
 
 
@@ -41,7 +69,15 @@ impl Class {
             fields: Vec::new(),
             methods: HashMap::new(),
             getters: HashMap::new(),
-            parent: String::new()
+            parent: String::new(),
+            typeparams: Vec::new(),
+            parent_typeargs: Vec::new(),
+            modifier: String::new(),
+            is_abstract: false,
+            is_mixin: false,
+            filename: String::new(),
+            linenum: 0,
+            symnum: 0
         }
     }
 
@@ -71,16 +107,30 @@ impl Class {
     }
 
 
+    pub fn has_method(&self, name: &str, state: &State) -> bool {
+        if self.methods.contains_key(name) {
+            return true;
+        }
+        if self.parent == "" {
+            return false;
+        }
+        state.objsys.get_class(self.parent.as_str()).has_method(name, state)
+    }
+
+
     pub fn add_getter(&mut self, name: String, g: Object) {
         self.getters.insert(name.clone(), g);
     }
 
 
-    pub fn has_getter(&self, name: &str) -> bool {
+    pub fn has_getter(&self, name: &str, state: &State) -> bool {
         if self.getters.contains_key(name) {
             return true;
         }
-        return false;
+        if self.parent == "" {
+            return false;
+        }
+        state.objsys.get_class(self.parent.as_str()).has_getter(name, state)
     }
 
 
@@ -88,7 +138,11 @@ impl Class {
         if self.getters.contains_key(name) {
             return self.getters[name].clone();
         }
-        evalerror(format!("No such getter: '{}'.", name), state, mnode)
+        if self.parent == "" {
+            evalerror(format!("No such getter: '{}'.", name), state, mnode)
+        }
+        let p = state.objsys.get_class(self.parent.as_str());
+        p.get_getter(name, state, mnode)
     }
 
 
@@ -100,11 +154,24 @@ impl Class {
 }
 
 
+/// A name for a type, as in typedef IntOp = int Function(int, int).
+#[derive(Clone)]
+pub struct Typedef {
+    pub name: String,
+    pub typeparams: Vec<TypeParam>,
+    pub typ: String
+}
+
+
 pub struct ObjSys {
     classmap: HashMap<String, Class>,
     instancemap: HashMap<RefKey, Box::<Instance>>,
     listmap: HashMap<RefKey, Box::<InternalList>>,
     filemap: HashMap<RefKey, Box::<InternalFile>>,
+    buffermap: HashMap<RefKey, Box::<InternalStringBuffer>>,
+    regexpmap: HashMap<RefKey, Box::<InternalRegExp>>,
+    generatormap: HashMap<RefKey, Box::<InternalGenerator>>,
+    typedefs: HashMap<String, Typedef>,
     this: RefKey,
 }
 
@@ -117,11 +184,25 @@ impl ObjSys {
             instancemap: HashMap::new(),
             listmap: HashMap::new(),
             filemap: HashMap::new(),
+            buffermap: HashMap::new(),
+            regexpmap: HashMap::new(),
+            generatormap: HashMap::new(),
+            typedefs: HashMap::new(),
             this: RefKey(String::from("")),
         }
     }
 
 
+    /// A copy of the classes and typedefs, with no objects,
+    /// as for the heap of a new isolate.
+    pub fn program(&self) -> ObjSys {
+        let mut objsys = ObjSys::new();
+        objsys.classmap = self.classmap.clone();
+        objsys.typedefs = self.typedefs.clone();
+        objsys
+    }
+
+
     pub fn register_class(&mut self, class: Class) {
         self.classmap.insert(class.name.clone(), class);
     }
@@ -132,6 +213,39 @@ impl ObjSys {
     }
 
 
+    pub fn get_class_mut(&mut self, name: &str) -> &mut Class {
+        self.classmap.get_mut(name).unwrap()
+    }
+
+
+    pub fn has_class(&self, name: &str) -> bool {
+        self.classmap.contains_key(name)
+    }
+
+
+    /// The classes that extend the class directly, in the order they were declared.
+    pub fn subclasses(&self, name: &str) -> Vec<&Class> {
+        let mut subs: Vec<&Class> = self.classmap.values().filter(|c| c.parent == name).collect();
+        subs.sort_by(|a, b| (&a.filename, a.linenum, a.symnum).cmp(&(&b.filename, b.linenum, b.symnum)));
+        subs
+    }
+
+
+    pub fn register_typedef(&mut self, def: Typedef) {
+        self.typedefs.insert(def.name.clone(), def);
+    }
+
+
+    pub fn get_typedef(&self, name: &str) -> &Typedef {
+        self.typedefs.get(name).unwrap()
+    }
+
+
+    pub fn has_typedef(&self, name: &str) -> bool {
+        self.typedefs.contains_key(name)
+    }
+
+
     pub fn register_instance(&mut self, instance: Instance) -> Object {
         let inst = Box::new(instance);
         let rk = inst.id.clone();
@@ -156,6 +270,30 @@ impl ObjSys {
     }
 
 
+    pub fn register_buffer(&mut self, buffer: InternalStringBuffer) -> Object {
+        let boxed = Box::new(buffer);
+        let rk = boxed.id.clone();
+        self.buffermap.insert(rk.clone(), boxed);
+        return Object::Reference(rk);
+    }
+
+
+    pub fn register_regexp(&mut self, regexp: InternalRegExp) -> Object {
+        let boxed = Box::new(regexp);
+        let rk = boxed.id.clone();
+        self.regexpmap.insert(rk.clone(), boxed);
+        return Object::Reference(rk);
+    }
+
+
+    pub fn register_generator(&mut self, generator: InternalGenerator) -> Object {
+        let boxed = Box::new(generator);
+        let rk = boxed.id.clone();
+        self.generatormap.insert(rk.clone(), boxed);
+        return Object::Reference(rk);
+    }
+
+
     pub fn has_list(&self, rk: &RefKey) -> bool {
         self.listmap.contains_key(rk)
     }
@@ -185,6 +323,30 @@ impl ObjSys {
     }
 
 
+    pub fn get_buffer(&self, id: &RefKey) -> &InternalStringBuffer {
+        if self.buffermap.contains_key(id) {
+            return &self.buffermap.get(id).unwrap();
+        }
+        panic!("InternalStringBuffer not found: {}", id);
+    }
+
+
+    pub fn get_regexp(&self, id: &RefKey) -> &InternalRegExp {
+        if self.regexpmap.contains_key(id) {
+            return &self.regexpmap.get(id).unwrap();
+        }
+        panic!("InternalRegExp not found: {}", id);
+    }
+
+
+    pub fn get_generator(&self, id: &RefKey) -> &InternalGenerator {
+        if self.generatormap.contains_key(id) {
+            return &self.generatormap.get(id).unwrap();
+        }
+        panic!("InternalGenerator not found: {}", id);
+    }
+
+
     pub fn get_instance_mut(&mut self, id: &RefKey) -> &mut Instance {
         return self.instancemap.get_mut(id).unwrap();
     }
@@ -200,11 +362,26 @@ impl ObjSys {
     }
 
 
+    pub fn get_generator_mut(&mut self, id: &RefKey) -> &mut InternalGenerator {
+        return self.generatormap.get_mut(id).unwrap();
+    }
+
+
+    pub fn get_buffer_mut(&mut self, id: &RefKey) -> &mut InternalStringBuffer {
+        return self.buffermap.get_mut(id).unwrap();
+    }
+
+
     pub fn has_instance(&self, id: &RefKey) -> bool {
         self.instancemap.contains_key(id)
     }
 
 
+    pub fn has_generator(&self, id: &RefKey) -> bool {
+        self.generatormap.contains_key(id)
+    }
+
+
     // NB, only Instances can be THIS for now.
 
     // This could just check if the this string is empty?
@@ -267,7 +444,7 @@ pub mod trashman {
             }
 
             for (_, obj) in p.fields.iter() {
-                if let Object::Reference(refid) = obj {
+                if let Some(refid) = obj.heap_ref() {
                     childs.push(refid.clone());
                 }
             }
@@ -281,7 +458,7 @@ pub mod trashman {
             p.marked = true;
 
             for obj in p.els.iter() {
-                if let Object::Reference(refid) = obj {
+                if let Some(refid) = obj.heap_ref() {
                     childs.push(refid.clone());
                 }
             }
@@ -290,6 +467,23 @@ pub mod trashman {
             let p = obs.filemap.get_mut(rk).unwrap();
             p.marked = true;
         }
+        else if obs.buffermap.contains_key(rk) {
+            let p = obs.buffermap.get_mut(rk).unwrap();
+            p.marked = true;
+        }
+        else if obs.regexpmap.contains_key(rk) {
+            let p = obs.regexpmap.get_mut(rk).unwrap();
+            p.marked = true;
+        }
+        else if obs.generatormap.contains_key(rk) {
+            let p = obs.generatormap.get_mut(rk).unwrap();
+
+            if p.marked {
+                return;
+            }
+            p.marked = true;
+            childs = p.references();
+        }
         else {
             panic!("GC could not find heap object: {}", rk)
         }
@@ -307,6 +501,9 @@ pub mod trashman {
         let mut del_instances: Vec<RefKey> = Vec::new();
         let mut del_lists: Vec<RefKey> = Vec::new();
         let mut del_files: Vec<RefKey> = Vec::new();
+        let mut del_buffers: Vec<RefKey> = Vec::new();
+        let mut del_regexps: Vec<RefKey> = Vec::new();
+        let mut del_generators: Vec<RefKey> = Vec::new();
 
         for (k, v) in obs.instancemap.iter() {
             if !v.marked {
@@ -323,6 +520,21 @@ pub mod trashman {
                 del_files.push(k.clone());
             }
         }
+        for (k, v) in &obs.buffermap {
+            if !v.marked {
+                del_buffers.push(k.clone());
+            }
+        }
+        for (k, v) in &obs.regexpmap {
+            if !v.marked {
+                del_regexps.push(k.clone());
+            }
+        }
+        for (k, v) in &obs.generatormap {
+            if !v.marked {
+                del_generators.push(k.clone());
+            }
+        }
 
         for k in del_instances {
             println!("GC instance: {}", k);
@@ -336,6 +548,18 @@ pub mod trashman {
             println!("GC file: {}", k);
             obs.filemap.remove(&k);
         }
+        for k in del_buffers {
+            println!("GC buffer: {}", k);
+            obs.buffermap.remove(&k);
+        }
+        for k in del_regexps {
+            println!("GC regexp: {}", k);
+            obs.regexpmap.remove(&k);
+        }
+        for k in del_generators {
+            println!("GC generator: {}", k);
+            obs.generatormap.remove(&k);
+        }
 
 
         // ELSE USE THIS:
@@ -355,6 +579,9 @@ pub mod trashman {
         let mut clear_instances: Vec<RefKey> = Vec::new();
         let mut clear_lists: Vec<RefKey> = Vec::new();
         let mut clear_files: Vec<RefKey> = Vec::new();
+        let mut clear_buffers: Vec<RefKey> = Vec::new();
+        let mut clear_regexps: Vec<RefKey> = Vec::new();
+        let mut clear_generators: Vec<RefKey> = Vec::new();
 
         for k in obs.instancemap.keys() {
             clear_instances.push(k.clone());
@@ -365,6 +592,15 @@ pub mod trashman {
         for k in obs.filemap.keys() {
             clear_files.push(k.clone());
         }
+        for k in obs.buffermap.keys() {
+            clear_buffers.push(k.clone());
+        }
+        for k in obs.regexpmap.keys() {
+            clear_regexps.push(k.clone());
+        }
+        for k in obs.generatormap.keys() {
+            clear_generators.push(k.clone());
+        }
 
         for k in clear_instances {
             obs.instancemap.get_mut(&k).unwrap().marked = false;
@@ -375,5 +611,14 @@ pub mod trashman {
         for k in clear_files {
             obs.filemap.get_mut(&k).unwrap().marked = false;
         }
+        for k in clear_buffers {
+            obs.buffermap.get_mut(&k).unwrap().marked = false;
+        }
+        for k in clear_regexps {
+            obs.regexpmap.get_mut(&k).unwrap().marked = false;
+        }
+        for k in clear_generators {
+            obs.generatormap.get_mut(&k).unwrap().marked = false;
+        }
     } 
 }
@@ -5,13 +5,26 @@ use crate::node::{NodeType, Node};
 use crate::expression::expression;
 use crate::error::parseerror;
 use crate::object::{ParamObj, Object};
-use crate::objsys::Class;
+use crate::objsys::{Class, Typedef};
 use crate::expression::access_help;
+use crate::types::{fn_type, make_type, split_type, TypeParam};
 
 
 fn autoincludes() -> Vec<String> {
     vec![
-        "auto:list.dart".to_string()
+        "auto:datetime.dart".to_string(),
+        "auto:duration.dart".to_string(),
+        "auto:future.dart".to_string(),
+        "auto:iterable.dart".to_string(),
+        "auto:list.dart".to_string(),
+        "auto:map.dart".to_string(),
+        "auto:object.dart".to_string(),
+        "auto:regexp.dart".to_string(),
+        "auto:set.dart".to_string(),
+        "auto:stopwatch.dart".to_string(),
+        "auto:stream.dart".to_string(),
+        "auto:stringbuffer.dart".to_string(),
+        "auto:type.dart".to_string()
     ]
 }
 
@@ -39,7 +52,16 @@ fn directives(reader: &mut Reader, ctx: &State) -> Vec<String> {
             Token::Import(_, _) => {
 
                 reader.next();
-                if let Token::Str(s, _, _, _) = reader.tok() {
+                if let Token::Str(parts, interpols, _, _) = reader.tok() {
+                    if !interpols.is_empty() {
+                        // As Dart.
+                        parseerror(
+                            "Can't use string interpolation in a URI.",
+                            ctx,
+                            reader.tok()
+                        );
+                    }
+                    let s = parts.concat();
                     reader.next();
                     reader.skip(";", ctx);
 
@@ -60,18 +82,38 @@ fn decl(reader: &mut Reader, state: &mut State) {
 
     match reader.tok() {
 
+        Token::Name(s, _, _) if s == "typedef" && matches!(reader.peek(), Token::Name(_, _, _)) => {
+            typedef(reader, state);
+        }
+
+        Token::Name(_, _, _) if has_class_modifiers(reader) => {
+            class(reader, state);
+        }
+
         // The type of a top level declaration.
-        Token::Name(typ, _, _) => {
+        Token::Name(s, _, _) => {
 
-            match reader.next() {
+            // Top level variables are lazy initialized anyway.
+            let late = s == "late" && matches!(reader.peek(), Token::Name(_, _, _));
+            let typ = if late {
+                reader.next();
+                late_type(reader, state)
+            }
+            else {
+                typename(reader, state)
+            };
+
+            match reader.tok() {
 
                 // Name of top level declaration.
                 Token::Name(name, linenum, symnum) => {
 
                     match reader.next() {
 
-                        Token::Paren1(_, _) => {
+                        Token::Paren1(_, _) |
+                        Token::LessThan(_, _) => {
                             // Top level function
+                            let rettype = typ.clone();
                             let mut node = Node::new(
                                 NodeType::FunDef(
                                     typ,
@@ -80,12 +122,16 @@ fn decl(reader: &mut Reader, state: &mut State) {
                                     linenum,
                                     symnum
                                 ));
+                            let mut tparams = None;
+                            if let Token::LessThan(_, _) = reader.tok() {
+                                tparams = Some(typeparams(reader, state));
+                            }
                             let params = paramlist(reader, state, false);
                             node.children.push(params);
 
-                            reader.skip("{", state);
-                            let body = block(reader, state);
+                            let body = function_body(&rettype, reader, state);
                             node.children.push(body);
+                            node.children.extend(tparams);
                             state.globals.push(node.clone());
                             return;
                         }
@@ -97,6 +143,7 @@ fn decl(reader: &mut Reader, state: &mut State) {
                             // interpreter will replace the TopVarLazy with a TopVar
                             // upon execution.
                             reader.next();
+                            let val = context_type(&typ, expression(reader, state));
                             let mut node = Node::new(
                                 NodeType::TopVarLazy(
                                     typ,
@@ -104,13 +151,34 @@ fn decl(reader: &mut Reader, state: &mut State) {
                                     linenum,
                                     symnum
                                 ));
-                            let val = expression(reader, state);
                             node.children.push(val);
                             state.globals.push(node);
                             reader.skip(";", state);
                             return;
                         }
 
+                        Token::EndSt(_, _) => {
+                            // Top level variable without an initializer.
+                            // Unless late, it starts out as null.
+                            reader.next();
+                            let val = if late {
+                                Node::new(NodeType::Late(linenum, symnum))
+                            }
+                            else {
+                                Node::new(NodeType::Null(linenum, symnum))
+                            };
+                            let mut node = Node::new(
+                                NodeType::TopVarLazy(
+                                    typ,
+                                    name,
+                                    linenum,
+                                    symnum
+                                ));
+                            node.children.push(val);
+                            state.globals.push(node);
+                            return;
+                        }
+
                         _ => parseerror(
                                 format!("Unexpected token: {}", reader.tok()),
                                 state,
@@ -206,16 +274,120 @@ fn decl(reader: &mut Reader, state: &mut State) {
 }
 
 
+/// A typedef, as in typedef IntOp = int Function(int, int), or
+/// in the older form, as in typedef bool Pred(int x).
+fn typedef(reader: &mut Reader, state: &mut State) {
+
+    reader.next();
+    let newform = match reader.peek() {
+        Token::Assign(_, _) => true,
+        Token::LessThan(_, _) => matches!(
+            typeargs_end(reader, reader.pos() + 1).and_then(|e| reader.tokens().get(e)),
+            Some(Token::Assign(_, _))
+        ),
+        _ => false
+    };
+
+    let ret = if newform || matches!(reader.peek(), Token::Paren1(_, _) | Token::LessThan(_, _)) {
+        String::from("dynamic")
+    }
+    else {
+        typename(reader, state)
+    };
+
+    if let Token::Name(name, _, _) = reader.tok() {
+
+        let mut tparams = Vec::new();
+        if let Token::LessThan(_, _) = reader.next() {
+            if let NodeType::TypeParams(params, _, _) = typeparams(reader, state).nodetype {
+                tparams = params;
+            }
+        }
+
+        let typ = if newform {
+            reader.skip("=", state);
+            typename(reader, state)
+        }
+        else {
+            let params = ParamObj::from_paramlist(&paramlist(reader, state, false));
+            fn_type(&ret, &[], &params)
+        };
+        reader.skip(";", state);
+        state.objsys.register_typedef(Typedef { name, typeparams: tparams, typ });
+        return;
+    }
+    parseerror(
+        "Expected typedef name",
+        state,
+        reader.tok()
+    );
+}
+
+
+/// Whether the names from the current position are modifiers of a class declaration.
+fn has_class_modifiers(reader: &Reader) -> bool {
+    let mut pos = reader.pos();
+    while let Some(Token::Name(s, _, _)) = reader.tokens().get(pos) {
+        if !matches!(s.as_str(), "abstract" | "sealed" | "base" | "interface" | "final" | "mixin") {
+            return false;
+        }
+        pos += 1;
+    }
+    matches!(reader.tokens().get(pos), Some(Token::Class(_, _)))
+}
+
+
+/// Read the modifiers before 'class', in the order Dart allows them: abstract,
+/// then one of sealed, base, interface and final, then mixin.
+fn class_modifiers(class: &mut Class, reader: &mut Reader, state: &State) {
+
+    while let Token::Name(s, _, _) = reader.tok() {
+        let allowed = !class.is_mixin && match s.as_str() {
+            "abstract" => !class.is_abstract && class.modifier == "",
+            "sealed" => !class.is_abstract && class.modifier == "",
+            "base" | "interface" | "final" => class.modifier == "",
+            _ => matches!(class.modifier.as_str(), "" | "base")
+        };
+        if !allowed {
+            parseerror(
+                format!("Can't use '{}' as a class modifier here.", s),
+                state,
+                reader.tok()
+            );
+        }
+        match s.as_str() {
+            "abstract" => class.is_abstract = true,
+            "mixin" => class.is_mixin = true,
+            _ => class.modifier = s
+        }
+        reader.next();
+    }
+}
+
+
 fn class(reader: &mut Reader, state: &mut State) {
 
-    if let Token::Name(classname, _, _) = reader.next() {
+    let mut class = Class::new(String::new());
+    class_modifiers(&mut class, reader, state);
+
+    if let Token::Name(classname, linenum, symnum) = reader.next() {
+
+        class.name = classname.clone();
+        class.filename = state.filepath.clone();
+        class.linenum = linenum;
+        class.symnum = symnum;
 
-        let mut class = Class::new(classname.clone());
+        if let Token::LessThan(_, _) = reader.next() {
+            if let NodeType::TypeParams(params, _, _) = typeparams(reader, state).nodetype {
+                class.typeparams = params;
+            }
+        }
 
-        if let Token::Extends(_, _) = reader.next() {
-            if let Token::Name(parentname, _, _) = reader.next() {
+        if let Token::Extends(_, _) = reader.tok() {
+            if let Token::Name(_, _, _) = reader.next() {
+                let (parentname, parentargs) = split_type(&typename(reader, state));
                 class.parent = parentname;
-                reader.next();
+                class.parent_typeargs = parentargs;
             }
             else {
                 parseerror(
@@ -225,8 +397,13 @@ fn class(reader: &mut Reader, state: &mut State) {
                 );
             }
         }
+        else if classname != "Object" {
+            class.parent = String::from("Object");
+        }
         reader.skip("{", state);
+        reader.in_class = classname;
         readmembers(&mut class, reader, state);
+        reader.in_class = String::new();
         reader.skip("}", state);
         state.objsys.register_class(class);
         return;
@@ -250,7 +427,7 @@ fn readmembers(class: &mut Class, reader: &mut Reader, state: &mut State) {
 
             Token::Name(mtype, linenum, symnum) => {
 
-                if *mtype == class.name {
+                if *mtype == class.name && matches!(reader.peek(), Token::Paren1(_, _)) {
                     // Constructor
 
                     reader.next();
@@ -303,56 +480,73 @@ fn readmembers(class: &mut Class, reader: &mut Reader, state: &mut State) {
                     continue;
                 }
 
-                match reader.next() {
+                let late = mtype == "late" && matches!(reader.peek(), Token::Name(_, _, _));
+                let mtype = if late {
+                    reader.next();
+                    late_type(reader, state)
+                }
+                else {
+                    typename(reader, state)
+                };
+
+                if let Token::Name(s, _, _) = reader.tok() {
+                    if s == "operator" {
+                        operator_method(mtype, class, reader, state);
+                        continue;
+                    }
+                }
+
+                match reader.tok() {
 
                     Token::Name(fieldname, linenum, symnum) => {
 
                         match reader.next() {
 
-                            Token::Paren1(_, _) => {
+                            Token::Paren1(_, _) |
+                            Token::LessThan(_, _) => {
                                 // Method
 
-                                // FIXME, why can't param_node be used directly?
-                                // Why do we need ParamObj which is not event a Node?
-                                let param_node = paramlist(reader, state, false);
-
-                                reader.skip("{", state);
-
-                                let body = block(reader, state);
-
-                                let mut args: Vec<ParamObj> = Vec::new();
-
-                                for i in 0..param_node.children.len() {
-                                    let p = &param_node.children[i];
-                                    match &p.nodetype {
-                                        NodeType::Name(s, _, _) => {
-                                            args.push(ParamObj{typ: String::from("var"), name: s.clone(), fieldinit: false});
-                                        }
-                                        NodeType::TypedVar(t, s, _, _) => {
-                                            args.push(ParamObj{typ: t.to_string(), name: s.clone(), fieldinit: false});
-                                        }
-                                        x => panic!("Invalid parameter: {}", x)
+                                let mut tparams = Vec::new();
+                                if let Token::LessThan(_, _) = reader.tok() {
+                                    if let NodeType::TypeParams(params, _, _) = typeparams(reader, state).nodetype {
+                                        tparams = params;
                                     }
                                 }
+                                let param_node = paramlist(reader, state, false);
+
+                                let body = function_body(&mtype, reader, state);
+                                let args = ParamObj::from_paramlist(&param_node);
 
-                                let methodobj = Object::Function(fieldname.to_string(), state.filepath.clone(), body, args);
+                                let methodobj = Object::Function(fieldname.to_string(), state.filepath.clone(), body, args, tparams, mtype);
                                 class.add_method(fieldname.clone(), methodobj);
                             }
 
                             Token::EndSt(_, _) => {
                                 // Uninitialized field declare
                                 reader.next();
-                                class.add_field(mtype, fieldname, Node::new(NodeType::Null(linenum, symnum)));
+                                let val = if late {
+                                    Node::new(NodeType::Late(linenum, symnum))
+                                }
+                                else {
+                                    Node::new(NodeType::Null(linenum, symnum))
+                                };
+                                class.add_field(mtype, fieldname, val);
                             }
 
                             Token::Assign(_, _) => {
                                 // Initialized field declare
                                 reader.next();
 
-                                let val = expression(reader, state);
+                                let mut val = context_type(&mtype, expression(reader, state));
 
                                 reader.skip(";", state);
 
+                                if late {
+                                    // Run on first read, with the instance as this.
+                                    let mut latenode = Node::new(NodeType::Late(linenum, symnum));
+                                    latenode.children.push(val);
+                                    val = latenode;
+                                }
                                 class.add_field(mtype, fieldname, val);
                             }
 
@@ -370,11 +564,9 @@ fn readmembers(class: &mut Class, reader: &mut Reader, state: &mut State) {
 
                             Token::Name(gettername, _, _) => {
                                 reader.next();
-                                reader.skip("{", state);
-
-                                let body = block(reader, state);
+                                let body = function_body(&mtype, reader, state);
 
-                                let getterfunc = Object::Function(gettername.to_string(), state.filepath.clone(), body, Vec::new());
+                                let getterfunc = Object::Function(gettername.to_string(), state.filepath.clone(), body, Vec::new(), Vec::new(), mtype);
                                 class.add_getter(gettername.to_string(), getterfunc);
                             }
 
@@ -426,12 +618,76 @@ fn readmembers(class: &mut Class, reader: &mut Reader, state: &mut State) {
 }
 
 
+/// A user defined operator, as in bool operator ==(Object other).
+/// It is a method named by the operator. Unary minus is named unary-.
+/// The index operators are named [] and []=.
+fn operator_method(rettype: String, class: &mut Class, reader: &mut Reader, state: &mut State) {
+
+    let mut op = match reader.next() {
+        Token::Equal(_, _) |
+        Token::Add(_, _) |
+        Token::Sub(_, _) |
+        Token::Mul(_, _) |
+        Token::Div(_, _) |
+        Token::TruncDiv(_, _) |
+        Token::LessThan(_, _) |
+        Token::GreaterThan(_, _) |
+        Token::LessOrEq(_, _) |
+        Token::GreaterOrEq(_, _) => reader.tok().to_string(),
+        Token::Brack1(_, _) if matches!(reader.peek(), Token::Brack2(_, _)) => {
+            reader.next();
+            if let Token::Assign(_, _) = reader.peek() {
+                reader.next();
+                String::from("[]=")
+            }
+            else {
+                String::from("[]")
+            }
+        }
+        x => parseerror(
+            format!("Operator '{}' can't be declared by user classes.", x),
+            state,
+            x
+        )
+    };
+    reader.next();
+    let param_node = paramlist(reader, state, false);
+    let params = ParamObj::from_paramlist(&param_node);
+    if op == "-" && params.is_empty() {
+        op = String::from("unary-");
+    }
+    else if op == "[]=" {
+        if params.len() != 2 || params.iter().any(|p| p.optional) {
+            // As dart.
+            parseerror(
+                "Operator '[]=' should have exactly two parameters.",
+                state,
+                reader.tok()
+            );
+        }
+    }
+    else if params.len() != 1 || params[0].optional {
+        // As dart.
+        parseerror(
+            format!("Operator '{}' should have exactly one parameter.", op),
+            state,
+            reader.tok()
+        );
+    }
+    let body = function_body(&rettype, reader, state);
+    let methodobj = Object::Function(op.clone(), state.filepath.clone(), body, params, Vec::new(), rettype);
+    class.add_method(op, methodobj);
+}
+
+
 fn paramlist(reader: &mut Reader, state: &State, is_constructor: bool) -> Node {
 
     if let Token::Paren1(linenum, symnum) = reader.tok() {
 
         let mut node = Node::new(NodeType::ParamList(linenum, symnum));
         let mut expect_comma = false;
+        let mut optional = false;
+        let mut named = false;
         reader.next();
 
         while reader.more() {
@@ -439,43 +695,52 @@ fn paramlist(reader: &mut Reader, state: &State, is_constructor: bool) -> Node {
             match reader.tok() {
 
                 Token::Paren2(_, _) => {
+                    if optional {
+                        // As dart.
+                        parseerror("Expected ']' before this.", state, reader.tok());
+                    }
+                    if named {
+                        // As dart.
+                        parseerror("Expected '}' before this.", state, reader.tok());
+                    }
                     reader.next();
                     return node;
                 }
 
-                Token::This(_, _) => {
-
-                    if !is_constructor {
-                        // As dart.
-                        parseerror(
-                            "Initializing formal parameters can only be used in constructors",
-                            state,
-                            reader.tok()
-                        );
+                Token::Brack1(_, _) => {
+                    if optional || named || expect_comma {
+                        parseerror("Unexpected token '['.", state, reader.tok());
                     }
-
+                    optional = true;
                     reader.next();
-                    reader.skip(".", state);
+                }
 
-                    match reader.tok() {
+                Token::Brack2(_, _) => {
+                    if !optional {
+                        parseerror("Unexpected token ']'.", state, reader.tok());
+                    }
+                    optional = false;
+                    reader.next();
+                    // Optional parameters come last.
+                    reader.expect(")", state);
+                }
 
-                        Token::Name(s, linenum, symnum) => {
-                            let this_fieldinit = Node::new(
-                                NodeType::ThisFieldInit(s, linenum, symnum)
-                            );
-                            node.children.push(this_fieldinit);
-                            expect_comma = true;
-                            reader.next();
-                        }
+                Token::Block1(_, _) => {
+                    if optional || named || expect_comma {
+                        parseerror("Unexpected token '{'.", state, reader.tok());
+                    }
+                    named = true;
+                    reader.next();
+                }
 
-                        x => {
-                            parseerror(
-                                format!("Expected identifier. Got {}", x),
-                                state,
-                                reader.tok()
-                            );
-                        }
+                Token::Block2(_, _) => {
+                    if !named {
+                        parseerror("Unexpected token '}'.", state, reader.tok());
                     }
+                    named = false;
+                    reader.next();
+                    // Named parameters come last.
+                    reader.expect(")", state);
                 }
 
                 Token::Comma(_, _) => {
@@ -491,34 +756,48 @@ fn paramlist(reader: &mut Reader, state: &State, is_constructor: bool) -> Node {
                     expect_comma = false;
                 }
 
-                Token::Name(s, linenum, symnum) => {
+                Token::This(linenum, symnum) |
+                Token::Name(_, linenum, symnum) => {
 
-                    if let Token::Name(s2, linenum, symnum) = reader.peek() {
+                    if named {
+                        let mut required = false;
+                        if let Token::Name(s, _, _) = reader.tok() {
+                            if s == "required" {
+                                if let Token::Name(_, _, _) = reader.peek() {
+                                    required = true;
+                                    reader.next();
+                                }
+                            }
+                        }
 
-                        reader.next();
+                        let mut namednode = Node::new(NodeType::NamedParam(required, linenum, symnum));
+                        namednode.children.push(param(reader, state, is_constructor));
 
-                        let param= Node::new(
-                            NodeType::TypedVar(
-                                s.to_string(),
-                                s2.to_string(),
-                                linenum,
-                                symnum
-                        ));
-                        node.children.push(param);
+                        if let Token::Assign(_, _) = reader.tok() {
+                            reader.next();
+                            namednode.children.push(expression(reader, state));
+                        }
+                        node.children.push(namednode);
                         expect_comma = true;
-                        reader.next();
+                        continue;
+                    }
+
+                    let param = param(reader, state, is_constructor);
+
+                    if optional {
+                        let mut optnode = Node::new(NodeType::OptionalParam(linenum, symnum));
+                        optnode.children.push(param);
+
+                        if let Token::Assign(_, _) = reader.tok() {
+                            reader.next();
+                            optnode.children.push(expression(reader, state));
+                        }
+                        node.children.push(optnode);
                     }
                     else {
-                        let paramnode= Node::new(
-                            NodeType::Name(
-                                s.to_string(),
-                                linenum,
-                                symnum
-                        ));
-                        node.children.push(paramnode);
-                        expect_comma = true;
-                        reader.next();
+                        node.children.push(param);
                     }
+                    expect_comma = true;
                 }
 
                 _ => {
@@ -538,77 +817,473 @@ fn paramlist(reader: &mut Reader, state: &State, is_constructor: bool) -> Node {
 }
 
 
-pub fn arglist(reader: &mut Reader, state: &State) -> Node {
+fn param(reader: &mut Reader, state: &State, is_constructor: bool) -> Node {
 
-    if let Token::Paren1(linenum, symnum) = reader.tok() {
+    match reader.tok() {
 
-        let mut node = Node::new(
-            NodeType::ArgList(linenum, symnum)
-        );
-        let mut expect_comma = false;
-        reader.next();
+        Token::This(_, _) => {
 
-        while reader.more() {
+            if !is_constructor {
+                // As dart.
+                parseerror(
+                    "Initializing formal parameters can only be used in constructors",
+                    state,
+                    reader.tok()
+                );
+            }
+
+            reader.next();
+            reader.skip(".", state);
 
             match reader.tok() {
 
-                Token::Paren2(_, _) => {
+                Token::Name(s, linenum, symnum) => {
                     reader.next();
-                    return node;
+                    Node::new(NodeType::ThisFieldInit(s, linenum, symnum))
                 }
 
-                Token::Comma(_, _) => {
-                    if !expect_comma {
-                        panic!("Error: Unexpected separator in arg list: ','.");
-                    }
+                x => {
+                    parseerror(
+                        format!("Expected identifier. Got {}", x),
+                        state,
+                        reader.tok()
+                    );
+                }
+            }
+        }
+
+        Token::Name(s, linenum, symnum) => {
+
+            if let Token::Paren1(_, _) = reader.peek() {
+                if s != "Function" {
+                    // A function-typed parameter without a return type, as in f(int x).
                     reader.next();
-                    expect_comma = false;
+                    return function_param(String::from("dynamic"), s, linenum, symnum, reader, state);
                 }
+            }
 
-                x => {
-                    if expect_comma {
-                        panic!("Error: Expected separator in arg list. Got: {}", x);
-                    }
-                    let arg = expression(reader, state);
-                    node.children.push(arg);
-                    expect_comma = true;
+            if is_function_type(reader, reader.pos() + 1) || matches!(
+                reader.peek(),
+                Token::LessThan(_, _) | Token::Question(_, _) | Token::Paren1(_, _)
+            ) {
+                let typ = typename(reader, state);
+
+                if let Token::Name(s2, linenum, symnum) = reader.tok() {
+                    reader.next();
+                    return function_param(typ, s2, linenum, symnum, reader, state);
                 }
+                parseerror(
+                    format!("Expected parameter name. Got: {}", reader.tok()),
+                    state,
+                    reader.tok()
+                )
+            }
+
+            if let Token::Name(s2, linenum, symnum) = reader.peek() {
+                reader.next();
+                reader.next();
+                return function_param(s, s2, linenum, symnum, reader, state);
             }
+            reader.next();
+            Node::new(NodeType::Name(s.to_string(), linenum, symnum))
         }
+
+        x => panic!("Unexpected token when reading parameters: {}", x)
     }
-    else {
-        panic!("Error: Expected start of arglist: '('. Found: {}", reader.tok())
-    }
-    panic!("Error when reading arg list.")
 }
 
 
-fn initializer_list(reader: &mut Reader, state: &State) -> Node {
+/// A parameter of the type and name. Followed by a parameter list, it
+/// is a function-typed parameter, as in void f(int x).
+fn function_param(typ: String, name: String, linenum: usize, symnum: usize, reader: &mut Reader, state: &State) -> Node {
+    let mut typ = typ;
+    if let Token::Paren1(_, _) = reader.tok() {
+        let params = ParamObj::from_paramlist(&paramlist(reader, state, false));
+        typ = fn_type(&typ, &[], &params);
 
-    let mut initlist = Node::new(NodeType::InitList(reader.linenum(), reader.symnum()));
-    let mut got_super = false;
-    let mut expect_comma = false;
-    let start = reader.tok().clone();
+        // A nullable function-typed parameter, as in [int compare(E a, E b)?].
+        if let Token::Question(_, _) = reader.tok() {
+            reader.next();
+            typ.push('?');
+        }
+    }
+    Node::new(NodeType::TypedVar(typ, name, linenum, symnum))
+}
 
-    reader.next();
 
-    while reader.more() {
+/// Read a type, like int, String?, Map<String, List<int>> or int Function(int).
+pub fn typename(reader: &mut Reader, state: &State) -> String {
 
-        match reader.tok() {
+    match reader.tok() {
 
-            Token::Name(name, _, _) => {
+        Token::Name(name, _, _) => {
+            reader.next();
 
-                if got_super {
-                    parseerror(
-                        // As dart
-                        "The superconstructor call must be last in the initializer list",
-                        state,
-                        reader.tok()
-                    );
-                }
-                if expect_comma {
-                    parseerror("Expected separator, got: '{}'", state, reader.tok());
-                }
+            let mut typ = name;
+            if typ == "Function" && matches!(reader.tok(), Token::Paren1(_, _)) {
+                // Function(int) has a dynamic return type.
+                typ = function_type("dynamic", reader, state);
+            }
+            if let Token::LessThan(_, _) = reader.tok() {
+                let args = typeargs(reader, state);
+                typ = make_type(&typ, &args);
+            }
+            if let Token::Question(_, _) = reader.tok() {
+                reader.next();
+                typ.push('?');
+            }
+            while is_function_type(reader, reader.pos()) {
+                reader.next();
+                typ = function_type(&typ, reader, state);
+                if let Token::Question(_, _) = reader.tok() {
+                    reader.next();
+                    typ.push('?');
+                }
+            }
+            typ
+        }
+
+        x => parseerror(
+            format!("Expected a type. Got: {}", x),
+            state,
+            reader.tok()
+        )
+    }
+}
+
+
+/// Whether the token at pos starts the Function part of a function type.
+fn is_function_type(reader: &Reader, pos: usize) -> bool {
+    let tokens = reader.tokens();
+    matches!(tokens.get(pos), Some(Token::Name(s, _, _)) if s == "Function")
+        && matches!(tokens.get(pos + 1), Some(Token::Paren1(_, _)) | Some(Token::LessThan(_, _)))
+}
+
+
+/// Read the rest of a function type after 'Function', like <T>(T, [int b]).
+/// Parameter names are optional, except for named parameters.
+fn function_type(ret: &str, reader: &mut Reader, state: &State) -> String {
+
+    let mut tparams = Vec::new();
+    if let Token::LessThan(_, _) = reader.tok() {
+        if let NodeType::TypeParams(params, _, _) = typeparams(reader, state).nodetype {
+            tparams = params;
+        }
+    }
+    reader.skip("(", state);
+
+    let mut params = Vec::new();
+    let mut optional = false;
+    let mut named = false;
+
+    loop {
+        match reader.tok() {
+
+            Token::Paren2(_, _) => {
+                reader.next();
+                return fn_type(ret, &tparams, &params);
+            }
+
+            Token::Brack1(_, _) => {
+                optional = true;
+                reader.next();
+            }
+
+            Token::Block1(_, _) => {
+                named = true;
+                reader.next();
+            }
+
+            Token::Brack2(_, _) |
+            Token::Block2(_, _) |
+            Token::Comma(_, _) => {
+                reader.next();
+            }
+
+            Token::Name(s, _, _) => {
+                let required = named && s == "required" && matches!(reader.peek(), Token::Name(_, _, _));
+                if required {
+                    reader.next();
+                }
+                let typ = typename(reader, state);
+                let mut name = String::new();
+                if let Token::Name(n, _, _) = reader.tok() {
+                    name = n;
+                    reader.next();
+                }
+                params.push(ParamObj {
+                    typ,
+                    name,
+                    fieldinit: false,
+                    optional: optional || (named && !required),
+                    named,
+                    default: None
+                });
+            }
+
+            x => parseerror(
+                format!("Expected a parameter type. Got: {}", x),
+                state,
+                reader.tok()
+            )
+        }
+    }
+}
+
+
+/// Read type arguments, like <String, int>.
+pub fn typeargs(reader: &mut Reader, state: &State) -> Vec<String> {
+
+    reader.skip("<", state);
+    let mut args = vec![typename(reader, state)];
+
+    while let Token::Comma(_, _) = reader.tok() {
+        reader.next();
+        args.push(typename(reader, state));
+    }
+    reader.skip(">", state);
+    args
+}
+
+
+/// Read the type parameters of a class or function, like <K, V extends num>.
+fn typeparams(reader: &mut Reader, state: &State) -> Node {
+
+    let (linenum, symnum) = reader.tok().find_token_position();
+    let mut params = Vec::new();
+    reader.skip("<", state);
+
+    loop {
+        match reader.tok() {
+
+            Token::Name(name, _, _) => {
+                reader.next();
+                let mut bound = String::from("dynamic");
+
+                if let Token::Extends(_, _) = reader.tok() {
+                    reader.next();
+                    bound = typename(reader, state);
+                }
+                params.push(TypeParam { name, bound });
+            }
+
+            x => parseerror(
+                format!("Expected a type variable. Got: {}", x),
+                state,
+                reader.tok()
+            )
+        }
+
+        match reader.tok() {
+            Token::Comma(_, _) => {
+                reader.next();
+            }
+            _ => break
+        }
+    }
+    reader.skip(">", state);
+    Node::new(NodeType::TypeParams(params, linenum, symnum))
+}
+
+
+/// If the tokens from pos could be type arguments, give the position
+/// after the closing '>'. This is what tells Foo<int> from a < b.
+pub fn typeargs_end(reader: &Reader, pos: usize) -> Option<usize> {
+
+    let tokens = reader.tokens();
+    let mut depth = 0;
+    let mut i = pos;
+
+    loop {
+        match tokens.get(i) {
+            Some(Token::LessThan(_, _)) |
+            Some(Token::Comma(_, _)) => {
+                if let Token::LessThan(_, _) = tokens[i] {
+                    depth += 1;
+                }
+                else if depth == 0 {
+                    return None;
+                }
+                if !matches!(tokens.get(i + 1), Some(Token::Name(_, _, _))) {
+                    return None;
+                }
+            }
+            Some(Token::GreaterThan(_, _)) => {
+                if depth == 0 {
+                    return None;
+                }
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            Some(Token::Name(s, _, _)) => {
+                if s == "Function" && matches!(tokens.get(i + 1), Some(Token::Paren1(_, _))) {
+                    // The parameters of a function type, as in List<int Function(int)>.
+                    i = paren_end(tokens, i + 1)?;
+                }
+                if !is_function_type(reader, i + 1) && !matches!(
+                    tokens.get(i + 1),
+                    Some(Token::LessThan(_, _)) | Some(Token::GreaterThan(_, _)) | Some(Token::Comma(_, _)) | Some(Token::Question(_, _))
+                ) {
+                    return None;
+                }
+            }
+            Some(Token::Question(_, _)) => {
+                if !matches!(tokens.get(i + 1), Some(Token::GreaterThan(_, _)) | Some(Token::Comma(_, _))) {
+                    return None;
+                }
+            }
+            _ => return None
+        }
+        i += 1;
+    }
+}
+
+
+/// The position of the ')' closing the '(' at pos.
+fn paren_end(tokens: &[Token], pos: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, t) in tokens.iter().enumerate().skip(pos) {
+        match t {
+            Token::Paren1(_, _) => depth += 1,
+            Token::Paren2(_, _) => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            Token::EndSt(_, _) | Token::End(_, _) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+
+/// Give a list, map or set literal without type arguments the element types
+/// of the declaration it initializes, as in List<int> xs = []. A literal
+/// in braces with only spreads, as {} is, is a set where a set is declared.
+fn context_type(typ: &str, mut valnode: Node) -> Node {
+
+    if let NodeType::Map(keytype, _, linenum, symnum) = &valnode.nodetype {
+        let (name, args) = split_type(typ);
+        let spreads = valnode.children.iter().all(|c| matches!(c.nodetype, NodeType::Spread(_, _, _)));
+        if keytype.is_empty() && name == "Set" && args.len() == 1 && spreads {
+            valnode.nodetype = NodeType::Set(String::new(), *linenum, *symnum);
+        }
+    }
+    if let NodeType::Set(elemtype, _, _) = &mut valnode.nodetype {
+        let (name, args) = split_type(typ);
+        if elemtype.is_empty() && name == "Set" && args.len() == 1 {
+            *elemtype = args[0].clone();
+        }
+    }
+
+    if let NodeType::List(elemtype, _, _) = &mut valnode.nodetype {
+        let (name, args) = split_type(typ);
+        if elemtype.is_empty() && name == "List" && args.len() == 1 {
+            *elemtype = args[0].clone();
+        }
+    }
+    if let NodeType::Map(keytype, valtype, _, _) = &mut valnode.nodetype {
+        let (name, args) = split_type(typ);
+        if keytype.is_empty() && name == "Map" && args.len() == 2 {
+            *keytype = args[0].clone();
+            *valtype = args[1].clone();
+        }
+    }
+    valnode
+}
+
+
+pub fn arglist(reader: &mut Reader, state: &State) -> Node {
+
+    if let Token::Paren1(linenum, symnum) = reader.tok() {
+
+        let mut node = Node::new(
+            NodeType::ArgList(linenum, symnum)
+        );
+        let mut expect_comma = false;
+        reader.next();
+
+        while reader.more() {
+
+            match reader.tok() {
+
+                Token::Paren2(_, _) => {
+                    reader.next();
+                    return node;
+                }
+
+                Token::Comma(_, _) => {
+                    if !expect_comma {
+                        panic!("Error: Unexpected separator in arg list: ','.");
+                    }
+                    reader.next();
+                    expect_comma = false;
+                }
+
+                x => {
+                    if expect_comma {
+                        panic!("Error: Expected separator in arg list. Got: {}", x);
+                    }
+
+                    if let Token::Name(name, linenum, symnum) = &x {
+                        if let Token::Colon(_, _) = reader.peek() {
+                            // Named argument.
+                            reader.next();
+                            reader.next();
+                            let mut named = Node::new(
+                                NodeType::NamedArg(name.clone(), *linenum, *symnum)
+                            );
+                            named.children.push(expression(reader, state));
+                            node.children.push(named);
+                            expect_comma = true;
+                            continue;
+                        }
+                    }
+
+                    let arg = expression(reader, state);
+                    node.children.push(arg);
+                    expect_comma = true;
+                }
+            }
+        }
+    }
+    else {
+        panic!("Error: Expected start of arglist: '('. Found: {}", reader.tok())
+    }
+    panic!("Error when reading arg list.")
+}
+
+
+fn initializer_list(reader: &mut Reader, state: &State) -> Node {
+
+    let mut initlist = Node::new(NodeType::InitList(reader.linenum(), reader.symnum()));
+    let mut got_super = false;
+    let mut expect_comma = false;
+    let start = reader.tok().clone();
+
+    reader.next();
+
+    while reader.more() {
+
+        match reader.tok() {
+
+            Token::Name(name, _, _) => {
+
+                if got_super {
+                    parseerror(
+                        // As dart
+                        "The superconstructor call must be last in the initializer list",
+                        state,
+                        reader.tok()
+                    );
+                }
+                if expect_comma {
+                    parseerror("Expected separator, got: '{}'", state, reader.tok());
+                }
 
                 let name_node = Node::new(NodeType::Name(name, reader.linenum(), reader.pos()));
                 reader.next();
@@ -642,6 +1317,7 @@ fn initializer_list(reader: &mut Reader, state: &State) -> Node {
                 reader.next();
                 super_node.children.push(arglist(reader, state));
                 initlist.children.push(super_node);
+                expect_comma = true;
             }
 
             Token::Comma(_, _) => {
@@ -657,6 +1333,11 @@ fn initializer_list(reader: &mut Reader, state: &State) -> Node {
                 break;
             }
 
+            // The constructor body.
+            Token::Block1(_, _) if expect_comma => {
+                break;
+            }
+
             x => {
                 parseerror(format!("Unexpected token: {}", x), state, x)
             }
@@ -728,11 +1409,204 @@ fn block(reader: &mut Reader, state: &State) -> Node {
 }
 
 
+/// A function body, which may be a sync* or async* generator, or async.
+fn function_body(rettype: &str, reader: &mut Reader, state: &State) -> Node {
+
+    let outer = reader.in_generator;
+    let outer_async = reader.in_async;
+    let mut generator = None;
+
+    if let Token::Name(s, linenum, symnum) = reader.tok() {
+        if s == "sync" && matches!(reader.peek(), Token::Mul(_, _)) {
+            reader.next();
+            reader.next();
+            generator = Some(Node::new(NodeType::SyncStar(linenum, symnum)));
+        }
+        else if s == "async" && matches!(reader.peek(), Token::Block1(_, _)) {
+            reader.next();
+            generator = Some(Node::new(NodeType::Async(future_value_type(rettype), linenum, symnum)));
+        }
+        else if s == "async" && matches!(reader.peek(), Token::Mul(_, _)) {
+            reader.next();
+            reader.next();
+            generator = Some(Node::new(NodeType::AsyncStar(stream_event_type(rettype), linenum, symnum)));
+        }
+    }
+
+    // An async* body both yields and awaits.
+    reader.in_generator = matches!(generator, Some(Node { nodetype: NodeType::SyncStar(_, _) | NodeType::AsyncStar(_, _, _), .. }));
+    reader.in_async = matches!(generator, Some(Node { nodetype: NodeType::Async(_, _, _) | NodeType::AsyncStar(_, _, _), .. }));
+    reader.skip("{", state);
+    let body = block(reader, state);
+    reader.in_generator = outer;
+    reader.in_async = outer_async;
+
+    match generator {
+        Some(mut node) => {
+            node.children.push(body);
+            node
+        }
+        None => body
+    }
+}
+
+
+/// The type of the value an async function completes its future
+/// with, as int for Future<int>. Other return types are reported
+/// by the type checker.
+fn future_value_type(rettype: &str) -> String {
+    let (name, args) = split_type(rettype.trim_end_matches('?'));
+    match (name.as_str(), args.first()) {
+        ("Future", Some(t)) => t.clone(),
+        ("void", _) => name,
+        _ => String::from("dynamic")
+    }
+}
+
+
+/// The type of the events of the stream of an async* function,
+/// as int for Stream<int>.
+fn stream_event_type(rettype: &str) -> String {
+    let (name, args) = split_type(rettype.trim_end_matches('?'));
+    match (name.as_str(), args.first()) {
+        ("Stream", Some(t)) => t.clone(),
+        _ => String::from("dynamic")
+    }
+}
+
+
+/// A typed variable or nested function declaration, from the name.
+fn declaration(typ: String, reader: &mut Reader, state: &State) -> Node {
+
+    match reader.tok() {
+
+        Token::Name(name, linenum, symnum) => {
+
+            let typed_var = Node::new(
+                NodeType::TypedVar(
+                    typ.clone(),
+                    name.clone(),
+                    linenum,
+                    symnum
+            ));
+            reader.next();
+
+            match reader.tok() {
+
+                Token::Assign(_, _) => {
+                    assign_help(typed_var, reader, state)
+                }
+
+                Token::EndSt(_, _) => {
+                    // Declared without an initializer, as in int? x;
+                    typed_var
+                }
+
+                Token::Paren1(_, _) |
+                Token::LessThan(_, _) => {
+                    // Nested function declaration.
+
+                    let mut tparams = None;
+                    if let Token::LessThan(_, _) = reader.tok() {
+                        tparams = Some(typeparams(reader, state));
+                    }
+                    let params = paramlist(reader, state, false);
+                    let body = function_body(&typ, reader, state);
+
+                    let mut funcnode = Node::new(
+                        NodeType::FunDef(
+                            typ,
+                            name,
+                            state.filepath.clone(),
+                            linenum,
+                            symnum
+                    ));
+                    funcnode.children.push(params);
+                    funcnode.children.push(body);
+                    funcnode.children.extend(tparams);
+                    funcnode
+                }
+
+                x => {
+                    panic!("Unexpected token: {}", x)
+                }
+            }
+        }
+
+        x => parseerror(
+            format!("Expected a name. Got: {}", x),
+            state,
+            reader.tok()
+        )
+    }
+}
+
+
+/// A local declaration after 'late', as in late int x = f().
+/// The initializer, if any, runs when the variable is first read.
+fn late_declaration(reader: &mut Reader, state: &State) -> Node {
+
+    let typ = late_type(reader, state);
+
+    match reader.tok() {
+
+        Token::Name(name, linenum, symnum) => {
+
+            let typed_var = Node::new(NodeType::TypedVar(typ.clone(), name, linenum, symnum));
+            let (late_linenum, late_symnum) = reader.next().find_token_position();
+            let mut late = Node::new(NodeType::Late(late_linenum, late_symnum));
+
+            if let Token::Assign(_, _) = reader.tok() {
+                reader.next();
+                late.children.push(context_type(&typ, expression(reader, state)));
+            }
+            let mut ass_node = Node::new(NodeType::Assign(late_linenum, late_symnum));
+            ass_node.children.push(typed_var);
+            ass_node.children.push(late);
+            ass_node
+        }
+
+        x => parseerror(
+            format!("Expected a name. Got: {}", x),
+            state,
+            reader.tok()
+        )
+    }
+}
+
+
+/// The type after 'late', which may be left out, as in late final x.
+fn late_type(reader: &mut Reader, state: &State) -> String {
+
+    if let Token::Name(s, _, _) = reader.tok() {
+        if s == "final" && matches!(reader.peek(), Token::Name(_, _, _)) {
+            reader.next();
+            if !matches!(reader.peek(), Token::Name(_, _, _) | Token::LessThan(_, _) | Token::Question(_, _)) {
+                return s;
+            }
+        }
+    }
+    typename(reader, state)
+}
+
+
+/// The position after a '?' at pos, as after the type arguments in List<int>? xs.
+fn skip_question(reader: &Reader, pos: usize) -> usize {
+    match reader.tokens().get(pos) {
+        Some(Token::Question(_, _)) => pos + 1,
+        _ => pos
+    }
+}
+
+
 fn assign_help(left_node: Node, reader: &mut Reader, state: &State) -> Node {
     let linenum = reader.linenum();
     let symnum = reader.symnum();
     reader.next();
-    let right_node = expression(reader, state);
+    let mut right_node = expression(reader, state);
+    if let NodeType::TypedVar(typ, _, _, _) = &left_node.nodetype {
+        right_node = context_type(typ, right_node);
+    }
     let mut ass_node = Node::new(NodeType::Assign(linenum, symnum));
     ass_node.children.push(left_node);
     ass_node.children.push(right_node);
@@ -744,53 +1618,75 @@ fn statement(reader: &mut Reader, state: &State) -> Node {
 
     match reader.tok() {
 
-        Token::Name(s, name_linenum1, name_symnum1) => {
+        Token::Name(s, linenum, symnum) if reader.in_generator && s == "yield" => {
 
-            match reader.peek() {
+            let mut node = Node::new(NodeType::Yield(linenum, symnum));
+            if let Token::Mul(_, _) = reader.next() {
+                node = Node::new(NodeType::YieldStar(linenum, symnum));
+                reader.next();
+            }
+            node.children.push(expression(reader, state));
+            return node;
+        }
 
-                Token::Name(name, name_linenum2, name_symnum2) => {
-                    // Two names in a row here indicates a typed variable or nested function declaration.
+        Token::Name(s, linenum, symnum) if s == "await" && matches!(reader.peek(), Token::For(_, _)) => {
+            if !reader.in_async {
+                // As dart.
+                parseerror(
+                    "The asynchronous for-in can only be used in functions marked with 'async' or 'async*'.",
+                    state,
+                    reader.tok()
+                );
+            }
+            reader.next();
+            let forloop = statement(reader, state);
+            if forloop.children.len() != 3 {
+                // As dart.
+                parseerror("The keyword 'await' isn't allowed for a normal 'for' statement.", state, Token::Name(s, linenum, symnum));
+            }
+            let mut node = Node::new(NodeType::AwaitFor(linenum, symnum));
+            node.children = forloop.children;
+            node
+        }
 
-                    let typed_var = Node::new(
-                        NodeType::TypedVar(
-                            s.to_string(),
-                            name.to_string(),
-                            name_linenum2,
-                            name_symnum2
-                    ));
-                    reader.next();
-                    reader.next();
+        Token::Name(s, _, _) if reader.in_async && s == "await" => {
+            expression(reader, state)
+        }
 
-                    match reader.tok() {
+        Token::Name(s, _, _) if s == "late" && matches!(reader.peek(), Token::Name(_, _, _)) => {
+            reader.next();
+            late_declaration(reader, state)
+        }
 
-                        Token::Assign(_, _) => {
-                            assign_help(typed_var, reader, state)
-                        }
+        Token::Name(_, _, _) if matches!(reader.peek(), Token::LessThan(_, _)) && matches!(
+            typeargs_end(reader, reader.pos() + 1).map(|e| skip_question(reader, e)).and_then(|e| reader.tokens().get(e)),
+            Some(Token::Name(_, _, _))
+        ) => {
+            // A declaration with type arguments, like List<int> xs = [].
+            let typ = typename(reader, state);
+            declaration(typ, reader, state)
+        }
 
-                        Token::Paren1(_, _) => {
-                            // Nested function declaration.
+        Token::Name(_, _, _) if is_function_type(reader, reader.pos()) || is_function_type(reader, reader.pos() + 1) => {
+            // A declaration with a function type, like int Function(int) f = twice.
+            let typ = typename(reader, state);
+            declaration(typ, reader, state)
+        }
 
-                            let params = paramlist(reader, state, false);
-                            reader.skip("{", state);
-                            let body = block(reader, state);
+        Token::Name(_, _, _) if matches!(reader.peek(), Token::Question(_, _)) => {
+            // A declaration with a nullable type, like int? x = null.
+            let typ = typename(reader, state);
+            declaration(typ, reader, state)
+        }
 
-                            let mut funcnode = Node::new(
-                                NodeType::FunDef(
-                                    s,
-                                    name.clone(),
-                                    state.filepath.clone(),
-                                    name_linenum2,
-                                    name_symnum2
-                            ));
-                            funcnode.children.push(params);
-                            funcnode.children.push(body);
-                            funcnode
-                        }
+        Token::Name(s, name_linenum1, name_symnum1) => {
 
-                        x => {
-                            panic!("Unexpected token: {}", x)
-                        }
-                    }
+            match reader.peek() {
+
+                Token::Name(_, _, _) => {
+                    // Two names in a row here indicates a typed variable or nested function declaration.
+                    reader.next();
+                    declaration(s, reader, state)
                 }
 
                 Token::Access(_, _) => {
@@ -909,8 +1805,8 @@ fn statement(reader: &mut Reader, state: &State) -> Node {
 
             match reader.tok() {
 
-                Token::Name(n1, name_linenum, name_symnum) => {
-                    reader.next();
+                Token::Name(_, name_linenum, name_symnum) => {
+                    let n1 = typename(reader, state);
 
                     match reader.tok() {
                         Token::Name(n2, name_linenum2, name_symnum2) => {
@@ -1042,13 +1938,40 @@ fn statement(reader: &mut Reader, state: &State) -> Node {
         }
 
         Token::Return(linenum, symnum) => {
-            reader.next();
-            let val = expression(reader, state);
+
+            let val = match reader.next() {
+                Token::EndSt(l, i) => Node::new(NodeType::Null(l, i)),
+                x if reader.in_generator => {
+                    let modifier = if reader.in_async { "async*" } else { "sync*" };
+                    // As dart.
+                    parseerror(
+                        format!("Can't return a value from a generator function (using the '{}' modifier).", modifier),
+                        state,
+                        x
+                    )
+                }
+                _ => expression(reader, state)
+            };
             let mut ret = Node::new(NodeType::Return(linenum, symnum));
             ret.children.push(val);
             return ret;
         }
 
+        Token::Switch(_, _) => {
+            switch_statement(reader, state)
+        }
+
+        Token::Super(_, _) => {
+            let left_node = expression(reader, state);
+
+            match reader.tok() {
+                Token::Assign(_, _) => {
+                    assign_help(left_node, reader, state)
+                }
+                _ => left_node
+            }
+        }
+
         _ => {
             return expression(reader, state);
         }
@@ -1120,3 +2043,249 @@ fn conditional(reader: &mut Reader, ctx: &State) -> Node {
         }
     }
 }
+
+
+/// The subject of a switch, as in switch (x) {.
+fn switch_subject(reader: &mut Reader, state: &State) -> Node {
+    reader.next();
+    reader.skip("(", state);
+    let subject = expression(reader, state);
+    reader.skip(")", state);
+    reader.skip("{", state);
+    subject
+}
+
+
+/// A switch statement. Cases don't fall through, but a case
+/// without statements shares the body of the case below it.
+fn switch_statement(reader: &mut Reader, state: &State) -> Node {
+
+    let mut node = Node::new(NodeType::Switch(reader.linenum(), reader.symnum()));
+    node.children.push(switch_subject(reader, state));
+
+    loop {
+        match reader.tok() {
+
+            Token::Block2(_, _) => {
+                reader.next();
+                return node;
+            }
+
+            Token::Case(linenum, symnum) |
+            Token::Default(linenum, symnum) => {
+
+                let mut heads = Vec::new();
+                loop {
+                    match reader.tok() {
+                        Token::Case(_, _) => {
+                            reader.next();
+                            heads.push(case_head(reader, state));
+                        }
+                        Token::Default(l, i) => {
+                            // The same as case _.
+                            reader.next();
+                            let mut head = Node::new(NodeType::CaseHead(l, i));
+                            head.children.push(Node::new(NodeType::VarPattern(String::from("var"), String::from("_"), l, i)));
+                            heads.push(head);
+                        }
+                        _ => break
+                    }
+                    reader.skip(":", state);
+                }
+
+                let mut body = Node::new(NodeType::Block(reader.linenum(), reader.symnum()));
+                loop {
+                    match reader.tok() {
+                        Token::Case(_, _) |
+                        Token::Default(_, _) |
+                        Token::Block2(_, _) |
+                        Token::End(_, _) => break,
+                        Token::EndSt(_, _) => {
+                            reader.next();
+                        }
+                        _ => {
+                            body.children.push(statement(reader, state));
+                            if let Token::EndSt(_, _) = reader.tok() {
+                                reader.next();
+                            }
+                        }
+                    }
+                }
+
+                let mut case = Node::new(NodeType::SwitchCase(linenum, symnum));
+                case.children.push(body);
+                case.children.extend(heads);
+                node.children.push(case);
+            }
+
+            x => parseerror(
+                format!("Expected 'case' or 'default'. Got: {}", x),
+                state,
+                x
+            )
+        }
+    }
+}
+
+
+/// A switch expression, as in switch (x) { 1 => "one", _ => "many" }.
+pub fn switch_expression(reader: &mut Reader, state: &State) -> Node {
+
+    let mut node = Node::new(NodeType::SwitchExpr(reader.linenum(), reader.symnum()));
+    node.children.push(switch_subject(reader, state));
+
+    loop {
+        match reader.tok() {
+
+            Token::Block2(_, _) => {
+                reader.next();
+                return node;
+            }
+
+            Token::Default(_, _) => parseerror(
+                "A switch expression can't have a 'default' case. Use '_' instead.",
+                state,
+                reader.tok()
+            ),
+
+            _ => {
+                let (linenum, symnum) = (reader.linenum(), reader.symnum());
+                let head = case_head(reader, state);
+                reader.skip("=>", state);
+                let mut case = Node::new(NodeType::SwitchCase(linenum, symnum));
+                case.children.push(expression(reader, state));
+                case.children.push(head);
+                node.children.push(case);
+
+                match reader.tok() {
+                    Token::Comma(_, _) => {
+                        reader.next();
+                    }
+                    Token::Block2(_, _) => {}
+                    x => parseerror(
+                        format!("Expected ',' or '}}' after switch case. Got: {}", x),
+                        state,
+                        x
+                    )
+                }
+            }
+        }
+    }
+}
+
+
+/// A pattern, and the guard that follows 'when', if any.
+fn case_head(reader: &mut Reader, state: &State) -> Node {
+
+    let mut head = Node::new(NodeType::CaseHead(reader.linenum(), reader.symnum()));
+    head.children.push(pattern(reader, state));
+
+    if let Token::Name(s, _, _) = reader.tok() {
+        if s == "when" {
+            reader.next();
+            head.children.push(expression(reader, state));
+        }
+    }
+    head
+}
+
+
+/// A pattern of a switch case. Either a variable, like int n or
+/// var n, where _ matches anything, an object pattern, like
+/// Circle(radius: var r), or a constant to compare with.
+fn pattern(reader: &mut Reader, state: &State) -> Node {
+
+    match reader.tok() {
+
+        Token::Name(s, linenum, symnum) if s == "_" => {
+            reader.next();
+            Node::new(NodeType::VarPattern(String::from("var"), s, linenum, symnum))
+        }
+
+        Token::Name(_, linenum, symnum) if matches!(
+            reader.peek(),
+            Token::Name(_, _, _) | Token::Question(_, _) | Token::Paren1(_, _) | Token::LessThan(_, _)
+        ) => {
+            let typ = typename(reader, state);
+
+            match reader.tok() {
+                Token::Paren1(_, _) => object_pattern(typ, linenum, symnum, reader, state),
+                Token::Name(name, linenum, symnum) => {
+                    reader.next();
+                    let typ = if typ == "final" { String::from("var") } else { typ };
+                    Node::new(NodeType::VarPattern(typ, name, linenum, symnum))
+                }
+                x => parseerror(
+                    format!("Expected a pattern. Got: {}", x),
+                    state,
+                    x
+                )
+            }
+        }
+
+        _ => {
+            let mut node = Node::new(NodeType::ConstPattern(reader.linenum(), reader.symnum()));
+            node.children.push(expression(reader, state));
+            node
+        }
+    }
+}
+
+
+/// The fields of an object pattern, like (radius: var r) or (:radius), where
+/// the field is named by the variable.
+fn object_pattern(typ: String, linenum: usize, symnum: usize, reader: &mut Reader, state: &State) -> Node {
+
+    let mut node = Node::new(NodeType::ObjectPattern(typ, linenum, symnum));
+    reader.skip("(", state);
+
+    while !matches!(reader.tok(), Token::Paren2(_, _)) {
+
+        let field = match reader.tok() {
+            Token::Colon(l, i) => {
+                reader.next();
+                let sub = pattern(reader, state);
+                match &sub.nodetype {
+                    NodeType::VarPattern(_, name, _, _) if name != "_" => {
+                        let mut field = Node::new(NodeType::FieldPattern(name.clone(), l, i));
+                        field.children.push(sub);
+                        field
+                    }
+                    // As dart.
+                    _ => parseerror(
+                        "The getter name is not specified explicitly, and the pattern is not a variable.",
+                        state,
+                        reader.tok()
+                    )
+                }
+            }
+            Token::Name(name, l, i) if matches!(reader.peek(), Token::Colon(_, _)) => {
+                reader.next();
+                reader.next();
+                let mut field = Node::new(NodeType::FieldPattern(name, l, i));
+                field.children.push(pattern(reader, state));
+                field
+            }
+            x => parseerror(
+                format!("Expected a field pattern. Got: {}", x),
+                state,
+                x
+            )
+        };
+        node.children.push(field);
+
+        match reader.tok() {
+            Token::Comma(_, _) => {
+                reader.next();
+            }
+            Token::Paren2(_, _) => {}
+            x => parseerror(
+                format!("Expected ',' or ')' in object pattern. Got: {}", x),
+                state,
+                x
+            )
+        }
+    }
+    reader.next();
+    node
+}
@@ -5,7 +5,13 @@ use crate::state::State;
 
 pub struct Reader {
     pos: usize,
-    tokens: Vec<Token>
+    tokens: Vec<Token>,
+    // Inside a sync* body, where yield is a keyword.
+    pub in_generator: bool,
+    // Inside an async body, where await is a keyword.
+    pub in_async: bool,
+    // The class whose members are being read, for super.
+    pub in_class: String
 }
 
 
@@ -14,7 +20,10 @@ impl Reader {
     pub fn new(tokens: Vec<Token>) -> Reader {
         Reader {
             pos: 0,
-            tokens
+            tokens,
+            in_generator: false,
+            in_async: false,
+            in_class: String::new()
         }
     }
 
@@ -0,0 +1,1324 @@
+// A backtracking regular expression engine with the semantics
+// of ECMAScript regular expressions, which Dart's RegExp follows.
+//
+// Input is matched as UTF-16 code units, so that match positions
+// are the same as Dart string indices. In unicode mode, surrogate
+// pairs are matched as a single character.
+
+
+use std::rc::Rc;
+
+
+pub type Captures = Vec<Option<(usize, usize)>>;
+
+
+#[derive(Clone, Copy)]
+enum Property {
+    Letter,
+    Uppercase,
+    Lowercase,
+    Number,
+    Whitespace,
+    Alphabetic,
+    Any
+}
+
+
+#[derive(Clone)]
+enum ClassItem {
+    Range(u32, u32),
+    Digit(bool),        // negated
+    Word(bool),
+    Space(bool),
+    Prop(Property, bool)
+}
+
+
+enum Node {
+    Empty,
+    Char(u32),
+    Any,
+    Class(Vec<ClassItem>, bool),            // items, negated
+    LineStart,
+    LineEnd,
+    WordBoundary(bool),                     // negated
+    Group(Box<Node>, Option<usize>),        // capture index
+    Backref(usize),
+    Look(Box<Node>, bool, bool),            // behind, negated
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat(Box<Node>, usize, Option<usize>, bool, usize, usize) // min, max, greedy, groups inside
+}
+
+
+pub struct Regex {
+    root: Node,
+    pub ngroups: usize,
+    // Group names by capture index. Index 0 is the whole match.
+    pub names: Vec<Option<String>>,
+    multiline: bool,
+    ignore_case: bool,
+    unicode: bool,
+    dot_all: bool
+}
+
+
+impl Regex {
+
+    pub fn new(
+        pattern: &str,
+        multiline: bool,
+        case_sensitive: bool,
+        unicode: bool,
+        dot_all: bool) -> Result<Regex, String> {
+
+        let chars: Vec<u32> = pattern.chars().map(|c| c as u32).collect();
+        let names = scan_groups(&chars)?;
+
+        let mut parser = Parser {
+            chars,
+            pos: 0,
+            unicode,
+            ngroups: 0,
+            names: names.clone()
+        };
+        let root = parser.disjunction()?;
+
+        if parser.more() {
+            // Only an unmatched ')' stops the top level disjunction.
+            return Err(String::from("Unmatched ')'"));
+        }
+
+        let mut allnames = vec![None];
+        allnames.extend(names);
+
+        Ok(Regex {
+            root,
+            ngroups: parser.ngroups,
+            names: allnames,
+            multiline,
+            ignore_case: !case_sensitive,
+            unicode,
+            dot_all
+        })
+    }
+
+
+    /// Find the first match starting at or after start.
+    pub fn exec(&self, input: &[u16], start: usize) -> Option<Captures> {
+
+        let m = Matcher { re: self, input };
+        let mut s = start;
+
+        while s <= input.len() {
+            let mut caps: Captures = vec![None; self.ngroups + 1];
+            if let Some(end) = m.run(&self.root, s, false, &mut caps) {
+                caps[0] = Some((s, end));
+                return Some(caps);
+            }
+            s += 1;
+        }
+        None
+    }
+
+
+    /// All non-overlapping matches from start, as Dart's allMatches finds them.
+    pub fn find_all(&self, input: &[u16], start: usize) -> Vec<Captures> {
+
+        let mut found = Vec::new();
+        let mut pos = start;
+
+        while pos <= input.len() {
+            let caps = match self.exec(input, pos) {
+                Some(caps) => caps,
+                None => break
+            };
+            let (s, e) = caps[0].unwrap();
+
+            // After an empty match, continue from the next character.
+            pos = if e > s { e } else { self.advance(input, e) };
+            found.push(caps);
+        }
+        found
+    }
+
+
+    /// The position after the character at pos.
+    pub fn advance(&self, input: &[u16], pos: usize) -> usize {
+        if self.unicode && pos + 1 < input.len() && is_high(input[pos]) && is_low(input[pos + 1]) {
+            return pos + 2;
+        }
+        pos + 1
+    }
+}
+
+
+// PARSING
+
+
+struct Parser {
+    chars: Vec<u32>,
+    pos: usize,
+    unicode: bool,
+    ngroups: usize,
+    names: Vec<Option<String>>
+}
+
+
+impl Parser {
+
+    fn more(&self) -> bool {
+        self.pos < self.chars.len()
+    }
+
+
+    fn peek(&self) -> Option<u32> {
+        self.chars.get(self.pos).copied()
+    }
+
+
+    fn peek_at(&self, offset: usize) -> Option<u32> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c as u32) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+
+    fn disjunction(&mut self) -> Result<Node, String> {
+
+        let mut alts = vec![self.alternative()?];
+
+        while self.eat('|') {
+            alts.push(self.alternative()?);
+        }
+        if alts.len() == 1 {
+            return Ok(alts.pop().unwrap());
+        }
+        Ok(Node::Alt(alts))
+    }
+
+
+    fn alternative(&mut self) -> Result<Node, String> {
+
+        let mut terms = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if c == '|' as u32 || c == ')' as u32 {
+                break;
+            }
+            terms.push(self.term()?);
+        }
+        match terms.len() {
+            0 => Ok(Node::Empty),
+            1 => Ok(terms.pop().unwrap()),
+            _ => Ok(Node::Concat(terms))
+        }
+    }
+
+
+    fn term(&mut self) -> Result<Node, String> {
+
+        let c = self.peek().unwrap();
+
+        // Assertions.
+        if c == '^' as u32 {
+            self.pos += 1;
+            return Ok(Node::LineStart);
+        }
+        if c == '$' as u32 {
+            self.pos += 1;
+            return Ok(Node::LineEnd);
+        }
+        if c == '\\' as u32 && (self.peek_at(1) == Some('b' as u32) || self.peek_at(1) == Some('B' as u32)) {
+            let negated = self.peek_at(1) == Some('B' as u32);
+            self.pos += 2;
+            return Ok(Node::WordBoundary(negated));
+        }
+        if c == '(' as u32 && self.peek_at(1) == Some('?' as u32) {
+            let look = match (self.peek_at(2), self.peek_at(3)) {
+                (Some(0x3D), _) => Some((false, false, 3)),            // (?=
+                (Some(0x21), _) => Some((false, true, 3)),             // (?!
+                (Some(0x3C), Some(0x3D)) => Some((true, false, 4)),    // (?<=
+                (Some(0x3C), Some(0x21)) => Some((true, true, 4)),     // (?<!
+                _ => None
+            };
+            if let Some((behind, negated, len)) = look {
+                self.pos += len;
+                let inner = self.disjunction()?;
+                if !self.eat(')') {
+                    return Err(String::from("Unterminated group"));
+                }
+                return Ok(Node::Look(Box::new(inner), behind, negated));
+            }
+        }
+
+        let groups_before = self.ngroups;
+        let atom = self.atom()?;
+        let groups_after = self.ngroups;
+
+        let (min, max) = match self.peek() {
+            Some(0x2A) => { self.pos += 1; (0, None) }         // *
+            Some(0x2B) => { self.pos += 1; (1, None) }         // +
+            Some(0x3F) => { self.pos += 1; (0, Some(1)) }      // ?
+            Some(0x7B) => {                                    // {
+                match self.braces()? {
+                    Some(q) => q,
+                    None => return Ok(atom)
+                }
+            }
+            _ => return Ok(atom)
+        };
+        let greedy = !self.eat('?');
+
+        Ok(Node::Repeat(Box::new(atom), min, max, greedy, groups_before + 1, groups_after + 1))
+    }
+
+
+    /// A {n}, {n,} or {n,m} quantifier. Outside unicode mode, a brace
+    /// that does not start a valid quantifier is a literal.
+    fn braces(&mut self) -> Result<Option<(usize, Option<usize>)>, String> {
+
+        let start = self.pos;
+        self.pos += 1;
+
+        let min = self.decimal();
+        let mut max = min;
+
+        if min.is_some() && self.eat(',') {
+            max = self.decimal();
+            if max.is_none() {
+                max = Some(usize::MAX);
+            }
+        }
+
+        if min.is_none() || !self.eat('}') {
+            if self.unicode {
+                return Err(String::from("Incomplete quantifier"));
+            }
+            self.pos = start;
+            return Ok(None);
+        }
+
+        let min = min.unwrap();
+        let max = if max == Some(usize::MAX) { None } else { max };
+
+        if let Some(m) = max {
+            if m < min {
+                return Err(String::from("numbers out of order in {} quantifier"));
+            }
+        }
+        Ok(Some((min, max)))
+    }
+
+
+    fn decimal(&mut self) -> Option<usize> {
+
+        let start = self.pos;
+        let mut n: usize = 0;
+
+        while let Some(c) = self.peek() {
+            if !(0x30..=0x39).contains(&c) {
+                break;
+            }
+            n = n.saturating_mul(10).saturating_add((c - 0x30) as usize);
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return None;
+        }
+        Some(n)
+    }
+
+
+    fn atom(&mut self) -> Result<Node, String> {
+
+        let c = self.peek().unwrap();
+        self.pos += 1;
+
+        match char::from_u32(c).unwrap_or('\u{FFFD}') {
+
+            '.' => Ok(Node::Any),
+
+            '(' => {
+                let mut index = None;
+
+                if self.eat('?') {
+                    if self.eat(':') {
+                        // Non-capturing.
+                    }
+                    else if self.eat('<') {
+                        // Named group. The name was collected by scan_groups.
+                        while self.more() && !self.eat('>') {
+                            self.pos += 1;
+                        }
+                        self.ngroups += 1;
+                        index = Some(self.ngroups);
+                    }
+                    else {
+                        return Err(String::from("Invalid group"));
+                    }
+                }
+                else {
+                    self.ngroups += 1;
+                    index = Some(self.ngroups);
+                }
+
+                let inner = self.disjunction()?;
+                if !self.eat(')') {
+                    return Err(String::from("Unterminated group"));
+                }
+                Ok(Node::Group(Box::new(inner), index))
+            }
+
+            '[' => self.class(),
+
+            '\\' => self.atom_escape(),
+
+            '*' | '+' | '?' => Err(String::from("Nothing to repeat")),
+
+            '{' => {
+                if self.unicode {
+                    return Err(String::from("Lone quantifier brackets"));
+                }
+                self.pos -= 1;
+                if self.braces()?.is_some() {
+                    return Err(String::from("Nothing to repeat"));
+                }
+                self.pos += 1;
+                Ok(Node::Char(c))
+            }
+
+            ']' | '}' if self.unicode => Err(String::from("Lone quantifier brackets")),
+
+            _ => Ok(self.literal(c))
+        }
+    }
+
+
+    fn literal(&self, c: u32) -> Node {
+        if c > 0xFFFF && !self.unicode {
+            // Outside unicode mode, the pattern is a sequence of code units too.
+            let (hi, lo) = split_surrogates(c);
+            return Node::Concat(vec![Node::Char(hi), Node::Char(lo)]);
+        }
+        Node::Char(c)
+    }
+
+
+    fn atom_escape(&mut self) -> Result<Node, String> {
+
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err(String::from("\\ at end of pattern"))
+        };
+
+        // Backreferences.
+        if (0x31..=0x39).contains(&c) {
+            let start = self.pos;
+            let n = self.decimal().unwrap();
+            if n <= self.names.len() {
+                return Ok(Node::Backref(n));
+            }
+            if self.unicode {
+                return Err(String::from("Invalid escape"));
+            }
+            self.pos = start;
+            return Ok(Node::Char(self.legacy_octal()));
+        }
+
+        if c == 'k' as u32 && (self.unicode || self.names.iter().any(|n| n.is_some())) {
+            self.pos += 1;
+            if !self.eat('<') {
+                return Err(String::from("Invalid named reference"));
+            }
+            let mut name = String::new();
+            while let Some(c) = self.peek() {
+                self.pos += 1;
+                if c == '>' as u32 {
+                    let index = self.names.iter().position(|n| n.as_deref() == Some(name.as_str()));
+                    return match index {
+                        Some(i) => Ok(Node::Backref(i + 1)),
+                        None => Err(String::from("Invalid named capture referenced"))
+                    };
+                }
+                name.push(char::from_u32(c).unwrap_or('\u{FFFD}'));
+            }
+            return Err(String::from("Invalid named reference"));
+        }
+
+        if let Some(item) = self.class_escape()? {
+            return Ok(Node::Class(vec![item], false));
+        }
+
+        let c = self.char_escape(false)?;
+        Ok(self.literal(c))
+    }
+
+
+    /// \d, \w, \s and their negations, and \p{..} in unicode mode.
+    fn class_escape(&mut self) -> Result<Option<ClassItem>, String> {
+
+        let item = match char::from_u32(self.peek().unwrap()).unwrap_or('\u{FFFD}') {
+            'd' => ClassItem::Digit(false),
+            'D' => ClassItem::Digit(true),
+            'w' => ClassItem::Word(false),
+            'W' => ClassItem::Word(true),
+            's' => ClassItem::Space(false),
+            'S' => ClassItem::Space(true),
+            c @ ('p' | 'P') if self.unicode => {
+                self.pos += 1;
+                if !self.eat('{') {
+                    return Err(String::from("Invalid property name"));
+                }
+                let mut name = String::new();
+                while let Some(c) = self.peek() {
+                    self.pos += 1;
+                    if c == '}' as u32 {
+                        break;
+                    }
+                    name.push(char::from_u32(c).unwrap_or('\u{FFFD}'));
+                }
+                let name = name.trim_start_matches("General_Category=").trim_start_matches("gc=");
+                let prop = match name {
+                    "L" | "Letter" => Property::Letter,
+                    "Lu" | "Uppercase_Letter" => Property::Uppercase,
+                    "Ll" | "Lowercase_Letter" => Property::Lowercase,
+                    "N" | "Nd" | "Number" | "Decimal_Number" => Property::Number,
+                    "White_Space" => Property::Whitespace,
+                    "Alphabetic" | "Alpha" => Property::Alphabetic,
+                    "Any" => Property::Any,
+                    _ => return Err(String::from("Invalid property name"))
+                };
+                return Ok(Some(ClassItem::Prop(prop, c == 'P')));
+            }
+            _ => return Ok(None)
+        };
+        self.pos += 1;
+        Ok(Some(item))
+    }
+
+
+    /// Escapes that stand for a single character.
+    fn char_escape(&mut self, in_class: bool) -> Result<u32, String> {
+
+        let c = self.peek().unwrap();
+        self.pos += 1;
+
+        let ch = char::from_u32(c).unwrap_or('\u{FFFD}');
+
+        Ok(match ch {
+            'n' => 0x0A,
+            'r' => 0x0D,
+            't' => 0x09,
+            'v' => 0x0B,
+            'f' => 0x0C,
+            'b' if in_class => 0x08,
+            '-' if in_class => c,
+            '0' if !self.peek().is_some_and(|d| (0x30..=0x39).contains(&d)) => 0,
+            '0'..='7' if !self.unicode => {
+                self.pos -= 1;
+                self.legacy_octal()
+            }
+            'c' => {
+                match self.peek() {
+                    Some(l) if char::from_u32(l).is_some_and(|l| l.is_ascii_alphabetic()) => {
+                        self.pos += 1;
+                        l % 32
+                    }
+                    _ => {
+                        if self.unicode {
+                            return Err(String::from("Invalid unicode escape"));
+                        }
+                        // The backslash is a literal.
+                        self.pos -= 1;
+                        '\\' as u32
+                    }
+                }
+            }
+            'x' => {
+                match self.hex(2) {
+                    Some(n) => n,
+                    None if self.unicode => return Err(String::from("Invalid escape")),
+                    None => c
+                }
+            }
+            'u' => {
+                match self.unicode_escape()? {
+                    Some(n) => n,
+                    None => c
+                }
+            }
+            _ => {
+                if self.unicode && !"^$\\.*+?()[]{}|/".contains(ch) {
+                    return Err(String::from("Invalid escape"));
+                }
+                // Identity escape.
+                c
+            }
+        })
+    }
+
+
+    fn unicode_escape(&mut self) -> Result<Option<u32>, String> {
+
+        if self.unicode && self.eat('{') {
+            let start = self.pos;
+            let mut n: u32 = 0;
+            while let Some(d) = self.peek().and_then(|c| char::from_u32(c)?.to_digit(16)) {
+                n = n.saturating_mul(16).saturating_add(d);
+                self.pos += 1;
+            }
+            if self.pos == start || !self.eat('}') || n > 0x10FFFF {
+                return Err(String::from("Invalid Unicode escape"));
+            }
+            return Ok(Some(n));
+        }
+
+        match self.hex(4) {
+            Some(hi) => {
+                // A surrogate pair written as two escapes is one character in unicode mode.
+                if self.unicode && is_high(hi as u16) && self.peek() == Some('\\' as u32) && self.peek_at(1) == Some('u' as u32) {
+                    let save = self.pos;
+                    self.pos += 2;
+                    if let Some(lo) = self.hex(4) {
+                        if is_low(lo as u16) {
+                            return Ok(Some(0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00)));
+                        }
+                    }
+                    self.pos = save;
+                }
+                Ok(Some(hi))
+            }
+            None if self.unicode => Err(String::from("Invalid Unicode escape")),
+            None => Ok(None)
+        }
+    }
+
+
+    fn hex(&mut self, len: usize) -> Option<u32> {
+
+        let mut n: u32 = 0;
+        for i in 0..len {
+            let d = self.peek_at(i).and_then(|c| char::from_u32(c)?.to_digit(16))?;
+            n = n * 16 + d;
+        }
+        self.pos += len;
+        Some(n)
+    }
+
+
+    fn legacy_octal(&mut self) -> u32 {
+
+        let first = self.peek().unwrap();
+        if first >= 0x38 {
+            // \8 and \9 are identity escapes.
+            self.pos += 1;
+            return first;
+        }
+
+        let mut n: u32 = 0;
+        let mut len = 0;
+        while let Some(c) = self.peek() {
+            if !(0x30..=0x37).contains(&c) || len == 3 || n * 8 + (c - 0x30) > 0xFF {
+                break;
+            }
+            n = n * 8 + (c - 0x30);
+            len += 1;
+            self.pos += 1;
+        }
+        n
+    }
+
+
+    fn class(&mut self) -> Result<Node, String> {
+
+        let negated = self.eat('^');
+        let mut items: Vec<ClassItem> = Vec::new();
+
+        loop {
+            let c = match self.peek() {
+                Some(c) => c,
+                None => return Err(String::from("Unterminated character class"))
+            };
+            if c == ']' as u32 {
+                self.pos += 1;
+                break;
+            }
+
+            let lo = self.class_atom()?;
+
+            if self.peek() == Some('-' as u32) && self.peek_at(1).is_some() && self.peek_at(1) != Some(']' as u32) {
+                self.pos += 1;
+                let hi = self.class_atom()?;
+
+                match (&lo, &hi) {
+                    (ClassItem::Range(a, _), ClassItem::Range(b, _)) => {
+                        if a > b {
+                            return Err(String::from("Range out of order in character class"));
+                        }
+                        items.push(ClassItem::Range(*a, *b));
+                    }
+                    _ => {
+                        if self.unicode {
+                            return Err(String::from("Invalid character class"));
+                        }
+                        items.push(lo);
+                        items.push(ClassItem::Range('-' as u32, '-' as u32));
+                        items.push(hi);
+                    }
+                }
+                continue;
+            }
+            items.push(lo);
+        }
+        Ok(Node::Class(items, negated))
+    }
+
+
+    fn class_atom(&mut self) -> Result<ClassItem, String> {
+
+        let c = self.peek().unwrap();
+        self.pos += 1;
+
+        if c != '\\' as u32 {
+            return Ok(ClassItem::Range(c, c));
+        }
+        if !self.more() {
+            return Err(String::from("\\ at end of pattern"));
+        }
+        if let Some(item) = self.class_escape()? {
+            return Ok(item);
+        }
+        let c = self.char_escape(true)?;
+        Ok(ClassItem::Range(c, c))
+    }
+}
+
+
+/// Collect capture group names in order, None for unnamed groups.
+fn scan_groups(chars: &[u32]) -> Result<Vec<Option<String>>, String> {
+
+    let mut names: Vec<Option<String>> = Vec::new();
+    let mut in_class = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\\' as u32 {
+            i += 2;
+            continue;
+        }
+        if in_class {
+            if c == ']' as u32 {
+                in_class = false;
+            }
+        }
+        else if c == '[' as u32 {
+            in_class = true;
+        }
+        else if c == '(' as u32 {
+            if chars.get(i + 1) != Some(&('?' as u32)) {
+                names.push(None);
+            }
+            else if chars.get(i + 2) == Some(&('<' as u32))
+                && chars.get(i + 3) != Some(&('=' as u32))
+                && chars.get(i + 3) != Some(&('!' as u32)) {
+
+                let mut name = String::new();
+                let mut j = i + 3;
+                while j < chars.len() && chars[j] != '>' as u32 {
+                    name.push(char::from_u32(chars[j]).unwrap_or('\u{FFFD}'));
+                    j += 1;
+                }
+                let valid = name.chars().enumerate().all(|(k, ch)| {
+                    ch == '$' || ch == '_' || ch.is_alphabetic() || (k > 0 && ch.is_alphanumeric())
+                });
+                if j == chars.len() || name.is_empty() || !valid {
+                    return Err(String::from("Invalid capture group name"));
+                }
+                if names.contains(&Some(name.clone())) {
+                    return Err(String::from("Duplicate capture group name"));
+                }
+                names.push(Some(name));
+            }
+        }
+        i += 1;
+    }
+    Ok(names)
+}
+
+
+// MATCHING
+
+
+// Matching keeps its backtracking state on the heap rather than the
+// native stack, so that long inputs cannot overflow it. What is left
+// to match after a node is a linked stack of frames, shared by the
+// choice points that are returned to on failure. Changes to the
+// captures are kept on a trail, to be undone on backtracking.
+
+
+struct Matcher<'a> {
+    re: &'a Regex,
+    input: &'a [u16]
+}
+
+
+// What remains to be done once a node has matched.
+enum Cont<'a> {
+    Seq(&'a [Node], usize),             // the nodes from index on
+    Capture(usize, usize),              // capture index, where the group started
+    Iterated(&'a Node, usize, usize)    // repeat node, count, where the iteration started
+}
+
+
+struct Frame<'a> {
+    cont: Cont<'a>,
+    next: Stack<'a>
+}
+
+
+type Stack<'a> = Option<Rc<Frame<'a>>>;
+
+
+fn push<'a>(cont: Cont<'a>, next: Stack<'a>) -> Stack<'a> {
+    Some(Rc::new(Frame { cont, next }))
+}
+
+
+// Frames are let go of one by one, as dropping a long
+// stack recursively could overflow the native stack.
+impl Drop for Frame<'_> {
+    fn drop(&mut self) {
+        let mut next = self.next.take();
+        while let Some(rc) = next {
+            match Rc::try_unwrap(rc) {
+                Ok(mut frame) => next = frame.next.take(),
+                Err(_) => break
+            }
+        }
+    }
+}
+
+
+enum Goal<'a> {
+    Match(&'a Node),
+    Continue,
+    Repeat(&'a Node, usize),            // repeat node, iterations so far
+    Iterate(&'a Node, usize),
+    Lazy(&'a Node, usize, usize)        // single character node, count, limit
+}
+
+
+// A point to backtrack to.
+struct Choice<'a> {
+    goal: Goal<'a>,
+    pos: usize,
+    stack: Stack<'a>,
+    trail: usize
+}
+
+
+impl<'a> Matcher<'a> {
+
+    /// Match node at pos, returning where the match ends. The captures
+    /// are left as they were at the match, or unchanged if there is none.
+    fn run(&self, node: &'a Node, pos: usize, back: bool, caps: &mut Captures) -> Option<usize> {
+
+        let mut choices: Vec<Choice<'a>> = Vec::new();
+        let mut trail: Vec<(usize, Option<(usize, usize)>)> = Vec::new();
+        let mut goal = Goal::Match(node);
+        let mut pos = pos;
+        let mut stack: Stack<'a> = None;
+
+        loop {
+            let next = match goal {
+                Goal::Match(node) => self.node(node, pos, back, &mut stack, caps, &mut choices, &mut trail),
+                Goal::Continue => {
+                    let frame = match stack.take() {
+                        Some(frame) => frame,
+                        None => return Some(pos)
+                    };
+                    self.cont(&frame, pos, back, &mut stack, caps, &mut trail)
+                }
+                Goal::Repeat(node, count) => self.repeat(node, count, pos, &stack, &mut choices, &trail),
+                Goal::Iterate(node, count) => {
+                    if let Node::Repeat(inner, _, _, _, glo, ghi) = node {
+                        // Captures inside the quantified atom are reset on each iteration.
+                        for (i, c) in caps.iter_mut().enumerate().take(*ghi).skip(*glo) {
+                            trail.push((i, c.take()));
+                        }
+                        stack = push(Cont::Iterated(node, count, pos), stack);
+                        Some((Goal::Match(inner), pos))
+                    }
+                    else {
+                        panic!("Expected repeat node.")
+                    }
+                }
+                Goal::Lazy(inner, count, limit) => {
+                    // One more step of a lazy single character repeat.
+                    if count >= limit {
+                        None
+                    }
+                    else {
+                        self.step(inner, pos, back).map(|p| {
+                            choices.push(Choice { goal: Goal::Lazy(inner, count + 1, limit), pos: p, stack: stack.clone(), trail: trail.len() });
+                            (Goal::Continue, p)
+                        })
+                    }
+                }
+            };
+
+            match next {
+                Some((g, p)) => {
+                    goal = g;
+                    pos = p;
+                }
+                None => {
+                    let choice = choices.pop()?;
+                    while trail.len() > choice.trail {
+                        let (i, old) = trail.pop().unwrap();
+                        caps[i] = old;
+                    }
+                    goal = choice.goal;
+                    pos = choice.pos;
+                    stack = choice.stack;
+                }
+            }
+        }
+    }
+
+
+    #[allow(clippy::too_many_arguments)]
+    fn node(
+        &self,
+        node: &'a Node,
+        pos: usize,
+        back: bool,
+        stack: &mut Stack<'a>,
+        caps: &mut Captures,
+        choices: &mut Vec<Choice<'a>>,
+        trail: &mut Vec<(usize, Option<(usize, usize)>)>) -> Option<(Goal<'a>, usize)> {
+
+        let matched = |p| Some((Goal::Continue, p));
+
+        match node {
+
+            Node::Empty => matched(pos),
+
+            Node::Char(_) |
+            Node::Any |
+            Node::Class(_, _) => self.step(node, pos, back).and_then(matched),
+
+            Node::LineStart => {
+                if pos == 0 || (self.re.multiline && is_line_terminator(self.input[pos - 1] as u32)) {
+                    return matched(pos);
+                }
+                None
+            }
+
+            Node::LineEnd => {
+                if pos == self.input.len() || (self.re.multiline && is_line_terminator(self.input[pos] as u32)) {
+                    return matched(pos);
+                }
+                None
+            }
+
+            Node::WordBoundary(negated) => {
+                let a = pos > 0 && is_word(self.input[pos - 1] as u32);
+                let b = pos < self.input.len() && is_word(self.input[pos] as u32);
+                if (a != b) != *negated {
+                    return matched(pos);
+                }
+                None
+            }
+
+            Node::Group(inner, index) => {
+                if let Some(i) = index {
+                    *stack = push(Cont::Capture(*i, pos), stack.take());
+                }
+                Some((Goal::Match(inner), pos))
+            }
+
+            Node::Backref(i) => {
+                let (s, e) = match caps[*i] {
+                    Some(span) => span,
+                    None => return matched(pos)
+                };
+                let len = e - s;
+                let start = if back {
+                    if pos < len {
+                        return None;
+                    }
+                    pos - len
+                }
+                else {
+                    if pos + len > self.input.len() {
+                        return None;
+                    }
+                    pos
+                };
+                for j in 0..len {
+                    let a = self.input[s + j] as u32;
+                    let b = self.input[start + j] as u32;
+                    if self.canonicalize(a) != self.canonicalize(b) {
+                        return None;
+                    }
+                }
+                matched(if back { start } else { pos + len })
+            }
+
+            Node::Look(inner, behind, negated) => {
+                // Lookarounds are atomic: once they match, they are
+                // not backtracked into. They nest no deeper than the pattern.
+                let mut inside = caps.clone();
+                let found = self.run(inner, pos, *behind, &mut inside).is_some();
+
+                if found == *negated {
+                    return None;
+                }
+                if !*negated {
+                    for (i, c) in inside.into_iter().enumerate() {
+                        if caps[i] != c {
+                            trail.push((i, caps[i]));
+                            caps[i] = c;
+                        }
+                    }
+                }
+                matched(pos)
+            }
+
+            Node::Concat(nodes) => {
+                if nodes.is_empty() {
+                    return matched(pos);
+                }
+                if nodes.len() > 1 {
+                    *stack = push(Cont::Seq(nodes, 1), stack.take());
+                }
+                // Lookbehind matches right to left.
+                let first = if back { nodes.last().unwrap() } else { &nodes[0] };
+                Some((Goal::Match(first), pos))
+            }
+
+            Node::Alt(alts) => {
+                for alt in alts[1..].iter().rev() {
+                    choices.push(Choice { goal: Goal::Match(alt), pos, stack: stack.clone(), trail: trail.len() });
+                }
+                Some((Goal::Match(&alts[0]), pos))
+            }
+
+            Node::Repeat(inner, min, max, greedy, glo, ghi) => {
+                if *glo == *ghi && is_single(inner) {
+                    return self.repeat_simple(inner, *min, *max, *greedy, pos, back, stack, choices, trail);
+                }
+                Some((Goal::Repeat(node, 0), pos))
+            }
+        }
+    }
+
+
+    /// Carry on with the frame on top of the stack, after a match up to pos.
+    fn cont(
+        &self,
+        frame: &Frame<'a>,
+        pos: usize,
+        back: bool,
+        stack: &mut Stack<'a>,
+        caps: &mut Captures,
+        trail: &mut Vec<(usize, Option<(usize, usize)>)>) -> Option<(Goal<'a>, usize)> {
+
+        *stack = frame.next.clone();
+
+        match frame.cont {
+
+            Cont::Seq(nodes, i) => {
+                if i + 1 < nodes.len() {
+                    *stack = push(Cont::Seq(nodes, i + 1), stack.take());
+                }
+                let node = if back { &nodes[nodes.len() - 1 - i] } else { &nodes[i] };
+                Some((Goal::Match(node), pos))
+            }
+
+            Cont::Capture(i, start) => {
+                trail.push((i, caps[i]));
+                caps[i] = Some(if back { (pos, start) } else { (start, pos) });
+                Some((Goal::Continue, pos))
+            }
+
+            Cont::Iterated(node, count, start) => {
+                if let Node::Repeat(_, min, _, _, _, _) = node {
+                    // An iteration past the minimum may not match the empty string.
+                    if pos == start && count >= *min {
+                        return None;
+                    }
+                    return Some((Goal::Repeat(node, count + 1), pos));
+                }
+                panic!("Expected repeat node.")
+            }
+        }
+    }
+
+
+    fn repeat(
+        &self,
+        node: &'a Node,
+        count: usize,
+        pos: usize,
+        stack: &Stack<'a>,
+        choices: &mut Vec<Choice<'a>>,
+        trail: &[(usize, Option<(usize, usize)>)]) -> Option<(Goal<'a>, usize)> {
+
+        if let Node::Repeat(_, min, max, greedy, _, _) = node {
+
+            if let Some(m) = max {
+                if count >= *m {
+                    return Some((Goal::Continue, pos));
+                }
+            }
+            if count < *min {
+                return Some((Goal::Iterate(node, count), pos));
+            }
+            let (first, second) = if *greedy {
+                (Goal::Iterate(node, count), Goal::Continue)
+            }
+            else {
+                (Goal::Continue, Goal::Iterate(node, count))
+            };
+            choices.push(Choice { goal: second, pos, stack: stack.clone(), trail: trail.len() });
+            return Some((first, pos));
+        }
+        panic!("Expected repeat node.")
+    }
+
+
+    /// Repetition of a single character matcher, without
+    /// a goal for each character.
+    #[allow(clippy::too_many_arguments)]
+    fn repeat_simple(
+        &self,
+        inner: &'a Node,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+        pos: usize,
+        back: bool,
+        stack: &Stack<'a>,
+        choices: &mut Vec<Choice<'a>>,
+        trail: &[(usize, Option<(usize, usize)>)]) -> Option<(Goal<'a>, usize)> {
+
+        let limit = max.unwrap_or(usize::MAX);
+        // Lazy repetition only steps past the minimum when it has to.
+        let wanted = if greedy { limit } else { min };
+        let mut positions = vec![pos];
+
+        while positions.len() - 1 < wanted {
+            match self.step(inner, *positions.last().unwrap(), back) {
+                Some(p) => positions.push(p),
+                None => break
+            }
+        }
+
+        if positions.len() - 1 < min {
+            return None;
+        }
+
+        if greedy {
+            let last = positions.pop().unwrap();
+            for p in positions.into_iter().skip(min) {
+                choices.push(Choice { goal: Goal::Continue, pos: p, stack: stack.clone(), trail: trail.len() });
+            }
+            return Some((Goal::Continue, last));
+        }
+
+        let p = positions[min];
+        choices.push(Choice { goal: Goal::Lazy(inner, min, limit), pos: p, stack: stack.clone(), trail: trail.len() });
+        Some((Goal::Continue, p))
+    }
+
+
+    /// Match a single character node at pos.
+    fn step(&self, node: &Node, pos: usize, back: bool) -> Option<usize> {
+
+        let (c, next) = self.read(pos, back)?;
+
+        let ok = match node {
+            Node::Char(p) => self.canonicalize(c) == self.canonicalize(*p),
+            Node::Any => self.re.dot_all || !is_line_terminator(c),
+            Node::Class(items, negated) => self.class_matches(items, c) != *negated,
+            Node::Group(inner, None) => return self.step(inner, pos, back),
+            // Every alternative reads the same character, so
+            // their order doesn't matter.
+            Node::Alt(alts) => return alts.iter().find_map(|alt| self.step(alt, pos, back)),
+            _ => panic!("Not a character node.")
+        };
+        if ok {
+            return Some(next);
+        }
+        None
+    }
+
+
+    fn read(&self, pos: usize, back: bool) -> Option<(u32, usize)> {
+
+        let input = self.input;
+
+        if back {
+            if pos == 0 {
+                return None;
+            }
+            let c = input[pos - 1];
+            if self.re.unicode && is_low(c) && pos >= 2 && is_high(input[pos - 2]) {
+                return Some((combine(input[pos - 2], c), pos - 2));
+            }
+            return Some((c as u32, pos - 1));
+        }
+
+        if pos >= input.len() {
+            return None;
+        }
+        let c = input[pos];
+        if self.re.unicode && is_high(c) && pos + 1 < input.len() && is_low(input[pos + 1]) {
+            return Some((combine(c, input[pos + 1]), pos + 2));
+        }
+        Some((c as u32, pos + 1))
+    }
+
+
+    fn class_matches(&self, items: &[ClassItem], c: u32) -> bool {
+
+        if class_has(items, c) {
+            return true;
+        }
+        if self.re.ignore_case {
+            if let Some(ch) = char::from_u32(c) {
+                let mut lower = ch.to_lowercase();
+                let mut upper = ch.to_uppercase();
+                if let (Some(l), None) = (lower.next(), lower.next()) {
+                    if class_has(items, l as u32) {
+                        return true;
+                    }
+                }
+                if let (Some(u), None) = (upper.next(), upper.next()) {
+                    if class_has(items, u as u32) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+
+    /// Case folding for case insensitive matching.
+    fn canonicalize(&self, c: u32) -> u32 {
+
+        if !self.re.ignore_case {
+            return c;
+        }
+        let ch = match char::from_u32(c) {
+            Some(ch) => ch,
+            None => return c
+        };
+
+        if self.re.unicode {
+            let mut lower = ch.to_lowercase();
+            if let (Some(l), None) = (lower.next(), lower.next()) {
+                return l as u32;
+            }
+            return c;
+        }
+
+        let mut upper = ch.to_uppercase();
+        if let (Some(u), None) = (upper.next(), upper.next()) {
+            // Non-ASCII characters don't fold into ASCII.
+            if c >= 128 && (u as u32) < 128 {
+                return c;
+            }
+            return u as u32;
+        }
+        c
+    }
+}
+
+
+/// Whether node always matches exactly one character, without
+/// captures, so step() can match it.
+fn is_single(node: &Node) -> bool {
+    match node {
+        Node::Char(_) | Node::Any | Node::Class(_, _) => true,
+        Node::Group(inner, None) => is_single(inner),
+        Node::Alt(alts) => alts.iter().all(is_single),
+        _ => false
+    }
+}
+
+
+fn class_has(items: &[ClassItem], c: u32) -> bool {
+
+    items.iter().any(|item| {
+        match item {
+            ClassItem::Range(lo, hi) => *lo <= c && c <= *hi,
+            ClassItem::Digit(neg) => ((0x30..=0x39).contains(&c)) != *neg,
+            ClassItem::Word(neg) => is_word(c) != *neg,
+            ClassItem::Space(neg) => is_space(c) != *neg,
+            ClassItem::Prop(prop, neg) => {
+                let ch = char::from_u32(c);
+                let has = match prop {
+                    Property::Letter | Property::Alphabetic => ch.is_some_and(|ch| ch.is_alphabetic()),
+                    Property::Uppercase => ch.is_some_and(|ch| ch.is_uppercase()),
+                    Property::Lowercase => ch.is_some_and(|ch| ch.is_lowercase()),
+                    Property::Number => ch.is_some_and(|ch| ch.is_numeric()),
+                    Property::Whitespace => ch.is_some_and(|ch| ch.is_whitespace()),
+                    Property::Any => true
+                };
+                has != *neg
+            }
+        }
+    })
+}
+
+
+fn is_word(c: u32) -> bool {
+    c < 128 && ((c as u8).is_ascii_alphanumeric() || c == '_' as u32)
+}
+
+
+fn is_space(c: u32) -> bool {
+    matches!(c,
+        0x09..=0x0D | 0x20 | 0xA0 | 0x1680 | 0x2000..=0x200A |
+        0x2028 | 0x2029 | 0x202F | 0x205F | 0x3000 | 0xFEFF)
+}
+
+
+fn is_line_terminator(c: u32) -> bool {
+    matches!(c, 0x0A | 0x0D | 0x2028 | 0x2029)
+}
+
+
+fn is_high(c: u16) -> bool {
+    (0xD800..0xDC00).contains(&c)
+}
+
+
+fn is_low(c: u16) -> bool {
+    (0xDC00..0xE000).contains(&c)
+}
+
+
+fn combine(hi: u16, lo: u16) -> u32 {
+    0x10000 + (((hi as u32) - 0xD800) << 10) + ((lo as u32) - 0xDC00)
+}
+
+
+fn split_surrogates(c: u32) -> (u32, u32) {
+    let v = c - 0x10000;
+    (0xD800 + (v >> 10), 0xDC00 + (v & 0x3FF))
+}
@@ -66,6 +66,31 @@ impl Stack {
     }
 
 
+    // A copy of the current call-frame, as captured by a generator.
+    pub fn top_frame(&self) -> Vec<HashMap<String, Object>> {
+        self.stack.last().unwrap().clone()
+    }
+
+
+    // Put a saved call-frame back on the call-stack, to resume a generator.
+    pub fn push_frame(&mut self, frame: Vec<HashMap<String, Object>>) {
+        self.lex_level = frame.len();
+        self.stack.push(frame);
+        self.call_level += 1;
+    }
+
+
+    // Remove the top frame from call-stack and hand it back for later.
+    pub fn pop_frame(&mut self) -> Vec<HashMap<String, Object>> {
+        let frame = self.stack.pop().unwrap();
+        self.call_level -= 1;
+        if !self.stack.is_empty() {
+            self.lex_level = self.stack.last().unwrap().len();
+        }
+        frame
+    }
+
+
     // Add a new frame to the lex-stack - a new lexical scope.
     pub fn push_lex(&mut self) {
         let lexframe : HashMap<String, Object> = HashMap::new();
@@ -87,6 +112,21 @@ impl Stack {
     }
 
 
+    // Remove the lexical scopes above the level and hand them back, as
+    // when an async body stops at an await in the middle of an expression.
+    pub fn leave_lex(&mut self, level: usize) -> Vec<HashMap<String, Object>> {
+        let scopes = self.stack.last_mut().unwrap().split_off(level);
+        self.lex_level = level;
+        scopes
+    }
+
+
+    // The lexical scopes above the level, innermost last.
+    pub fn lex_above(&mut self, level: usize) -> &mut [HashMap<String, Object>] {
+        &mut self.stack.last_mut().unwrap()[level..]
+    }
+
+
     // Add a new key-value pair to the current frame.
     pub fn add_new(&mut self, s: &str, v: Object) {
         let callframe = self.stack.last_mut().unwrap();
@@ -171,11 +211,8 @@ impl Stack {
         for node in globals {
             match node.nodetype.clone() {
                 NodeType::TopVar(_, _, val, _, _) => {
-                    match *val {
-                        Object::Reference(rk) => {
-                            trashman::mark(objsys, &rk);
-                        }
-                        _ => {}
+                    if let Some(rk) = val.heap_ref() {
+                        trashman::mark(objsys, rk);
                     }
                 }
                 // ConstTopVar?
@@ -200,8 +237,8 @@ impl Stack {
                 let lexframe = callframe.get(ll - 1).unwrap();
 
                 for (_, v) in lexframe {
-                    match v {
-                        Object::Reference(rk) => {
+                    match v.heap_ref() {
+                        Some(rk) => {
 
                             if objsys.has_instance(rk) {
                                 trashman::mark(objsys, rk)
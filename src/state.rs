@@ -1,9 +1,13 @@
 use std::time::Instant;
+use std::sync::Arc;
 use std::collections::HashMap;
 use crate::stack::Stack;
 use crate::objsys::ObjSys;
 use crate::objsys::RefKey;
 use crate::node::Node;
+use crate::eventloop::EventLoop;
+use crate::isolate::Program;
+use crate::generator::Replay;
 
 
 // filepath:     Name of the file we are currently executing in.
@@ -20,6 +24,12 @@ use crate::node::Node;
 //               so we can avoid stuff that are not allowed.
 // start_time:   Timestamp when we started the program, so we can measure time.
 // last_gc:      Timestamp when the garbage collector last ran.
+// eventloop:    Queued microtasks and timers, run after main returns.
+// runtime_checks: Check values against the declared types of variables,
+//               parameters, fields and returns.
+// program:      The program before main ran, if it spawns isolates.
+// replaying:    What the statements of async bodies that can stop at an
+//               await have evaluated, for the innermost one last.
 // debug:        Enable debug messages.
 pub struct State {
     pub filepath: String,
@@ -32,6 +42,13 @@ pub struct State {
     pub in_const: bool,
     pub start_time: Instant,
     pub last_gc: Instant,
+    pub eventloop: EventLoop,
+    pub runtime_checks: bool,
+    pub program: Option<Arc<Program>>,
+    pub replaying: Vec<Replay>,
+    // Whether this is a spawned isolate, which an unhandled exception
+    // ends without ending the process.
+    pub spawned: bool,
     pub debug: bool
 }
 
@@ -50,6 +67,11 @@ impl  State {
             in_const: false,
             start_time: Instant::now(),
             last_gc: Instant::now(),
+            eventloop: EventLoop::new(),
+            runtime_checks: true,
+            program: None,
+            replaying: Vec::new(),
+            spawned: false,
             debug: false
         }
     }
@@ -126,7 +126,52 @@ pub const TESTS: &'static [&'static str] = &[
     "121.getter2.dart",
     "122.list_get_length.dart",
     "123.list_get_first.dart",
-    "124.list_get_last.dart"
+    "124.list_get_last.dart",
+    "125.string_escapes.dart",
+    "126.raw_and_multiline_strings.dart",
+    "127.simple_interpolation.dart",
+    "128.adjacent_strings.dart",
+    "129.number_literals.dart",
+    "130.int_wraparound.dart",
+    "131.double_printing.dart",
+    "132.truncating_division.dart",
+    "133.number_parsing.dart",
+    "134.number_formatting.dart",
+    "135.number_methods.dart",
+    "136.stringbuffer.dart",
+    "137.optional_parameters.dart",
+    "138.named_parameters.dart",
+    "139.regexp.dart",
+    "140.regexp_lookaround_named_groups.dart",
+    "141.string_patterns.dart",
+    "142.list_methods.dart",
+    "143.list_sort.dart",
+    "144.iterable_lazy.dart",
+    "145.iterable_user_defined.dart",
+    "146.sync_star.dart",
+    "147.loop_scopes.dart",
+    "148.collection_elements.dart",
+    "149.generics.dart",
+    "150.type_tests.dart",
+    "151.runtime_type_checks.dart",
+    "152.static_checks.dart",
+    "153.null_safety.dart",
+    "154.function_types.dart",
+    "155.tear_offs.dart",
+    "156.super_calls.dart",
+    "157.object_members.dart",
+    "158.sealed_switch.dart",
+    "159.async_await.dart",
+    "160.streams.dart",
+    "161.isolates.dart",
+    "162.timers.dart",
+    "163.user_operators.dart",
+    "164.datetime.dart",
+    "165.maps.dart",
+    "166.json.dart",
+    "167.isolate_errors.dart",
+    "168.sets.dart",
+    "169.type_parameter_names.dart"
 ];
 
 
@@ -148,7 +193,65 @@ pub const FAILTESTS: &'static [&'static str] = &[
     "14.topvar_stack_leak.dart",
     "15.wrong_owner.dart",
     "16.wrong_owner2.dart",
-    "17.missing_return.dart"
+    "17.missing_return.dart",
+    "18.interpolation_position.dart",
+    "19.int_division_by_zero.dart",
+    "20.misplaced_digit_separator.dart",
+    "21.int_literal_too_large.dart",
+    "22.int_parse_format_exception.dart",
+    "23.fraction_digits_out_of_range.dart",
+    "24.round_infinity.dart",
+    "25.too_many_optional_args.dart",
+    "26.required_named_parameter.dart",
+    "27.invalid_regexp.dart",
+    "28.missing_named_group.dart",
+    "29.sublist_range.dart",
+    "30.sort_concurrent_modification.dart",
+    "31.iterable_no_element.dart",
+    "32.iterable_take_negative.dart",
+    "33.generator_return_value.dart",
+    "34.generator_running.dart",
+    "35.spread_non_iterable.dart",
+    "36.generic_type_arguments.dart",
+    "37.list_literal_type_arguments.dart",
+    "38.failed_cast.dart",
+    "39.variable_type.dart",
+    "40.parameter_type.dart",
+    "41.return_type.dart",
+    "42.field_type.dart",
+    "43.static_errors.dart",
+    "44.null_check.dart",
+    "45.late_uninitialized.dart",
+    "46.null_receiver.dart",
+    "47.null_safety_errors.dart",
+    "48.function_type_errors.dart",
+    "49.function_type_mismatch.dart",
+    "50.not_callable.dart",
+    "51.tear_off_errors.dart",
+    "52.super_errors.dart",
+    "53.super_outside_class.dart",
+    "54.no_such_getter.dart",
+    "55.super_no_such_method.dart",
+    "56.non_exhaustive_switch.dart",
+    "57.class_modifiers/main.dart",
+    "58.async_errors.dart",
+    "59.await_outside_async.dart",
+    "60.unhandled_future_error.dart",
+    "61.async_star_errors.dart",
+    "62.await_for_outside_async.dart",
+    "63.stream_listened_twice.dart",
+    "64.send_receive_port.dart",
+    "65.unsendable_message.dart",
+    "66.timer_without_import.dart",
+    "67.invalid_date_format.dart",
+    "68.operator_arity.dart",
+    "69.invalid_json.dart",
+    "70.json_unencodable.dart",
+    "71.map_value_type.dart",
+    "72.type_literal_argument.dart",
+    "73.type_parameter_bounds.dart",
+    "74.set_element_type.dart",
+    "75.callback_type.dart"
 ];
 
 
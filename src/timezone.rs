@@ -0,0 +1,433 @@
+// Time zones and the calendar, for DateTime.
+//
+// The local zone is given by the TZ environment variable, either as the
+// name of a zone in the time zone database, like Europe/Oslo, or as a
+// POSIX rule, like CET-1CEST,M3.5.0,M10.5.0/3. Without TZ, the zone of
+// /etc/localtime is used. Zones are read from the files of the system,
+// so no network is needed. Where no zone is found, local time is UTC.
+//
+// Dates are in the proleptic Gregorian calendar, counted in days since
+// 1970-01-01, which may be negative.
+
+use std::env;
+use std::fs;
+use std::sync::OnceLock;
+
+
+const ZONEINFO: &str = "/usr/share/zoneinfo";
+
+
+/// The offset from UTC of local time, and the name of the zone at it, as CEST.
+#[derive(Clone)]
+pub struct LocalTime {
+    pub offset: i64,
+    pub name: String
+}
+
+
+// A date in a year, as given in POSIX rules.
+enum RuleDate {
+    // Jn: day 1 to 365, never counting February 29.
+    Julian(i64),
+    // n: day 0 to 365, counting February 29.
+    Day(i64),
+    // Mm.w.d: day d of week w of month m, where week 5 is the last.
+    Weekday(i64, i64, i64)
+}
+
+
+// Daylight saving time, with the local times it starts and ends at.
+struct Dst {
+    time: LocalTime,
+    start: (RuleDate, i64),
+    end: (RuleDate, i64)
+}
+
+
+// A POSIX rule, for standard time with optional daylight saving.
+struct Rule {
+    std: LocalTime,
+    dst: Option<Dst>
+}
+
+
+// transitions: Times the offset changed, in seconds since the epoch,
+//              with the index of the local time from then on.
+// times:       The local times used by the zone.
+// rule:        Gives the local time after the last transition.
+pub struct Zone {
+    transitions: Vec<(i64, usize)>,
+    times: Vec<LocalTime>,
+    rule: Option<Rule>
+}
+
+
+impl Zone {
+
+    fn utc() -> Zone {
+        Zone {
+            transitions: Vec::new(),
+            times: vec![LocalTime { offset: 0, name: String::from("UTC") }],
+            rule: None
+        }
+    }
+
+
+    /// The local time at a time, in seconds since the epoch.
+    pub fn at(&self, secs: i64) -> LocalTime {
+
+        let i = self.transitions.partition_point(|(t, _)| *t <= secs);
+        if i == 0 {
+            // Before the first transition, or without any.
+            if let (true, Some(rule)) = (self.transitions.is_empty(), &self.rule) {
+                return rule.at(secs);
+            }
+            return self.times[0].clone();
+        }
+        if i == self.transitions.len() {
+            if let Some(rule) = &self.rule {
+                return rule.at(secs);
+            }
+        }
+        self.times[self.transitions[i - 1].1].clone()
+    }
+
+
+    /// Seconds since the epoch for a local time, given in seconds
+    /// since the epoch as if it were UTC. Times skipped by a change
+    /// to daylight saving are taken to be in the earlier offset.
+    pub fn to_utc(&self, local: i64) -> i64 {
+        let guess = local - self.at(local).offset;
+        local - self.at(guess).offset
+    }
+}
+
+
+impl Rule {
+
+    fn at(&self, secs: i64) -> LocalTime {
+
+        let dst = match &self.dst {
+            Some(dst) => dst,
+            None => return self.std.clone()
+        };
+        let (year, _, _) = civil_from_days((secs + self.std.offset).div_euclid(86400));
+        let start = rule_day(year, &dst.start.0) * 86400 + dst.start.1 - self.std.offset;
+        let end = rule_day(year, &dst.end.0) * 86400 + dst.end.1 - dst.time.offset;
+
+        let in_dst = if start < end {
+            start <= secs && secs < end
+        }
+        else {
+            // As south of the equator, where it spans the new year.
+            !(end <= secs && secs < start)
+        };
+        if in_dst { dst.time.clone() } else { self.std.clone() }
+    }
+}
+
+
+/// The zone of local time, read once.
+pub fn local() -> &'static Zone {
+    static LOCAL: OnceLock<Zone> = OnceLock::new();
+    LOCAL.get_or_init(load)
+}
+
+
+fn load() -> Zone {
+
+    let tz = match env::var("TZ") {
+        Ok(tz) => tz,
+        Err(_) => {
+            return fs::read("/etc/localtime").ok()
+                .and_then(|data| parse_tzif(&data))
+                .unwrap_or_else(Zone::utc);
+        }
+    };
+    let tz = tz.trim_start_matches(':');
+    if tz.is_empty() {
+        return Zone::utc();
+    }
+
+    let path = if tz.starts_with('/') { tz.to_string() } else { format!("{}/{}", ZONEINFO, tz) };
+    if !tz.contains("..") {
+        if let Some(zone) = fs::read(path).ok().and_then(|data| parse_tzif(&data)) {
+            return zone;
+        }
+    }
+    match parse_rule(tz) {
+        Some(rule) => Zone {
+            transitions: Vec::new(),
+            times: vec![rule.std.clone()],
+            rule: Some(rule)
+        },
+        None => Zone::utc()
+    }
+}
+
+
+// TZIF FILES
+
+fn be_int(data: &[u8], pos: usize, size: usize) -> Option<i64> {
+    let bytes = data.get(pos..pos + size)?;
+    let mut n: i64 = if bytes[0] & 0x80 != 0 { -1 } else { 0 };
+    for b in bytes {
+        n = (n << 8) | *b as i64;
+    }
+    Some(n)
+}
+
+
+/// A zone from a file of the time zone database, as described in RFC 8536.
+fn parse_tzif(data: &[u8]) -> Option<Zone> {
+
+    if data.get(0..4)? != b"TZif" {
+        return None;
+    }
+    let version = *data.get(4)?;
+
+    // The counts of the header: isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt.
+    let counts = |pos: usize| -> Option<Vec<usize>> {
+        (0..6).map(|i| be_int(data, pos + 20 + i * 4, 4).map(|n| n as usize)).collect()
+    };
+
+    let mut pos = 0;
+    let mut timesize = 4;
+    let mut c = counts(pos)?;
+    if version >= b'2' {
+        // Skip the data for 32 bit readers.
+        pos += 44 + c[3] * 5 + c[4] * 6 + c[5] + c[2] * 8 + c[1] + c[0];
+        timesize = 8;
+        c = counts(pos)?;
+    }
+    let (timecnt, typecnt, charcnt) = (c[3], c[4], c[5]);
+    pos += 44;
+
+    let mut transitions = Vec::new();
+    for i in 0..timecnt {
+        let t = be_int(data, pos + i * timesize, timesize)?;
+        let idx = *data.get(pos + timecnt * timesize + i)? as usize;
+        transitions.push((t, idx));
+    }
+    pos += timecnt * (timesize + 1);
+
+    let chars = data.get(pos + typecnt * 6..pos + typecnt * 6 + charcnt)?;
+    let mut times = Vec::new();
+    for i in 0..typecnt {
+        let offset = be_int(data, pos + i * 6, 4)?;
+        let abbr = *data.get(pos + i * 6 + 5)? as usize;
+        let name: Vec<u8> = chars.get(abbr..)?.iter().take_while(|b| **b != 0).cloned().collect();
+        times.push(LocalTime { offset, name: String::from_utf8_lossy(&name).to_string() });
+    }
+    if times.is_empty() || transitions.iter().any(|(_, i)| *i >= times.len()) {
+        return None;
+    }
+    pos += typecnt * 6 + charcnt + c[2] * (timesize + 4) + c[1] + c[0];
+
+    // A POSIX rule for later times ends the file, between newlines.
+    let mut rule = None;
+    if version >= b'2' {
+        if let Some(footer) = data.get(pos..) {
+            let footer = String::from_utf8_lossy(footer);
+            rule = parse_rule(footer.trim_matches('\n'));
+        }
+    }
+    Some(Zone { transitions, times, rule })
+}
+
+
+// POSIX RULES
+
+struct Scanner<'a> {
+    s: &'a [u8],
+    pos: usize
+}
+
+
+impl Scanner<'_> {
+
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).cloned()
+    }
+
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+
+    fn number(&mut self) -> Option<i64> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.s[start..self.pos]).ok()?.parse().ok()
+    }
+
+
+    // A zone name, as CET, or quoted, as <+03>.
+    fn name(&mut self) -> Option<String> {
+        let start = self.pos;
+        if self.eat(b'<') {
+            while !self.eat(b'>') {
+                self.peek()?;
+                self.pos += 1;
+            }
+            let name = &self.s[start + 1..self.pos - 1];
+            return Some(String::from_utf8_lossy(name).to_string());
+        }
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        if self.pos - start < 3 {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&self.s[start..self.pos]).to_string())
+    }
+
+
+    // A time of day, as [+-]hh[:mm[:ss]], in seconds.
+    fn time(&mut self) -> Option<i64> {
+        let sign = if self.eat(b'-') { -1 } else { self.eat(b'+'); 1 };
+        let mut secs = self.number()? * 3600;
+        if self.eat(b':') {
+            secs += self.number()? * 60;
+            if self.eat(b':') {
+                secs += self.number()?;
+            }
+        }
+        Some(sign * secs)
+    }
+
+
+    fn date(&mut self) -> Option<(RuleDate, i64)> {
+        let date = if self.eat(b'J') {
+            RuleDate::Julian(self.number()?)
+        }
+        else if self.eat(b'M') {
+            let m = self.number()?;
+            self.eat(b'.').then_some(())?;
+            let w = self.number()?;
+            self.eat(b'.').then_some(())?;
+            RuleDate::Weekday(m, w, self.number()?)
+        }
+        else {
+            RuleDate::Day(self.number()?)
+        };
+        let time = if self.eat(b'/') { self.time()? } else { 7200 };
+        Some((date, time))
+    }
+}
+
+
+/// A POSIX rule, as for the TZ environment variable.
+fn parse_rule(s: &str) -> Option<Rule> {
+
+    let mut sc = Scanner { s: s.as_bytes(), pos: 0 };
+
+    // Offsets are given as the time to add to get UTC.
+    let name = sc.name()?;
+    let std = LocalTime { offset: -sc.time()?, name };
+    if sc.peek().is_none() {
+        return Some(Rule { std, dst: None });
+    }
+
+    let name = sc.name()?;
+    let offset = match sc.peek() {
+        Some(b',') | None => std.offset + 3600,
+        _ => -sc.time()?
+    };
+    let time = LocalTime { offset, name };
+
+    // Without dates, as in the United States.
+    let (start, end) = if sc.eat(b',') {
+        let start = sc.date()?;
+        sc.eat(b',').then_some(())?;
+        (start, sc.date()?)
+    }
+    else {
+        ((RuleDate::Weekday(3, 2, 0), 7200), (RuleDate::Weekday(11, 1, 0), 7200))
+    };
+    if sc.peek().is_some() {
+        return None;
+    }
+    Some(Rule { std, dst: Some(Dst { time, start, end }) })
+}
+
+
+fn rule_day(year: i64, date: &RuleDate) -> i64 {
+
+    let jan1 = days_from_civil(year, 1, 1);
+    match date {
+        RuleDate::Julian(n) => {
+            jan1 + n - 1 + if is_leap(year) && *n >= 60 { 1 } else { 0 }
+        }
+        RuleDate::Day(n) => jan1 + n,
+        RuleDate::Weekday(m, w, d) => {
+            let first = days_from_civil(year, *m, 1);
+            let mut day = first + (d - weekday(first) % 7 + 7) % 7 + (w - 1) * 7;
+            while day >= first + days_in_month(year, *m) {
+                day -= 7;
+            }
+            day
+        }
+    }
+}
+
+
+// CALENDAR
+
+pub fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+
+pub fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+
+/// Days since 1970-01-01 of a date. The month must be 1 to 12,
+/// but the day may be outside the month.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // From Howard Hinnant's date algorithms, with years starting in March.
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468 + day - 1
+}
+
+
+/// The year, month and day of a day since 1970-01-01.
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+
+/// The day of the week of a day since 1970-01-01, from 1 for
+/// Monday to 7 for Sunday, as in Dart.
+pub fn weekday(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday.
+    (days + 3).rem_euclid(7) + 1
+}
@@ -12,6 +12,7 @@ pub enum Token {
   Sub(usize, usize),
   Mul(usize, usize),
   Div(usize, usize),
+  TruncDiv(usize, usize),
   Increment(usize, usize),
   Decrement(usize, usize),
   // Logic
@@ -27,11 +28,13 @@ pub enum Token {
   LessOrEq(usize, usize),
   GreaterOrEq(usize, usize),
   Equal(usize, usize),
+  NotEqual(usize, usize),
   // Primitive
   Int(i64, usize, usize),
   Double(f64, usize, usize),
-  Str(String, Vec<Vec<Token>>, usize, usize),
+  Str(Vec<String>, Vec<Vec<Token>>, usize, usize), // literal parts, interpolations
   Bool(bool, usize, usize),
+  Null(usize, usize),
   Name(String, usize, usize),
   // Structure
   Class(usize, usize),
@@ -42,6 +45,8 @@ pub enum Token {
   Do(usize, usize),
   For(usize, usize),
   In(usize, usize),
+  Is(usize, usize),
+  As(usize, usize),
   Paren1(usize, usize),
   Paren2(usize, usize),
   Block1(usize, usize),
@@ -50,11 +55,18 @@ pub enum Token {
   Brack2(usize, usize),
   Comma(usize, usize),
   Colon(usize, usize),
+  Question(usize, usize),
+  Switch(usize, usize),
+  Case(usize, usize),
+  Default(usize, usize),
+  Arrow(usize, usize),
   // Modifiers
   Const(usize, usize),
   // Other
   Assign(usize, usize),
   Access(usize, usize),
+  Spread(usize, usize),
+  SpreadNull(usize, usize),
   This(usize, usize),
   Return(usize, usize),
   Get(usize, usize),
@@ -74,6 +86,7 @@ impl fmt::Display for Token {
       Token::Sub(_, _) => write!(f, "-"),
       Token::Mul(_, _) => write!(f, "*"),
       Token::Div(_, _) => write!(f, "/"),
+      Token::TruncDiv(_, _) => write!(f, "~/"),
       Token::Increment(_, _) => write!(f, "++"),
       Token::Decrement(_, _) => write!(f, "--"),
       // Logic
@@ -89,25 +102,26 @@ impl fmt::Display for Token {
       Token::LessOrEq(_, _)    => write!(f, "<="),
       Token::GreaterOrEq(_, _) => write!(f, ">="),
       Token::Equal(_, _) => write!(f, "=="),
+      Token::NotEqual(_, _) => write!(f, "!="),
       // Primitive
       Token::Int(s, _, _)     => write!(f, "{}", s),
       Token::Double(s, _, _)     => write!(f, "{}", s),
-      Token::Str(s, interpols, _, _)  => {
-        if interpols.len() > 0 {
-          write!(f, "\"{}\"", s).ok();
-          write!(f, "( ").ok();
-          for itp in interpols {
-            for t in itp {
-              write!(f, "{} ", t).ok();
+      Token::Str(parts, interpols, _, _)  => {
+        write!(f, "\"{}", parts[0]).ok();
+        for i in 0 .. interpols.len() {
+          write!(f, "${{ ").ok();
+          for t in &interpols[i] {
+            if let Token::End(_, _) = t {
+              continue;
             }
+            write!(f, "{} ", t).ok();
           }
-          write!(f, ")")
-        }
-        else {
-          write!(f, "\"{}\"", s)
+          write!(f, "}}{}", parts[i + 1]).ok();
         }
+        write!(f, "\"")
       },
       Token::Bool(v, _, _)     => write!(f, "{}", v),
+      Token::Null(_, _)        => write!(f, "null"),
       Token::Name(s, _, _)    => write!(f, "{}", s),
       // Structure
       Token::Class(_, _) => write!(f, "class"),
@@ -118,6 +132,8 @@ impl fmt::Display for Token {
       Token::Do(_, _) => write!(f, "do"),
       Token::For(_, _) => write!(f, "for"),
       Token::In(_, _) => write!(f, "in"),
+      Token::Is(_, _) => write!(f, "is"),
+      Token::As(_, _) => write!(f, "as"),
       Token::Paren1(_, _) => write!(f, "("),
       Token::Paren2(_, _) => write!(f, ")"),
       Token::Block1(_, _) => write!(f, "{{"),
@@ -126,11 +142,18 @@ impl fmt::Display for Token {
       Token::Brack2(_, _) => write!(f, "]"),
       Token::Comma(_, _) => write!(f, ","),
       Token::Colon(_, _) => write!(f, ":"),
+      Token::Question(_, _) => write!(f, "?"),
+      Token::Switch(_, _) => write!(f, "switch"),
+      Token::Case(_, _) => write!(f, "case"),
+      Token::Default(_, _) => write!(f, "default"),
+      Token::Arrow(_, _) => write!(f, "=>"),
       // Modifiers
       Token::Const(_, _) => write!(f, "const"),
       // Other
       Token::Assign(_, _) => write!(f, "="),
       Token::Access(_, _) => write!(f, "."),
+      Token::Spread(_, _) => write!(f, "..."),
+      Token::SpreadNull(_, _) => write!(f, "...?"),
       Token::This(_, _)   => write!(f, "this"),
       Token::Return(_, _) => write!(f, "return"),
       Token::Get(_, _) => write!(f, "get"),
@@ -152,6 +175,7 @@ impl Token {
       Token::Sub(l, i) |
       Token::Mul(l, i) |
       Token::Div(l, i) |
+      Token::TruncDiv(l, i) |
       Token::Increment(l, i) |
       Token::Decrement(l, i) |
       // Logic
@@ -167,11 +191,13 @@ impl Token {
       Token::LessOrEq(l, i) |
       Token::GreaterOrEq(l, i) |
       Token::Equal(l, i) |
+      Token::NotEqual(l, i) |
       // Primitive
       Token::Int(_, l, i) |
       Token::Double(_, l, i) |
       Token::Str(_, _, l, i) |
       Token::Bool(_, l, i) |
+      Token::Null(l, i) |
       Token::Name(_, l, i) |
       // Structure
       Token::Class(l, i) |
@@ -182,6 +208,8 @@ impl Token {
       Token::Do(l, i) |
       Token::For(l, i) |
       Token::In(l, i) |
+      Token::Is(l, i) |
+      Token::As(l, i) |
       Token::Paren1(l, i) |
       Token::Paren2(l, i) |
       Token::Block1(l, i) |
@@ -190,11 +218,18 @@ impl Token {
       Token::Brack2(l, i) |
       Token::Comma(l, i) |
       Token::Colon(l, i) |
+      Token::Question(l, i) |
+      Token::Switch(l, i) |
+      Token::Case(l, i) |
+      Token::Default(l, i) |
+      Token::Arrow(l, i) |
       // Modifiers
       Token::Const(l, i) |
       // Other
       Token::Assign(l, i) |
       Token::Access(l, i) |
+      Token::Spread(l, i) |
+      Token::SpreadNull(l, i) |
       Token::This(l, i) |
       Token::Super(l, i) |
       Token::Return(l, i) |
@@ -1,172 +1,2223 @@
+// The static pass. It runs over the whole program after the files are
+// parsed and before main, and reports all the errors it finds at once.
+//
+// Static types are strings, as at runtime. Where the type of an expression
+// can not be told, it is dynamic, and dynamic is not held against anything.
 
-use crate::{node::{Node, NodeType}, state::State};
+use std::collections::{HashMap, HashSet};
+use std::process;
+use crate::builtin;
+use crate::node::{Node, NodeType};
+use crate::object::{Object, ParamObj};
+use crate::objsys::Class;
+use crate::state::State;
+use crate::types::{self, TypeParam};
 
 
-fn hastype(t: &String, state: &State) -> bool {
+/// What a name on a class stands for, with the
+/// type arguments of the receiver filled in.
+enum Member {
+    Field(String),
+    Getter(String),
+    Method(Vec<ParamObj>, Vec<TypeParam>, String)   // params, typeparams, return type
+}
+
+
+struct Checker<'a> {
+    state: &'a State,
+    filepath: String,
+    // Types of local variables, by lexical scope.
+    scopes: Vec<HashMap<String, String>>,
+    // The type of this, like Box<T>, when checking class members.
+    this: Option<String>,
+    typeparams: Vec<String>,
+    // The function being checked, and its declared return type.
+    fname: String,
+    rettype: String,
+    // In an async function, the type of the value of its future.
+    async_value: Option<String>,
+    // Switch statements that match every value, so can be returned from.
+    exhaustive: HashSet<(usize, usize)>,
+    // Int literals given where a double is expected, by file.
+    double_literals: HashMap<String, HashSet<(usize, usize)>>,
+    errors: Vec<String>,
+    warnings: Vec<String>
+}
+
+
+/// Check the program, and make the int literals given where a double is
+/// expected double literals, as in `double d = 3;`.
+pub fn typecheck(state: &mut State, memo: &HashMap<String, (usize, usize)>) {
+
+    let mut checker = Checker {
+        state,
+        filepath: String::new(),
+        scopes: Vec::new(),
+        this: None,
+        typeparams: Vec::new(),
+        fname: String::new(),
+        rettype: String::from("dynamic"),
+        async_value: None,
+        exhaustive: HashSet::new(),
+        double_literals: HashMap::new(),
+        errors: Vec::new(),
+        warnings: Vec::new()
+    };
+
+    // In the order the files were read.
+    let mut files: Vec<(&String, &(usize, usize))> = memo.iter().collect();
+    files.sort_by_key(|(_, (start, _))| *start);
+
+    for (filepath, (start, end)) in files {
+        checker.filepath = filepath.clone();
+        checker.exhaustive.clear();
+        for n in &state.globals[*start .. *end] {
+            checker.tc_global(n);
+        }
+    }
+
+    for w in &checker.warnings {
+        println!("{}", w);
+    }
+    if !checker.errors.is_empty() {
+        for e in &checker.errors {
+            println!("{}", e);
+        }
+        process::exit(1);
+    }
+
+    let double_literals = checker.double_literals;
+    for (filepath, (start, end)) in memo {
+        if let Some(positions) = double_literals.get(filepath) {
+            for n in &mut state.globals[*start .. *end] {
+                make_doubles(n, positions);
+                if let NodeType::Constructor(cname, _, _, _, _, _, _) = &n.nodetype {
+                    if state.objsys.has_class(cname) {
+                        make_class_doubles(state.objsys.get_class_mut(cname), positions);
+                    }
+                }
+            }
+        }
+    }
+}
+
+
+/// Make the int literals at the positions in the node double literals.
+fn make_doubles(node: &mut Node, positions: &HashSet<(usize, usize)>) {
+    if let NodeType::Int(val, linenum, symnum) = node.nodetype {
+        if positions.contains(&(linenum, symnum)) {
+            node.nodetype = NodeType::Double(val as f64, linenum, symnum);
+        }
+    }
+    match &mut node.nodetype {
+        NodeType::Constructor(_, paramlist, initlist, body, _, _, _) => {
+            make_doubles(paramlist, positions);
+            make_doubles(initlist, positions);
+            make_doubles(body, positions);
+        }
+        NodeType::MethodCall(_, owner, _, _, _) => make_doubles(owner, positions),
+        _ => {}
+    }
+    for child in &mut node.children {
+        make_doubles(child, positions);
+    }
+}
+
+
+/// The same for the fields, methods and getters of a class,
+/// which hold copies of their nodes.
+fn make_class_doubles(class: &mut Class, positions: &HashSet<(usize, usize)>) {
+    for (_, _, initexpr) in &mut class.fields {
+        make_doubles(initexpr, positions);
+    }
+    for f in class.methods.values_mut().chain(class.getters.values_mut()) {
+        if let Object::Function(_, _, body, params, _, _) = f {
+            make_doubles(body, positions);
+            for p in params {
+                if let Some(default) = &mut p.default {
+                    make_doubles(default, positions);
+                }
+            }
+        }
+    }
+}
+
+
+impl<'a> Checker<'a> {
+
+    fn error<S: Into<String>>(&mut self, msg: S, node: &Node) {
+        let (linenum, symnum) = node.find_node_position();
+        self.errors.push(format!("{}:{}:{}: Error: {}", self.filepath, linenum, symnum, msg.into()));
+    }
+
+
+    fn warning<S: Into<String>>(&mut self, msg: S, node: &Node) {
+        let (linenum, symnum) = node.find_node_position();
+        self.warnings.push(format!("{}:{}:{}: Warning: {}", self.filepath, linenum, symnum, msg.into()));
+    }
+
+
+    fn tc_global(&mut self, node: &Node) {
+
+        match &node.nodetype {
+
+            NodeType::FunDef(typ, name, _, _, _) => {
+                let params = ParamObj::from_paramlist(&node.children[0]);
+                let typeparams = match node.children.get(2) {
+                    Some(Node { nodetype: NodeType::TypeParams(tparams, _, _), .. }) => {
+                        tparams.iter().map(|p| p.name.clone()).collect()
+                    }
+                    _ => Vec::new()
+                };
+                self.tc_function(name, typ, &params, typeparams, &node.children[1], node);
+            }
+
+            NodeType::Constructor(cname, params, initlist, body, _, _, _) => {
+                self.tc_class(cname, node);
+                self.tc_constructor(cname, params, initlist, body);
+            }
+
+            NodeType::TopVarLazy(typ, name, _, _) if matches!(node.children[0].nodetype, NodeType::Null(_, _)) => {
+                self.hastype(typ, node);
+                if !self.nullable(typ) {
+                    self.error(format!("The non-nullable variable '{}' must be initialized.", name), node);
+                }
+            }
+
+            NodeType::TopVarLazy(typ, _, _, _) |
+            NodeType::ConstTopLazy(typ, _, _, _) => {
+                self.hastype(typ, node);
+                let t = self.tc_expression(&node.children[0]);
+                self.check_assign(&t, typ, &node.children[0]);
+            }
+
+            _ => {}
+        }
+    }
+
+
+    fn tc_function(
+        &mut self,
+        name: &str,
+        typ: &str,
+        params: &[ParamObj],
+        typeparams: Vec<String>,
+        body: &Node,
+        node: &Node) {
+
+        let outer_scopes = std::mem::take(&mut self.scopes);
+        let outer_fname = std::mem::replace(&mut self.fname, String::from(name));
+        let outer_rettype = std::mem::replace(&mut self.rettype, String::from(typ));
+        let outer_async_value = self.async_value.take();
+        let outer_typeparams = self.typeparams.clone();
+        self.typeparams.extend(typeparams);
+
+        self.hastype(typ, node);
+        self.scopes.push(HashMap::new());
+        self.tc_params(params, node);
+
+        match &body.nodetype {
+            NodeType::SyncStar(_, _) => {
+                // A generator returns no value, the parser sees to that.
+                self.rettype = String::from("void");
+                self.tc_block(&body.children[0]);
+            }
+            NodeType::AsyncStar(_, _, _) => {
+                let (rname, _) = types::split_type(typ.trim_end_matches('?'));
+                if !matches!(rname.as_str(), "Stream" | "dynamic" | "Object") {
+                    // As dart.
+                    self.error("Functions marked 'async*' must have a return type assignable to 'Stream'.", node);
+                }
+                self.rettype = String::from("void");
+                self.tc_block(&body.children[0]);
+            }
+            NodeType::Async(valuetype, _, _) => {
+                let (rname, _) = types::split_type(typ.trim_end_matches('?'));
+                if !matches!(rname.as_str(), "Future" | "void" | "dynamic" | "Object") {
+                    // As dart.
+                    self.error("Functions marked 'async' must have a return type assignable to 'Future'.", node);
+                }
+                self.async_value = Some(valuetype.clone());
+                self.tc_block(&body.children[0]);
+                if name != "main" && must_return(valuetype) && self.completes(&body.children[0]) {
+                    self.error(
+                        format!("A non-null value must be returned since the return type '{}' doesn't allow null.", typ),
+                        node
+                    );
+                }
+            }
+            _ => {
+                self.tc_block(body);
+                // The result of main is ignored.
+                if name != "main" && must_return(typ) && self.completes(body) {
+                    self.error(
+                        format!("A non-null value must be returned since the return type '{}' doesn't allow null.", typ),
+                        node
+                    );
+                }
+            }
+        }
+
+        self.scopes = outer_scopes;
+        self.fname = outer_fname;
+        self.rettype = outer_rettype;
+        self.async_value = outer_async_value;
+        self.typeparams = outer_typeparams;
+    }
+
+
+    fn tc_params(&mut self, params: &[ParamObj], node: &Node) {
+        for p in params {
+            if let Some(default) = &p.default {
+                let t = self.tc_expression(default);
+                self.check_assign(&t, &p.typ, default);
+            }
+            if p.fieldinit {
+                let ftype = self.this.clone()
+                    .and_then(|this| self.member(&this, &p.name))
+                    .and_then(|m| match m { Member::Field(t) => Some(t), _ => None });
+                match ftype {
+                    Some(t) => self.declare(&p.name, &t),
+                    None => self.error(format!("'{}' isn't a field in the enclosing class.", p.name), node)
+                }
+                continue;
+            }
+            self.hastype(&p.typ, node);
+            if p.optional && p.default.is_none() && !self.nullable(&p.typ) {
+                self.error(
+                    format!("The parameter '{}' can't have a value of 'null' because of its type '{}', but the implicit default value is 'null'.",
+                        p.name, p.typ),
+                    node
+                );
+            }
+            self.declare(&p.name, &p.typ);
+        }
+    }
+
+
+    /// Check the members of a class, once, from its constructor.
+    fn tc_class(&mut self, cname: &str, node: &Node) {
+
+        let state = self.state;
+        let class = state.objsys.get_class(cname);
+        let typeparams: Vec<String> = class.typeparams.iter().map(|p| p.name.clone()).collect();
+        self.this = Some(types::make_type(cname, &typeparams));
+        self.typeparams = typeparams;
+
+        for p in &class.typeparams {
+            self.hastype(&p.bound, node);
+        }
+        if class.parent != "" {
+            self.hastype(&types::make_type(&class.parent, &class.parent_typeargs), node);
+        }
+        if state.objsys.has_class(&class.parent) {
+            self.tc_modifiers(class);
+        }
+
+        for (ftype, _, initexpr) in &class.fields {
+            self.hastype(ftype, node);
+            if let NodeType::Null(_, _) = initexpr.nodetype {
+                // Checked with the constructor.
+                continue;
+            }
+            self.scopes.push(HashMap::new());
+            let t = self.tc_expression(initexpr);
+            self.scopes.pop();
+            self.check_assign(&t, ftype, initexpr);
+        }
+
+        // Methods and getters, in the order they were written.
+        let mut functions: Vec<&Object> = class.methods.values().chain(class.getters.values()).collect();
+        functions.sort_by_key(|f| match f {
+            Object::Function(_, _, body, _, _, _) => body.find_node_position(),
+            _ => (0, 0)
+        });
+
+        for f in functions {
+            if let Object::Function(fname, _, body, params, typeparams, rettype) = f {
+                let tparams = typeparams.iter().map(|p| p.name.clone()).collect();
+                self.tc_function(fname, rettype, params, tparams, body, body);
+            }
+        }
+    }
+
+
+    /// Check that the class extends its parent as the modifiers of both allow.
+    /// Sealed, final and interface classes can only be extended in their own
+    /// library, which is the file they were declared in.
+    fn tc_modifiers(&mut self, class: &Class) {
+
+        let state = self.state;
+        let parent = state.objsys.get_class(&class.parent);
+        let node = Node::new(NodeType::Name(class.name.clone(), class.linenum, class.symnum));
+
+        if parent.filename != class.filename {
+            match parent.modifier.as_str() {
+                "sealed" => self.error(
+                    format!("The class '{}' can't be extended, implemented, or mixed in outside of its library because it's a sealed class.", parent.name),
+                    &node
+                ),
+                "final" | "interface" => self.error(
+                    format!("The class '{}' can't be extended outside of its library because it's {} {} class.",
+                        parent.name, if parent.modifier == "final" { "a" } else { "an" }, parent.modifier),
+                    &node
+                ),
+                _ => {}
+            }
+        }
+
+        if class.is_mixin && parent.name != "Object" {
+            self.error(
+                format!("The class '{}' can't be used as a mixin because it extends a class other than 'Object'.", class.name),
+                &node
+            );
+        }
+
+        // Subtypes of base and final classes must keep the restriction.
+        if !matches!(class.modifier.as_str(), "base" | "final" | "sealed") {
+            let mut sup = parent;
+            while sup.name != "Object" {
+                if matches!(sup.modifier.as_str(), "base" | "final") {
+                    self.error(
+                        format!("The type '{}' must be 'base', 'final' or 'sealed' because the supertype '{}' is '{}'.",
+                            class.name, sup.name, sup.modifier),
+                        &node
+                    );
+                    break;
+                }
+                sup = state.objsys.get_class(&sup.parent);
+            }
+        }
+    }
+
+
+    fn tc_constructor(&mut self, cname: &str, params: &Node, initlist: &Node, body: &Node) {
+
+        let params = match params.nodetype {
+            NodeType::ParamList(_, _) => ParamObj::from_paramlist(params),
+            _ => Vec::new()
+        };
+
+        self.fname = String::from(cname);
+        self.rettype = String::from("void");
+        self.scopes.push(HashMap::new());
+        self.tc_params(&params, body);
+
+        for initter in &initlist.children {
+            match initter.nodetype {
+                NodeType::Initializer(_, _) => {
+                    let t = self.tc_expression(&initter.children[1]);
+                    if let NodeType::Name(fieldname, _, _) = &initter.children[0].nodetype {
+                        let this = self.this.clone().unwrap();
+                        match self.member(&this, fieldname) {
+                            Some(Member::Field(ftype)) => self.check_assign(&t, &ftype, &initter.children[1]),
+                            _ => self.error(
+                                format!("'{}' isn't a field in the enclosing class.", fieldname),
+                                &initter.children[0]
+                            )
+                        }
+                    }
+                }
+                NodeType::Super(_, _) => {
+                    let parent = self.state.objsys.get_class(cname).parent.clone();
+                    if parent == "" {
+                        self.tc_args(&initter.children[0]);
+                    }
+                    else {
+                        self.tc_constructor_call(&parent, &initter.children[0], None, initter);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Fields without an initializer must be given a value before the
+        // body runs, by an initializing formal or the initializer list.
+        let state = self.state;
+        for (ftype, fname, initexpr) in &state.objsys.get_class(cname).fields {
+            if !matches!(initexpr.nodetype, NodeType::Null(_, _)) || self.nullable(ftype) {
+                continue;
+            }
+            let by_param = params.iter().any(|p| p.fieldinit && p.name == *fname);
+            let by_initlist = initlist.children.iter().any(|i| matches!(
+                i.children.first().map(|n| &n.nodetype),
+                Some(NodeType::Name(n, _, _)) if n == fname
+            ));
+            if !by_param && !by_initlist {
+                self.error(
+                    format!("Field '{}' should be initialized because its type '{}' doesn't allow null.", fname, ftype),
+                    initexpr
+                );
+            }
+        }
+
+        if let NodeType::Block(_, _) = body.nodetype {
+            self.tc_block(body);
+        }
+        self.scopes.pop();
+        self.this = None;
+        self.typeparams.clear();
+    }
+
+
+    fn tc_block(&mut self, node: &Node) {
+
+        self.scopes.push(HashMap::new());
+        let mut done = false;
+
+        for n in &node.children {
+            if done {
+                self.warning("Dead code.", n);
+                done = false;
+            }
+            self.tc_statement(n);
+            if !self.completes(n) {
+                done = true;
+            }
+        }
+        self.scopes.pop();
+    }
+
+
+    /// A body of a loop or conditional, which may be a single statement.
+    fn tc_body(&mut self, node: &Node) {
+        if let NodeType::Block(_, _) = node.nodetype {
+            self.tc_block(node);
+            return;
+        }
+        self.scopes.push(HashMap::new());
+        self.tc_statement(node);
+        self.scopes.pop();
+    }
+
+
+    fn tc_statement(&mut self, node: &Node) {
+
+        match &node.nodetype {
+
+            NodeType::Assign(_, _) => {
+                self.tc_assign(node);
+            }
+
+            NodeType::Return(_, _) => {
+                let mut t = self.tc_expression(&node.children[0]);
+                let rettype = self.rettype.clone();
+                // An async function returns the value of its future, or a future of it.
+                let mut expected = rettype.clone();
+                if let Some(valuetype) = &self.async_value {
+                    expected = valuetype.clone();
+                    t = self.awaited_type(&t);
+                }
+                t = self.literal_type(&t, &expected, &node.children[0]);
+                if expected != "void" && !self.assignable(&t, &expected) {
+                    let msg = if self.async_value.is_some() {
+                        format!("A value of type '{}' can't be returned from an async function with return type '{}'.",
+                            t, rettype)
+                    }
+                    else {
+                        format!("A value of type '{}' can't be returned from the function '{}' because it has a return type of '{}'.",
+                            t, self.fname, rettype)
+                    };
+                    self.error(msg, &node.children[0]);
+                }
+            }
+
+            NodeType::Conditional(_, _) => {
+                // The conditions that failed, for promotion in later branches.
+                let mut failed: Vec<&Node> = Vec::new();
+
+                for condnode in &node.children {
+                    self.scopes.push(HashMap::new());
+                    for cond in &failed {
+                        self.promote(cond, false);
+                    }
+                    match condnode.nodetype {
+                        NodeType::If(_, _) |
+                        NodeType::ElseIf(_, _) => {
+                            self.tc_condition(&condnode.children[0]);
+                            self.promote(&condnode.children[0], true);
+                            self.tc_body(&condnode.children[1]);
+                            failed.push(&condnode.children[0]);
+                        }
+                        _ => {
+                            self.tc_body(&condnode.children[0]);
+                        }
+                    }
+                    self.scopes.pop();
+                }
+
+                // After if (x == null) return; x is not null.
+                if node.children.len() == 1 && !self.completes(&node.children[0].children[1]) {
+                    self.promote(&node.children[0].children[0], false);
+                }
+            }
+
+            NodeType::TypedVar(typ, name, _, _) => {
+                if self.scopes.last().unwrap().contains_key(&types::var_key(name)) {
+                    self.error(format!("'{}' is already declared in this scope.", name), node);
+                }
+                self.hastype(typ, node);
+                self.declare(name, typ);
+            }
+
+            NodeType::While(_, _) => {
+                // The body runs while the condition holds, as an if body does.
+                self.demote_assigned(node);
+                self.tc_condition(&node.children[0]);
+                self.scopes.push(HashMap::new());
+                self.promote(&node.children[0], true);
+                self.tc_body(&node.children[1]);
+                self.scopes.pop();
+            }
+
+            NodeType::DoWhile(_, _) => {
+                self.demote_assigned(node);
+                self.tc_body(&node.children[0]);
+                self.tc_condition(&node.children[1]);
+            }
+
+            NodeType::For(_, _) => {
+                self.demote_assigned(node);
+                self.scopes.push(HashMap::new());
+                if node.children.len() == 3 {
+                    self.tc_loop_variable(node, "Iterable");
+                    self.tc_body(&node.children[2]);
+                }
+                else {
+                    self.tc_statement(&node.children[0]);
+                    self.tc_condition(&node.children[1]);
+                    self.scopes.push(HashMap::new());
+                    self.promote(&node.children[1], true);
+                    self.tc_expression(&node.children[2]);
+                    self.tc_body(&node.children[3]);
+                    self.scopes.pop();
+                }
+                self.scopes.pop();
+            }
+
+            NodeType::AwaitFor(_, _) => {
+                self.scopes.push(HashMap::new());
+                self.tc_loop_variable(node, "Stream");
+                self.tc_body(&node.children[2]);
+                self.scopes.pop();
+            }
+
+            NodeType::Block(_, _) => {
+                self.tc_block(node);
+            }
+
+            NodeType::FunDef(typ, name, _, _, _) => {
+                // Nested functions run in a call-frame of their own.
+                self.declare(name, &fundef_type(node));
+                let params = ParamObj::from_paramlist(&node.children[0]);
+                let mut typeparams = Vec::new();
+                if let Some(Node { nodetype: NodeType::TypeParams(tparams, _, _), .. }) = node.children.get(2) {
+                    typeparams = tparams.iter().map(|p| p.name.clone()).collect();
+                }
+                self.tc_function(name, typ, &params, typeparams, &node.children[1], node);
+            }
+
+            NodeType::Yield(_, _) |
+            NodeType::YieldStar(_, _) => {
+                self.tc_expression(&node.children[0]);
+            }
+
+            NodeType::Switch(_, _) => {
+                self.tc_switch(node);
+            }
+
+            _ => {
+                self.tc_expression(node);
+            }
+        }
+    }
+
+
+    /// Declare the variable of a for-in loop or element, in the current scope.
+    /// The source is an Iterable, or a Stream for await for.
+    fn tc_loop_variable(&mut self, node: &Node, source: &str) {
+
+        let iterable = self.tc_expression(&node.children[1]);
+        let elemtype = match types::as_super(&iterable, source, self.state) {
+            Some(args) => args[0].clone(),
+            None => String::from("dynamic")
+        };
+
+        if let NodeType::TypedVar(typ, name, _, _) = &node.children[0].nodetype {
+            if typ == "var" {
+                self.declare(name, &elemtype);
+            }
+            else {
+                self.hastype(typ, &node.children[0]);
+                self.check_assign(&elemtype, typ, &node.children[1]);
+                self.declare(name, typ);
+            }
+        }
+    }
+
+
+    fn tc_assign(&mut self, node: &Node) {
+
+        let left = &node.children[0];
+        let right = &node.children[1];
+        let t = self.tc_expression(right);
+
+        match &left.nodetype {
+
+            NodeType::TypedVar(typ, name, _, _) => {
+                if self.scopes.last().unwrap().contains_key(&types::var_key(name)) {
+                    self.error(format!("'{}' is already declared in this scope.", name), left);
+                }
+                if typ == "var" {
+                    let inferred = if t == "Null" { String::from("dynamic") } else { t };
+                    self.declare(name, &inferred);
+                }
+                else {
+                    self.hastype(typ, left);
+                    self.check_assign(&t, typ, right);
+                    self.declare(name, typ);
+                    self.promote_assigned(name, &t);
+                }
+            }
+
+            NodeType::Name(name, _, _) if left.children.is_empty() => {
+                if let Some(declared) = self.lookup_local(&types::var_key(name)) {
+                    self.check_assign(&t, &declared, right);
+                    self.promote_assigned(name, &t);
+                    return;
+                }
+                match self.variable(name) {
+                    Some(vtype) => self.check_assign(&t, &vtype, right),
+                    None => self.error(format!("Setter not found: '{}'.", name), left)
+                }
+            }
+
+            NodeType::Name(name, _, _) if matches!(left.children[0].nodetype, NodeType::SuperRef(_, _, _)) => {
+                if let Some(Member::Field(ftype)) = self.super_member(&left.children[0], name, "setter", left) {
+                    self.check_assign(&t, &ftype, right);
+                }
+            }
+
+            NodeType::Name(name, _, _) => {
+                let owner = self.tc_expression(&left.children[0]);
+                let owner = self.non_null_receiver(&owner, "property", name, left);
+                match self.member(&owner, name) {
+                    Some(Member::Field(ftype)) => self.check_assign(&t, &ftype, right),
+                    Some(_) => {}
+                    None if self.is_class_type(&owner) => {
+                        let (cname, _) = types::split_type(&owner);
+                        self.error(format!("The setter '{}' isn't defined for the class '{}'.", name, cname), left);
+                    }
+                    None => {}
+                }
+            }
+
+            NodeType::CollAccess(_, _) => {
+                let owner = self.tc_expression(&left.children[0]);
+                let owner = self.non_null_receiver(&owner, "method", "[]=", left);
+                let elemtype = self.tc_index(&owner, "[]=", left);
+                self.check_assign(&t, &elemtype, right);
+            }
+
+            _ => {
+                self.tc_expression(left);
+            }
+        }
+    }
+
+
+    fn tc_condition(&mut self, node: &Node) {
+        let t = self.tc_expression(node);
+        if self.known(&t) && t != "bool" && t != "dynamic" {
+            self.error("Conditions must have a static type of 'bool'.", node);
+        }
+    }
+
+
+    /// Promote local variables by a condition known to be true, or
+    /// false. Where x is T or x != null holds, x has type T or is not null.
+    /// Variables are also promoted by assignment and by x!.
+    fn promote(&mut self, cond: &Node, holds: bool) {
+
+        match &cond.nodetype {
+
+            NodeType::Is(typ, negated, _, _) if *negated != holds => {
+                if let Some(name) = self.local_name(&cond.children[0]) {
+                    self.promote_to(&name, typ);
+                }
+            }
+
+            NodeType::Equal(_, _) if !holds => {
+                let tested = match (&cond.children[0].nodetype, &cond.children[1].nodetype) {
+                    (_, NodeType::Null(_, _)) => &cond.children[0],
+                    (NodeType::Null(_, _), _) => &cond.children[1],
+                    _ => return
+                };
+                if let Some(name) = self.local_name(tested) {
+                    let t = self.lookup_local(&name).unwrap();
+                    self.promote_to(&name, t.trim_end_matches('?'));
+                }
+            }
+
+            NodeType::Not(_, _) => {
+                self.promote(&cond.children[0], !holds);
+            }
+
+            // Both hold, or neither does.
+            NodeType::LogAnd(_, _) if holds => {
+                self.promote(&cond.children[0], true);
+                self.promote(&cond.children[1], true);
+            }
+            NodeType::LogOr(_, _) if !holds => {
+                self.promote(&cond.children[0], false);
+                self.promote(&cond.children[1], false);
+            }
+
+            _ => {}
+        }
+    }
+
+
+    /// The name of a local variable, if the node is one.
+    fn local_name(&self, node: &Node) -> Option<String> {
+        if let NodeType::Name(name, _, _) = &node.nodetype {
+            if node.children.is_empty() && self.lookup_local(name).is_some() {
+                return Some(name.clone());
+            }
+        }
+        None
+    }
+
+
+    fn tc_expression(&mut self, node: &Node) -> String {
+
+        match &node.nodetype {
+
+            NodeType::Int(_, _, _) => String::from("int"),
+            NodeType::Double(_, _, _) => String::from("double"),
+            NodeType::Str(_, _, _) => String::from("String"),
+            NodeType::Bool(_, _, _) => String::from("bool"),
+            NodeType::Null(_, _) => String::from("Null"),
+
+            NodeType::SwitchExpr(_, _) => self.tc_switch(node),
+
+            NodeType::StrInterpol(_, _, _) => {
+                for c in &node.children {
+                    self.tc_expression(c);
+                }
+                String::from("String")
+            }
+
+            NodeType::Add(_, _) |
+            NodeType::Sub(_, _) |
+            NodeType::Mul(_, _) |
+            NodeType::Div(_, _) |
+            NodeType::TruncDiv(_, _) => self.tc_arithmetic(node),
+
+            NodeType::PreIncrement(_, _) |
+            NodeType::PostIncrement(_, _) |
+            NodeType::PreDecrement(_, _) |
+            NodeType::PostDecrement(_, _) => self.tc_expression(&node.children[0]),
+
+            NodeType::Not(_, _) => {
+                self.tc_condition(&node.children[0]);
+                String::from("bool")
+            }
+
+            NodeType::LogOr(_, _) |
+            NodeType::LogAnd(_, _) => {
+                // The right operand runs only if the left one holds, for &&.
+                let holds = matches!(node.nodetype, NodeType::LogAnd(_, _));
+                self.tc_condition(&node.children[0]);
+                self.scopes.push(HashMap::new());
+                self.promote(&node.children[0], holds);
+                self.tc_condition(&node.children[1]);
+                self.scopes.pop();
+                String::from("bool")
+            }
+
+            NodeType::LessThan(_, _) |
+            NodeType::GreaterThan(_, _) |
+            NodeType::LessOrEq(_, _) |
+            NodeType::GreaterOrEq(_, _) => {
+                let t1 = self.tc_expression(&node.children[0]);
+                let t2 = self.tc_expression(&node.children[1]);
+                if is_number(&t1) {
+                    self.check_operand(&t2, "num", &node.children[1]);
+                    return String::from("bool");
+                }
+                self.user_operator(&t1, &t2, node).unwrap_or(String::from("bool"))
+            }
+
+            NodeType::Equal(_, _) => {
+                self.tc_expression(&node.children[0]);
+                self.tc_expression(&node.children[1]);
+                String::from("bool")
+            }
+
+            NodeType::BitOr(_, _) |
+            NodeType::BitXor(_, _) |
+            NodeType::BitAnd(_, _) => {
+                let t1 = self.tc_expression(&node.children[0]);
+                let t2 = self.tc_expression(&node.children[1]);
+                if t1 == "int" && t2 == "int" {
+                    return String::from("int");
+                }
+                String::from("dynamic")
+            }
+
+            NodeType::Is(typ, _, _, _) => {
+                self.hastype(typ, node);
+                self.tc_expression(&node.children[0]);
+                String::from("bool")
+            }
+
+            NodeType::As(typ, _, _) => {
+                self.hastype(typ, node);
+                self.tc_expression(&node.children[0]);
+                typ.clone()
+            }
+
+            NodeType::NullCheck(_, _) => {
+                let t = self.tc_expression(&node.children[0]);
+                let t = String::from(t.trim_end_matches('?'));
+                // After x!, x is not null.
+                if let Some(name) = self.local_name(&node.children[0]) {
+                    self.promote_to(&name, &t);
+                }
+                t
+            }
+
+            NodeType::Await(_, _) => {
+                let t = self.tc_expression(&node.children[0]);
+                self.awaited_type(&t)
+            }
+
+            NodeType::Late(_, _) => {
+                match node.children.first() {
+                    Some(initexpr) => self.tc_expression(initexpr),
+                    None => String::from("dynamic")
+                }
+            }
+
+            NodeType::Name(name, _, _) => self.tc_name(name, node),
+
+            NodeType::NamedArg(_, _, _) => self.tc_expression(&node.children[0]),
+
+            NodeType::CollAccess(_, _) => {
+                let owner = self.tc_expression(&node.children[0]);
+                let owner = self.non_null_receiver(&owner, "method", "[]", node);
+                self.tc_index(&owner, "[]", node)
+            }
+
+            NodeType::FunCall(name, _, _) => self.tc_call(name, node),
+
+            NodeType::MethodCall(name, owner, _, _, _) => self.tc_method_call(name, owner, node),
+
+            NodeType::List(_, _, _) |
+            NodeType::Set(_, _, _) => self.tc_list(node),
+
+            NodeType::Map(_, _, _, _) => self.tc_map(node),
+
+            NodeType::SuperRef(cname, _, _) => {
+                let parent = self.state.objsys.get_class(cname);
+                if parent.parent == "" {
+                    return String::from("dynamic");
+                }
+                types::make_type(&parent.parent, &parent.parent_typeargs)
+            }
+
+            NodeType::This(_, _) => {
+                match &self.this {
+                    Some(t) => t.clone(),
+                    None => {
+                        self.error("Expected identifier, but got 'this'.", node);
+                        String::from("dynamic")
+                    }
+                }
+            }
+
+            NodeType::Assign(_, _) => {
+                self.tc_assign(node);
+                String::from("dynamic")
+            }
+
+            _ => {
+                for c in &node.children {
+                    self.tc_expression(c);
+                }
+                String::from("dynamic")
+            }
+        }
+    }
+
+
+    /// The type of awaiting a value of the type, as int for Future<int>.
+    fn awaited_type(&self, t: &str) -> String {
+        match types::as_super(t, "Future", self.state) {
+            Some(args) => args[0].clone(),
+            None => String::from(t)
+        }
+    }
+
+
+    fn tc_arithmetic(&mut self, node: &Node) -> String {
+
+        if node.children.len() == 1 {
+            // Unary minus.
+            let t = self.tc_expression(&node.children[0]);
+            if let Some(Member::Method(_, _, rettype)) = self.member(&t, "unary-") {
+                return rettype;
+            }
+            return t;
+        }
+
+        let t1 = self.tc_expression(&node.children[0]);
+        let t1 = self.non_null_receiver(&t1, "operator", &node.nodetype.to_string(), node);
+        let t2 = self.tc_expression(&node.children[1]);
+
+        if is_number(&t1) {
+            if !self.check_operand(&t2, "num", &node.children[1]) {
+                return String::from("dynamic");
+            }
+            return match node.nodetype {
+                NodeType::Div(_, _) => String::from("double"),
+                NodeType::TruncDiv(_, _) => String::from("int"),
+                _ if t2 == "dynamic" => String::from("dynamic"),
+                _ if t1 == "double" || t2 == "double" => String::from("double"),
+                _ if t1 == "int" && t2 == "int" => String::from("int"),
+                _ => String::from("num")
+            }
+        }
+
+        if t1 == "String" {
+            match node.nodetype {
+                NodeType::Add(_, _) => {
+                    self.check_operand(&t2, "String", &node.children[1]);
+                }
+                NodeType::Mul(_, _) => {
+                    self.check_operand(&t2, "int", &node.children[1]);
+                }
+                _ => {
+                    self.error(format!("The operator '{}' isn't defined for the class 'String'.", node.nodetype), node);
+                }
+            }
+            return t1;
+        }
+        self.user_operator(&t1, &t2, node).unwrap_or(String::from("dynamic"))
+    }
+
+
+    /// The type of a user defined operator on the left operand,
+    /// as in d1 + d2 for Durations, checking the right operand.
+    fn user_operator(&mut self, t1: &str, t2: &str, node: &Node) -> Option<String> {
+        match self.member(t1, &node.nodetype.to_string()) {
+            Some(Member::Method(params, _, rettype)) => {
+                self.check_operand(t2, &params[0].typ, &node.children[1]);
+                Some(rettype)
+            }
+            _ => None
+        }
+    }
+
+
+    /// The element type of an index expression, as read with [] or written
+    /// with []=. User classes, like Map, declare the index operators.
+    fn tc_index(&mut self, owner: &str, op: &str, node: &Node) -> String {
+        let index = self.tc_expression(&node.children[1]);
+        if let Some(Member::Method(params, _, rettype)) = self.member(owner, op) {
+            self.check_operand(&index, &params[0].typ, &node.children[1]);
+            if op == "[]=" {
+                return params[1].typ.clone();
+            }
+            return rettype;
+        }
+        if owner == "String" {
+            return String::from(owner);
+        }
+        match types::as_super(owner, "List", self.state) {
+            Some(args) => {
+                self.check_operand(&index, "int", &node.children[1]);
+                args[0].clone()
+            }
+            None => String::from("dynamic")
+        }
+    }
+
+
+    /// Check the type of a right operand, which can be dynamic.
+    fn check_operand(&mut self, t: &str, expected: &str, node: &Node) -> bool {
+        if self.assignable(t, expected) {
+            return true;
+        }
+        self.error(
+            format!("The argument type '{}' can't be assigned to the parameter type '{}'.", t, expected),
+            node
+        );
+        false
+    }
+
+
+    fn tc_name(&mut self, name: &str, node: &Node) -> String {
+
+        if !node.children.is_empty() {
+            let owner = &node.children[0];
+            if let NodeType::SuperRef(_, _, _) = owner.nodetype {
+                return match self.super_member(owner, name, "getter", node) {
+                    Some(Member::Field(ftype)) |
+                    Some(Member::Getter(ftype)) => ftype,
+                    Some(Member::Method(params, typeparams, rettype)) => method_type(&params, &typeparams, &rettype),
+                    None => String::from("dynamic")
+                };
+            }
+            if self.is_type_literal(owner) {
+                if let NodeType::Name(cname, _, _) = &owner.nodetype {
+                    if name == "new" && self.state.objsys.has_class(cname) {
+                        self.instantiable(cname, node);
+                        return self.constructor_type(cname);
+                    }
+                }
+                // Static members, like double.nan.
+                return String::from("dynamic");
+            }
+            let t = self.tc_expression(owner);
+            let t = if is_object_member(name) {
+                String::from(t.trim_end_matches('?'))
+            }
+            else {
+                self.non_null_receiver(&t, "property", name, node)
+            };
+            return match self.member(&t, name) {
+                Some(Member::Field(ftype)) |
+                Some(Member::Getter(ftype)) => ftype,
+                Some(Member::Method(params, typeparams, rettype)) => method_type(&params, &typeparams, &rettype),
+                None if name == "runtimeType" => String::from("Type"),
+                None if name == "hashCode" => String::from("int"),
+                None if self.is_class_type(&t) => {
+                    let (cname, _) = types::split_type(&t);
+                    self.error(format!("The getter '{}' isn't defined for the class '{}'.", name, cname), node);
+                    String::from("dynamic")
+                }
+                None => String::from("dynamic")
+            }
+        }
+
+        if let Some(t) = self.variable(name) {
+            return t;
+        }
+        if let Some(this) = self.this.clone() {
+            if let Some(Member::Method(params, typeparams, rettype)) = self.member(&this, name) {
+                return method_type(&params, &typeparams, &rettype);
+            }
+        }
+        if let Some(n) = self.global(name) {
+            if let NodeType::FunDef(_, _, _, _, _) = n.nodetype {
+                return fundef_type(n);
+            }
+        }
+        if self.is_type_name(name) {
+            if name.contains('<') {
+                self.hastype(name, node);
+            }
+            return String::from("Type");
+        }
+        if let Some(f) = builtin::tear_off(name, self.state) {
+            return types::type_name(&f, self.state);
+        }
+        if builtin::has_function(name) {
+            return String::from("Function");
+        }
+        self.error(format!("Undefined name '{}'.", name), node);
+        String::from("dynamic")
+    }
+
+
+    fn tc_call(&mut self, name: &str, node: &Node) -> String {
+
+        let typeargs = self.typeargs(node);
+
+        if let Some(t) = self.lookup_local(name) {
+            return self.tc_call_value(&t, node);
+        }
+
+        if let Some(n) = self.global(name) {
+            match &n.nodetype {
+                NodeType::FunDef(rettype, _, _, _, _) => {
+                    let params = ParamObj::from_paramlist(&n.children[0]);
+                    let typeparams: Vec<TypeParam> = match n.children.get(2) {
+                        Some(Node { nodetype: NodeType::TypeParams(tparams, _, _), .. }) => tparams.clone(),
+                        _ => Vec::new()
+                    };
+                    return self.tc_method_args(name, &params, &typeparams, rettype, typeargs, node);
+                }
+                NodeType::Constructor(cname, _, _, _, _, _, _) => {
+                    let cname = cname.clone();
+                    self.instantiable(&cname, node);
+                    return self.tc_constructor_call(&cname, &node.children[0], typeargs, node);
+                }
+                _ => {
+                    let t = self.variable(name).unwrap_or(String::from("dynamic"));
+                    return self.tc_call_value(&t, node);
+                }
+            }
+        }
+
+        if let Some(this) = self.this.clone() {
+            match self.member(&this, name) {
+                Some(Member::Method(params, typeparams, rettype)) => {
+                    return self.tc_method_args(name, &params, &typeparams, &rettype, typeargs, node);
+                }
+                Some(Member::Field(t)) |
+                Some(Member::Getter(t)) => {
+                    return self.tc_call_value(&t, node);
+                }
+                None => {}
+            }
+        }
+
+        self.tc_args(&node.children[0]);
+        if builtin::has_function(name) {
+            return String::from("dynamic");
+        }
+        self.error(format!("Method not found: '{}'.", name), node);
+        String::from("dynamic")
+    }
+
+
+    /// Check a call of a constructor, giving the type of the new instance.
+    fn tc_constructor_call(&mut self, cname: &str, args: &Node, typeargs: Option<Vec<String>>, node: &Node) -> String {
+
+        let state = self.state;
+        let class = state.objsys.get_class(cname);
+        let params = self.constructor_params(cname);
+
+        let explicit = typeargs.is_some();
+        let bindings = bind_typeparams(&class.typeparams, typeargs);
+        if explicit {
+            self.check_bounds(&class.typeparams, &bindings, cname, false, node);
+        }
+        let instance_type = types::make_type(cname, &class.typeparams.iter().map(|p| bindings[&p.name].clone()).collect::<Vec<String>>());
+
+        // Initializing formals have the type of their field.
+        let ptypes: Vec<String> = params.iter().map(|p| {
+            if p.fieldinit {
+                return match self.member(&instance_type, &p.name) {
+                    Some(Member::Field(t)) => t,
+                    _ => String::from("dynamic")
+                }
+            }
+            types::substitute(&p.typ, &bindings)
+        }).collect();
+
+        let argtypes = self.tc_call_args(&params, &ptypes, args, node);
+
+        if explicit || class.typeparams.is_empty() {
+            return instance_type;
+        }
+        // The type arguments are inferred at runtime.
+        let declared: Vec<String> = params.iter().map(|p| {
+            match class.fields.iter().find(|(_, fname, _)| p.fieldinit && *fname == p.name) {
+                Some((ftype, _, _)) => ftype.clone(),
+                None => p.typ.clone()
+            }
+        }).collect();
+        self.check_inferred(&class.typeparams, &declared, &argtypes, cname, node);
+        types::make_type(cname, &vec![String::from("dynamic"); class.typeparams.len()])
+    }
+
+
+    /// Report abstract classes, which sealed classes are too, being instantiated,
+    /// and List, whose lists are made by literals.
+    fn instantiable(&mut self, cname: &str, node: &Node) {
+        let class = self.state.objsys.get_class(cname);
+        if class.is_abstract || class.modifier == "sealed" {
+            // As dart.
+            self.error(format!("The class '{}' is abstract and can't be instantiated.", cname), node);
+        }
+        if cname == "List" {
+            // As dart.
+            self.error("Can't use the default List constructor.", node);
+        }
+    }
+
+
+    fn constructor_params(&self, cname: &str) -> Vec<ParamObj> {
+        match self.global(cname).map(|n| &n.nodetype) {
+            Some(NodeType::Constructor(_, paramlist, _, _, _, _, _)) if matches!(paramlist.nodetype, NodeType::ParamList(_, _)) => {
+                ParamObj::from_paramlist(paramlist)
+            }
+            _ => Vec::new()
+        }
+    }
+
+
+    /// The function type of a constructor torn off its class, as by Point.new.
+    fn constructor_type(&self, cname: &str) -> String {
+
+        let class = self.state.objsys.get_class(cname);
+        let typeparams: Vec<String> = class.typeparams.iter().map(|p| p.name.clone()).collect();
+        let instance_type = types::make_type(cname, &typeparams);
+
+        // Initializing formals have the type of their field.
+        let params: Vec<ParamObj> = self.constructor_params(cname).into_iter().map(|mut p| {
+            if p.fieldinit {
+                if let Some(Member::Field(t)) = self.member(&instance_type, &p.name) {
+                    p.typ = t;
+                }
+            }
+            p
+        }).collect();
+        types::fn_type(&instance_type, &class.typeparams, &params)
+    }
 
 
-    if t == "void"
-    || t == "bool"
-    || t == "int"
-    || t == "double"
-    || t == "num"
-    || t == "String" {
-        return true;
-    }
+    fn tc_method_call(&mut self, name: &str, owner: &Node, node: &Node) -> String {
 
-    if state.has_global(&t) {
-        let funcnode = state.get_global(&t);
-        if let NodeType::Constructor(_, _, _, _, _, _, _) = funcnode.nodetype {
-            return true;
+        let typeargs = self.typeargs(node);
+
+        if self.is_type_literal(owner) {
+            // Static methods, like int.parse().
+            self.tc_args(&node.children[0]);
+            return String::from("dynamic");
+        }
+
+        if let NodeType::SuperRef(_, _, _) = owner.nodetype {
+            return match self.super_member(owner, name, "method", node) {
+                Some(Member::Method(params, typeparams, rettype)) => {
+                    self.tc_method_args(name, &params, &typeparams, &rettype, typeargs, node)
+                }
+                Some(Member::Field(t)) |
+                Some(Member::Getter(t)) => self.tc_call_value(&t, node),
+                None => {
+                    self.tc_args(&node.children[0]);
+                    String::from("dynamic")
+                }
+            };
+        }
+
+        let t = self.tc_expression(owner);
+        let t = if name == "toString" { t } else { self.non_null_receiver(&t, "method", name, node) };
+
+        match self.member(&t, name) {
+            Some(Member::Method(params, typeparams, rettype)) => {
+                self.tc_method_args(name, &params, &typeparams, &rettype, typeargs, node)
+            }
+            Some(Member::Field(t)) |
+            Some(Member::Getter(t)) => {
+                self.tc_call_value(&t, node)
+            }
+            None => {
+                self.tc_args(&node.children[0]);
+                if name == "toString" {
+                    return String::from("String");
+                }
+                if self.is_class_type(&t) {
+                    let (cname, _) = types::split_type(&t);
+                    self.error(format!("The method '{}' isn't defined for the class '{}'.", name, cname), node);
+                }
+                String::from("dynamic")
+            }
         }
     }
-    return false;
-}
 
 
+    /// Check a call of a function or method, giving its return type.
+    fn tc_method_args(
+        &mut self,
+        name: &str,
+        params: &[ParamObj],
+        typeparams: &[TypeParam],
+        rettype: &str,
+        typeargs: Option<Vec<String>>,
+        node: &Node) -> String {
+
+        let explicit = typeargs.is_some();
+        let bindings = bind_typeparams(typeparams, typeargs);
+        if explicit {
+            self.check_bounds(typeparams, &bindings, name, false, node);
+        }
+        let ptypes: Vec<String> = params.iter().map(|p| types::substitute(&p.typ, &bindings)).collect();
+        let argtypes = self.tc_call_args(params, &ptypes, &node.children[0], node);
+        if !explicit {
+            let declared: Vec<String> = params.iter().map(|p| p.typ.clone()).collect();
+            self.check_inferred(typeparams, &declared, &argtypes, name, node);
+        }
+        types::substitute(rettype, &bindings)
+    }
 
-pub fn typecheck(state: &mut State) {
 
+    /// Report type arguments that are not within the bounds of their
+    /// type parameters. A bound can refer to the type parameters, as in
+    /// T extends Comparable<T>.
+    fn check_bounds(&mut self, typeparams: &[TypeParam], bindings: &HashMap<String, String>, owner: &str, inferred: bool, node: &Node) {
+        for p in typeparams {
+            let arg = &bindings[&p.name];
+            let bound = types::substitute(&p.bound, bindings);
+            if !self.assignable(arg, &bound) {
+                // As dart.
+                let what = if inferred { "Inferred type argument" } else { "Type argument" };
+                self.error(
+                    format!("{} '{}' doesn't conform to the bound '{}' of the type variable '{}' on '{}'.", what, arg, bound, p.name, owner),
+                    node
+                );
+            }
+        }
+    }
 
-    let globals = &mut state.globals;
 
+    /// Check the type arguments left out of a call against the bounds, as
+    /// inferred from the arguments given for parameters of just a type
+    /// parameter. Those that can't be told this way are left out.
+    fn check_inferred(&mut self, typeparams: &[TypeParam], declared: &[String], argtypes: &[Option<String>], owner: &str, node: &Node) {
 
-    for n in globals {
+        let mut bindings = HashMap::new();
+        for p in typeparams {
+            let inferred = declared.iter().zip(argtypes)
+                .filter(|(t, _)| **t == p.name)
+                .filter_map(|(_, a)| a.clone())
+                .reduce(|a, b| types::upper_bound(&a, &b, self.state));
+            bindings.insert(p.name.clone(), inferred.unwrap_or(String::from("dynamic")));
+        }
+        self.check_bounds(typeparams, &bindings, owner, true, node);
+    }
 
-        match &n.nodetype {
 
-            NodeType::FunDef(typ, name, filename, _, _) => {
-                println!("Typechecking: {} in {}", name, filename);
+    /// Check the number, names and types of the arguments of a call,
+    /// giving the types of the arguments by parameter.
+    fn tc_call_args(&mut self, params: &[ParamObj], ptypes: &[String], args: &Node, node: &Node) -> Vec<Option<String>> {
 
-                let oldfilepath = state.filepath.clone();
-                state.filepath = filename.clone();
+        let mut argtypes = vec![None; params.len()];
+        let positional: Vec<usize> = (0 .. params.len()).filter(|i| !params[*i].named).collect();
+        let required = positional.iter().filter(|i| !params[**i].optional).count();
+        let mut given = 0;
+        let mut named: Vec<&String> = Vec::new();
 
-                // if !hastype(&typ, state) {
-                    // panic!("Type not found: {}", typ);
-                // }
-                // if !state.has_type(&typ) {
-                //     panic!("Type not found: {}", typ);
-                // }
+        for arg in &args.children {
+            let t = self.tc_expression(arg);
 
-                tc_block(&mut n.children[1]);
+            let param = match &arg.nodetype {
+                NodeType::NamedArg(argname, _, _) => {
+                    named.push(argname);
+                    let found = (0 .. params.len()).find(|i| params[*i].named && params[*i].name == *argname);
+                    if found.is_none() {
+                        self.error(format!("No named parameter with the name '{}'.", argname), arg);
+                    }
+                    found
+                }
+                _ => {
+                    given += 1;
+                    positional.get(given - 1).cloned()
+                }
+            };
 
-                state.filepath = oldfilepath;
+            if let Some(i) = param {
+                let t = self.literal_type(&t, &ptypes[i], arg);
+                if !self.assignable(&t, &ptypes[i]) {
+                    self.error(
+                        format!("The argument type '{}' can't be assigned to the parameter type '{}'.", t, types::expand(&ptypes[i], self.state)),
+                        arg
+                    );
+                }
+                argtypes[i] = Some(t);
             }
+        }
 
-            NodeType::Constructor(name, _, _, _, filename, _, _) => {
-                // println!("Typechecking: {} in {}", name, filename)
+        if given > positional.len() {
+            self.error(
+                format!("Too many positional arguments: {} allowed, but {} found.", positional.len(), given),
+                node
+            );
+        }
+        else if given < required {
+            self.error(
+                format!("Too few positional arguments: {} required, {} given.", required, given),
+                node
+            );
+        }
+        for p in params {
+            if p.named && !p.optional && !named.contains(&&p.name) {
+                self.error(format!("Required named parameter '{}' must be provided.", p.name), node);
             }
+        }
+        argtypes
+    }
 
-            NodeType::ConstTopLazy(typ, name, _, _) => {
-                // println!("Typechecking: {}:{}", name, typ)
+
+    /// Check a call of a function held by a variable or field of the type,
+    /// giving the return type. Only function types and classes with a
+    /// call method tell what to expect.
+    fn tc_call_value(&mut self, t: &str, node: &Node) -> String {
+        if let Some(Member::Method(params, typeparams, rettype)) = self.member(t, "call") {
+            // An instance of a class with a call method.
+            return self.tc_method_args("call", &params, &typeparams, &rettype, None, node);
+        }
+        match types::parse_fn(&types::expand(t, self.state)) {
+            Some(f) if f.typeparams.is_empty() => {
+                let ptypes: Vec<String> = f.params.iter().map(|p| p.typ.clone()).collect();
+                self.tc_call_args(&f.params, &ptypes, &node.children[0], node);
+                f.ret
+            }
+            _ => {
+                if self.is_class_type(t) {
+                    self.error("The expression doesn't evaluate to a function, so it can't be invoked.", node);
+                }
+                self.tc_args(&node.children[0]);
+                String::from("dynamic")
             }
+        }
+    }
+
+
+    /// Check arguments where the callee is not known.
+    fn tc_args(&mut self, args: &Node) {
+        for arg in &args.children {
+            self.tc_expression(arg);
+        }
+    }
+
+
+    /// The type of a list or set literal.
+    fn tc_list(&mut self, node: &Node) -> String {
 
-            NodeType::TopVarLazy(typ, name, _, _) => {
-                // println!("Typechecking: {}:{}", name, typ)
+        let (name, elemtype) = match &node.nodetype {
+            NodeType::Set(elemtype, _, _) => ("Set", elemtype.clone()),
+            NodeType::List(elemtype, _, _) => ("List", elemtype.clone()),
+            _ => ("List", String::new())
+        };
+
+        let mut types_found = Vec::new();
+        for c in &node.children {
+            let mut t = self.tc_element(c);
+            if !elemtype.is_empty() {
+                t = self.literal_type(&t, &elemtype, c);
+            }
+            if !elemtype.is_empty() && !self.assignable(&t, &elemtype) {
+                self.error(
+                    format!("The element type '{}' can't be assigned to the {} type '{}'.", t, name.to_lowercase(), elemtype),
+                    c
+                );
             }
+            types_found.push(t);
+        }
 
-            _ => panic!("Not typechecking: {}", n)
+        if !elemtype.is_empty() {
+            self.hastype(&elemtype, node);
+            return format!("{}<{}>", name, elemtype);
         }
+        let inferred = types_found.into_iter()
+            .reduce(|a, b| types::upper_bound(&a, &b, self.state))
+            .unwrap_or(String::from("dynamic"));
+        format!("{}<{}>", name, inferred)
     }
 
-}
 
+    fn tc_map(&mut self, node: &Node) -> String {
 
-// pub fn tc_block(node: &mut Node, state: &State) {
-pub fn tc_block(node: &mut Node) {
+        let (keytype, valtype) = match &node.nodetype {
+            NodeType::Map(keytype, valtype, _, _) => (keytype.clone(), valtype.clone()),
+            _ => (String::new(), String::new())
+        };
 
+        let mut keys_found = Vec::new();
+        let mut vals_found = Vec::new();
+        for c in &node.children {
+            let (k, v) = self.tc_map_element(c, &keytype, &valtype);
+            keys_found.push(k);
+            vals_found.push(v);
+        }
 
-    for n in &mut node.children {
-        tc_statement(n);
+        if !keytype.is_empty() {
+            self.hastype(&keytype, node);
+            self.hastype(&valtype, node);
+            return format!("Map<{}, {}>", keytype, valtype);
+        }
+        let infer = |found: Vec<String>, state| found.into_iter()
+            .reduce(|a, b| types::upper_bound(&a, &b, state))
+            .unwrap_or(String::from("dynamic"));
+        format!("Map<{}, {}>", infer(keys_found, self.state), infer(vals_found, self.state))
     }
-}
 
 
-// pub fn tc_statement(node: &mut Node, state: &State) -> String {
-pub fn tc_statement(node: &mut Node) -> String {
+    /// The key and value types of the entries an element of a map literal
+    /// stands for. Keys and values are checked against the types given.
+    fn tc_map_element(&mut self, node: &Node, keytype: &str, valtype: &str) -> (String, String) {
 
-    match node.nodetype {
+        match &node.nodetype {
 
-        NodeType::Assign(_, _) => {
+            NodeType::MapPair(_, _) => {
+                let mut k = self.tc_expression(&node.children[0]);
+                let mut v = self.tc_expression(&node.children[1]);
+                if !keytype.is_empty() {
+                    k = self.literal_type(&k, keytype, &node.children[0]);
+                    self.check_entry(&k, "key", keytype, &node.children[0]);
+                }
+                if !valtype.is_empty() {
+                    v = self.literal_type(&v, valtype, &node.children[1]);
+                    self.check_entry(&v, "value", valtype, &node.children[1]);
+                }
+                (k, v)
+            }
 
-            let n = &node.children[0];
-            let v = &node.children[1];
+            NodeType::Spread(_, _, _) => {
+                let t = self.tc_expression(&node.children[0]);
+                let (k, v) = match types::as_super(t.trim_end_matches('?'), "Map", self.state) {
+                    Some(args) => (args[0].clone(), args[1].clone()),
+                    None => (String::from("dynamic"), String::from("dynamic"))
+                };
+                if !keytype.is_empty() {
+                    self.check_entry(&k, "key", keytype, &node.children[0]);
+                    self.check_entry(&v, "value", valtype, &node.children[0]);
+                }
+                (k, v)
+            }
 
-            match &n.nodetype {
+            NodeType::CollectionIf(_, _) => {
+                self.tc_condition(&node.children[0]);
+                let (k, v) = self.tc_map_element(&node.children[1], keytype, valtype);
+                match node.children.get(2) {
+                    Some(elsenode) => {
+                        let (k2, v2) = self.tc_map_element(elsenode, keytype, valtype);
+                        (types::upper_bound(&k, &k2, self.state), types::upper_bound(&v, &v2, self.state))
+                    }
+                    None => (k, v)
+                }
+            }
 
-                NodeType::TypedVar(typ, name, lc, cc) => {
+            NodeType::CollectionFor(_, _) => {
+                self.scopes.push(HashMap::new());
+                let kv = if node.children.len() == 3 {
+                    self.tc_loop_variable(node, "Iterable");
+                    self.tc_map_element(&node.children[2], keytype, valtype)
+                }
+                else {
+                    self.tc_statement(&node.children[0]);
+                    self.tc_condition(&node.children[1]);
+                    self.tc_expression(&node.children[2]);
+                    self.tc_map_element(&node.children[3], keytype, valtype)
+                };
+                self.scopes.pop();
+                kv
+            }
+
+            _ => {
+                self.tc_expression(node);
+                (String::from("dynamic"), String::from("dynamic"))
+            }
+        }
+    }
+
+
+    fn check_entry(&mut self, t: &str, part: &str, expected: &str, node: &Node) {
+        if !self.assignable(t, expected) {
+            self.error(
+                format!("The element type '{}' can't be assigned to the map {} type '{}'.", t, part, expected),
+                node
+            );
+        }
+    }
 
 
-                    //let nn = Node::new(NodeType::Return(1, 1));
-                    //n.children.push(n.clone());
+    /// The type of the elements an element of a list literal stands for.
+    fn tc_element(&mut self, node: &Node) -> String {
 
+        match &node.nodetype {
+
+            NodeType::Spread(_, _, _) => {
+                let t = self.tc_expression(&node.children[0]);
+                match types::as_super(&t, "Iterable", self.state) {
+                    Some(args) => args[0].clone(),
+                    None => String::from("dynamic")
+                }
+            }
 
-                    let res = tc_expression(v);
-                    if res != *typ && *typ != "var" {
-                        panic!("Typecheck expected: {}, got: {}", typ, res)
+            NodeType::CollectionIf(_, _) => {
+                self.tc_condition(&node.children[0]);
+                let t = self.tc_element(&node.children[1]);
+                match node.children.get(2) {
+                    Some(elsenode) => {
+                        let t2 = self.tc_element(elsenode);
+                        types::upper_bound(&t, &t2, self.state)
                     }
-                    let nn = Node::new(NodeType::TypedVar(res.clone(), name.clone(), *lc, *cc));
-                    node.children[0] = nn;
+                    None => t
+                }
+            }
 
-                    return res;
+            NodeType::CollectionFor(_, _) => {
+                self.scopes.push(HashMap::new());
+                let t = if node.children.len() == 3 {
+                    self.tc_loop_variable(node, "Iterable");
+                    self.tc_element(&node.children[2])
                 }
+                else {
+                    self.tc_statement(&node.children[0]);
+                    self.tc_condition(&node.children[1]);
+                    self.tc_expression(&node.children[2]);
+                    self.tc_element(&node.children[3])
+                };
+                self.scopes.pop();
+                t
+            }
 
-                _ => panic!("Not typechecking1")
+            _ => self.tc_expression(node)
+        }
+    }
+
+
+    /// The explicit type arguments of a call, if any.
+    fn typeargs(&mut self, node: &Node) -> Option<Vec<String>> {
+        match node.children.get(1) {
+            Some(Node { nodetype: NodeType::TypeArgs(args, _, _), .. }) => {
+                for a in args {
+                    self.hastype(a, node);
+                }
+                Some(args.clone())
             }
+            _ => None
         }
+    }
 
-        NodeType::FunCall(_, _, _) => String::from("void"),
 
-        _ => {
-            panic!("Not typechecking2")
+    /// Report a member used on a receiver that can be null,
+    /// and give the type of the receiver when it is not.
+    fn non_null_receiver(&mut self, t: &str, kind: &str, name: &str, node: &Node) -> String {
+        if t.ends_with('?') || t == "Null" {
+            let verb = if kind == "property" { "accessed" } else { "invoked" };
+            self.error(
+                format!("The {} '{}' can't be unconditionally {} because the receiver can be 'null'.", kind, name, verb),
+                node
+            );
+            if t == "Null" {
+                return String::from("dynamic");
+            }
         }
+        String::from(t.trim_end_matches('?'))
     }
-}
 
 
-// pub fn tc_expression(node: &Node, state: &State) -> String {
-pub fn tc_expression(node: &Node) -> String {
+    /// Whether null is a value of the type. Types that can't be told
+    /// for sure, like type parameters, are taken to allow null.
+    fn nullable(&self, typ: &str) -> bool {
+        !self.known(typ) || types::is_nullable(typ, self.state)
+    }
+
+
+    /// Whether the type can be told for sure. Type parameters in scope
+    /// are type variables, even where a class has the same name.
+    fn known(&self, typ: &str) -> bool {
+        types::is_known(typ, self.state) && !self.has_typevar(typ)
+    }
+
+
+    fn has_typevar(&self, typ: &str) -> bool {
+        !self.typeparams.is_empty()
+            && typ.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .any(|name| self.typeparams.iter().any(|p| p == name))
+    }
+
+
+    fn check_assign(&mut self, t: &str, declared: &str, node: &Node) {
+        let t = &self.literal_type(t, declared, node);
+        if !self.assignable(t, declared) {
+            self.error(
+                format!("A value of type '{}' can't be assigned to a variable of type '{}'.", t, types::expand(declared, self.state)),
+                node
+            );
+        }
+    }
+
+
+    /// The type of an expression given where the expected type is. An int
+    /// literal, possibly negated, is a double where an int can't be given
+    /// but a double can, as in `double d = 3;`.
+    fn literal_type(&mut self, t: &str, expected: &str, node: &Node) -> String {
+        let mut literal = node;
+        if let NodeType::NamedArg(_, _, _) = literal.nodetype {
+            literal = &literal.children[0];
+        }
+        if let NodeType::Sub(_, _) = literal.nodetype {
+            if literal.children.len() == 1 {
+                literal = &literal.children[0];
+            }
+        }
+        if let NodeType::Int(_, linenum, symnum) = literal.nodetype {
+            if t == "int" && !self.assignable("int", expected) && self.assignable("double", expected) {
+                self.double_literals.entry(self.filepath.clone()).or_default().insert((linenum, symnum));
+                return String::from("double");
+            }
+        }
+        String::from(t)
+    }
+
+
+    /// Whether a value of the static type can be given where the declared
+    /// type is expected. Only types that can be told for sure are compared.
+    fn assignable(&self, t: &str, declared: &str) -> bool {
+        if !self.known(t) || !self.known(declared) {
+            return true;
+        }
+        types::assignable(t, declared, self.state)
+    }
+
+
+    /// Report type names that are not declared or imported.
+    fn hastype(&mut self, typ: &str, node: &Node) {
+
+        if let Some(f) = types::parse_fn(typ.trim_end_matches('?')) {
+            let outer_typeparams = self.typeparams.clone();
+            self.typeparams.extend(f.typeparams.iter().map(|p| p.name.clone()));
+            self.hastype(&f.ret, node);
+            for p in &f.params {
+                self.hastype(&p.typ, node);
+            }
+            self.typeparams = outer_typeparams;
+            return;
+        }
+        let (name, args) = types::split_type(typ.trim_end_matches('?'));
+
+        if !self.is_type_name(&name) && !matches!(name.as_str(), "var" | "void") {
+            self.error(format!("Type '{}' not found.", name), node);
+        }
+        for a in &args {
+            self.hastype(a, node);
+        }
+        if self.state.objsys.has_class(&name) && !self.typeparams.contains(&name) {
+            let typeparams = &self.state.objsys.get_class(&name).typeparams;
+            if typeparams.len() == args.len() {
+                let bindings = bind_typeparams(typeparams, Some(args));
+                self.check_bounds(typeparams, &bindings, &name, false, node);
+            }
+        }
+    }
+
+
+    fn is_type_name(&self, name: &str) -> bool {
+        let (base, args) = types::split_type(name);
+        if !args.is_empty() {
+            return self.is_type_name(&base);
+        }
+        if types::is_builtin_type(name) || self.typeparams.iter().any(|p| p == name) || name.starts_with("__")
+            || self.state.objsys.has_typedef(name) {
+            return true;
+        }
+        matches!(self.global(name).map(|n| &n.nodetype), Some(NodeType::Constructor(_, _, _, _, _, _, _)))
+    }
+
+
+    /// Whether a node names a type, rather than a variable.
+    fn is_type_literal(&self, node: &Node) -> bool {
+        if let NodeType::Name(name, _, _) = &node.nodetype {
+            return node.children.is_empty()
+                && self.is_type_name(name)
+                && self.variable(name).is_none();
+        }
+        false
+    }
+
+
+    fn is_class_type(&self, typ: &str) -> bool {
+        let (name, _) = types::split_type(typ);
+        self.state.objsys.has_class(&name)
+    }
+
+
+    fn global(&self, name: &str) -> Option<&'a Node> {
+        let state = self.state;
+        state.looktables[&self.filepath].get(name).map(|i| &state.globals[*i])
+    }
 
 
-    match node.nodetype {
+    /// Declare a local variable. Its declared type is kept apart from
+    /// the type it is promoted to, as at runtime, for assignments.
+    fn declare(&mut self, name: &str, typ: &str) {
+        let t = if typ == "var" { "dynamic" } else { typ };
+        let scope = self.scopes.last_mut().unwrap();
+        scope.insert(String::from(name), String::from(t));
+        scope.insert(types::var_key(name), String::from(t));
+    }
 
-        NodeType::Int(i, _, _) => String::from("int"),
 
-        NodeType::Add(ln, cn) |
-        NodeType::Sub(ln, cn) |
-        NodeType::Mul(ln, cn) => {
+    /// Give a local variable a more specific type, in the current scope.
+    fn promote_to(&mut self, name: &str, typ: &str) {
+        self.scopes.last_mut().unwrap().insert(String::from(name), String::from(typ));
+    }
 
-            let t1 = tc_expression(&node.children[0]);
-            let t2 = tc_expression(&node.children[1]);
 
-            match t1.as_str() {
+    /// After a value of type t is assigned to a local variable, promotions
+    /// that t doesn't fit are undone, and a nullable variable is promoted
+    /// to not null, if t is.
+    fn promote_assigned(&mut self, name: &str, t: &str) {
+        let key = types::var_key(name);
+        let declared = match self.lookup_local(&key) {
+            Some(declared) => declared,
+            None => return
+        };
+        for i in (0 .. self.scopes.len()).rev() {
+            if let Some(promoted) = self.scopes[i].get(name).cloned() {
+                if !self.assignable(t, &promoted) {
+                    self.scopes[i].insert(String::from(name), declared.clone());
+                }
+            }
+            if self.scopes[i].contains_key(&key) {
+                break;
+            }
+        }
+        if self.nullable(&declared) && self.known(t) && !self.nullable(t) {
+            self.promote_to(name, declared.trim_end_matches('?'));
+        }
+    }
 
-                "int" => {
 
-                    match t2.as_str() {
-                        "int" => String::from("int"),
-                        "num" => String::from("num"),
-                        _ => panic!("no type check")
+    /// Undo the promotions of the local variables assigned in a loop, as
+    /// they may no longer hold when the loop comes round again.
+    fn demote_assigned(&mut self, node: &Node) {
+        for c in &node.children {
+            self.demote_assigned(c);
+        }
+        if let NodeType::Assign(_, _) = node.nodetype {
+            if let NodeType::Name(name, _, _) = &node.children[0].nodetype {
+                let key = types::var_key(name);
+                let declared = match self.lookup_local(&key) {
+                    Some(declared) => declared,
+                    None => return
+                };
+                for scope in self.scopes.iter_mut().rev() {
+                    if scope.contains_key(name) {
+                        scope.insert(name.clone(), declared.clone());
                     }
+                    if scope.contains_key(&key) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+
+    fn lookup_local(&self, name: &str) -> Option<String> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).cloned())
+    }
+
+
+    /// The type of a variable by the name: a local, a field
+    /// or getter of this, or a top level variable.
+    fn variable(&self, name: &str) -> Option<String> {
+
+        if let Some(t) = self.lookup_local(name) {
+            return Some(t);
+        }
+        if let Some(this) = &self.this {
+            match self.member(this, name) {
+                Some(Member::Field(t)) |
+                Some(Member::Getter(t)) => return Some(t),
+                _ => {}
+            }
+        }
+        match self.global(name).map(|n| &n.nodetype) {
+            Some(NodeType::TopVarLazy(typ, _, _, _)) |
+            Some(NodeType::TopVar(typ, _, _, _, _)) |
+            Some(NodeType::ConstTopLazy(typ, _, _, _)) |
+            Some(NodeType::ConstTopVar(typ, _, _, _, _)) => {
+                if typ == "var" {
+                    return Some(String::from("dynamic"));
+                }
+                Some(typ.clone())
+            }
+            _ => None
+        }
+    }
+
+
+    /// Look up a member on a class type, through the classes it inherits from.
+    /// A member of the parent of the class, as used through super.
+    fn super_member(&mut self, owner: &Node, name: &str, kind: &str, node: &Node) -> Option<Member> {
+        let t = self.tc_expression(owner);
+        let m = self.member(&t, name);
+        if m.is_none() {
+            // As dart.
+            self.error(format!("Superclass has no {} named '{}'.", kind, name), node);
+        }
+        m
+    }
+
+
+    fn member(&self, typ: &str, name: &str) -> Option<Member> {
+
+        let (cname, args) = types::split_type(&types::expand(typ.trim_end_matches('?'), self.state));
+        if !self.state.objsys.has_class(&cname) || self.typeparams.contains(&cname) {
+            return None;
+        }
+        let bindings = types::class_bindings(&cname, &args, self.state);
+        let mut class = self.state.objsys.get_class(&cname);
+
+        loop {
+            if let Some((ftype, _, _)) = class.fields.iter().find(|(_, fname, _)| fname == name) {
+                return Some(Member::Field(types::substitute(ftype, &bindings)));
+            }
+            if let Some(Object::Function(_, _, _, _, _, rettype)) = class.getters.get(name) {
+                return Some(Member::Getter(types::substitute(rettype, &bindings)));
+            }
+            if let Some(Object::Function(_, _, _, params, typeparams, rettype)) = class.methods.get(name) {
+                // Type parameters of the method shadow those of the class.
+                let mut bindings = bindings.clone();
+                for p in typeparams {
+                    bindings.remove(&p.name);
+                }
+                let params = params.iter().map(|p| {
+                    let mut p = p.clone();
+                    p.typ = types::substitute(&p.typ, &bindings);
+                    p
+                }).collect();
+                return Some(Member::Method(params, typeparams.clone(), types::substitute(rettype, &bindings)));
+            }
+            if class.parent == "" {
+                return None;
+            }
+            class = self.state.objsys.get_class(&class.parent);
+        }
+    }
+
+
+    /// Check a switch statement or expression, giving the type of the
+    /// expression. The cases must match every value of the subject when
+    /// it is an expression, or when the subject has a type like a sealed
+    /// class or bool, whose values the cases can be told to match.
+    fn tc_switch(&mut self, node: &Node) -> String {
+
+        let subject = self.tc_expression(&node.children[0]);
+        let is_expr = matches!(node.nodetype, NodeType::SwitchExpr(_, _));
+        let mut result: Option<String> = None;
+        let mut unguarded: Vec<&Node> = Vec::new();
+
+        for case in &node.children[1..] {
+            self.scopes.push(HashMap::new());
+            for head in &case.children[1..] {
+                self.tc_pattern(&head.children[0], &subject);
+                match head.children.get(1) {
+                    Some(guard) => self.tc_condition(guard),
+                    None => unguarded.push(&head.children[0])
+                }
+            }
+            if is_expr {
+                let t = self.tc_expression(&case.children[0]);
+                result = Some(match result {
+                    Some(r) => types::upper_bound(&r, &t, self.state),
+                    None => t
+                });
+            }
+            else {
+                self.tc_block(&case.children[0]);
+            }
+            self.scopes.pop();
+        }
+
+        match self.unmatched(&subject, &unguarded) {
+            None if !is_expr => {
+                self.exhaustive.insert(node.find_node_position());
+            }
+            Some(missing) if (is_expr && subject != "dynamic") || self.always_exhaustive(&subject) => {
+                self.error(
+                    format!("The type '{}' is not exhaustively matched by the switch cases since it doesn't match '{}'.",
+                        types::display(&subject), missing),
+                    node
+                );
+            }
+            _ => {}
+        }
+        result.unwrap_or(String::from("dynamic"))
+    }
+
+
+    /// Check a pattern matched against a value of the type, declaring its variables.
+    fn tc_pattern(&mut self, pattern: &Node, t: &str) {
+
+        match &pattern.nodetype {
+
+            NodeType::VarPattern(typ, name, _, _) => {
+                let typ = if typ == "var" { t } else { typ };
+                self.hastype(typ, pattern);
+                if name != "_" {
+                    self.declare(name, typ);
                 }
+            }
 
-                "num" => {
+            NodeType::ConstPattern(_, _) => {
+                self.tc_expression(&pattern.children[0]);
+            }
 
-                    match t2.as_str() {
-                        "int" => String::from("num"),
-                        "num" => String::from("num"),
-                        _ => panic!("no type check")
+            NodeType::ObjectPattern(typ, _, _) => {
+                self.hastype(typ, pattern);
+                for field in &pattern.children {
+                    if let NodeType::FieldPattern(name, _, _) = &field.nodetype {
+                        match self.member(typ, name) {
+                            Some(Member::Field(ftype)) |
+                            Some(Member::Getter(ftype)) => self.tc_pattern(&field.children[0], &ftype),
+                            Some(Member::Method(params, typeparams, rettype)) => {
+                                let ftype = method_type(&params, &typeparams, &rettype);
+                                self.tc_pattern(&field.children[0], &ftype);
+                            }
+                            None if self.is_class_type(typ) => {
+                                // As dart.
+                                self.error(format!("The getter '{}' isn't defined for the type '{}'.", name, typ), field);
+                                self.tc_pattern(&field.children[0], "dynamic");
+                            }
+                            None => self.tc_pattern(&field.children[0], "dynamic")
+                        }
                     }
                 }
+            }
+
+            _ => {}
+        }
+    }
+
+
+    /// Whether a switch over the type must be exhaustive even as a statement:
+    /// bool, a sealed class, or either of those made nullable.
+    fn always_exhaustive(&self, t: &str) -> bool {
+        let (name, _) = types::split_type(t.trim_end_matches('?'));
+        name == "bool" || name == "Null"
+            || (self.state.objsys.has_class(&name) && self.state.objsys.get_class(&name).modifier == "sealed")
+    }
+
+
+    /// A value of the type that none of the patterns match, written as
+    /// a pattern, like 'Square()', or None if they match every value.
+    fn unmatched(&self, t: &str, patterns: &[&Node]) -> Option<String> {
+
+        if patterns.iter().any(|p| self.covers(p, t)) {
+            return None;
+        }
+        if let Some(base) = t.strip_suffix('?') {
+            if !patterns.iter().any(|p| self.covers(p, "Null")) {
+                return Some(String::from("null"));
+            }
+            return self.unmatched(base, patterns);
+        }
+        if t == "bool" {
+            let matched = |b: bool| patterns.iter().any(|p| matches!(p.nodetype, NodeType::ConstPattern(_, _))
+                && matches!(p.children[0].nodetype, NodeType::Bool(v, _, _) if v == b));
+            return [true, false].into_iter().find(|b| !matched(*b)).map(|b| b.to_string());
+        }
+
+        let (name, _) = types::split_type(t);
+        let state = self.state;
+        if state.objsys.has_class(&name) && state.objsys.get_class(&name).modifier == "sealed" {
+            // The values of a sealed class are those of its subclasses.
+            return state.objsys.subclasses(&name).iter()
+                .find_map(|sub| {
+                    let args = vec![String::from("dynamic"); sub.typeparams.len()];
+                    self.unmatched(&types::make_type(&sub.name, &args), patterns)
+                });
+        }
+        Some(format!("{}()", types::display(t)))
+    }
+
+
+    /// Whether the pattern matches every value of the type.
+    fn covers(&self, pattern: &Node, t: &str) -> bool {
+
+        match &pattern.nodetype {
+
+            NodeType::VarPattern(typ, _, _, _) => typ == "var" || types::is_subtype(t, typ, self.state),
+
+            NodeType::ConstPattern(_, _) => {
+                t == "Null" && matches!(pattern.children[0].nodetype, NodeType::Null(_, _))
+            }
+
+            NodeType::ObjectPattern(typ, _, _) => {
+                types::is_subtype(t, typ, self.state) && pattern.children.iter().all(|field| match &field.nodetype {
+                    NodeType::FieldPattern(name, _, _) => match self.member(typ, name) {
+                        Some(Member::Field(ftype)) |
+                        Some(Member::Getter(ftype)) => self.covers(&field.children[0], &ftype),
+                        _ => false
+                    },
+                    _ => false
+                })
+            }
+
+            _ => false
+        }
+    }
+
+
+    /// Whether execution can continue after the statement.
+    fn completes(&self, node: &Node) -> bool {
 
+        match &node.nodetype {
 
-                _ => panic!("Type check failed")
+            NodeType::Return(_, _) => false,
+
+            NodeType::Block(_, _) => node.children.iter().all(|n| self.completes(n)),
+
+            NodeType::Conditional(_, _) => {
+                let has_else = node.children.iter().any(|c| matches!(c.nodetype, NodeType::Else(_, _)));
+                !has_else || node.children.iter().any(|c| match c.nodetype {
+                    NodeType::Else(_, _) => self.completes(&c.children[0]),
+                    _ => self.completes(&c.children[1])
+                })
+            }
+
+            NodeType::Switch(_, _) => {
+                !self.exhaustive.contains(&node.find_node_position())
+                    || node.children[1..].iter().any(|c| self.completes(&c.children[0]))
             }
+
+            NodeType::While(_, _) => !matches!(node.children[0].nodetype, NodeType::Bool(true, _, _)),
+
+            NodeType::DoWhile(_, _) => {
+                self.completes(&node.children[0]) && !matches!(node.children[1].nodetype, NodeType::Bool(true, _, _))
+            }
+
+            NodeType::FunCall(name, _, _) => name != "__CORE_THROW",
+
+            _ => true
         }
+    }
+}
+
 
-        _ => panic!("Type check not implemented")
+/// Bind type parameters to the explicit type arguments, or
+/// to dynamic when they are left to be inferred at runtime.
+fn bind_typeparams(typeparams: &[TypeParam], typeargs: Option<Vec<String>>) -> HashMap<String, String> {
+    let args = match typeargs {
+        Some(args) if args.len() == typeparams.len() => args,
+        _ => vec![String::from("dynamic"); typeparams.len()]
+    };
+    typeparams.iter().map(|p| p.name.clone()).zip(args).collect()
+}
+
+
+/// The function type of a function declaration.
+fn fundef_type(node: &Node) -> String {
+    let typeparams = match node.children.get(2) {
+        Some(Node { nodetype: NodeType::TypeParams(tparams, _, _), .. }) => tparams.clone(),
+        _ => Vec::new()
+    };
+    match &node.nodetype {
+        NodeType::FunDef(typ, _, _, _, _) => types::fn_type(typ, &typeparams, &ParamObj::from_paramlist(&node.children[0])),
+        _ => String::from("Function")
     }
 }
 
 
+/// The function type of a method, as torn off an instance.
+fn method_type(params: &[ParamObj], typeparams: &[TypeParam], rettype: &str) -> String {
+    types::fn_type(rettype, typeparams, params)
+}
+
+
+fn is_number(t: &str) -> bool {
+    matches!(t, "int" | "double" | "num")
+}
+
+
+/// Members that null has too, so they can be used on any receiver.
+fn is_object_member(name: &str) -> bool {
+    matches!(name, "runtimeType" | "hashCode")
+}
+
 
+/// Whether a function with the return type must return a value.
+fn must_return(typ: &str) -> bool {
+    !matches!(typ, "var" | "void" | "dynamic" | "Null") && !typ.ends_with('?')
+}
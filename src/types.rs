@@ -0,0 +1,793 @@
+// Types are kept as strings, written the way they are in the source,
+// like "int" or "Map<String, List<int>>".
+//
+// Function types are written the way Dart writes them in the source, like
+// "int Function(String, [int])", and typedef names stand for the type they
+// are declared as.
+//
+// Type arguments are reified. Instances of generic classes keep their
+// type arguments, and the type arguments of a call to a generic function
+// are bound in its call-frame.
+
+use std::collections::HashMap;
+use crate::object::{Object, ParamObj};
+use crate::objsys::RefKey;
+use crate::state::State;
+
+
+#[derive(Clone)]
+pub struct TypeParam {
+    pub name: String,
+    // The type given after 'extends', or dynamic.
+    pub bound: String
+}
+
+
+const BUILTIN_TYPES: [&str; 9] = ["int", "double", "num", "String", "bool", "Null", "Object", "dynamic", "Function"];
+
+
+pub fn is_builtin_type(name: &str) -> bool {
+    BUILTIN_TYPES.contains(&name)
+}
+
+
+/// A function type, like int Function(String, [int]). Positional
+/// parameters have no names.
+pub struct FnType {
+    pub ret: String,
+    pub typeparams: Vec<TypeParam>,
+    pub params: Vec<ParamObj>
+}
+
+
+impl FnType {
+
+    /// The function type with f applied to its return and parameter types.
+    fn map(&self, f: &dyn Fn(&str) -> String) -> String {
+        let params: Vec<ParamObj> = self.params.iter().map(|p| {
+            let mut p = p.clone();
+            p.typ = f(&p.typ);
+            p
+        }).collect();
+        fn_type(&f(&self.ret), &self.typeparams, &params)
+    }
+}
+
+
+/// Split a type into its name and type arguments.
+/// A function type is taken as a name.
+pub fn split_type(typ: &str) -> (String, Vec<String>) {
+
+    let start = match typ.find('<') {
+        Some(i) if !typ.ends_with(')') => i,
+        _ => return (String::from(typ), Vec::new())
+    };
+
+    let args = split_list(&typ[start + 1 .. typ.len() - 1]);
+    (String::from(&typ[.. start]), args)
+}
+
+
+/// Split a comma separated list of types, leaving the
+/// commas inside type arguments and parameter lists.
+fn split_list(s: &str) -> Vec<String> {
+
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut item = String::new();
+
+    for c in s.chars() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(String::from(item.trim()));
+                item.clear();
+                continue;
+            }
+            _ => {}
+        }
+        item.push(c);
+    }
+    if !item.trim().is_empty() {
+        items.push(String::from(item.trim()));
+    }
+    items
+}
+
+
+/// Write a function type the way Dart writes it in the source.
+pub fn fn_type(ret: &str, typeparams: &[TypeParam], params: &[ParamObj]) -> String {
+    format!("{} Function{}({})", declared(ret), fmt_typeparams(typeparams), fmt_params(params, &declared))
+}
+
+
+/// A declared type, where var and a left out type are dynamic.
+fn declared(typ: &str) -> String {
+    if typ == "var" || typ == "" {
+        return String::from("dynamic");
+    }
+    String::from(typ)
+}
+
+
+fn fmt_typeparams(typeparams: &[TypeParam]) -> String {
+    if typeparams.is_empty() {
+        return String::new();
+    }
+    let params: Vec<String> = typeparams.iter().map(|p| {
+        if p.bound == "dynamic" {
+            return p.name.clone();
+        }
+        format!("{} extends {}", p.name, p.bound)
+    }).collect();
+    format!("<{}>", params.join(", "))
+}
+
+
+/// The parameters of a function type, like int, [String] or {required int x}.
+fn fmt_params(params: &[ParamObj], show: &dyn Fn(&str) -> String) -> String {
+
+    let mut parts: Vec<String> = params.iter()
+        .filter(|p| !p.optional && !p.named)
+        .map(|p| show(&p.typ))
+        .collect();
+
+    let optional: Vec<String> = params.iter()
+        .filter(|p| p.optional && !p.named)
+        .map(|p| show(&p.typ))
+        .collect();
+    if !optional.is_empty() {
+        parts.push(format!("[{}]", optional.join(", ")));
+    }
+
+    let named: Vec<String> = params.iter()
+        .filter(|p| p.named)
+        .map(|p| format!("{}{} {}", if p.optional { "" } else { "required " }, show(&p.typ), p.name))
+        .collect();
+    if !named.is_empty() {
+        parts.push(format!("{{{}}}", named.join(", ")));
+    }
+    parts.join(", ")
+}
+
+
+/// Read a function type, or None if typ is not one.
+pub fn parse_fn(typ: &str) -> Option<FnType> {
+
+    if !typ.ends_with(')') {
+        return None;
+    }
+    let paren = matching_open(typ)?;
+    let mut head = &typ[.. paren];
+
+    let mut typeparams = Vec::new();
+    if head.ends_with('>') {
+        let angle = matching_open(head)?;
+        for p in split_list(&head[angle + 1 .. head.len() - 1]) {
+            typeparams.push(match p.split_once(" extends ") {
+                Some((name, bound)) => TypeParam { name: String::from(name), bound: String::from(bound) },
+                None => TypeParam { name: p, bound: String::from("dynamic") }
+            });
+        }
+        head = &head[.. angle];
+    }
+    let ret = head.strip_suffix("Function")?.trim();
+    if !head.ends_with(" Function") && !ret.is_empty() {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    for p in split_list(&typ[paren + 1 .. typ.len() - 1]) {
+        if let Some(inner) = p.strip_prefix('[') {
+            for t in split_list(&inner[.. inner.len() - 1]) {
+                params.push(fn_param(t, String::new(), true, false));
+            }
+        }
+        else if let Some(inner) = p.strip_prefix('{') {
+            for n in split_list(&inner[.. inner.len() - 1]) {
+                let (optional, n) = match n.strip_prefix("required ") {
+                    Some(rest) => (false, rest),
+                    None => (true, n.as_str())
+                };
+                let (t, name) = n.rsplit_once(' ')?;
+                params.push(fn_param(String::from(t), String::from(name), optional, true));
+            }
+        }
+        else {
+            params.push(fn_param(p, String::new(), false, false));
+        }
+    }
+    Some(FnType { ret: declared(ret), typeparams, params })
+}
+
+
+fn fn_param(typ: String, name: String, optional: bool, named: bool) -> ParamObj {
+    ParamObj { typ, name, fieldinit: false, optional, named, default: None }
+}
+
+
+/// The position of the bracket that opens the one typ ends with.
+fn matching_open(typ: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in typ.char_indices().rev() {
+        match c {
+            '>' | ')' | ']' | '}' => depth += 1,
+            '<' | '(' | '[' | '{' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+
+/// A type the way Dart shows it at runtime, where
+/// function types are written like (int, [int]) => String.
+pub fn display(typ: &str) -> String {
+
+    if let Some(base) = typ.strip_suffix('?') {
+        if parse_fn(base).is_some() {
+            return format!("({})?", display(base));
+        }
+        return format!("{}?", display(base));
+    }
+    if let Some(f) = parse_fn(typ) {
+        return format!("{}({}) => {}", fmt_typeparams(&f.typeparams), fmt_params(&f.params, &display), display(&f.ret));
+    }
+    let (name, args) = split_type(typ);
+    let args: Vec<String> = args.iter().map(|a| display(a)).collect();
+    make_type(&name, &args)
+}
+
+
+/// Replace the typedef names in typ by the types they stand for.
+pub fn expand(typ: &str, state: &State) -> String {
+
+    if let Some(base) = typ.strip_suffix('?') {
+        return nullable(&expand(base, state));
+    }
+    if let Some(f) = parse_fn(typ) {
+        return f.map(&|t| expand(t, state));
+    }
+
+    let (name, args) = split_type(typ);
+    let args: Vec<String> = args.iter().map(|a| expand(a, state)).collect();
+
+    if state.objsys.has_typedef(&name) {
+        let def = state.objsys.get_typedef(&name);
+        let bindings = def.typeparams.iter()
+            .map(|p| p.name.clone())
+            .zip(class_args(&def.typeparams, &args))
+            .collect();
+        return expand(&substitute(&def.typ, &bindings), state);
+    }
+    make_type(&name, &args)
+}
+
+
+pub fn make_type(name: &str, args: &[String]) -> String {
+    if args.is_empty() {
+        return String::from(name);
+    }
+    format!("{}<{}>", name, args.join(", "))
+}
+
+
+/// The call-frame key holding the type argument of a type parameter.
+pub fn frame_key(name: &str) -> String {
+    format!("<{}>", name)
+}
+
+
+/// The call-frame key holding the declared type of a variable.
+pub fn var_key(name: &str) -> String {
+    format!(":{}", name)
+}
+
+
+/// Replace the type parameters in typ with the type
+/// arguments bound where it is evaluated.
+pub fn resolve(typ: &str, state: &State) -> String {
+
+    if let Some(base) = typ.strip_suffix('?') {
+        return nullable(&resolve(base, state));
+    }
+
+    let key = frame_key(typ);
+    if state.stack.has(&key) {
+        if let Object::String(s) = state.stack.get(&key) {
+            return s.clone();
+        }
+    }
+    if let Some(f) = parse_fn(typ) {
+        return f.map(&|t| resolve(t, state));
+    }
+
+    let (name, args) = split_type(typ);
+
+    if args.is_empty() {
+        if state.objsys.has_this() {
+            if let Some(t) = instance_bindings(&state.objsys.get_this(), state).get(&name) {
+                return t.clone();
+            }
+        }
+        return expand(&name, state);
+    }
+
+    let resolved: Vec<String> = args.iter().map(|a| resolve(a, state)).collect();
+    expand(&make_type(&name, &resolved), state)
+}
+
+
+/// Replace the type parameters in typ by the bindings.
+pub fn substitute(typ: &str, bindings: &HashMap<String, String>) -> String {
+
+    if let Some(base) = typ.strip_suffix('?') {
+        return nullable(&substitute(base, bindings));
+    }
+    if let Some(f) = parse_fn(typ) {
+        return f.map(&|t| substitute(t, bindings));
+    }
+
+    let (name, args) = split_type(typ);
+
+    if args.is_empty() {
+        return match bindings.get(&name) {
+            Some(t) => t.clone(),
+            None => name
+        };
+    }
+    let substituted: Vec<String> = args.iter().map(|a| substitute(a, bindings)).collect();
+    make_type(&name, &substituted)
+}
+
+
+/// The type arguments of an instance, by the type parameters
+/// of its class and of the classes it inherits from.
+pub fn instance_bindings(rk: &RefKey, state: &State) -> HashMap<String, String> {
+    let inst = state.objsys.get_instance(rk);
+    class_bindings(&inst.classname, &inst.typeargs, state)
+}
+
+
+/// The type arguments of a class given the type arguments,
+/// by its type parameters and those of the classes it inherits from.
+pub fn class_bindings(name: &str, args: &[String], state: &State) -> HashMap<String, String> {
+
+    let mut class = state.objsys.get_class(name);
+    let mut args = class_args(&class.typeparams, args);
+    let mut bindings = HashMap::new();
+
+    loop {
+        for (param, arg) in class.typeparams.iter().zip(args) {
+            bindings.entry(param.name.clone()).or_insert(arg);
+        }
+        if class.parent == "" {
+            return bindings;
+        }
+        args = class.parent_typeargs.iter().map(|a| substitute(a, &bindings)).collect();
+        class = state.objsys.get_class(&class.parent);
+        args = class_args(&class.typeparams, &args);
+    }
+}
+
+
+/// The given type arguments, or the bounds when there are none.
+fn class_args(params: &[TypeParam], args: &[String]) -> Vec<String> {
+    if args.len() == params.len() {
+        return args.to_vec();
+    }
+    params.iter().map(|p| p.bound.clone()).collect()
+}
+
+
+/// The runtime type of an object, as Dart names it.
+pub fn type_name(obj: &Object, state: &State) -> String {
+    match obj {
+        Object::Int(_) => String::from("int"),
+        Object::Double(_) => String::from("double"),
+        Object::Bool(_) => String::from("bool"),
+        Object::String(_) => String::from("String"),
+        Object::Null => String::from("Null"),
+        Object::Reference(rk) => {
+            let inst = state.objsys.get_instance(rk);
+            let class = state.objsys.get_class(&inst.classname);
+            make_type(&inst.classname, &class_args(&class.typeparams, &inst.typeargs))
+        }
+        Object::Function(_, _, _, params, typeparams, rettype) => fn_type(rettype, typeparams, params),
+        Object::Constructor(consname, _, params, _, _) => fn_type(consname, &[], params),
+        Object::BoundMethod(_, method) => type_name(method, state),
+        _ => String::from("Function")
+    }
+}
+
+
+/// Infer the type arguments of a generic call from pairs of declared
+/// parameter types and the types of the arguments passed. A type
+/// parameter that is not used by the parameters gets its bound.
+pub fn infer(params: &[TypeParam], pairs: &[(String, String)], state: &State) -> Vec<String> {
+
+    params.iter().map(|p| {
+        let mut inferred: Option<String> = None;
+
+        for (declared, actual) in pairs {
+            if let Some(t) = unify(declared, actual, &p.name) {
+                inferred = Some(match inferred {
+                    Some(prev) => upper_bound(&prev, &t, state),
+                    None => t
+                });
+            }
+        }
+        inferred.unwrap_or(p.bound.clone())
+    }).collect()
+}
+
+
+/// Match a declared type against an actual one, giving
+/// what the type parameter must be for them to agree.
+fn unify(declared: &str, actual: &str, param: &str) -> Option<String> {
+
+    if declared == param {
+        return Some(String::from(actual));
+    }
+
+    let (dname, dargs) = split_type(declared);
+    let (aname, aargs) = split_type(actual);
+
+    if dname != aname || dargs.len() != aargs.len() {
+        return None;
+    }
+    dargs.iter().zip(aargs.iter()).find_map(|(d, a)| unify(d, a, param))
+}
+
+
+/// The least type that both types are subtypes of, as
+/// used for inferring the element type of list literals.
+pub fn upper_bound(a: &str, b: &str, state: &State) -> String {
+
+    if a == b {
+        return String::from(a);
+    }
+    if a == "dynamic" || b == "dynamic" {
+        return String::from("dynamic");
+    }
+    if a == "Null" {
+        return nullable(b);
+    }
+    if b == "Null" {
+        return nullable(a);
+    }
+    if a.ends_with('?') || b.ends_with('?') {
+        return nullable(&upper_bound(a.trim_end_matches('?'), b.trim_end_matches('?'), state));
+    }
+
+    let numbers = ["int", "double", "num"];
+    if numbers.contains(&a) && numbers.contains(&b) {
+        return String::from("num");
+    }
+    if parse_fn(a).is_some() && parse_fn(b).is_some() {
+        return String::from("Function");
+    }
+
+    let (aname, aargs) = split_type(a);
+    let (bname, bargs) = split_type(b);
+
+    if aname == bname && aargs.len() == bargs.len() {
+        let args: Vec<String> = aargs.iter().zip(bargs.iter())
+            .map(|(x, y)| upper_bound(x, y, state))
+            .collect();
+        return make_type(&aname, &args);
+    }
+
+    // The nearest class both inherit from.
+    let bsupers = superclasses(&bname, state);
+    for c in superclasses(&aname, state) {
+        if bsupers.contains(&c) {
+            let class = state.objsys.get_class(&c);
+            return make_type(&c, &class_args(&class.typeparams, &[]));
+        }
+    }
+    String::from("Object")
+}
+
+
+/// The class and the classes it inherits from.
+fn superclasses(name: &str, state: &State) -> Vec<String> {
+
+    let mut supers = Vec::new();
+    let mut current = String::from(name);
+
+    while state.objsys.has_class(&current) {
+        supers.push(current.clone());
+        current = state.objsys.get_class(&current).parent.clone();
+    }
+    supers
+}
+
+
+fn nullable(typ: &str) -> String {
+    if typ.ends_with('?') || typ == "dynamic" || typ == "Null" {
+        return String::from(typ);
+    }
+    format!("{}?", typ)
+}
+
+
+/// Whether sub is a subtype of sup. Type arguments are covariant,
+/// so a Box<int> is a Box<num>.
+pub fn is_subtype(sub: &str, sup: &str, state: &State) -> bool {
+    subtype(&expand(sub, state), &expand(sup, state), false, state)
+}
+
+
+/// Whether a value of type sub can be given where sup is declared.
+/// Dynamic type arguments, as left where inference falls short, fit.
+/// An instance of a class with a call method can be given
+/// where a function is expected, as if its call method was.
+pub fn assignable(sub: &str, sup: &str, state: &State) -> bool {
+    let sup = expand(sup, state);
+    if sup == "Function" || parse_fn(&sup).is_some() {
+        if let Some(t) = call_type(sub, state) {
+            return subtype(&t, &sup, true, state);
+        }
+    }
+    subtype(&expand(sub, state), &sup, true, state)
+}
+
+
+/// The function type of the call method of a class type, if it has one.
+pub fn call_type(typ: &str, state: &State) -> Option<String> {
+
+    let (name, args) = split_type(&expand(typ, state));
+    if !state.objsys.has_class(&name) {
+        return None;
+    }
+    let bindings = class_bindings(&name, &args, state);
+    let mut class = state.objsys.get_class(&name);
+
+    loop {
+        if let Some(Object::Function(_, _, _, params, typeparams, rettype)) = class.methods.get("call") {
+            return Some(substitute(&fn_type(rettype, typeparams, params), &bindings));
+        }
+        if class.parent == "" {
+            return None;
+        }
+        class = state.objsys.get_class(&class.parent);
+    }
+}
+
+
+/// Whether null is a value of the type, as for int?, dynamic and var.
+pub fn is_nullable(typ: &str, state: &State) -> bool {
+    !is_known(typ, state) || subtype("Null", &expand(typ, state), false, state)
+}
+
+
+/// Like is_subtype, but when loose, a dynamic type argument
+/// of sub fits any type argument of sup.
+fn subtype(sub: &str, sup: &str, loose: bool, state: &State) -> bool {
+
+    if sub == sup || sup == "dynamic" || sup == "void" || sup == "Object?" {
+        return true;
+    }
+    if loose && sub == "dynamic" {
+        return true;
+    }
+    if let Some(base) = sup.strip_suffix('?') {
+        return sub == "Null" || subtype(sub.trim_end_matches('?'), base, loose, state);
+    }
+    if sub == "Null" || sub == "dynamic" || sub.ends_with('?') {
+        return false;
+    }
+    if sup == "Object" {
+        return true;
+    }
+    if sup == "num" {
+        return sub == "int" || sub == "double";
+    }
+    if sup == "Function" {
+        return parse_fn(sub).is_some();
+    }
+    if let Some(supfn) = parse_fn(sup) {
+        return match parse_fn(sub) {
+            Some(subfn) => fn_subtype(&subfn, &supfn, loose, state),
+            None => false
+        };
+    }
+
+    let (supname, supargs) = split_type(sup);
+
+    match as_super(sub, &supname, state) {
+        Some(args) => supargs.is_empty() || (supargs.len() == args.len() &&
+            args.iter().zip(supargs.iter()).all(|(a, b)| subtype(a, b, loose, state))),
+        None => false
+    }
+}
+
+
+/// Whether a function of type sub can be given where sup is expected.
+/// It must take at least the arguments sup does, of the types or wider,
+/// and return the type of sup or narrower. A generic function is taken
+/// with the type arguments the types of sup give, as Dart instantiates
+/// it there. Otherwise generic ones must be equal.
+fn fn_subtype(sub: &FnType, sup: &FnType, loose: bool, state: &State) -> bool {
+
+    if !sub.typeparams.is_empty() && sup.typeparams.is_empty() {
+        let mut pairs = vec![(sub.ret.clone(), sup.ret.clone())];
+        pairs.extend(sub.params.iter().zip(&sup.params).map(|(p, q)| (p.typ.clone(), q.typ.clone())));
+        let args = infer(&sub.typeparams, &pairs, state);
+        if !sub.typeparams.iter().zip(&args).all(|(p, a)| subtype(a, &p.bound, loose, state)) {
+            return false;
+        }
+        let bindings = sub.typeparams.iter().map(|p| p.name.clone()).zip(args).collect();
+        let instantiated = FnType {
+            ret: substitute(&sub.ret, &bindings),
+            typeparams: Vec::new(),
+            params: sub.params.iter().map(|p| {
+                let mut p = p.clone();
+                p.typ = substitute(&p.typ, &bindings);
+                p
+            }).collect()
+        };
+        return fn_subtype(&instantiated, sup, loose, state);
+    }
+    if !sub.typeparams.is_empty() || !sup.typeparams.is_empty() {
+        return false;
+    }
+    if sup.ret != "void" && !subtype(&sub.ret, &sup.ret, loose, state) {
+        return false;
+    }
+
+    let subpos: Vec<&ParamObj> = sub.params.iter().filter(|p| !p.named).collect();
+    let suppos: Vec<&ParamObj> = sup.params.iter().filter(|p| !p.named).collect();
+    let required = |ps: &[&ParamObj]| ps.iter().filter(|p| !p.optional).count();
+
+    if required(&subpos) > required(&suppos) || subpos.len() < suppos.len() {
+        return false;
+    }
+    if !subpos.iter().zip(suppos.iter()).all(|(p, q)| subtype(&q.typ, &p.typ, loose, state)) {
+        return false;
+    }
+
+    let find = |f: &FnType, name: &str| f.params.iter().find(|p| p.named && p.name == name).cloned();
+
+    sup.params.iter().filter(|q| q.named).all(|q| match find(sub, &q.name) {
+        Some(p) => subtype(&q.typ, &p.typ, loose, state) && (p.optional || !q.optional),
+        None => false
+    })
+    && sub.params.iter().filter(|p| p.named && !p.optional).all(|p| find(sup, &p.name).is_some())
+}
+
+
+/// The Type object for a type, as given by runtimeType and type literals.
+pub fn type_object(typ: &str, state: &mut State) -> Object {
+    let mut inst = state.objsys.get_class("Type").instantiate();
+    inst.set_field(String::from("__name"), Object::String(display(typ)));
+    state.objsys.register_instance(*inst)
+}
+
+
+/// The type held by a Type object, or None for other objects.
+pub fn type_of_type_object(obj: &Object, state: &State) -> Option<String> {
+    if let Object::Reference(rk) = obj {
+        let inst = state.objsys.get_instance(rk);
+        if inst.classname == "Type" {
+            if let Object::String(s) = inst.get_field("__name") {
+                return Some(s);
+            }
+        }
+    }
+    None
+}
+
+
+/// The type a name stands for when used as an expression, like
+/// int or a class name, or a type parameter bound where it is used.
+pub fn type_literal(name: &str, state: &State) -> Option<String> {
+
+    // With type arguments, as in List<int>.
+    let (base, args) = split_type(name);
+    if !args.is_empty() {
+        type_literal(&base, state)?;
+        return Some(resolve(name, state));
+    }
+
+    let resolved = resolve(name, state);
+    if resolved != name {
+        return Some(resolved);
+    }
+    if BUILTIN_TYPES.contains(&name) {
+        return Some(String::from(name));
+    }
+    if state.objsys.has_typedef(name) {
+        return Some(expand(name, state));
+    }
+    if state.objsys.has_class(name) {
+        let class = state.objsys.get_class(name);
+        return Some(make_type(name, &class_args(&class.typeparams, &[])));
+    }
+    None
+}
+
+
+/// Whether all the names in a type are of known types, so
+/// values can be checked against it.
+pub fn is_known(typ: &str, state: &State) -> bool {
+    known(&expand(typ, state), state)
+}
+
+
+fn known(typ: &str, state: &State) -> bool {
+    let typ = typ.trim_end_matches('?');
+    if let Some(f) = parse_fn(typ) {
+        return f.typeparams.is_empty()
+            && (f.ret == "void" || known(&f.ret, state))
+            && f.params.iter().all(|p| known(&p.typ, state));
+    }
+    let (name, args) = split_type(typ);
+    (BUILTIN_TYPES.contains(&name.as_str()) || state.objsys.has_class(&name))
+        && args.iter().all(|a| known(a, state))
+}
+
+
+/// The runtime type of obj when it does not fit the declared type.
+/// Dynamic type arguments, which are what is left where inference
+/// falls short, fit any type. A late variable is checked once set.
+/// The type must be resolved.
+pub fn mismatch(obj: &Object, typ: &str, state: &State) -> Option<String> {
+
+    if matches!(obj, Object::Late(_)) {
+        return None;
+    }
+    if !is_known(typ, state) {
+        return None;
+    }
+    let actual = type_name(obj, state);
+    if assignable(&actual, typ, state) {
+        return None;
+    }
+    Some(actual)
+}
+
+
+/// The declared type of a field, with the type arguments of the instance.
+/// It is resolved, and must not be resolved again, since a type argument
+/// may be a class named like a type parameter.
+pub fn field_type(rk: &RefKey, field: &str, state: &State) -> Option<String> {
+    let class = state.objsys.get_class(&state.objsys.get_instance(rk).classname);
+    class.fields.iter()
+        .find(|(_, fname, _)| fname == field)
+        .map(|(ftype, _, _)| expand(&substitute(ftype, &instance_bindings(rk, state)), state))
+}
+
+
+/// The type arguments a type gives to a class it is or inherits
+/// from, like the element type of a List as an Iterable.
+pub fn as_super(typ: &str, supname: &str, state: &State) -> Option<Vec<String>> {
+
+    let (mut name, mut args) = split_type(typ);
+
+    while state.objsys.has_class(&name) {
+        let class = state.objsys.get_class(&name);
+        args = class_args(&class.typeparams, &args);
+
+        if name == supname {
+            return Some(args);
+        }
+        if class.parent == "" {
+            break;
+        }
+        let bindings: HashMap<String, String> = class.typeparams.iter()
+            .map(|p| p.name.clone())
+            .zip(args)
+            .collect();
+        args = class.parent_typeargs.iter().map(|a| substitute(a, &bindings)).collect();
+        name = class.parent.clone();
+    }
+    None
+}
@@ -0,0 +1,12 @@
+
+void main() {
+    assert("a\tb" == "a	b");
+    assert('it\'s' == "it's");
+    assert("say \"hi\"" == 'say "hi"');
+    assert("back\\slash" == r"back\slash");
+    assert("\x41B\u{43}" == "ABC");
+    assert("\u{1F600}" == "😀");
+    assert("😀" == "😀");
+    assert("cost: \$5" == r"cost: $5");
+    print("line1\nline2");
+}
@@ -0,0 +1,14 @@
+
+void main() {
+    var raw = r'no $interpolation or \n escapes';
+    print(raw);
+
+    var multi = '''
+first
+  second ${1 + 2}''';
+    assert(multi == "first\n  second 3");
+
+    var quoted = """She said "hi" and 'bye'""";
+    print(quoted);
+    print(multi);
+}
@@ -0,0 +1,19 @@
+
+class Pet {
+    String name = "Rex";
+
+    String toString() {
+        return "Pet $name";
+    }
+}
+
+
+void main() {
+    var n = 3;
+    var price = 12;
+    var pet = Pet();
+    assert("$n items at \$$price" == "3 items at \$12");
+    assert("$pet!" == "Pet Rex!");
+    assert("${pet.name}s" == "Rexs");
+    print("I have $n pets called ${pet.name}, costing \$$price.");
+}
@@ -0,0 +1,9 @@
+
+void main() {
+    var n = 2;
+    var s = 'con' "cat"
+        'ena' r'tion $n '
+        "number $n";
+    print(s);
+    assert(s == r"concatenation $n number 2");
+}
@@ -0,0 +1,15 @@
+
+void main() {
+    assert(0xFF == 255);
+    assert(0XaB == 171);
+    assert(0xFFFFFFFFFFFFFFFF == -1);
+    assert(1_000_000 == 1000000);
+    assert(0x7FFF_FFFF == 2147483647);
+    assert(1e3 == 1000);
+    assert(2.5E-3 == 0.0025);
+    assert(1e+2 == 100.0);
+    assert(.5 + .25 == 0.75);
+    assert(1__0.0_1 == 10.01);
+    print(0xFF);
+    print(.5);
+}
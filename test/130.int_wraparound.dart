@@ -0,0 +1,13 @@
+
+void main() {
+    var max = 9223372036854775807;
+    var min = -9223372036854775807 - 1;
+    assert(max + 1 == min);
+    assert(min - 1 == max);
+    assert(max * 2 == -2);
+    assert(-min == min);
+    var i = max;
+    i++;
+    assert(i == min);
+    print(max + 1);
+}
@@ -0,0 +1,17 @@
+
+void main() {
+    assert("${1.0}" == "1.0");
+    assert("${4 / 2}" == "2.0");
+    assert("${1 / 3}" == "0.3333333333333333");
+    assert("${1e21}" == "1e+21");
+    assert("${1e20}" == "100000000000000000000.0");
+    assert("${0.000001}" == "0.000001");
+    assert("${1.5e-7}" == "1.5e-7");
+    assert("${0.0 / 0.0}" == "NaN");
+    assert("${1 / 0}" == "Infinity");
+    assert("${-1 / 0}" == "-Infinity");
+    assert("${-0.0}" == "-0.0");
+    print(1.0);
+    print(1e21);
+    print(2.5e-3);
+}
@@ -0,0 +1,11 @@
+
+void main() {
+    assert(7 ~/ 2 == 3);
+    assert(-7 ~/ 2 == -3);
+    assert(7.9 ~/ 2 == 3);
+    assert(7 ~/ 2.5 == 2);
+    assert(7 / 2 == 3.5);
+    assert("${6 / 2}" == "3.0");
+    assert(1 + 7 ~/ 2 * 2 == 7);
+    print(7 ~/ 2);
+}
@@ -0,0 +1,34 @@
+void main() {
+    assert(int.parse("42") == 42);
+    assert(int.parse(" -17 ") == -17);
+    assert(int.parse("+5") == 5);
+    assert(int.parse("0x1F") == 31);
+    assert(int.parse("-0xff") == -255);
+    assert(int.parse("ff", radix: 16) == 255);
+    assert(int.parse("zz", radix: 36) == 1295);
+    assert(int.parse("9223372036854775807") == 9223372036854775807);
+
+    assert(int.tryParse("12a") == null);
+    assert(int.tryParse("") == null);
+    assert(int.tryParse("101", radix: 2) == 5);
+    assert(int.tryParse("2", radix: 2) == null);
+    assert(int.tryParse("9223372036854775808") == null);
+
+    assert(double.parse("1.5") == 1.5);
+    assert(double.parse("1e3") == 1000.0);
+    assert(double.parse(".5") == 0.5);
+    assert(double.parse("-2.5E-1") == -0.25);
+    assert(double.parse("Infinity") == double.infinity);
+    assert(double.parse("-Infinity") == double.negativeInfinity);
+    assert(double.parse("NaN").isNaN);
+    assert(double.tryParse("inf") == null);
+    assert(double.tryParse("1e") == null);
+    assert(double.tryParse("abc") == null);
+
+    assert(num.parse("7") == 7);
+    assert(num.parse("7.25") == 7.25);
+    assert(num.tryParse("seven") == null);
+
+    print(int.parse("42"));
+    print(double.parse("42"));
+}
@@ -0,0 +1,29 @@
+void main() {
+    assert(3.14159.toStringAsFixed(2) == "3.14");
+    assert(2.5.toStringAsFixed(0) == "3");
+    assert(1.005.toStringAsFixed(2) == "1.00");
+    assert(5.toStringAsFixed(2) == "5.00");
+    assert((-0.0001).toStringAsFixed(2) == "-0.00");
+    assert(1e21.toStringAsFixed(2) == "1e+21");
+
+    assert(123.456.toStringAsPrecision(2) == "1.2e+2");
+    assert(123.456.toStringAsPrecision(4) == "123.5");
+    assert(0.00001234.toStringAsPrecision(3) == "0.0000123");
+    assert(0.0000001234.toStringAsPrecision(3) == "1.23e-7");
+    assert(9.99.toStringAsPrecision(2) == "10");
+
+    assert(123456.0.toStringAsExponential() == "1.23456e+5");
+    assert(123456.toStringAsExponential(2) == "1.23e+5");
+    assert(0.000123.toStringAsExponential(1) == "1.2e-4");
+    assert(1.0.toStringAsExponential() == "1e+0");
+
+    assert(255.toRadixString(16) == "ff");
+    assert(255.toRadixString(2) == "11111111");
+    assert((-255).toRadixString(16) == "-ff");
+    assert(35.toRadixString(36) == "z");
+
+    assert(double.nan.toStringAsFixed(2) == "NaN");
+    assert(double.infinity.toStringAsPrecision(3) == "Infinity");
+
+    print(3.14159.toStringAsFixed(3));
+}
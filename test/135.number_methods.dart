@@ -0,0 +1,62 @@
+void main() {
+    assert(15.clamp(0, 10) == 10);
+    assert((-3).clamp(0, 10) == 0);
+    assert(3.7.clamp(1, 2.5) == 2.5);
+    assert(5.clamp(0, 10) == 5);
+
+    assert((-7).abs() == 7);
+    assert((-7.5).abs() == 7.5);
+    assert((-7).sign == -1);
+    assert(0.sign == 0);
+    assert(2.5.sign == 1.0);
+    assert(double.nan.sign.isNaN);
+
+    assert(2.5.round() == 3);
+    assert((-2.5).round() == -3);
+    assert(2.7.floor() == 2);
+    assert((-2.2).floor() == -3);
+    assert(2.2.ceil() == 3);
+    assert((-2.7).truncate() == -2);
+    assert(7.round() == 7);
+
+    assert(2.5.roundToDouble() == 3.0);
+    assert(2.7.floorToDouble() == 2.0);
+    assert(2.2.ceilToDouble() == 3.0);
+    assert((-2.7).truncateToDouble() == -2.0);
+    assert(7.floorToDouble() == 7.0);
+
+    assert(double.nan.isNaN);
+    assert(!1.5.isNaN);
+    assert(!double.infinity.isFinite);
+    assert(double.infinity.isInfinite);
+    assert(3.isFinite);
+    assert((-0.0).isNegative);
+    assert(!0.isNegative);
+
+    assert(12.gcd(18) == 6);
+    assert((-12).gcd(18) == 6);
+    assert(0.gcd(0) == 0);
+    assert(4.modPow(13, 497) == 445);
+    assert((-3).modPow(3, 5) == 3);
+    assert(255.bitLength == 8);
+    assert((-256).bitLength == 8);
+    assert(0.bitLength == 0);
+    assert(5.isOdd);
+    assert(4.isEven);
+
+    assert(7.compareTo(3) == 1);
+    assert((-0.0).compareTo(0.0) == -1);
+    assert(double.nan.compareTo(double.infinity) == 1);
+    assert(7.remainder(-3) == 1);
+    assert((-7.5).remainder(2) == -1.5);
+
+    assert(3.9.toInt() == 3);
+    assert(3.toDouble() == 3.0);
+    assert(3.toString() == "3");
+
+    assert(double.maxFinite == 1.7976931348623157e308);
+    assert(double.minPositive == 5e-324);
+
+    print(double.maxFinite);
+    print(12.gcd(18));
+}
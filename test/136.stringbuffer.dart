@@ -0,0 +1,67 @@
+class Point {
+    int x;
+    int y;
+
+    Point(this.x, this.y);
+
+    String toString() {
+        return "($x, $y)";
+    }
+}
+
+
+String report(List rows) {
+    var sb = StringBuffer("Report\n");
+    var i = 0;
+    while (i < rows.length) {
+        sb.write("row ");
+        sb.write(i);
+        sb.write(": ");
+        sb.writeln(rows[i]);
+        i++;
+    }
+    return sb.toString();
+}
+
+
+void main() {
+    var sb = StringBuffer();
+    assert(sb.isEmpty);
+    assert(sb.length == 0);
+
+    sb.write("abc");
+    sb.write(12);
+    sb.write(1.5);
+    sb.write(true);
+    sb.write(Point(1, 2));
+    assert(sb.toString() == "abc121.5true(1, 2)");
+    sb.write(null);
+    assert(sb.toString() == "abc121.5true(1, 2)null");
+    assert(sb.isNotEmpty);
+
+    sb.clear();
+    sb.writeln("first");
+    sb.writeln();
+    sb.writeAll([1, 2, 3], ", ");
+    sb.writeAll(["a", "b"]);
+    assert(sb.toString() == "first\n\n1, 2, 3ab");
+
+    sb.clear();
+    sb.writeCharCode(72);
+    sb.writeCharCode(105);
+    sb.writeCharCode(128512);
+    assert("$sb" == "Hi😀");
+    // Length counts UTF-16 code units, like Dart strings.
+    assert(sb.length == 4);
+
+    // The buffer must survive garbage collection while it grows.
+    var big = StringBuffer();
+    var n = 0;
+    while (n < 3000) {
+        big.write("x");
+        n++;
+    }
+    assert(big.length == 3000);
+
+    print(report(["alpha", Point(3, 4)]));
+}
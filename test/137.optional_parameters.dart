@@ -0,0 +1,37 @@
+String greet(String name, [String greeting = "Hello", punctuation]) {
+    if (punctuation == null) {
+        punctuation = ".";
+    }
+    return "$greeting, $name$punctuation";
+}
+
+
+class Range {
+    int start;
+    int end;
+
+    Range(this.start, [this.end = 10]);
+
+    int length([int step = 1]) {
+        return (end - start) ~/ step;
+    }
+}
+
+
+int add(int a, int b) {
+    return a + b;
+}
+
+
+void main() {
+    assert(greet("Ada") == "Hello, Ada.");
+    assert(greet("Ada", "Hi") == "Hi, Ada.");
+    assert(greet("Ada", "Hi", "!") == "Hi, Ada!");
+
+    assert(Range(4).length() == 6);
+    assert(Range(0, 20).length(5) == 4);
+
+    assert(add(2, 3) == 5);
+
+    print(greet("World"));
+}
@@ -0,0 +1,42 @@
+String join(String a, String b, {String sep = " ", bool upper = false}) {
+    if (upper) {
+        return "${a}${sep}${b}!";
+    }
+    return "${a}${sep}${b}";
+}
+
+
+int area({required int width, required int height}) {
+    return width * height;
+}
+
+
+class Box {
+    int w;
+    int h;
+
+    Box({this.w = 1, this.h = 2});
+
+    int scaled(int factor, {int extra = 0}) {
+        return w * h * factor + extra;
+    }
+}
+
+
+void main() {
+    assert(join("a", "b") == "a b");
+    assert(join("a", "b", sep: "-") == "a-b");
+    assert(join("a", "b", upper: true, sep: "+") == "a+b!");
+
+    assert(area(height: 3, width: 4) == 12);
+
+    var box = Box();
+    assert(box.w == 1);
+    assert(box.h == 2);
+
+    var other = Box(h: 5);
+    assert(other.w == 1);
+    assert(other.h == 5);
+    assert(other.scaled(2) == 10);
+    assert(other.scaled(2, extra: 1) == 11);
+}
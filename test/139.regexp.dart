@@ -0,0 +1,86 @@
+String? matched(RegExpMatch m) {
+    return m.group(0);
+}
+
+
+void main() {
+    var re = RegExp(r"(\w+)@(\w+)\.com");
+    assert(re.pattern == r"(\w+)@(\w+)\.com");
+    assert(re.isCaseSensitive);
+    assert(!re.isMultiLine);
+
+    assert(re.hasMatch("mail bob@example.com now"));
+    assert(!re.hasMatch("no address here"));
+
+    var m = re.firstMatch("mail bob@example.com now")!;
+    assert(m.group(0) == "bob@example.com");
+    assert(m.group(1) == "bob");
+    assert(m.group(2) == "example");
+    assert(m.groupCount == 2);
+    assert(m.start == 5);
+    assert(m.end == 20);
+    assert(m.input == "mail bob@example.com now");
+    assert(re.firstMatch("nothing") == null);
+
+    assert(re.stringMatch("x a@b.com") == "a@b.com");
+    assert(re.stringMatch("nothing") == null);
+
+    var all = RegExp(r"\d+").allMatches("a1 b22 c333");
+    assert(all is Iterable<RegExpMatch>);
+    assert(all.length == 3);
+    assert(all.first.group(0) == "1");
+    assert(all.elementAt(2).group(0) == "333");
+    assert(all.last.start == 8);
+    print(all.map(matched));
+    assert(RegExp(r"\d+").allMatches("a1 b22 c333", 3).length == 2);
+
+    // Empty matches advance by one.
+    assert(RegExp(r"x*").allMatches("axxb").length == 4);
+
+    // Flags.
+    assert(RegExp("HELLO", caseSensitive: false).hasMatch("hello"));
+    assert(!RegExp("HELLO").hasMatch("hello"));
+    assert(RegExp(r"^b$", multiLine: true).hasMatch("a\nb\nc"));
+    assert(!RegExp(r"^b$").hasMatch("a\nb\nc"));
+    assert(RegExp(r"a.b", dotAll: true).hasMatch("a\nb"));
+    assert(!RegExp(r"a.b").hasMatch("a\nb"));
+    assert(RegExp(r"^.$", unicode: true).hasMatch("😀"));
+    assert(!RegExp(r"^.$").hasMatch("😀"));
+    assert(RegExp(r"\u{1F600}", unicode: true).hasMatch("x😀"));
+
+    // Quantifiers and backtracking.
+    assert(RegExp(r"a{2,3}").stringMatch("aaaa") == "aaa");
+    assert(RegExp(r"a{2,3}?").stringMatch("aaaa") == "aa");
+    assert(RegExp(r"<.+?>").stringMatch("<a><b>") == "<a>");
+    assert(RegExp(r"(a+)+b").hasMatch("aaaaaaaaaaaaaaaab"));
+    assert(RegExp(r"[^a-c]+").stringMatch("abcdefabc") == "def");
+    assert(RegExp(r"\bis\b").firstMatch("this is it")!.start == 5);
+
+    // Long repetitions of single character atoms.
+    String long = "ab";
+    for (int i = 0; i < 15; i++) {
+        long = long + long;
+    }
+    assert(RegExp(r"^(?:a|b)*$").hasMatch(long));
+    assert(RegExp(r"^(?:[ab]|x)+?$").hasMatch(long));
+    assert(!RegExp(r"^(?:a|c)*$").hasMatch(long));
+    assert(RegExp(r"(?:b|a){3}").stringMatch(long) == "aba");
+
+    // Long repetitions of longer atoms and groups.
+    assert(RegExp(r"^(?:ab|cd)*$").hasMatch(long));
+    String words = "";
+    for (int i = 0; i < 2000; i++) {
+        words = words + "word ";
+    }
+    assert(RegExp(r"^(\w+ )*$").firstMatch(words)!.group(1) == "word ");
+    assert(!RegExp(r"^(\w+ )*x$").hasMatch(words));
+
+    // Backreferences, and groups that did not participate.
+    assert(RegExp(r"(\w)\1").stringMatch("abccd") == "cc");
+    assert(RegExp(r"(a)|b").firstMatch("b")!.group(1) == null);
+    assert(RegExp(r"(z)((a+)?(b+)?(c))*").firstMatch("zaacbbbcac")!.group(4) == null);
+
+    assert("$re" == r"RegExp/(\w+)@(\w+)\.com/");
+    var flagged = RegExp("a", multiLine: true, caseSensitive: false);
+    assert("$flagged" == "RegExp/a/mi");
+}
@@ -0,0 +1,29 @@
+void main() {
+    // Lookahead.
+    assert(RegExp(r"\d+(?=%)").stringMatch("50 of 75%") == "75");
+    assert(RegExp(r"\d+(?!\d|%)").stringMatch("75% of 50") == "50");
+
+    // Lookbehind.
+    assert(RegExp(r"(?<=\$)\d+").stringMatch("cost: \$42") == "42");
+    assert(RegExp(r"(?<!\$)\b\d+").stringMatch("\$42 or 17") == "17");
+    assert(RegExp(r"(?<=(\d)(\d))x").firstMatch("12x")!.group(1) == "1");
+    assert(RegExp(r"(?<=\1(a))b").hasMatch("aab"));
+
+    // Named groups.
+    var date = RegExp(r"(?<year>\d{4})-(?<month>\d{2})-(?<day>\d{2})");
+    var m = date.firstMatch("Due 2024-03-15.")!;
+    assert(m.namedGroup("year") == "2024");
+    assert(m.namedGroup("month") == "03");
+    assert(m.namedGroup("day") == "15");
+    assert(m.group(2) == "03");
+    assert(m.groupCount == 3);
+    assert(m.groupNames.length == 3);
+    assert(m.groupNames[0] == "year");
+
+    // Named backreference.
+    assert(RegExp(r"(?<q>['" + '"' + r"])\w+\k<q>").stringMatch("say 'hi' now") == "'hi'");
+
+    // Unicode property escapes.
+    assert(RegExp(r"\p{Lu}+", unicode: true).stringMatch("abcDEFg") == "DEF");
+    assert(RegExp(r"\P{L}+", unicode: true).stringMatch("ab12cd") == "12");
+}
@@ -0,0 +1,33 @@
+void main() {
+    var parts = "a,b;c".split(RegExp(r"[,;]"));
+    assert(parts.length == 3);
+    assert(parts[2] == "c");
+
+    parts = "a, b,c".split(", ");
+    assert(parts.length == 2);
+    assert(parts[1] == "b,c");
+
+    parts = "abc".split("");
+    assert(parts.length == 3);
+    assert(parts[1] == "b");
+
+    parts = "abc".split(RegExp(""));
+    assert(parts.length == 3);
+    assert(parts[0] == "a");
+
+    assert("".split(RegExp("")).length == 0);
+    assert("".split(",").length == 1);
+    assert(",a,".split(",").length == 3);
+
+    assert("a1b22c333".replaceAll(RegExp(r"\d+"), "#") == "a#b#c#");
+    assert("a.b.c".replaceAll(".", "") == "abc");
+    assert("abc".replaceAll("", "-") == "-a-b-c-");
+    assert("aaa".replaceAll(RegExp("b*"), "-") == "-a-a-a-");
+
+    assert("Hello".contains(RegExp("ell")));
+    assert(!"Hello".contains(RegExp("^ell")));
+    assert("Hello".contains("lo"));
+    assert("Hello".contains("lo", 3));
+    assert(!"Hello".contains("He", 1));
+    assert("Hello".contains(""));
+}
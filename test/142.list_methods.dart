@@ -0,0 +1,116 @@
+// Points are == when their coordinates are.
+class Point {
+    int x;
+    int y;
+
+    Point(this.x, this.y);
+
+    bool operator ==(Object other) {
+        if (other is Point) {
+            return x == other.x && y == other.y;
+        }
+        return false;
+    }
+
+    int get hashCode {
+        return x * 31 + y;
+    }
+}
+
+
+bool isEven(int x) {
+    return x.isEven;
+}
+
+
+bool isBig(int x) {
+    return x > 10;
+}
+
+
+void main() {
+    var l = [5, 4, 3, 2, 1, 3];
+
+    assert(l.indexOf(3) == 2);
+    assert(l.indexOf(3, 3) == 5);
+    assert(l.indexOf(9) == -1);
+    assert(l.lastIndexOf(3) == 5);
+    assert(l.lastIndexOf(3, 4) == 2);
+    assert(l.lastIndexOf(5, -1) == -1);
+    assert(l.indexWhere(isEven) == 1);
+    assert(l.indexWhere(isEven, 2) == 3);
+    assert(l.indexWhere(isBig) == -1);
+    assert(l.lastIndexWhere(isEven) == 3);
+
+    var sub = l.sublist(1, 3);
+    assert(sub.length == 2);
+    assert(sub[0] == 4);
+    assert(l.sublist(4).length == 2);
+    assert(l.getRange(0, 2).elementAt(1) == 4);
+    assert(l.reversed.first == 3);
+    assert(l.reversed.length == 6);
+    print(l.getRange(1, 3));
+    print(l.reversed);
+
+    assert(l.remove(3));
+    assert(!l.remove(30));
+    assert(l.length == 5);
+    assert(l[2] == 2);
+    List<int?> holes = [1, null, 2];
+    assert(holes.remove(null) && holes.length == 2);
+
+    l.removeWhere(isEven);
+    assert("$l" == "[5, 1, 3]");
+
+    var m = [1, 2, 3, 4, 5, 6];
+    m.retainWhere(isEven);
+    assert("$m" == "[2, 4, 6]");
+
+    m.insertAll(1, [7, 8]);
+    assert("$m" == "[2, 7, 8, 4, 6]");
+    m.insertAll(5, [9]);
+    assert("$m" == "[2, 7, 8, 4, 6, 9]");
+
+    m.setAll(0, [0, 0]);
+    assert("$m" == "[0, 0, 8, 4, 6, 9]");
+
+    m.setRange(1, 3, [1, 2, 3, 4], 2);
+    assert("$m" == "[0, 3, 4, 4, 6, 9]");
+    m.setRange(0, 2, [7, 7]);
+    assert("$m" == "[7, 7, 4, 4, 6, 9]");
+
+    m.replaceRange(0, 2, [1]);
+    assert("$m" == "[1, 4, 4, 6, 9]");
+    m.replaceRange(1, 1, [2, 3]);
+    assert("$m" == "[1, 2, 3, 4, 4, 6, 9]");
+
+    m.fillRange(0, 2, 5);
+    assert("$m" == "[5, 5, 3, 4, 4, 6, 9]");
+    m.fillRange(5, 7);
+    assert("$m" == "[5, 5, 3, 4, 4, null, null]");
+
+    assert(m.removeAt(2) == 3);
+    assert(m.length == 6);
+
+    var nested = [[1, 2], [3]];
+    assert("$nested" == "[[1, 2], [3]]");
+
+    var letters = ["a", "b", "c"];
+    Map<int, String> byIndex = letters.asMap();
+    assert("$byIndex" == "{0: a, 1: b, 2: c}");
+    assert(byIndex[1] == "b");
+    assert(byIndex.length == 3);
+    assert(<int>[].asMap().isEmpty);
+
+    // Elements are compared with their ==
+    var points = [Point(1, 2), Point(3, 4), Point(1, 2)];
+    assert(points.indexOf(Point(1, 2)) == 0);
+    assert(points.indexOf(Point(1, 2), 1) == 2);
+    assert(points.lastIndexOf(Point(1, 2)) == 2);
+    assert(points.indexOf(Point(2, 1)) == -1);
+    assert(points.contains(Point(3, 4)));
+    assert(points.remove(Point(1, 2)));
+    assert(!points.remove(Point(5, 5)));
+    assert(points.length == 2 && points.indexOf(Point(1, 2)) == 1);
+    assert([1, 2.0].indexOf(2) == 1);
+}
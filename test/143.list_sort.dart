@@ -0,0 +1,58 @@
+class Version {
+    int major;
+    int minor;
+
+    Version(this.major, this.minor);
+
+    int compareTo(Version other) {
+        if (major == other.major) {
+            return minor - other.minor;
+        }
+        return major - other.major;
+    }
+
+    String toString() {
+        return "$major.$minor";
+    }
+}
+
+
+int descending(a, b) {
+    return b - a;
+}
+
+
+void main() {
+    var nums = [5, 3, 9, 1, 7, 3];
+    nums.sort();
+    assert("$nums" == "[1, 3, 3, 5, 7, 9]");
+
+    nums.sort(descending);
+    assert("$nums" == "[9, 7, 5, 3, 3, 1]");
+
+    var mixed = [2.5, 1, -3, 2];
+    mixed.sort();
+    assert("$mixed" == "[-3, 1, 2, 2.5]");
+
+    var words = ["pear", "Apple", "fig", "apple"];
+    words.sort();
+    assert("$words" == "[Apple, apple, fig, pear]");
+
+    // Sort by a local comparator.
+    int byMinor(Version a, Version b) {
+        return a.minor - b.minor;
+    }
+    var versions = [Version(2, 0), Version(1, 10), Version(1, 2)];
+    versions.sort();
+    assert("$versions" == "[1.2, 1.10, 2.0]");
+    versions.sort(byMinor);
+    assert("$versions" == "[2.0, 1.2, 1.10]");
+
+    var empty = [];
+    empty.sort();
+    assert(empty.length == 0);
+
+    var one = [1];
+    one.sort(descending);
+    assert(one[0] == 1);
+}
@@ -0,0 +1,75 @@
+var calls = 0;
+
+int twice(int x) {
+    calls = calls + 1;
+    return x * 2;
+}
+
+bool isOdd(int x) {
+    return x.isOdd;
+}
+
+bool isSmall(int x) {
+    return x < 3;
+}
+
+List pair(int x) {
+    return [x, x];
+}
+
+int sum(int a, int b) {
+    return a + b;
+}
+
+int none() {
+    return -1;
+}
+
+void main() {
+    var l = [1, 2, 3, 4, 5];
+
+    // Nothing runs until the iterable is iterated.
+    var mapped = l.map(twice);
+    assert(calls == 0);
+    assert(mapped.first == 2);
+    assert(calls == 1);
+
+    // Each iteration runs the callback again.
+    assert(mapped.toList().length == 5);
+    assert(calls == 6);
+
+    // Views see later changes to the list.
+    var odd = l.where(isOdd);
+    l.add(7);
+    assert(odd.length == 4);
+    l.removeLast();
+
+    assert("${l.where(isOdd).map(twice)}" == "(2, 6, 10)");
+    assert("${l.expand(pair).toList()}" == "[1, 1, 2, 2, 3, 3, 4, 4, 5, 5]");
+    assert("${l.skip(1).take(3).toList()}" == "[2, 3, 4]");
+    assert(l.take(10).length == 5);
+    assert(l.skip(10).isEmpty);
+    assert("${l.takeWhile(isSmall).toList()}" == "[1, 2]");
+    assert("${l.skipWhile(isSmall).toList()}" == "[3, 4, 5]");
+    assert("${l.followedBy([6, 7]).toList()}" == "[1, 2, 3, 4, 5, 6, 7]");
+
+    assert(l.fold(10, sum) == 25);
+    assert(l.reduce(sum) == 15);
+    assert(l.any(isOdd));
+    assert(!l.every(isOdd));
+    assert(l.contains(3));
+    assert(!l.where(isOdd).contains(4));
+    assert(l.join() == "12345");
+    assert(l.join(", ") == "1, 2, 3, 4, 5");
+
+    var it = l.where(isOdd);
+    assert(it.first == 1);
+    assert(it.last == 5);
+    assert(it.elementAt(1) == 3);
+    assert(l.where(isSmall).skip(1).single == 2);
+    assert(l.firstWhere(isOdd) == 1);
+    assert(l.lastWhere(isSmall) == 2);
+    assert(l.skip(5).firstWhere(isOdd, orElse: none) == -1);
+    assert(l.isNotEmpty);
+    assert(!l.where(isOdd).isEmpty);
+}
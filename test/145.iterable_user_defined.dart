@@ -0,0 +1,79 @@
+class Range extends Iterable {
+    int start;
+    int end;
+
+    Range(this.start, this.end);
+
+    Iterator get iterator {
+        return RangeIterator(start, end);
+    }
+}
+
+
+class RangeIterator extends Iterator {
+    int next;
+    int end;
+    int current = 0;
+
+    RangeIterator(this.next, this.end);
+
+    bool moveNext() {
+        if (next < end) {
+            current = next;
+            next = next + 1;
+            return true;
+        }
+        return false;
+    }
+}
+
+
+int square(int x) {
+    return x * x;
+}
+
+
+int firstAbove(Iterable numbers, int limit) {
+    for (var n in numbers) {
+        if (n > limit) {
+            return n;
+        }
+    }
+    return -1;
+}
+
+
+void main() {
+    var total = 0;
+    for (var i in Range(1, 5)) {
+        total = total + i;
+    }
+    assert(total == 10);
+
+    // Inherited members run against the user iterator.
+    var r = Range(0, 4);
+    assert(r.length == 4);
+    assert("$r" == "(0, 1, 2, 3)");
+    assert("${r.map(square).toList()}" == "[0, 1, 4, 9]");
+    assert(r.contains(3));
+    assert(r.last == 3);
+
+    // Returning from inside a for-in loop.
+    assert(firstAbove(Range(0, 100), 41) == 42);
+    assert(firstAbove([1, 2, 3], 1) == 2);
+    assert(firstAbove(Range(0, 3), 5) == -1);
+
+    // Lists accept any iterable.
+    var l = [0];
+    l.addAll(Range(1, 3));
+    l.insertAll(0, r.skip(2));
+    assert("$l" == "[2, 3, 0, 1, 2]");
+
+    // A list iterator by hand.
+    var it = [7, 8].iterator;
+    assert(it.moveNext());
+    assert(it.current == 7);
+    assert(it.moveNext());
+    assert(it.current == 8);
+    assert(!it.moveNext());
+}
@@ -0,0 +1,118 @@
+var log = [];
+
+
+Iterable range(int n) sync* {
+    for (var i = 0; i < n; i++) {
+        log.add(i);
+        yield i;
+    }
+}
+
+
+Iterable naturals() sync* {
+    var n = 1;
+    while (true) {
+        yield n;
+        n++;
+    }
+}
+
+
+Iterable countdown(int depth) sync* {
+    if (depth > 0) {
+        yield depth;
+        yield* countdown(depth - 1);
+    }
+    else {
+        yield 0;
+    }
+}
+
+
+Iterable early(bool stop) sync* {
+    yield 1;
+    if (stop) {
+        return;
+    }
+    yield 2;
+}
+
+
+Iterable evens(Iterable xs) sync* {
+    for (var x in xs) {
+        if (x.isEven) {
+            yield x;
+        }
+    }
+}
+
+
+Iterable withDefault([int start = 10]) sync* {
+    var i = start;
+    do {
+        var next = i + 1;
+        yield i;
+        i = next;
+    } while (i < start + 2);
+}
+
+
+bool isAnswer(int x) {
+    return x > 41;
+}
+
+
+class Tree {
+    int value;
+    List kids = [];
+
+    Tree(this.value);
+
+    Iterable get walk sync* {
+        yield value;
+        for (var k in kids) {
+            yield* k.walk;
+        }
+    }
+}
+
+
+void main() {
+    // Nothing runs until moveNext is called.
+    var r = range(3);
+    assert(log.length == 0);
+    var it = r.iterator;
+    assert(it.moveNext());
+    assert(it.current == 0);
+    assert(log.length == 1);
+
+    // Each iterator starts the body over.
+    assert("${r.toList()}" == "[0, 1, 2]");
+    assert(log.length == 4);
+    assert(it.moveNext());
+    assert(it.current == 1);
+
+    // Infinite generators are fine when only a part is used.
+    assert("${naturals().take(5).toList()}" == "[1, 2, 3, 4, 5]");
+    assert(naturals().skip(500).first == 501);
+    assert(naturals().firstWhere(isAnswer) == 42);
+
+    assert("${countdown(3).toList()}" == "[3, 2, 1, 0]");
+    assert("${early(true).toList()}" == "[1]");
+    assert("${early(false).toList()}" == "[1, 2]");
+    assert("${evens(range(10)).toList()}" == "[0, 2, 4, 6, 8]");
+    assert("${withDefault().toList()}" == "[10, 11]");
+    assert("${withDefault(5).toList()}" == "[5, 6]");
+
+    var t = Tree(1);
+    t.kids.add(Tree(2));
+    t.kids[0].kids.add(Tree(3));
+    t.kids.add(Tree(4));
+    assert("${t.walk.toList()}" == "[1, 2, 3, 4]");
+
+    var total = 0;
+    for (var i in range(5)) {
+        total = total + i;
+    }
+    assert(total == 10);
+}
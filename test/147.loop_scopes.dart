@@ -0,0 +1,39 @@
+int sumWithLocals(List xs) {
+    var total = 0;
+    for (var x in xs) {
+        var doubled = x * 2;
+        total = total + doubled;
+    }
+    return total;
+}
+
+
+void main() {
+    // Each iteration declares its variables anew.
+    var i = 0;
+    var seen = [];
+    while (i < 3) {
+        var square = i * i;
+        seen.add(square);
+        i++;
+    }
+    assert("$seen" == "[0, 1, 4]");
+
+    do {
+        var last = i;
+        i--;
+    } while (i > 0);
+    assert(i == 0);
+
+    // The loop variable is scoped to the loop.
+    for (var j = 0; j < 2; j++) {
+        var k = j;
+        seen.add(k);
+    }
+    for (var j = 5; j < 6; j++) {
+        seen.add(j);
+    }
+    assert("$seen" == "[0, 1, 4, 0, 1, 5]");
+
+    assert(sumWithLocals([1, 2, 3]) == 12);
+}
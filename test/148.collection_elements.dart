@@ -0,0 +1,48 @@
+Iterable countTo(int n) sync* {
+    for (var i = 1; i <= n; i++) {
+        yield i;
+    }
+}
+
+
+bool isOdd(int x) {
+    return x - (x ~/ 2) * 2 == 1;
+}
+
+
+void main() {
+    var a = [1, 2];
+    var b = null;
+    var debug = true;
+
+    // Spreads
+    assert("${[...a, ...a]}" == "[1, 2, 1, 2]");
+    assert("${[0, ...?b, 3]}" == "[0, 3]");
+    assert("${[...?a]}" == "[1, 2]");
+    assert("${[...[]]}" == "[]");
+    assert("${[...countTo(3), ...a.where(isOdd)]}" == "[1, 2, 3, 1]");
+
+    // If elements
+    assert("${[if (debug) 'x', 'y']}" == "[x, y]");
+    assert("${[if (!debug) 'x', 'y']}" == "[y]");
+    assert("${[if (a.length > 5) 'long' else 'short']}" == "[short]");
+    assert("${[if (debug) ...a else 0]}" == "[1, 2]");
+
+    // For elements
+    var xs = [1, 2, 3];
+    assert("${[for (var i in xs) i * 2]}" == "[2, 4, 6]");
+    assert("${[for (var i = 0; i < 3; i++) i]}" == "[0, 1, 2]");
+    assert("${[for (var i in countTo(4)) if (isOdd(i)) i]}" == "[1, 3]");
+    assert("${[for (var i in xs) for (var j in xs) if (i < j) i * 10 + j]}" == "[12, 13, 23]");
+    assert("${[for (var i in xs) ...[i, i]]}" == "[1, 1, 2, 2, 3, 3]");
+
+    // Elements are evaluated in order
+    var log = [];
+    var built = [log.length, for (var x in xs) log.add(x), log.length];
+    assert("$built" == "[0, null, null, null, 3]");
+
+    // The loop variable is scoped to the element
+    var i = 100;
+    var ys = [for (var i = 0; i < 2; i++) i, i];
+    assert("$ys" == "[0, 1, 100]");
+}
@@ -0,0 +1,125 @@
+class Box<T extends num> {
+    T value;
+    List<T> history = [];
+
+    Box(this.value);
+
+    T get doubled {
+        return value * 2;
+    }
+
+    void set(T v) {
+        history.add(value);
+        value = v;
+    }
+
+    List<R> pairWith<R>(R other) {
+        return <R>[other, other];
+    }
+}
+
+
+class IntBox extends Box<int> {
+    IntBox(int v) : super(v);
+}
+
+
+class Pair<A, B> {
+    A first;
+    B second;
+
+    Pair(this.first, this.second);
+
+    Pair<B, A> swap() {
+        return Pair<B, A>(second, first);
+    }
+}
+
+
+T firstOf<T>(List<T> xs) {
+    return xs[0];
+}
+
+
+List<T> twice<T>(T x) {
+    return <T>[x, x];
+}
+
+
+T larger<T extends num>(T x, T y) {
+    if (x > y) {
+        return x;
+    }
+    return y;
+}
+
+
+int count<K, V>(List<Pair<K, V>> pairs) {
+    return pairs.length;
+}
+
+
+void main() {
+    // Comparisons are still comparisons.
+    var a = 1;
+    var b = 2;
+    assert(a < b);
+    assert(!(a > b));
+    assert((a < b) == (b > a));
+    var c = [a < b, b > a];
+    assert("$c" == "[true, true]");
+
+    // Generic functions, with and without type arguments.
+    List<int> xs = [1, 2, 3];
+    assert(firstOf<int>(xs) == 1);
+    assert(firstOf(xs) == 1);
+    assert("${twice<String>("x")}" == "[x, x]");
+    assert("${twice(2)}" == "[2, 2]");
+
+    // Generic classes.
+    var box = Box<int>(3);
+    box.set(4);
+    assert(box.value == 4);
+    assert("${box.history}" == "[3]");
+    assert(box.doubled == 8);
+    assert("${box.pairWith<String>("y")}" == "[y, y]");
+    assert(IntBox(5).doubled == 10);
+
+    // Type arguments within the bounds, given or inferred.
+    assert(Box(1.5).doubled == 3.0);
+    assert(larger<int>(2, 3) == 3);
+    assert(larger(2.5, 1) == 2.5);
+
+    var p = Pair<String, int>("a", 1);
+    var q = p.swap();
+    assert(q.first == 1);
+    assert(q.second == "a");
+
+    // Nested type arguments.
+    List<List<int>> grid = [[1], [2, 3]];
+    assert(grid[1][1] == 3);
+    List<Pair<String, int>> pairs = [p, Pair("b", 2)];
+    assert(count<String, int>(pairs) == 2);
+    for (Pair<String, int> pair in pairs) {
+        assert(pair.second > 0);
+    }
+
+    // Typed list literals.
+    var names = <String>["a", "b"];
+    names.add("c");
+    assert("$names" == "[a, b, c]");
+    var empty = <int>[];
+    assert(empty.isEmpty);
+    assert("${[for (var i in xs) <int>[i]]}" == "[[1], [2], [3]]");
+    assert("${xs.map<List<int>>(twice).toList()}" == "[[1, 1], [2, 2], [3, 3]]");
+
+    // Type literals with type arguments.
+    print(List<int>);
+    Type t = Pair<String, List<int>>;
+    assert("$t" == "Pair<String, List<int>>");
+    assert(List<int> == List<int>);
+    assert(List<int> != List<String>);
+    var lo = 1;
+    var hi = 2;
+    assert("${[lo < hi, hi > lo]}" == "[true, true]");
+}
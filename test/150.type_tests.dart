@@ -0,0 +1,115 @@
+class Animal {
+    String name;
+    Animal(this.name);
+}
+
+
+class Dog extends Animal {
+    Dog(String name) : super(name);
+
+    String bark() {
+        return name + " says woof";
+    }
+}
+
+
+class Box<T> {
+    T value;
+    Box(this.value);
+
+    bool holds(Object x) {
+        return x is T;
+    }
+
+    Type get elementType {
+        return T;
+    }
+}
+
+
+class NumBox<T extends num> extends Box<T> {
+    NumBox(T v) : super(v);
+}
+
+
+String describe(Object x) {
+    if (x is int) {
+        return "int " + x.toString();
+    }
+    if (x is String) {
+        return "String " + x;
+    }
+    if (x is Dog) {
+        return x.bark();
+    }
+    return "something else";
+}
+
+
+bool isA<T>(Object x) {
+    return x is T;
+}
+
+
+void main() {
+    print(1 is int);
+    print(1 is num);
+    print(1 is double);
+    print(1.5 is num);
+    print("a" is String);
+    print("a" is! String);
+    print(true is bool);
+    print(null is Null);
+    print(null is Object);
+    print(null is dynamic);
+    print(1 is Object);
+    print(main is Function);
+
+    Animal a = Dog("Rex");
+    print(a is Animal);
+    print(a is Dog);
+    print(Animal("Tom") is Dog);
+    print(Animal("Tom") is! Dog);
+
+    print(describe(3));
+    print(describe("hi"));
+    print(describe(a));
+    print(describe(true));
+
+    print([1, 2] is List<int>);
+    print([1, 2] is List<num>);
+    print([1, 2] is List<String>);
+    print([1, 2] is Iterable<int>);
+    print([1, 2] is List);
+    print(<Object>[1] is List<int>);
+
+    print(Box<int>(1) is Box<num>);
+    print(Box<num>(1) is Box<int>);
+    print(NumBox<int>(1) is Box<int>);
+    print(NumBox<int>(1) is Box<String>);
+    print(Box<int>(1).holds(2));
+    print(Box<int>(1).holds("2"));
+    print(isA<String>("x"));
+    print(isA<String>(1));
+
+    Object o = a;
+    Dog d = o as Dog;
+    print(d.bark());
+    num n = 2 as num;
+    print(n);
+
+    print(1.runtimeType);
+    print(1.5.runtimeType);
+    print("s".runtimeType);
+    print(null.runtimeType);
+    print(a.runtimeType);
+    print([1, 2].runtimeType);
+    print(Box("x").runtimeType);
+    print(Box<int>(1).elementType);
+
+    print(a.runtimeType == Dog);
+    print(a.runtimeType == Animal);
+    print(1.runtimeType == int);
+    print(Box);
+    print(String);
+}
@@ -0,0 +1,99 @@
+class Animal {
+    String name;
+    Animal(this.name);
+}
+
+
+class Dog extends Animal {
+    Dog(String name) : super(name);
+}
+
+
+class Box<T> {
+    T value;
+    List<T> items = [];
+
+    Box(this.value);
+
+    void put(T v) {
+        items.add(v);
+        value = v;
+    }
+
+    T get first {
+        return items[0];
+    }
+}
+
+
+num half(num x) {
+    return x / 2;
+}
+
+
+Animal pick(Animal a, Animal b) {
+    return a;
+}
+
+
+T same<T>(T x) {
+    return x;
+}
+
+
+List<int> evens(int n) {
+    List<int> xs = [];
+    for (int i = 0; i < n; i++) {
+        xs.add(i * 2);
+    }
+    return xs;
+}
+
+
+Iterable<int> count(int n) sync* {
+    for (int i = 0; i < n; i++) {
+        yield i;
+    }
+}
+
+
+int counter = 0;
+
+
+void main() {
+    // Subtypes fit the declared type.
+    num n = 1;
+    n = 2.5;
+    print(n);
+    Object o = "text";
+    o = 3;
+    print(o);
+    print(half(3));
+    print(pick(Dog("Rex"), Animal("Tom")).name);
+    Animal a = Dog("Fido");
+    print(a.name);
+
+    // Null fits nullable types.
+    String? s = null;
+    print(s);
+
+    // Type parameters are checked by what they are bound to.
+    var box = Box<num>(1);
+    box.put(2);
+    box.put(3.5);
+    print(box.value);
+    print(box.first);
+    print(same<String>("x"));
+    print(same(4));
+
+    print(evens(3));
+    for (int i in count(3)) {
+        print(i);
+    }
+    dynamic d = 7;
+    int fromDynamic = d;
+    print(fromDynamic);
+
+    counter = counter + 1;
+    print(counter);
+}
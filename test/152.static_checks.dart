@@ -0,0 +1,60 @@
+class Point {
+    int x;
+    int y;
+    Point(this.x, this.y);
+
+    int sum() {
+        return x + y;
+    }
+}
+
+class Rect {
+    double width;
+    double height;
+    Rect(this.width, {this.height = 1});
+}
+
+double half() {
+    return 3;
+}
+
+String describe(Object o) {
+    if (o is Point) {
+        return "Point " + o.sum().toString();
+    }
+    return "something";
+}
+
+int sign(int n) {
+    if (n < 0) {
+        return -1;
+    } else {
+        return 1;
+    }
+}
+
+void main() {
+    var p = Point(1, 2);
+    var total = p.sum() + sign(-5);
+    assert(total == 2);
+    print(describe(p));
+    print(describe(3));
+
+    var names = <String>["a", "b"];
+    String first = names[0];
+    assert(first == "a");
+
+    // Int literals given for doubles are doubles.
+    double d = 3;
+    assert("$d" == "3.0");
+    d = -4;
+    assert("$d" == "-4.0");
+    assert("${half()}" == "3.0");
+    var r = Rect(2, height: 5);
+    assert("${r.width} ${r.height}" == "2.0 5.0");
+    List<double> sizes = [1, 2.5];
+    assert("$sizes" == "[1.0, 2.5]");
+    num n = 3;
+    assert(n is int);
+    print(d);
+}
@@ -0,0 +1,96 @@
+var log = <String>[];
+
+int counted(int value) {
+    log.add("init $value");
+    return value;
+}
+
+late int lazyTop = counted(3);
+
+class Node {
+    int value;
+    Node? next;
+    late int doubled = counted(value * 2);
+
+    Node(this.value, [this.next]);
+}
+
+int sum(Node? node) {
+    var total = 0;
+    while (node != null) {
+        total = total + node.value;
+        node = node.next;
+    }
+    return total;
+}
+
+String describe(int? n) {
+    if (n == null) {
+        return "nothing";
+    }
+    return "number " + n.toString();
+}
+
+int area({required int width, int height = 1}) {
+    return width * height;
+}
+
+void main() {
+    int? missing;
+    assert(missing == null);
+    String? name = "Dart";
+    assert(name != null);
+    if (name != null) {
+        assert(name + "!" == "Dart!");
+    }
+
+    var list = Node(1, Node(2, Node(3)));
+    assert(sum(list) == 6);
+    assert(sum(null) == 0);
+    assert(list.next!.next!.value == 3);
+    assert(list.next!.next!.next == null);
+
+    assert(describe(null) == "nothing");
+    assert(describe(7) == "number 7");
+    assert(null.toString() == "null");
+
+    // Late variables run their initializer on first read.
+    late int lazy = counted(1);
+    assert(log.length == 0);
+    assert(lazy == 1);
+    assert(lazy == 1);
+    assert(log.length == 1);
+
+    late String assigned;
+    assigned = "later";
+    assert(assigned == "later");
+
+    var node = Node(5);
+    assert(log.length == 1);
+    assert(node.doubled == 10);
+    assert(node.doubled == 10);
+    assert(log.length == 2);
+
+    assert(lazyTop == 3);
+    assert(log.length == 3);
+    assert(log[2] == "init 3");
+
+    assert(area(width: 3) == 3);
+    assert(area(width: 3, height: 2) == 6);
+
+    List<int?> holes = [1, null, 3];
+    assert(holes[1] == null);
+    print(holes);
+
+    // Variables are promoted by initialization, assignment and x!.
+    int? four = 4;
+    assert(four + 1 == 5);
+    int? three;
+    three = 3;
+    assert(three + 1 == 4);
+    four = null;
+    assert(four == null);
+    RegExpMatch? m = RegExp(r"b+").firstMatch("abbc");
+    assert(m!.group(0) == "bb");
+    assert(m.start == 1);
+}
@@ -0,0 +1,137 @@
+typedef IntOp = int Function(int, int);
+typedef bool Pred(int x);
+typedef Mapper<T> = T Function(T);
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int mul(int a, int b) {
+    return a * b;
+}
+
+bool isEven(int x) {
+    return x - (x ~/ 2) * 2 == 0;
+}
+
+String label(int n, [String suffix = "!"]) {
+    return n.toString() + suffix;
+}
+
+int twice(int x) {
+    return x * 2;
+}
+
+int fold(List<int> xs, int start, IntOp op) {
+    var acc = start;
+    for (var x in xs) {
+        acc = op(acc, x);
+    }
+    return acc;
+}
+
+int count(List<int> xs, Pred test) {
+    var n = 0;
+    for (var x in xs) {
+        if (test(x)) {
+            n = n + 1;
+        }
+    }
+    return n;
+}
+
+void each(List<int> xs, void f(int x)) {
+    for (var x in xs) {
+        f(x);
+    }
+}
+
+var seen = <int>[];
+
+void remember(int x) {
+    seen.add(x);
+}
+
+class Button {
+    String name;
+    void Function(String) onClick;
+
+    Button(this.name, this.onClick);
+
+    void click() {
+        onClick(name);
+    }
+}
+
+var clicked = <String>[];
+
+void record(String s) {
+    clicked.add(s);
+}
+
+IntOp pick(bool sum) {
+    if (sum) {
+        return add;
+    }
+    return mul;
+}
+
+void main() {
+    assert(fold([1, 2, 3, 4], 0, add) == 10);
+    assert(fold([1, 2, 3, 4], 1, mul) == 24);
+    assert(count([1, 2, 3, 4, 5, 6], isEven) == 3);
+
+    each([3, 1, 2], remember);
+    assert(seen.length == 3);
+    assert(seen[0] == 3);
+
+    IntOp op = pick(false);
+    assert(op(6, 7) == 42);
+    int Function(int, int) same = op;
+    assert(same(2, 3) == 6);
+
+    Mapper<int> m = twice;
+    assert(m(21) == 42);
+
+    var b = Button("ok", record);
+    b.click();
+    b.onClick("direct");
+    assert(clicked.length == 2);
+    assert(clicked[1] == "direct");
+
+    assert(add is IntOp);
+    assert(add is Function);
+    assert(add is int Function(int, int));
+    assert(isEven is Pred);
+    assert(isEven is! IntOp);
+    assert(label is String Function(int));
+    assert(label is String Function(int, [String]));
+    assert(label is! String Function(String));
+    assert(twice is Mapper<int>);
+    assert(remember is void Function(int));
+    assert(record is! void Function(int));
+
+    Function f = label;
+    assert(f is String Function(int));
+
+    // The library takes typed callbacks.
+    var xs = [3, 1, 2];
+    assert(xs.fold(0, add) == 6);
+    assert(xs.fold<int>(1, mul) == 6);
+    assert(xs.where(isEven).length == 1);
+    assert(xs.indexWhere(isEven) == 2);
+    assert(xs.firstWhere(isEven) == 2);
+    assert(xs.reduce(add) == 6);
+    assert("${xs.map(label)}" == "(3!, 1!, 2!)");
+    xs.forEach(twice);
+    xs.removeWhere(isEven);
+    assert("$xs" == "[3, 1]");
+
+    print(add);
+    print(label);
+    print(each);
+    print(add.runtimeType);
+    print(IntOp);
+    print(Pred);
+    print(label(3));
+}
@@ -0,0 +1,110 @@
+class Counter {
+    int count = 0;
+
+    int increment(int by) {
+        count = count + by;
+        return count;
+    }
+
+    Function incrementer() {
+        return increment;
+    }
+}
+
+class Point {
+    int x;
+    int y;
+
+    Point(this.x, this.y);
+
+    String toString() {
+        return "Point(" + x.toString() + ", " + y.toString() + ")";
+    }
+}
+
+class Adder {
+    int base;
+
+    Adder(this.base);
+
+    int call(int x) {
+        return base + x;
+    }
+}
+
+class Box<T> {
+    List<T> items = [];
+
+    void add(T item) {
+        items.add(item);
+    }
+}
+
+int apply(int Function(int) f, int x) {
+    return f(x);
+}
+
+// The instance is only kept alive by the torn off method.
+Function lonely() {
+    return Counter().increment;
+}
+
+int twice(int x) {
+    return x * 2;
+}
+
+void main() {
+    var counter = Counter();
+    var inc = counter.increment;
+    inc(2);
+    inc(3);
+    assert(counter.count == 5);
+    assert(apply(counter.increment, 10) == 15);
+    assert(inc is int Function(int));
+    assert(inc == counter.increment);
+    assert(inc != Counter().increment);
+
+    Function viaThis = counter.incrementer();
+    viaThis(1);
+    assert(counter.count == 16);
+
+    var make = Point.new;
+    var p = make(1, 2);
+    assert(p.x == 1);
+    assert(p.y == 2);
+    assert(make is Point Function(int, int));
+
+    var add5 = Adder(5);
+    assert(add5(1) == 6);
+    assert(apply(add5, 10) == 15);
+    assert(add5.call(2) == 7);
+
+    var box = Box<int>();
+    var put = box.add;
+    put(1);
+    put(2);
+    assert(box.items.length == 2);
+    assert(put is void Function(int));
+    assert(put is! void Function(String));
+
+    var f = twice;
+    assert(f == twice);
+    assert(f(4) == 8);
+
+    var kept = lonely();
+    for (var i = 0; i < 300; i++) {
+        Point(i, i);
+    }
+    assert(kept(3) == 3);
+    assert(kept(4) == 7);
+
+    var g = print;
+    g("printed through a tear-off");
+    [1, 2, 3].forEach(print);
+
+    print(inc);
+    print(put);
+    print(make);
+    print(g);
+    print(p);
+}
@@ -0,0 +1,143 @@
+var registry = <Animal>[];
+
+class Animal {
+    String name;
+    int legs = 4;
+    int steps = 0;
+
+    Animal(this.name) {
+        // Runs with the most derived object as this.
+        registry.add(this);
+        print("new " + kind());
+    }
+
+    String kind() {
+        return "animal";
+    }
+
+    String sound() {
+        return "...";
+    }
+
+    String describe() {
+        return name + " the " + kind() + " says " + sound();
+    }
+
+    void walk() {
+        steps = steps + legs;
+    }
+
+    int get weight {
+        return 10;
+    }
+
+    String toString() {
+        return "Animal(" + name + ")";
+    }
+}
+
+class Bird extends Animal {
+    int legs = 2;
+
+    Bird(String name) : super(name);
+
+    String kind() {
+        return "bird";
+    }
+
+    String sound() {
+        return "tweet";
+    }
+
+    int get weight {
+        return super.weight ~/ 5;
+    }
+
+    String toString() {
+        return "Bird:" + super.toString();
+    }
+
+    int parentLegs() {
+        return super.legs;
+    }
+
+    void clipLegs() {
+        super.legs = 3;
+    }
+}
+
+class Parrot extends Bird {
+    Parrot(String name) : super(name);
+
+    String kind() {
+        return "parrot";
+    }
+
+    String sound() {
+        return "hello " + super.sound();
+    }
+
+    String sounds() {
+        return "${super.sound()} ${super.weight}";
+    }
+
+    String describe() {
+        // Found in Animal, as Bird does not declare it.
+        return super.describe() + "!";
+    }
+
+    bool sameAs(Object other) {
+        return super == other;
+    }
+}
+
+class Box<T> {
+    T value;
+
+    Box(this.value);
+
+    T read() {
+        return value;
+    }
+}
+
+class IntBox extends Box<int> {
+    IntBox(int v) : super(v);
+
+    int read() {
+        return super.read() + 1;
+    }
+
+    int Function() reader() {
+        return super.read;
+    }
+}
+
+void main() {
+    var b = Bird("Tweety");
+    print(b.describe());
+    b.walk();
+    b.walk();
+    assert(b.steps == 4);
+    assert(b.weight == 2);
+    assert(b.parentLegs() == 4);
+    b.clipLegs();
+    assert(b.legs == 2);
+    assert(b.parentLegs() == 3);
+
+    var p = Parrot("Polly");
+    print(p.describe());
+    assert(p.sameAs(p));
+    assert(!p.sameAs(b));
+    print(p.sounds());
+    assert(registry.length == 2);
+    assert(registry[1] == p);
+
+    var ib = IntBox(41);
+    assert(ib.read() == 42);
+    var r = ib.reader();
+    assert(r() == 41);
+
+    print(b);
+    print(p);
+}
@@ -0,0 +1,131 @@
+class Plain {}
+
+class Point {
+    int x;
+    int y;
+
+    Point(this.x, this.y);
+
+    bool operator ==(Object other) {
+        if (other is Point) {
+            return x == other.x && y == other.y;
+        }
+        return false;
+    }
+
+    int get hashCode {
+        return x * 31 + y;
+    }
+
+    String toString() {
+        return "Point(" + x.toString() + ", " + y.toString() + ")";
+    }
+}
+
+// The right operand of == runs a body, where the garbage collector can run.
+Point pointAt(int x) {
+    List<int> parts = [x, x];
+    int sum = 0;
+    for (int part in parts) {
+        sum = sum + part;
+    }
+    return Point(x, sum - x);
+}
+
+class Point3 extends Point {
+    int z;
+
+    Point3(int x, int y, this.z) : super(x, y);
+
+    bool samePlane(Object other) {
+        return super == other;
+    }
+
+    String toString() {
+        return super.toString() + " at " + z.toString();
+    }
+}
+
+class Box<T> {
+    T value;
+
+    Box(this.value);
+}
+
+// Records what is called on it.
+class Recorder {
+    var calls = <String>[];
+
+    dynamic noSuchMethod(Invocation invocation) {
+        var name = invocation.memberName.toString();
+        if (invocation.isGetter) {
+            calls.add("get " + name);
+            return 1;
+        }
+        if (invocation.isSetter) {
+            calls.add("set " + name);
+            return null;
+        }
+        calls.add("call " + name + " with " + invocation.positionalArguments.length.toString());
+        if (invocation.namedArguments.isNotEmpty) {
+            calls.add("named ${invocation.namedArguments}");
+        }
+        return invocation.positionalArguments.length;
+    }
+}
+
+void main() {
+    var a = Plain();
+    var b = Plain();
+    assert(a == a);
+    assert(a != b);
+    assert(identical(a, a));
+    assert(!identical(a, b));
+    assert(a.hashCode == a.hashCode);
+    assert(a.hashCode == identityHashCode(a));
+    assert(a.runtimeType == Plain);
+    assert(a.toString() == "Instance of 'Plain'");
+    print(a);
+    print("interpolated: $a");
+    print(Box<int>(1));
+
+    var p = Point(1, 2);
+    var q = Point(1, 2);
+    assert(p == q);
+    assert(p != Point(2, 1));
+    assert(!identical(p, q));
+    assert(p.hashCode == q.hashCode);
+    assert(p != null);
+    assert([p].indexOf(p) == 0);
+
+    var r = Point3(1, 2, 3);
+    assert(r == p);
+    assert(r.samePlane(q));
+    print(r);
+
+    assert(identical(1, 1));
+    assert(identical("a", "a"));
+    assert(3.hashCode == 3);
+    assert("abc".hashCode == "abc".hashCode);
+
+    dynamic rec = Recorder();
+    assert(rec.fly(1, 2) == 2);
+    assert(rec.land(3, speed: 5) == 1);
+    assert(rec.wings == 1);
+    rec.wings = 4;
+    rec();
+    print(rec.calls);
+
+    Object o = p;
+    print(o.toString());
+    assert(Symbol("fly") == Symbol("fly"));
+    print(Symbol("fly"));
+
+    int equal = 0;
+    for (int i = 0; i < 2000; i++) {
+        if (Point(i, i) == pointAt(i) && Point(i, -1) != pointAt(i)) {
+            equal = equal + 1;
+        }
+    }
+    assert(equal == 2000);
+}
@@ -0,0 +1,156 @@
+sealed class Shape {}
+
+class Circle extends Shape {
+    double radius;
+    Circle(this.radius);
+}
+
+class Rect extends Shape {
+    double width;
+    double height;
+    Rect(this.width, this.height);
+}
+
+// A sealed subclass is matched by its own subclasses.
+sealed class Line extends Shape {}
+
+class Segment extends Line {
+    double length;
+    Segment(this.length);
+}
+
+class Ray extends Line {}
+
+
+abstract class Animal {
+    String sound() {
+        return "...";
+    }
+}
+
+class Dog extends Animal {
+    String sound() {
+        return "woof";
+    }
+}
+
+
+base class Vehicle {
+    int wheels = 4;
+}
+
+final class Car extends Vehicle {}
+
+base class Bike extends Vehicle {
+    Bike() {
+        wheels = 2;
+    }
+}
+
+interface class Printer {
+    String format(int n) {
+        return "#" + n.toString();
+    }
+}
+
+mixin class Greeter {
+    String greet() {
+        return "hello";
+    }
+}
+
+abstract base mixin class Named {}
+
+
+double area(Shape s) {
+    return switch (s) {
+        Circle(radius: var r) => 3.0 * r * r,
+        Rect(:var width, :var height) => width * height,
+        Segment() => 0.0,
+        Ray() => 0.0
+    };
+}
+
+
+// Every case returns, and the cases cover Shape.
+String describe(Shape s) {
+    switch (s) {
+        case Circle c when c.radius > 10.0:
+            return "big circle";
+        case Circle():
+            return "circle";
+        case Rect(width: 1.0, height: 1.0):
+            return "unit square";
+        case Rect r:
+            return "rect " + r.width.toString();
+        case Line():
+            return "line";
+    }
+}
+
+
+String size(int n) {
+    switch (n) {
+        case 0:
+            return "none";
+        case 1:
+        case 2:
+            return "few";
+        case int m when m < 0:
+            return "negative";
+        default:
+            return "many";
+    }
+}
+
+
+String answer(bool? b) {
+    return switch (b) {
+        true => "yes",
+        false => "no",
+        null => "unknown"
+    };
+}
+
+
+void main() {
+    List<Shape> shapes = [Circle(1.0), Circle(20.0), Rect(1.0, 1.0), Rect(2.0, 3.0), Segment(5.0), Ray()];
+    for (Shape s in shapes) {
+        print(describe(s));
+        print(area(s));
+    }
+
+    for (int n in [-3, 0, 1, 2, 7]) {
+        print(size(n));
+    }
+
+    print(answer(true));
+    print(answer(false));
+    print(answer(null));
+
+    // A switch statement runs the first matching case only.
+    String word = "b";
+    switch (word) {
+        case "a":
+            print("first");
+        case "b":
+            print("second");
+        case "c":
+            print("third");
+    }
+
+    // Pattern variables are scoped to their case.
+    var kind = switch (shapes[3]) {
+        Rect(width: var w) when w > 1.0 => "wide",
+        _ => "narrow"
+    };
+    print(kind);
+
+    Animal a = Dog();
+    print(a.sound());
+
+    print(Car().wheels);
+    print(Bike().wheels);
+    print(Printer().format(3));
+    print(Greeter().greet());
+}
@@ -0,0 +1,164 @@
+class Account {
+    int balance = 0;
+
+    Future<int> deposit(int amount) async {
+        await Future.delayed(Duration(milliseconds: 10));
+        balance = balance + amount;
+        return balance;
+    }
+}
+
+
+Future<int> double(int x) async {
+    print("doubling " + x.toString());
+    int y = await Future.value(x);
+    return y * 2;
+}
+
+
+Future<int> sumTo(int n) async {
+    int sum = 0;
+    for (int i = 1; i <= n; i++) {
+        sum = sum + await Future.value(i);
+    }
+    int tries = 0;
+    while (await Future.value(tries < 3)) {
+        tries++;
+    }
+    if (await Future.value(false)) {
+        print("not printed");
+    }
+    else if (await Future.value(true)) {
+        print("tries " + tries.toString());
+    }
+    return sum;
+}
+
+
+// The future of an async function completes with the returned future.
+Future<T> later<T>(T x) async {
+    await Future.delayed(Duration(seconds: 1));
+    return Future.value(x);
+}
+
+
+Future<String> fails() async {
+    await Future.error("boom");
+    print("not printed");
+    return "never";
+}
+
+
+Future<String> describe(int n) async {
+    switch (await Future.value(n)) {
+        case 1:
+            return "one";
+        case int m when m > 5:
+            String s = await Future.value("big");
+            return s;
+    }
+    return "other";
+}
+
+
+List<String> events = [];
+
+
+String note(String s) {
+    events.add(s);
+    return s;
+}
+
+
+Future<String> noteLater(String s) async {
+    events.add(s);
+    return s;
+}
+
+
+Future<bool> check(bool b) async {
+    events.add("checked");
+    return b;
+}
+
+
+int square(int x) {
+    return x * x;
+}
+
+
+int answer() {
+    return 42;
+}
+
+
+void show(dynamic x) {
+    print("value: $x");
+}
+
+
+void handle(dynamic e) {
+    print("error: $e");
+}
+
+
+void done() {
+    print("done");
+}
+
+
+void tick(String name) async {
+    await null;
+    print("tick " + name);
+}
+
+
+void main() async {
+    print("main start");
+
+    // Callbacks run after main has reached its first await.
+    Future.value(3).then(square).then(show);
+    Future.error("bad").catchError(handle);
+    Future.value(1).whenComplete(done);
+    fails().then(show, onError: handle);
+    tick("a");
+
+    // Awaits run left to right.
+    print(await double(3) + await double(4));
+
+    Future<int> f = sumTo(4);
+    print(f.runtimeType);
+    print(await f);
+
+    // Timers run in the order they are due.
+    Future.delayed(Duration(milliseconds: 30), answer).then(show);
+    Future.delayed(Duration(milliseconds: 20)).then(show);
+    Account account = Account();
+    account.deposit(5).then(show);
+    account.deposit(7).then(show);
+
+    print(await Future.wait([Future.value(1), Future.delayed(Duration(milliseconds: 5), answer), later("x")]));
+    print(await describe(1));
+    print(await describe(9));
+    print(await describe(3));
+    print(await Future(answer));
+    print(await 5);
+
+    // An await runs after what comes before it,
+    // and not at all when it is short-circuited.
+    print(note("left") + await noteLater("right"));
+    print(false && await check(true));
+    print(true || await check(false));
+    print(events);
+
+    // Awaits in for elements see the loop variables.
+    List<int> xs = [1, 2, 3];
+    print([for (var x in xs) await Future.value(x * 10)]);
+    print([for (int i = 0; i < 3; i++) i + await Future.value(i)]);
+    print({for (var x in xs) x: await Future.value(x * x)});
+    print([for (var x in xs) for (var y in [10, 20]) x + await Future.value(y)]);
+
+    // And in interpolated strings.
+    print("${await later("p")} and ${await double(5)}");
+    print("main end");
+}
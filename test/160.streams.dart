@@ -0,0 +1,154 @@
+Stream<int> countTo(int n) async* {
+    for (int i = 1; i <= n; i++) {
+        await Future.delayed(Duration(milliseconds: 10));
+        yield i;
+    }
+}
+
+
+Stream<int> naturals() async* {
+    int i = 0;
+    while (true) {
+        i++;
+        print("yielding $i");
+        yield i;
+    }
+}
+
+
+// yield* passes on the events of another stream.
+Stream<int> joined() async* {
+    yield 0;
+    yield* countTo(2);
+    yield* Stream.fromIterable([7, 8]);
+}
+
+
+Stream<String> failing() async* {
+    yield "first";
+    await Future.error("broken");
+    yield "not reached";
+}
+
+
+class Lines {
+    List<String> text;
+
+    Lines(this.text);
+
+    Stream<String> nonEmpty() async* {
+        for (String line in text) {
+            if (line != "") {
+                yield line;
+            }
+        }
+    }
+}
+
+
+StreamSubscription<int>? subscription;
+int received = 0;
+
+
+void onNatural(int n) {
+    print("natural $n");
+    received++;
+    StreamSubscription<int> s = subscription!;
+    if (received == 2) {
+        s.pause(Future.delayed(Duration(seconds: 1)));
+        print("paused");
+    }
+    if (received == 4) {
+        s.cancel();
+        print("cancelled");
+    }
+}
+
+
+bool isLarge(int x) {
+    return x > 2;
+}
+
+
+int square(int x) {
+    return x * x;
+}
+
+
+void show(dynamic x) {
+    print("event $x");
+}
+
+
+void showError(dynamic e) {
+    print("error $e");
+}
+
+
+void done() {
+    print("done");
+}
+
+
+void listening() {
+    print("listening");
+}
+
+
+void cancelling() {
+    print("cancelling");
+}
+
+
+void main() async {
+    StreamController<String> controller = StreamController<String>();
+    controller.stream.listen(show, onDone: done);
+    controller.add("a");
+    controller.add("b");
+    controller.close();
+
+    await for (int x in countTo(3)) {
+        print("got $x");
+    }
+
+    subscription = naturals().listen(onNatural);
+    await Future.delayed(Duration(seconds: 2));
+
+    failing().listen(show, onError: showError, onDone: done);
+    await Future.delayed(Duration(seconds: 1));
+
+    print(await joined().toList());
+    print(await Stream.fromIterable([1, 2, 3, 4, 5]).where(isLarge).map(square).take(2).toList());
+    print(await Stream.periodic(Duration(seconds: 1), square).take(4).toList());
+    await Stream.value("single").forEach(show);
+    print(await Stream.empty().toList());
+    Stream.error("failed").listen(show, onError: showError);
+    await failing().toList().catchError(showError);
+
+    List<String> lines = [];
+    await for (String line in Lines(["one", "", "two"]).nonEmpty()) {
+        lines.add(line);
+    }
+    print(lines);
+
+    StreamController<int> hooked = StreamController<int>(onListen: listening, onCancel: cancelling);
+    print(hooked.hasListener);
+    StreamSubscription<int> s = hooked.stream.listen(show);
+    print(hooked.hasListener);
+    hooked.add(1);
+    await Future.delayed(Duration(seconds: 1));
+    s.cancel();
+    print(hooked.hasListener);
+
+    StreamController<int> errors = StreamController<int>();
+    errors.stream.listen(show, onError: showError, cancelOnError: true);
+    errors.add(1);
+    errors.addError("bad");
+    errors.add(2);
+    await errors.close();
+    print(errors.isClosed);
+
+    Stream<int> stream = countTo(1);
+    print(stream.runtimeType);
+    print(stream is Stream<int>);
+}
@@ -0,0 +1,105 @@
+import 'dart:isolate';
+
+
+int spawned = 0;
+
+
+class Node {
+    String name;
+    Node? next;
+
+    Node(this.name);
+}
+
+
+// Each isolate has its own top level variables.
+void worker(List args) {
+    SendPort reply = args[0];
+    int n = args[1];
+    spawned = spawned + 1;
+    reply.send(n * 2);
+    reply.send(spawned);
+    reply.send([1, "two", 3.5, null, true, [4, 5]]);
+
+    Node a = Node("a");
+    Node b = Node("b");
+    a.next = b;
+    b.next = a;
+    reply.send(a);
+    reply.send("done");
+}
+
+
+// Echoes messages until it gets null.
+void echo(SendPort reply) async {
+    ReceivePort inbox = ReceivePort();
+    reply.send(inbox.sendPort);
+    await for (dynamic msg in inbox) {
+        if (msg == null) {
+            inbox.close();
+        }
+        else {
+            reply.send(msg);
+        }
+    }
+}
+
+
+int fib(int n) {
+    if (n < 2) {
+        return n;
+    }
+    return fib(n - 1) + fib(n - 2);
+}
+
+
+int fib20() {
+    return fib(20);
+}
+
+
+Future<String> later() async {
+    await Future.delayed(Duration(milliseconds: 20));
+    return "later";
+}
+
+
+void main() async {
+    ReceivePort port = ReceivePort();
+    await Isolate.spawn(worker, [port.sendPort, 21]);
+    await for (dynamic msg in port) {
+        if (msg is Node) {
+            Node next = msg.next!;
+            print(msg.name);
+            print(next.name);
+            print(identical(next.next, msg));
+        }
+        else if (msg == "done") {
+            port.close();
+        }
+        else {
+            print(msg);
+        }
+    }
+    print(spawned);
+
+    ReceivePort replies = ReceivePort();
+    await Isolate.spawn(echo, replies.sendPort);
+    StreamIterator<dynamic> it = StreamIterator<dynamic>(replies);
+    await it.moveNext();
+    SendPort echoer = it.current;
+    List<int> sent = [1, 2];
+    echoer.send(sent);
+    await it.moveNext();
+    List<dynamic> echoed = it.current;
+    print(echoed);
+    print(identical(echoed, sent));
+    echoer.send(null);
+    replies.close();
+
+    print(await Isolate.run(fib20));
+    print(await Isolate.run(later));
+
+    print(port.sendPort == port.sendPort);
+    print(port.sendPort == replies.sendPort);
+}
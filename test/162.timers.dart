@@ -0,0 +1,64 @@
+import 'dart:async';
+
+
+List<String> events = [];
+Stopwatch watch = Stopwatch();
+Timer? single;
+
+
+void fired() {
+    events.add("fired after 50ms: ${watch.elapsedMilliseconds >= 50}");
+}
+
+
+void never() {
+    events.add("cancelled timer fired");
+}
+
+
+void ran() {
+    events.add("ran");
+}
+
+
+void onTick(Timer timer) {
+    events.add("tick ${timer.tick}");
+    if (timer.tick == 3) {
+        timer.cancel();
+        events.add("periodic active: ${timer.isActive}");
+    }
+}
+
+
+void report() {
+    watch.stop();
+    Timer t = single!;
+    print(events);
+    print(t.isActive);
+    print(t.tick);
+    print(watch.isRunning);
+    print(watch.elapsedMilliseconds >= 200);
+    print(watch.elapsed.inMilliseconds == watch.elapsedMilliseconds);
+    print(watch.elapsedMicroseconds == watch.elapsedTicks);
+
+    int stopped = watch.elapsedMicroseconds;
+    print(watch.elapsedMicroseconds == stopped);
+    watch.reset();
+    print(watch.elapsedMicroseconds);
+}
+
+
+void main() {
+    print(watch.isRunning);
+    print(watch.elapsedMilliseconds);
+    print(watch.frequency);
+    watch.start();
+    print(watch.isRunning);
+
+    single = Timer(Duration(milliseconds: 50), fired);
+    Timer(Duration(seconds: 10), never).cancel();
+    Timer.periodic(Duration(milliseconds: 40), onTick);
+    Timer.run(ran);
+    Timer(Duration(milliseconds: 200), report);
+    print(single!.isActive);
+}
@@ -0,0 +1,130 @@
+class Vector {
+    int x;
+    int y;
+
+    Vector(this.x, this.y);
+
+
+    Vector operator +(Vector other) {
+        return Vector(x + other.x, y + other.y);
+    }
+
+
+    Vector operator -(Vector other) {
+        return Vector(x - other.x, y - other.y);
+    }
+
+
+    Vector operator -() {
+        return Vector(0 - x, 0 - y);
+    }
+
+
+    Vector operator *(int factor) {
+        return Vector(x * factor, y * factor);
+    }
+
+
+    double operator /(int divisor) {
+        return length / divisor;
+    }
+
+
+    Vector operator ~/(int divisor) {
+        return Vector(x ~/ divisor, y ~/ divisor);
+    }
+
+
+    bool operator <(Vector other) {
+        return length < other.length;
+    }
+
+
+    bool operator >(Vector other) {
+        return length > other.length;
+    }
+
+
+    bool operator <=(Vector other) {
+        return length <= other.length;
+    }
+
+
+    bool operator >=(Vector other) {
+        return length >= other.length;
+    }
+
+
+    bool operator ==(Object other) {
+        return other is Vector && other.x == x && other.y == y;
+    }
+
+
+    int get length {
+        return x * x + y * y;
+    }
+
+
+    String toString() {
+        return "($x, $y)";
+    }
+}
+
+
+// Operators are inherited like other methods.
+class NamedVector extends Vector {
+    String name;
+
+    NamedVector(this.name, int x, int y) : super(x, y);
+}
+
+
+// Overridden operators can call the operator of the parent class.
+class DoublingVector extends Vector {
+    DoublingVector(int x, int y) : super(x, y);
+
+
+    Vector operator +(Vector other) {
+        return super + other * 2;
+    }
+
+
+    Vector operator -() {
+        Vector negated = -super;
+        return negated * 2;
+    }
+}
+
+
+void main() {
+    Vector a = Vector(1, 2);
+    Vector b = Vector(3, 5);
+
+    print(a + b);
+    print(b - a);
+    print(-a);
+    print(a * 3);
+    print(b / 2);
+    print(b ~/ 2);
+    print(a < b);
+    print(a > b);
+    print(a <= a);
+    print(b >= a);
+    print(a + b == Vector(4, 7));
+
+    Vector sum = a + b * 2 - -a;
+    print(sum);
+
+    NamedVector n = NamedVector("n", 1, 1);
+    print(n + a);
+    print(n < a);
+
+    Vector acc = Vector(0, 0);
+    acc = acc + a;
+    acc = acc + a;
+    print(acc);
+
+    DoublingVector d = DoublingVector(1, 1);
+    print(d + a);
+    print(-d);
+}
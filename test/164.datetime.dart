@@ -0,0 +1,89 @@
+void main() {
+    // Duration
+    Duration d = Duration(hours: 1, minutes: 2, seconds: 3);
+    print(d);
+    print(-d);
+    print(Duration(days: 2, microseconds: 5));
+    print(d + Duration(milliseconds: 5));
+    print(d - Duration(hours: 2));
+    print(d * 2);
+    print(d * 0.5);
+    print(d ~/ 4);
+    print(d < Duration(hours: 2));
+    print(d > Duration(hours: 2));
+    print(d <= d);
+    print(d >= Duration.zero);
+    print(d == Duration(seconds: 3723));
+    print(d.inDays);
+    print(d.inHours);
+    print(d.inMinutes);
+    print(d.inSeconds);
+    print(d.inMilliseconds);
+    print((-d).isNegative);
+    print((-d).abs() == d);
+    print(d.compareTo(Duration.zero));
+    print(Duration.zero);
+    print(Duration.microsecondsPerDay);
+    print(Duration.secondsPerHour);
+
+    // DateTime in UTC
+    DateTime t = DateTime.utc(2024, 2, 29, 13, 45, 30, 123, 456);
+    print(t);
+    print(t.toIso8601String());
+    print(t.isUtc);
+    print(t.year);
+    print(t.month);
+    print(t.day);
+    print(t.hour);
+    print(t.minute);
+    print(t.second);
+    print(t.millisecond);
+    print(t.microsecond);
+    print(t.weekday == DateTime.thursday);
+    print(t.millisecondsSinceEpoch);
+    print(t.microsecondsSinceEpoch);
+    print(t.timeZoneName);
+
+    print(t.add(Duration(days: 1)));
+    print(t.subtract(Duration(days: 366)));
+    DateTime next = DateTime.utc(2024, 13, 1);
+    print(next);
+    print(next.difference(t));
+    print(t.isBefore(next));
+    print(t.isAfter(next));
+    print(t.isAtSameMomentAs(t.add(Duration.zero)));
+    print(t.compareTo(next));
+    print(t == DateTime.utc(2024, 2, 29, 13, 45, 30, 123, 456));
+    print(DateTime.utc(2023, 1, 1, 0, 0, 0, 5));
+    print(DateTime.utc(1969, 12, 31, 23, 59, 59, 999));
+    print(DateTime.utc(-1, 1, 1).toIso8601String());
+    print(DateTime.utc(12345, 1, 1).toIso8601String());
+    print(DateTime.fromMillisecondsSinceEpoch(0, isUtc: true));
+    print(DateTime.fromMicrosecondsSinceEpoch(-1, isUtc: true));
+
+    // Parsing
+    print(DateTime.parse("2020-01-02T03:04:05.123456789Z"));
+    print(DateTime.parse("20200102 030405+0130"));
+    print(DateTime.parse("2020-01-02 03:04:05,5 -02:00"));
+    print(DateTime.parse("-002020-01-02T00:00:00Z").toIso8601String());
+    print(DateTime.parse("2020-02-30T24:00Z"));
+    print(DateTime.tryParse("2020-1-02"));
+    print(DateTime.tryParse("not a date"));
+    print(DateTime.parse("2020-01-02").isUtc);
+
+    // Local time, whatever the zone.
+    DateTime local = DateTime(2024, 7, 1, 12, 30);
+    print(local.isUtc);
+    print(local.hour);
+    print(local.minute);
+    print(local.toUtc().toLocal() == local);
+    print(local.toUtc().isAtSameMomentAs(local));
+    print(local.toUtc() == local);
+    print(local.toUtc().add(local.timeZoneOffset).hour);
+    print(DateTime.parse("2024-07-01 12:30") == local);
+
+    DateTime before = DateTime.now();
+    DateTime after = DateTime.now();
+    print(after.isBefore(before));
+    print(before.isAfter(DateTime.utc(2020)));
+}
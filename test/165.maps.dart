@@ -0,0 +1,147 @@
+// A key that is == to another with the same id, as its hashCode is.
+class Id {
+    int id;
+
+    Id(this.id);
+
+    bool operator ==(Object other) {
+        if (other is Id) {
+            return id == other.id;
+        }
+        return false;
+    }
+
+    int get hashCode {
+        return id;
+    }
+
+    String toString() {
+        return "Id($id)";
+    }
+}
+
+
+class Counter {
+    Map<String, int> counts = {};
+
+    void count(String word) {
+        int? n = counts[word];
+        if (n == null) {
+            counts[word] = 1;
+        }
+        else {
+            counts[word] = n + 1;
+        }
+    }
+}
+
+
+int zero() {
+    return 0;
+}
+
+
+void show(String key, int value) {
+    print("$key -> $value");
+}
+
+
+void main() {
+    // Literals
+    var m = {"one": 1, "two": 2};
+    print(m);
+    print(m["one"]);
+    print(m["three"]);
+    print(m.length);
+    print(m is Map<String, int>);
+    print({});
+    print(<String, double>{"pi": 3.14});
+    print({"k": 1, "k": 2});
+    print({1: "a", 2: [1, 2], 3: {"x": null}});
+
+    // Setting, adding and removing
+    Map<String, int> ages = {};
+    ages["ann"] = 30;
+    ages["bob"] = 25;
+    ages["ann"] = 31;
+    print(ages);
+    print(ages.containsKey("bob"));
+    print(ages.containsValue(25));
+    print(ages.remove("bob"));
+    print(ages.remove("bob"));
+    print(ages);
+    print(ages.isEmpty);
+    print(ages.isNotEmpty);
+    print(ages.putIfAbsent("cid", zero));
+    print(ages.putIfAbsent("ann", zero));
+    ages.addAll({"dan": 40, "ann": 32});
+    print(ages);
+
+    // Keys, values and entries, in the order added
+    print(ages.keys);
+    print(ages.values);
+    print(ages.entries);
+    for (var key in ages.keys) {
+        print(key);
+    }
+    for (var entry in ages.entries) {
+        print("${entry.key}=${entry.value}");
+    }
+    ages.forEach(show);
+    ages.clear();
+    print(ages);
+
+    // Nested
+    Map<String, List<int>> lists = {"a": [1]};
+    lists["a"]!.add(2);
+    print(lists);
+    Map<String, Map<String, int>> nested = {"x": {"y": 1}};
+    nested["x"]!["z"] = 2;
+    print(nested["x"]!["z"]);
+    print(nested);
+
+    // In a field
+    Counter counter = Counter();
+    counter.count("a");
+    counter.count("b");
+    counter.count("a");
+    print(counter.counts);
+
+    // Other kinds of keys
+    Map<int, String> names = Map<int, String>();
+    names[2] = "two";
+    names[1] = "one";
+    print(names);
+    print(names[1]);
+    Map<bool, String> flags = {true: "yes", false: "no"};
+    print(flags[false]);
+    print(Map());
+
+    // Spread, if and for elements
+    var base = {"a": 1};
+    bool more = true;
+    print({...base, if (more) "b": 2, if (!more) "c": 3 else "d": 4});
+    print({for (var k in ["x", "y"]) k: k + k});
+    print({for (int i = 0; i < 3; i++) i: i * i});
+    Map<String, int>? missing;
+    print(<String, num>{...?missing, ...base, "e": 1.5});
+
+    // Keys are compared with their ==, and found by their hashCode
+    Map<Id, String> ids = {};
+    ids[Id(1)] = "a";
+    ids[Id(1)] = "b";
+    print(ids.length);
+    print(ids[Id(1)]);
+    print(ids.containsKey(Id(2)));
+    print({Id(2): "x", Id(3): "y", Id(2): "z"});
+    print({1: "one"}[1.0]);
+    Map<int, int> squares = {};
+    for (int i = 0; i < 500; i++) {
+        squares[i * 7] = i * i;
+    }
+    for (int i = 0; i < 500; i++) {
+        assert(squares[i * 7] == i * i && squares[i * 7 + 1] == null);
+    }
+    print(squares.remove(70));
+    print([squares.length, squares[77], squares.containsKey(70)]);
+}
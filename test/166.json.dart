@@ -0,0 +1,106 @@
+import 'dart:convert';
+
+
+class Point {
+    int x;
+    int y;
+
+    Point(this.x, this.y);
+
+    Map<String, dynamic> toJson() {
+        return {"x": x, "y": y};
+    }
+}
+
+
+class Shape {
+    String name;
+    List<Point> points;
+
+    Shape(this.name, this.points);
+
+    Map<String, dynamic> toJson() {
+        return {"name": name, "points": points};
+    }
+}
+
+
+class Secret {
+    String value = "hidden";
+}
+
+
+Object? encodeSecret(Object? object) {
+    return "<secret>";
+}
+
+
+Object? doubleInts(Object? key, Object? value) {
+    if (value is int) {
+        return value * 2;
+    }
+    return value;
+}
+
+
+Object? traceReviver(Object? key, Object? value) {
+    print("revive $key: $value");
+    return value;
+}
+
+
+void main() {
+    // Encoding
+    print(jsonEncode(null));
+    print(jsonEncode(true));
+    print(jsonEncode(42));
+    print(jsonEncode(-1.5));
+    print(jsonEncode(2.0));
+    print(jsonEncode("tab\there \"quoted\" back\\slash \u0001 é"));
+    print(jsonEncode([1, "two", [3.0], {}]));
+    print(jsonEncode({"a": 1, "b": null, "c": [true, false]}));
+    print(jsonEncode(Point(1, 2)));
+    print(jsonEncode(Shape("line", [Point(0, 0), Point(3, 4)])));
+    print(jsonEncode([Secret()], toEncodable: encodeSecret));
+
+    // Indented
+    JsonEncoder encoder = JsonEncoder.withIndent("  ");
+    print(encoder.convert({"name": "redart", "tags": ["dart", "rust"], "empty": [], "none": {}, "n": 1}));
+    print(JsonEncoder.withIndent("\t").convert([[1, 2], {"k": "v"}]));
+    print(JsonEncoder.withIndent(null).convert({"a": [1]}));
+    print(JsonEncoder().convert(Point(5, 6)));
+
+    // Decoding
+    var config = jsonDecode('{"name": "redart", "threads": 4, "ratio": 0.75, "debug": false, "paths": ["a", "b"], "extra": null}');
+    print(config);
+    print(config is Map<String, dynamic>);
+    print(config["name"]);
+    print(config["threads"] + 1);
+    print(config["ratio"]);
+    print(config["paths"] is List<dynamic>);
+    print(config["paths"][1]);
+    print(config["extra"]);
+    print(config["missing"]);
+    print(jsonDecode("[]"));
+    print(jsonDecode("{}"));
+    print(jsonDecode(" \n\t 12 \r\n"));
+    print(jsonDecode("-0.5e2"));
+    print(jsonDecode("1E3"));
+    print(jsonDecode("123456789012345678901234567890"));
+    print(jsonEncode(jsonDecode('"\\u00e9\\u0041\\ud83d\\ude00 \\"\\\\\\/\\b\\f\\r\\t"')));
+    print(jsonDecode('"line\\nbreak"'));
+    print(jsonDecode('{"k": 1, "k": 2}'));
+    print(jsonDecode('[{"a": [{"b": []}]}]'));
+    print(JsonDecoder().convert("true"));
+
+    // Revivers
+    print(jsonDecode('{"a": 1, "b": [2, {"c": 3}], "d": "x"}', reviver: doubleInts));
+    print(JsonDecoder(doubleInts).convert("[1, 2]"));
+    jsonDecode('{"a": [1, {"b": 2}]}', reviver: traceReviver);
+
+    // Round trip
+    String text = jsonEncode(Shape("square", [Point(0, 0), Point(1, 1)]));
+    Map<String, dynamic> shape = jsonDecode(text);
+    print(shape["points"][1]["y"]);
+    print(jsonEncode(shape) == text);
+}
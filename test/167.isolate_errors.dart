@@ -0,0 +1,38 @@
+import 'dart:isolate';
+
+
+int parseBad() {
+    return int.parse("x");
+}
+
+
+int answer() {
+    return 42;
+}
+
+
+String recover(Object error) {
+    return "caught: $error";
+}
+
+
+// Ends with an unhandled exception after its first message.
+void crash(SendPort reply) {
+    reply.send("before");
+    int.parse("y");
+    reply.send("after");
+}
+
+
+void main() async {
+    // The exception of Isolate.run is that of its future.
+    print(await Isolate.run(parseBad).catchError(recover));
+    print(await Isolate.run(answer));
+
+    // An unhandled exception ends only the isolate it is thrown in.
+    ReceivePort port = ReceivePort();
+    await Isolate.spawn(crash, port.sendPort);
+    print(await port.first);
+    port.close();
+    print(await Isolate.run(answer));
+}
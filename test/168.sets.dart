@@ -0,0 +1,97 @@
+// Words are == when they have the same letters, whatever their order.
+class Word {
+    String text;
+
+    Word(this.text);
+
+    bool operator ==(Object other) {
+        if (other is Word) {
+            return sorted() == other.sorted();
+        }
+        return false;
+    }
+
+    int get hashCode {
+        return sorted().hashCode;
+    }
+
+    String sorted() {
+        List<String> letters = text.split("");
+        letters.sort();
+        return letters.join();
+    }
+
+    String toString() {
+        return text;
+    }
+}
+
+
+String twice(String s) {
+    return s + s;
+}
+
+
+void main() {
+    Set<int> s = [3, 1, 3, 2, 1].toSet();
+    print(s);
+    assert(s.length == 3);
+    assert(s.contains(2));
+    assert(!s.contains(5));
+
+    assert(s.add(4));
+    assert(!s.add(4));
+    assert(s.remove(1));
+    assert(!s.remove(1));
+    print(s);
+
+    Set<int> other = Set<int>();
+    other.addAll([2, 4, 6]);
+    print(s.union(other));
+    print(s.intersection(other));
+    print(s.difference(other));
+    assert(s.containsAll([2, 4]));
+    assert(!s.containsAll([2, 6]));
+
+    int sum = 0;
+    for (int x in s) {
+        sum = sum + x;
+    }
+    assert(sum == 9);
+    assert(s is Iterable<int>);
+    print(["a", "b", "a"].map(twice).toSet());
+
+    Set<int> copy = s.toSet();
+    copy.clear();
+    assert(copy.isEmpty);
+    assert(s.length == 3);
+
+    // Elements are found by their hashCode and compared with their ==
+    Set<Word> words = [Word("listen"), Word("silent"), Word("tinsel"), Word("rust")].toSet();
+    print(words);
+    assert(words.contains(Word("ruts")));
+    assert(!words.add(Word("enlist")));
+    assert(words.remove(Word("stur")));
+    assert(words.length == 1);
+    Set<int> many = Set<int>();
+    for (int i = 0; i < 300; i++) {
+        many.add(i * 3);
+        many.add(i * 3);
+    }
+    assert(many.length == 300 && many.contains(297) && !many.contains(298));
+
+    // Set literals, with spread, if and for elements
+    var a = [2, 3];
+    var lit = {1, 2};
+    assert(lit is Set<int>);
+    print({1, ...a});
+    print(<num>{1, 2.5, 1});
+    print({for (var x in a) x * 2, if (lit.contains(1)) 4 else 5, ...?null});
+    Set<String> empty = {};
+    assert(empty.isEmpty && empty is Set<String>);
+    Set<int> spread = {...a, ...a};
+    assert(spread.length == 2);
+    var map = {};
+    assert(map is Map);
+    assert({Word("listen"), Word("silent")}.length == 1);
+}
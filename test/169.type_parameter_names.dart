@@ -0,0 +1,71 @@
+// Classes named like the type parameters of library and user classes.
+// Inside a generic declaration, the name is the type parameter.
+class K {}
+class V {}
+class E {}
+class T {}
+
+
+class Pair<K, V> {
+    K key;
+    V value;
+
+    Pair(this.key, this.value);
+
+    K first() {
+        return key;
+    }
+
+    void swap(V v, K k) {
+        value = v;
+        key = k;
+    }
+}
+
+
+class Lists<K, V> {
+    List<K> keys = <K>[];
+    List<V> values = <V>[];
+
+    Lists();
+
+    void clear() {
+        keys = <K>[];
+        values = <V>[];
+    }
+}
+
+
+T pick<T>(List<T> xs) {
+    T x = xs[0];
+    return x;
+}
+
+
+void main() {
+    Pair<String, int> p = Pair<String, int>("a", 1);
+    String k = p.first();
+    int n = pick<int>([3, 4]);
+    print([k, n, p.value, pick(["s"])]);
+
+    Map<String, int> m = {"a": 1};
+    m["b"] = 2;
+    print(m);
+
+    List<int> xs = [1, 2, 2];
+    assert(xs.indexOf(2) == 1);
+    Set<int> s = xs.toSet();
+    print(s);
+
+    Pair<K, E> q = Pair<K, E>(K(), E());
+    print([q.key, q.value, T()]);
+
+    Pair<V, K> r = Pair<V, K>(V(), K());
+    r.swap(K(), V());
+    print([r.key, r.value]);
+    Lists<V, String> ls = Lists<V, String>();
+    ls.clear();
+    ls.keys.add(V());
+    ls.values.add("v");
+    print([ls.keys, ls.values]);
+}
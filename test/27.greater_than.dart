@@ -1,6 +1,6 @@
 
 void main() {
-  let b = 10 > 9;
+  var b = 10 > 9;
   print(b);
   assert(b);
 }
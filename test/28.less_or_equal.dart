@@ -1,6 +1,6 @@
 
 void main() {
-  let b = 10 <= 10;
+  var b = 10 <= 10;
   print(b);
   assert(b);
 }
@@ -1,6 +1,6 @@
 
 class Banana {
-  String bananatype;
+  late String bananatype;
 
   Banana(t) {
     bananatype = t;
@@ -12,7 +12,7 @@ class A {
 
 
 class B {
-    A a;
+    late A a;
 
     B() {
         a = A();
@@ -21,7 +21,7 @@ class B {
 
 
 class C {
-    B b;
+    late B b;
 
     C() {
         b = B();
@@ -2,7 +2,7 @@ import "speaker.dart";
 
 
 class Objection {
-    Speaker speaker;
+    late Speaker speaker;
 
 
     Objection() {
@@ -0,0 +1,10 @@
+// Dart error:
+// bin/main.dart:8:9: Error: Undefined name 'missing'.
+
+void main() {
+    var s = """
+one
+two
+three ${missing}""";
+    print(s);
+}
@@ -0,0 +1,8 @@
+// Dart error:
+// Unhandled exception:
+// IntegerDivisionByZeroException
+
+void main() {
+    var zero = 0;
+    print(1 ~/ zero);
+}
@@ -0,0 +1,7 @@
+// Dart error:
+// bin/main.dart:5:13: Error: Digit separators ('_') in a number literal can only be placed between two digits.
+
+void main() {
+    var n = 1000_;
+    print(n);
+}
@@ -0,0 +1,7 @@
+// Dart error:
+// bin/main.dart:5:13: Error: The integer literal 9223372036854775808 can't be represented in 64 bits.
+
+void main() {
+    var n = 9223372036854775808;
+    print(n);
+}
@@ -0,0 +1,9 @@
+// Dart error:
+// Unhandled exception:
+// FormatException: Invalid radix-10 number (at character 1)
+// 12a
+// ^
+
+void main() {
+    print(int.parse("12a"));
+}
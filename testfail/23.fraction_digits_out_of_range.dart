@@ -0,0 +1,7 @@
+// Dart error:
+// Unhandled exception:
+// RangeError (fractionDigits): Invalid value: Not in inclusive range 0..20: 21
+
+void main() {
+    print(1.5.toStringAsFixed(21));
+}
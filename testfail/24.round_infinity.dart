@@ -0,0 +1,8 @@
+// Dart error:
+// Unhandled exception:
+// Unsupported operation: Infinity
+
+void main() {
+    var x = double.infinity;
+    print(x.round());
+}
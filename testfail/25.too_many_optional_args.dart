@@ -0,0 +1,10 @@
+// Dart error:
+// Too many positional arguments: 2 allowed, but 3 found.
+
+int f(int a, [int b = 1]) {
+    return a + b;
+}
+
+void main() {
+    print(f(1, 2, 3));
+}
@@ -0,0 +1,11 @@
+
+// Dart error:
+// Required named parameter 'height' must be provided.
+
+int area({required int width, required int height}) {
+    return width * height;
+}
+
+void main() {
+    print(area(width: 2));
+}
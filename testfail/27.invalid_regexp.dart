@@ -0,0 +1,10 @@
+
+// Dart error:
+// Unhandled exception:
+// FormatException: Unterminated group
+// a(b
+
+void main() {
+    var re = RegExp("a(b");
+    print(re.hasMatch("ab"));
+}
@@ -0,0 +1,9 @@
+
+// Dart error:
+// Unhandled exception:
+// Invalid argument (name): Not a capture group name: "month"
+
+void main() {
+    var m = RegExp(r"(?<year>\d{4})").firstMatch("2024")!;
+    print(m.namedGroup("month"));
+}
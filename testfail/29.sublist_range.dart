@@ -0,0 +1,9 @@
+
+// Dart error:
+// Unhandled exception:
+// RangeError (end): Invalid value: Not in inclusive range 2..3: 1
+
+void main() {
+    var l = [1, 2, 3];
+    print(l.sublist(2, 1));
+}
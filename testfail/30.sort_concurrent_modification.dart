@@ -0,0 +1,15 @@
+
+// Dart error:
+// Unhandled exception:
+// Concurrent modification during iteration: Instance(length:4) of '_GrowableList'.
+
+var nums = [3, 1, 2];
+
+int compare(a, b) {
+    nums.add(0);
+    return a - b;
+}
+
+void main() {
+    nums.sort(compare);
+}
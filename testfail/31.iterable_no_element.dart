@@ -0,0 +1,13 @@
+
+// Dart error:
+// Unhandled exception:
+// Bad state: No element
+
+bool isNegative(int x) {
+    return x < 0;
+}
+
+void main() {
+    var l = [1, 2, 3];
+    print(l.where(isNegative).first);
+}
@@ -0,0 +1,9 @@
+
+// Dart error:
+// Unhandled exception:
+// RangeError (count): Invalid value: Not greater than or equal to 0: -1
+
+void main() {
+    var l = [1, 2, 3];
+    print(l.take(-1));
+}
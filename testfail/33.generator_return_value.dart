@@ -0,0 +1,12 @@
+
+// Dart error:
+// Error: Can't return a value from a generator function (using the 'sync*' modifier).
+
+Iterable ones() sync* {
+    yield 1;
+    return 2;
+}
+
+void main() {
+    print(ones());
+}
@@ -0,0 +1,18 @@
+
+// Dart error:
+// Unhandled exception:
+// Bad state: Generator is already running
+
+List iterators = [];
+
+Iterable selfish() sync* {
+    yield 1;
+    iterators[0].moveNext();
+}
+
+void main() {
+    var it = selfish().iterator;
+    iterators.add(it);
+    it.moveNext();
+    it.moveNext();
+}
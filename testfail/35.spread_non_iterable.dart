@@ -0,0 +1,9 @@
+
+// Dart error:
+// Unhandled exception:
+// type 'int' is not a subtype of type 'Iterable<dynamic>'
+
+void main() {
+    var n = 5;
+    var xs = [1, ...n];
+}
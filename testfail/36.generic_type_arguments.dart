@@ -0,0 +1,15 @@
+
+// Dart error:
+// Unhandled exception:
+// type 'Box<List<int>>' is not a subtype of type 'Iterable<dynamic>'
+
+class Box<T> {
+    T value;
+    Box(this.value);
+}
+
+void main() {
+    // The type argument is inferred, and kept by the instance.
+    dynamic box = Box([1, 2]);
+    var xs = [...box];
+}
@@ -0,0 +1,6 @@
+// Dart error:
+// Error: List literal requires exactly one type argument or none, but 2 found.
+
+void main() {
+    var xs = <int, int>[];
+}
@@ -0,0 +1,14 @@
+// Dart error:
+// Unhandled exception:
+// type 'Box<String>' is not a subtype of type 'Box<int>' in type cast
+
+class Box<T> {
+    T value;
+    Box(this.value);
+}
+
+void main() {
+    Object box = Box("a");
+    var ok = box as Box<Object>;
+    var bad = box as Box<int>;
+}
@@ -0,0 +1,9 @@
+// Dart error:
+// Unhandled exception:
+// type 'String' is not a subtype of type 'int'
+
+void main() {
+    dynamic d = "hello";
+    int x = 1;
+    x = d;
+}
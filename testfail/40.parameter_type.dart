@@ -0,0 +1,12 @@
+// Dart error:
+// Unhandled exception:
+// type 'double' is not a subtype of type 'int' of 'count'
+
+String repeat(String s, int count) {
+    return s * count;
+}
+
+void main() {
+    dynamic n = 2.5;
+    print(repeat("ab", n));
+}
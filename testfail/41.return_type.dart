@@ -0,0 +1,11 @@
+// Dart error:
+// Unhandled exception:
+// type 'String' is not a subtype of type 'int'
+
+int parse(dynamic s) {
+    return s;
+}
+
+void main() {
+    print(parse("12"));
+}
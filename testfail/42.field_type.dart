@@ -0,0 +1,14 @@
+// Dart error:
+// Unhandled exception:
+// type 'List<String>' is not a subtype of type 'List<int>'
+
+class Box<T> {
+    List<T> items;
+    Box(this.items);
+}
+
+void main() {
+    var box = Box<int>([1, 2]);
+    dynamic words = ["a", "b"];
+    box.items = words;
+}
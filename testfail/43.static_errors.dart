@@ -0,0 +1,16 @@
+// Dart error:
+// 8:13: Error: A value of type 'String' can't be assigned to a variable of type 'int'.
+// 9:5: Error: Setter not found: 'missing'.
+// 10:12: Error: Too few positional arguments: 2 required, 1 given.
+// 14:17: Error: Type 'Frobnicator' not found.
+
+int add(int a, int b) {
+    int x = "one";
+    missing = 2;
+    return add(1);
+}
+
+void main() {
+    Frobnicator f = add(1, 2);
+    print(f);
+}
@@ -0,0 +1,17 @@
+// Dart error:
+// Unhandled exception:
+// Null check operator used on a null value
+
+int? find(List<int> items, int wanted) {
+    for (var item in items) {
+        if (item == wanted) {
+            return item;
+        }
+    }
+    return null;
+}
+
+void main() {
+    int found = find([1, 2, 3], 4)!;
+    print(found);
+}
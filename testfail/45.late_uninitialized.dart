@@ -0,0 +1,8 @@
+// Dart error:
+// Unhandled exception:
+// LateInitializationError: Local 'greeting' has not been initialized.
+
+void main() {
+    late String greeting;
+    print(greeting);
+}
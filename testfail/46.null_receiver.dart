@@ -0,0 +1,10 @@
+// Dart error:
+// Unhandled exception:
+// NoSuchMethodError: The getter 'length' was called on null.
+// Receiver: null
+// Tried calling: length
+
+void main() {
+    dynamic items = null;
+    print(items.length);
+}
@@ -0,0 +1,27 @@
+// Dart error:
+// 9:5: Error: The non-nullable variable 'counter' must be initialized.
+// 11:6: Error: The parameter 'name' can't have a value of 'null' because of its type 'String', but the implicit default value is 'null'.
+// 16:13: Error: A value of type 'Null' can't be assigned to a variable of type 'int'.
+// 18:17: Error: The property 'length' can't be unconditionally accessed because the receiver can be 'null'.
+// 21:13: Error: The operator '+' can't be unconditionally invoked because the receiver can be 'null'.
+// 24:17: Error: The operator '+' can't be unconditionally invoked because the receiver can be 'null'.
+
+int counter;
+
+void greet([String name]) {
+    print(name);
+}
+
+void main() {
+    int n = null;
+    List<int>? items;
+    print(items.length);
+    int? k = 1;
+    k = null;
+    print(k + 1);
+    int? e = 1;
+    while (n == 0) {
+        print(e + 1);
+        e = null;
+    }
+}
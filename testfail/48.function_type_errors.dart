@@ -0,0 +1,23 @@
+// Dart error:
+// 20:16: Error: A value of type 'bool Function(int)' can't be assigned to a variable of type 'int Function(int, int)'.
+// 21:17: Error: The argument type 'int Function(int, int)' can't be assigned to the parameter type 'void Function(String)'.
+// 22:5: Error: Too few positional arguments: 2 required, 1 given.
+
+typedef IntOp = int Function(int, int);
+
+int add(int a, int b) {
+    return a + b;
+}
+
+bool isEven(int x) {
+    return x == 0;
+}
+
+void each(List<String> xs, void f(String s)) {
+}
+
+void main() {
+    IntOp op = isEven;
+    each(["a"], add);
+    add(1);
+}
@@ -0,0 +1,15 @@
+// Dart error:
+// Unhandled exception:
+// type '(int) => bool' is not a subtype of type '(int, int) => int'
+
+typedef IntOp = int Function(int, int);
+
+bool isEven(int x) {
+    return x == 0;
+}
+
+void main() {
+    dynamic f = isEven;
+    IntOp op = f;
+    print(op(1, 2));
+}
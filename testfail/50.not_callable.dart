@@ -0,0 +1,17 @@
+// Dart error:
+// Unhandled exception:
+// NoSuchMethodError: Class 'Point' has no instance method 'call'.
+// Receiver: Instance of 'Point'
+// Tried calling: call(3)
+
+class Point {
+    int x;
+    int y;
+
+    Point(this.x, this.y);
+}
+
+void main() {
+    dynamic p = Point(1, 2);
+    p(3);
+}
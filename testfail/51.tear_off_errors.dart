@@ -0,0 +1,21 @@
+// Dart error:
+// 18:42: Error: A value of type 'int Function(int)' can't be assigned to a variable of type 'int Function(String)'.
+// 19:5: Error: The expression doesn't evaluate to a function, so it can't be invoked.
+// 20:9: Error: The argument type 'String' can't be assigned to the parameter type 'int'.
+
+class Counter {
+    int count = 0;
+
+    int increment(int by) {
+        count = count + by;
+        return count;
+    }
+}
+
+void main() {
+    var counter = Counter();
+    var inc = counter.increment;
+    int Function(String) wrong = counter.increment;
+    counter(1);
+    inc("one");
+}
@@ -0,0 +1,27 @@
+// Dart error:
+// 18:15: Error: Superclass has no method named 'fly'.
+// 19:21: Error: Superclass has no getter named 'wings'.
+// 20:15: Error: Superclass has no setter named 'wings'.
+// 21:26: Error: The argument type 'String' can't be assigned to the parameter type 'int'.
+
+class Animal {
+    int legs = 4;
+
+    int walk(int steps) {
+        return steps * legs;
+    }
+}
+
+class Bird extends Animal {
+    void test() {
+        super.walk(1);
+        super.fly();
+        print(super.wings);
+        super.wings = 2;
+        print(super.walk("far"));
+    }
+}
+
+void main() {
+    Bird().test();
+}
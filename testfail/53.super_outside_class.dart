@@ -0,0 +1,8 @@
+// Dart error:
+// 7:11: Error: Expected identifier, but got 'super'.
+
+void main() {
+    var x = 1;
+    print(x);
+    print(super.toString());
+}
@@ -0,0 +1,18 @@
+// Dart error:
+// Unhandled exception:
+// NoSuchMethodError: Class 'Point' has no instance getter 'z'.
+// Receiver: Instance of 'Point'
+// Tried calling: z
+
+class Point {
+    int x;
+    int y;
+
+    Point(this.x, this.y);
+}
+
+void main() {
+    dynamic p = Point(1, 2);
+    print(p.x);
+    print(p.z);
+}
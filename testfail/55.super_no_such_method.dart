@@ -0,0 +1,24 @@
+// Dart error:
+// Unhandled exception:
+// NoSuchMethodError: Class 'Quiet' has no instance setter 'volume='.
+// Receiver: Instance of 'Quiet'
+// Tried calling: volume=11
+
+class Quiet {
+    var ignored = <String>[];
+
+    dynamic noSuchMethod(Invocation invocation) {
+        if (invocation.isMethod) {
+            ignored.add(invocation.memberName.toString());
+            return null;
+        }
+        return super.noSuchMethod(invocation);
+    }
+}
+
+void main() {
+    dynamic q = Quiet();
+    q.shout("hello");
+    print(q.ignored);
+    q.volume = 11;
+}
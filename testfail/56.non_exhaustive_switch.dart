@@ -0,0 +1,54 @@
+// Dart error:
+// 19:12: Error: The type 'Shape' is not exhaustively matched by the switch cases since it doesn't match 'Triangle()'.
+// 27:5: Error: The type 'Shape?' is not exhaustively matched by the switch cases since it doesn't match 'null'.
+// 37:5: Error: The type 'bool' is not exhaustively matched by the switch cases since it doesn't match 'false'.
+// 45:12: Error: The type 'int' is not exhaustively matched by the switch cases since it doesn't match 'int()'.
+
+sealed class Shape {}
+
+class Circle extends Shape {}
+
+sealed class Polygon extends Shape {}
+
+class Square extends Polygon {}
+
+class Triangle extends Polygon {}
+
+
+String name(Shape s) {
+    return switch (s) {
+        Circle() => "circle",
+        Square() => "square"
+    };
+}
+
+
+void check(Shape? s) {
+    switch (s) {
+        case Circle c:
+            print("circle");
+        case Polygon():
+            print("polygon");
+    }
+}
+
+
+void flag(bool b) {
+    switch (b) {
+        case true:
+            print("yes");
+    }
+}
+
+
+String count(int n) {
+    return switch (n) {
+        0 => "zero",
+        1 => "one"
+    };
+}
+
+
+void main() {
+    print(name(Circle()));
+}
@@ -0,0 +1,9 @@
+sealed class Shape {}
+
+final class Point {}
+
+interface class Reader {}
+
+base class Vehicle {}
+
+class Animal {}
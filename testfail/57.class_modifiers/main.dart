@@ -0,0 +1,32 @@
+// Dart error:
+// 13:7: Error: The class 'Shape' can't be extended, implemented, or mixed in outside of its library because it's a sealed class.
+// 15:13: Error: The class 'Point' can't be extended outside of its library because it's a final class.
+// 17:7: Error: The class 'Reader' can't be extended outside of its library because it's an interface class.
+// 19:7: Error: The type 'Truck' must be 'base', 'final' or 'sealed' because the supertype 'Vehicle' is 'base'.
+// 23:13: Error: The class 'Dog' can't be used as a mixin because it extends a class other than 'Object'.
+// 29:11: Error: The class 'Shape' is abstract and can't be instantiated.
+// 30:11: Error: The class 'Pet' is abstract and can't be instantiated.
+
+import "lib.dart";
+
+
+class Square extends Shape {}
+
+final class Pixel extends Point {}
+
+class Novel extends Reader {}
+
+class Truck extends Vehicle {}
+
+abstract class Pet {}
+
+mixin class Dog extends Animal {}
+
+base class Bus extends Vehicle {}
+
+
+void main() {
+    print(Shape());
+    print(Pet());
+    print(Bus());
+}
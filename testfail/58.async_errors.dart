@@ -0,0 +1,19 @@
+// Dart error:
+// 6:5: Error: Functions marked 'async' must have a return type assignable to 'Future'.
+// 11:12: Error: A value of type 'String' can't be returned from an async function with return type 'Future<int>'.
+// 17:16: Error: A value of type 'int' can't be assigned to a variable of type 'String'.
+
+int count() async {
+    return 1;
+}
+
+Future<int> name() async {
+    return "name";
+}
+
+
+void main() async {
+    Future<int> f = Future.value(1);
+    String s = await f;
+    print(s);
+}
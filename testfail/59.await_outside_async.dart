@@ -0,0 +1,7 @@
+// Dart error:
+// 5:13: Error: 'await' can only be used in 'async' or 'async*' methods.
+
+void main() {
+    int x = await Future.value(1);
+    print(x);
+}
@@ -0,0 +1,16 @@
+// Dart error:
+// Unhandled exception:
+// failed
+
+Future<int> compute() async {
+    await null;
+    int n = await Future.error("failed");
+    return n;
+}
+
+
+void main() async {
+    print("start");
+    compute();
+    print("end");
+}
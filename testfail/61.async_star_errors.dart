@@ -0,0 +1,18 @@
+// Dart error:
+// 5:5: Error: Functions marked 'async*' must have a return type assignable to 'Stream'.
+// 15:28: Error: A value of type 'int' can't be assigned to a variable of type 'String'.
+
+int numbers() async* {
+    yield 1;
+}
+
+Stream<int> more() async* {
+    yield 2;
+}
+
+
+void main() async {
+    await for (String s in more()) {
+        print(s);
+    }
+}
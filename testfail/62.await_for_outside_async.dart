@@ -0,0 +1,12 @@
+// Dart error:
+// 9:5: Error: The asynchronous for-in can only be used in functions marked with 'async' or 'async*'.
+
+Stream<int> numbers() async* {
+    yield 1;
+}
+
+void main() {
+    await for (int n in numbers()) {
+        print(n);
+    }
+}
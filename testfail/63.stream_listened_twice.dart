@@ -0,0 +1,14 @@
+// Dart error:
+// Unhandled exception:
+// Bad state: Stream has already been listened to.
+
+void show(int n) {
+    print(n);
+}
+
+
+void main() {
+    Stream<int> numbers = Stream.fromIterable([1, 2]);
+    numbers.listen(show);
+    numbers.listen(show);
+}
@@ -0,0 +1,11 @@
+// Dart error:
+// Unhandled exception:
+// Invalid argument(s): Illegal argument in isolate message: (object is a ReceivePort)
+
+import 'dart:isolate';
+
+
+void main() {
+    ReceivePort port = ReceivePort();
+    port.sendPort.send([1, port]);
+}
@@ -0,0 +1,17 @@
+// Dart error:
+// Unhandled exception:
+// Invalid argument(s): Illegal argument in isolate message: object is unsendable
+
+import 'dart:isolate';
+
+
+void child(StringBuffer buffer) {
+    print(buffer);
+}
+
+
+void main() async {
+    StringBuffer buffer = StringBuffer();
+    buffer.write("not sent");
+    await Isolate.spawn(child, buffer);
+}
@@ -0,0 +1,12 @@
+// Dart error:
+// Error: Type 'Timer' not found.
+
+
+void tick(Timer timer) {
+    print(timer.tick);
+}
+
+
+void main() {
+    Timer.periodic(Duration(seconds: 1), tick);
+}
@@ -0,0 +1,11 @@
+// Dart error:
+// Unhandled exception:
+// FormatException: Invalid date format
+// 2020/01/02
+
+
+void main() {
+    print(DateTime.parse("2020-01-02"));
+    DateTime time = DateTime.parse("2020/01/02");
+    print(time);
+}
@@ -0,0 +1,17 @@
+// Dart error:
+// Error: Operator '+' should have exactly one parameter.
+
+
+class Money {
+    int cents;
+    Money(this.cents);
+
+    Money operator +(Money a, Money b) {
+        return Money(a.cents + b.cents);
+    }
+}
+
+
+void main() {
+    print(Money(1).cents);
+}
@@ -0,0 +1,13 @@
+// Dart error:
+// Unhandled exception:
+// FormatException: Unexpected character (at line 3, character 16)
+//     "b": [1, 2,]
+//                ^
+
+import 'dart:convert';
+
+
+void main() {
+    print(jsonDecode('{"a": 1}'));
+    print(jsonDecode('{\n    "a": 1,\n    "b": [1, 2,]\n}'));
+}
@@ -0,0 +1,17 @@
+// Dart error:
+// Unhandled exception:
+// Converting object to an encodable object failed: Instance of 'Point'
+
+import 'dart:convert';
+
+
+class Point {
+    int x = 1;
+    int y = 2;
+}
+
+
+void main() {
+    print(jsonEncode([1, 2]));
+    print(jsonEncode([Point()]));
+}
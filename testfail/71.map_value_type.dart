@@ -0,0 +1,8 @@
+// Dart error:
+// Error: The element type 'String' can't be assigned to the map value type 'int'.
+
+
+void main() {
+    Map<String, int> ages = {"ann": 31, "bob": "young"};
+    print(ages);
+}
@@ -0,0 +1,8 @@
+// Dart error:
+// Error: Type 'Foo' not found.
+
+
+void main() {
+    print(List<int>);
+    print(List<Foo>);
+}
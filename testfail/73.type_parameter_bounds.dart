@@ -0,0 +1,32 @@
+// Dart error:
+// 25:13: Error: Type argument 'String' doesn't conform to the bound 'num' of the type variable 'T' on 'Box'.
+// 26:13: Error: Inferred type argument 'String' doesn't conform to the bound 'num' of the type variable 'T' on 'Box'.
+// 27:18: Error: Type argument 'String' doesn't conform to the bound 'num' of the type variable 'T' on 'Box'.
+// 28:11: Error: Type argument 'String' doesn't conform to the bound 'num' of the type variable 'T' on 'big'.
+// 29:11: Error: Inferred type argument 'String' doesn't conform to the bound 'num' of the type variable 'T' on 'big'.
+// 30:18: Error: Type argument 'int' doesn't conform to the bound 'Box<int>' of the type variable 'R' on 'same'.
+// 31:14: Error: Can't use the default List constructor.
+
+class Box<T extends num> {
+    T value;
+
+    Box(this.value);
+
+    R same<R extends Box<int>>(R box) {
+        return box;
+    }
+}
+
+T big<T extends num>(T a) {
+    return a;
+}
+
+void main() {
+    var a = Box<String>("x");
+    var b = Box("x");
+    Box<String>? c;
+    print(big<String>("s"));
+    print(big("s"));
+    print(Box(1).same<int>(1));
+    var xs = List<int>();
+}
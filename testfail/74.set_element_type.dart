@@ -0,0 +1,8 @@
+// Dart error:
+// Error: The element type 'String' can't be assigned to the set type 'int'.
+
+
+void main() {
+    Set<int> ids = {1, 2, "three"};
+    print(ids);
+}
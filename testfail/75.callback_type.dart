@@ -0,0 +1,14 @@
+// Dart error:
+// 12:19: Error: The argument type 'String Function(String)' can't be assigned to the parameter type 'bool Function(int)'.
+// 13:19: Error: The argument type 'String Function(String)' can't be assigned to the parameter type 'bool Function(int)'.
+
+
+String shout(String s) {
+    return s + "!";
+}
+
+void main() {
+    var l = [3, 1, 2];
+    print(l.where(shout));
+    l.removeWhere(shout);
+}